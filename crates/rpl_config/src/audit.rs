@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ConfError;
+
+/// Name of the environment variable of the directory where `rpl-driver` writes the summaries of
+/// the dependencies it audits, set by `cargo rpl --audit-deps`.
+pub const AUDIT_DIR_ENV: &str = "RPL_AUDIT_DIR";
+
+/// A crate with at least this many unsafe blocks, functions, impls and traits in total is
+/// highlighted as unsafe-heavy in the audit report.
+pub const UNSAFE_HEAVY_THRESHOLD: usize = 20;
+
+/// Usage of `unsafe` in a crate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsafeStats {
+    pub unsafe_blocks: usize,
+    pub unsafe_fns: usize,
+    pub unsafe_impls: usize,
+    pub unsafe_traits: usize,
+    /// Lines of the source files of the crate.
+    pub lines: usize,
+}
+
+impl UnsafeStats {
+    pub fn total(&self) -> usize {
+        self.unsafe_blocks + self.unsafe_fns + self.unsafe_impls + self.unsafe_traits
+    }
+
+    pub fn is_unsafe_heavy(&self) -> bool {
+        self.total() >= UNSAFE_HEAVY_THRESHOLD
+    }
+}
+
+impl std::ops::AddAssign for UnsafeStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.unsafe_blocks += rhs.unsafe_blocks;
+        self.unsafe_fns += rhs.unsafe_fns;
+        self.unsafe_impls += rhs.unsafe_impls;
+        self.unsafe_traits += rhs.unsafe_traits;
+        self.lines += rhs.lines;
+    }
+}
+
+/// The summary of a crate of an audited dependency, written by `rpl-driver`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateSummary {
+    pub package: String,
+    pub version: String,
+    /// The name of the crate, e.g. `build_script_build` for the build script of the package.
+    pub krate: String,
+    /// The `-C extra-filename` cargo passes for the crate, which tells apart the builds of the same
+    /// crate, e.g. with different features.
+    pub extra_filename: String,
+    pub stats: UnsafeStats,
+}
+
+impl CrateSummary {
+    /// The file name of the artifact of the crate without its prefix and extension, e.g.
+    /// `libc-0123456789abcdef` of `liblibc-0123456789abcdef.rlib`.
+    pub fn artifact(&self) -> String {
+        format!("{}{}", self.krate, self.extra_filename)
+    }
+
+    /// Write the summary to `dir`, replacing the one of the same build of the crate from a previous
+    /// run.
+    pub fn write(&self, dir: &Path) -> Result<(), ConfError> {
+        let path = dir.join(format!("{}-{}-{}.json", self.package, self.version, self.artifact()));
+        let content = serde_json::to_string(self).map_err(|source| ConfError::Json {
+            path: path.clone(),
+            source,
+        })?;
+        fs::create_dir_all(dir)
+            .and_then(|()| fs::write(&path, content))
+            .map_err(|source| ConfError::Io { path, source })
+    }
+
+    /// Read all the summaries in `dir`, which may not exist yet.
+    pub fn read_dir(dir: &Path) -> Result<Vec<Self>, ConfError> {
+        let io_error = |source| ConfError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut summaries = Vec::new();
+        for entry in fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).map_err(|source| ConfError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            summaries.push(serde_json::from_str(&content).map_err(|source| ConfError::Json {
+                path: path.clone(),
+                source,
+            })?);
+        }
+        Ok(summaries)
+    }
+}
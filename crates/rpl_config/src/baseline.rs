@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ConfError;
+
+/// A known finding. Line numbers are left out on purpose, so that unrelated edits in the same
+/// file do not invalidate the baseline.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub lint: String,
+    pub file: String,
+    pub message: String,
+}
+
+impl BaselineEntry {
+    /// Extract the entry from a diagnostic in the JSON format of rustc, if it is an RPL lint.
+    pub fn from_diagnostic(diag: &serde_json::Value) -> Option<Self> {
+        let lint = diag.get("code")?.get("code")?.as_str()?;
+        if !lint.starts_with("rpl::") {
+            return None;
+        }
+        let file = diag
+            .get("spans")?
+            .as_array()?
+            .iter()
+            .find(|span| span.get("is_primary").and_then(serde_json::Value::as_bool) == Some(true))?
+            .get("file_name")?
+            .as_str()?;
+        let message = diag.get("message")?.as_str()?;
+        Some(Self {
+            lint: lint.to_owned(),
+            file: file.to_owned(),
+            message: message.to_owned(),
+        })
+    }
+}
+
+/// A set of known findings that are not reported again.
+///
+/// A finding occurring more times than it is recorded is still reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Baseline {
+    entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn read(path: &Path) -> Result<Self, ConfError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_str(&content).map_err(|source| ConfError::Json {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ConfError> {
+        let mut content = serde_json::to_string_pretty(self).map_err(|source| ConfError::Json {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        content.push('\n');
+        fs::write(path, content).map_err(|source| ConfError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn insert(&mut self, entry: BaselineEntry) {
+        let index = self.entries.partition_point(|e| *e <= entry);
+        self.entries.insert(index, entry);
+    }
+
+    /// Remove one occurrence of `entry`, returning whether it was known.
+    pub fn remove(&mut self, entry: &BaselineEntry) -> bool {
+        match self.entries.iter().position(|e| e == entry) {
+            Some(index) => {
+                self.entries.remove(index);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
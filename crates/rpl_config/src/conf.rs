@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use serde::Deserialize;
+
+use crate::glob::glob_match;
+
+/// Name of the environment variable of the directory to look up the configuration file from,
+/// overriding the manifest directory of the crate being checked.
+pub const CONF_DIR_ENV: &str = "RPL_CONF_DIR";
+
+/// Names of the standalone configuration files, in the order of precedence.
+const CONF_FILE_NAMES: &[&str] = &["rpl.toml", ".rpl.toml"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfError {
+    #[error("failed to read `{}`: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("failed to parse `{}`: {source}", path.display())]
+    Toml { path: PathBuf, source: toml::de::Error },
+    #[error("failed to parse `{}`: {source}", path.display())]
+    Json { path: PathBuf, source: serde_json::Error },
+    #[error("lint level of `{lint}` in `{}` is specified more than once", path.display())]
+    DuplicatedLint { path: PathBuf, lint: String },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Run both inline and normal detectors.
+    #[default]
+    Both,
+    /// Run only the detectors matching MIR with callees inlined.
+    Inline,
+    /// Run only the detectors matching MIR without inlining.
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Rendered diagnostics, as rustc prints them.
+    #[default]
+    Human,
+    /// One JSON diagnostic per line, as `rustc --error-format=json` prints them.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
+impl LintLevel {
+    /// The rustc flag setting a lint to this level.
+    pub fn flag(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "-A",
+            LintLevel::Warn => "-W",
+            LintLevel::Deny => "-D",
+            LintLevel::Forbid => "-F",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Detectors {
+    /// If not empty, only these detectors are run.
+    pub enable: Vec<String>,
+    /// These detectors are never run, even if they are enabled.
+    pub disable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Conf {
+    pub detectors: Detectors,
+    pub mode: Mode,
+    pub lints: BTreeMap<String, LintLevel>,
+    pub exclude: Vec<String>,
+    pub output_format: OutputFormat,
+    pub baseline: Option<PathBuf>,
+    /// The file this configuration is read from, or `None` for the default configuration.
+    #[serde(skip)]
+    pub file: Option<PathBuf>,
+}
+
+/// Look up the configuration file from `dir` and its ancestors.
+///
+/// In each directory, `rpl.toml` and `.rpl.toml` are preferred to a `Cargo.toml` with a
+/// `[workspace.metadata.rpl]` section. The lookup stops at the workspace root, i.e. the first
+/// `Cargo.toml` with a `[workspace]` section.
+pub fn lookup_conf_file(dir: &Path) -> Result<Option<PathBuf>, ConfError> {
+    for dir in dir.ancestors() {
+        for name in CONF_FILE_NAMES {
+            let path = dir.join(name);
+            if path.is_file() {
+                return Ok(Some(path));
+            }
+        }
+        let manifest = dir.join("Cargo.toml");
+        if manifest.is_file() {
+            let table = read_toml(&manifest)?;
+            let workspace = table.get("workspace");
+            if workspace
+                .and_then(|workspace| workspace.get("metadata"))
+                .and_then(|metadata| metadata.get("rpl"))
+                .is_some()
+            {
+                return Ok(Some(manifest));
+            }
+            if workspace.is_some() {
+                break;
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn read_toml(path: &Path) -> Result<toml::Table, ConfError> {
+    let content = fs::read_to_string(path).map_err(|source| ConfError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    content.parse().map_err(|source| ConfError::Toml {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+impl Conf {
+    /// Read the configuration from `path`, either a standalone `rpl.toml` or a `Cargo.toml`.
+    pub fn read(path: &Path) -> Result<Self, ConfError> {
+        let mut table = read_toml(path)?;
+        let value = if path.file_name().is_some_and(|name| name == "Cargo.toml") {
+            table
+                .remove("workspace")
+                .and_then(|mut workspace| workspace.get_mut("metadata")?.as_table_mut()?.remove("rpl"))
+                .unwrap_or_else(|| toml::Value::Table(toml::Table::new()))
+        } else {
+            toml::Value::Table(table)
+        };
+        let mut conf: Conf = value.try_into().map_err(|source| ConfError::Toml {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        conf.file = Some(path.to_path_buf());
+        conf.check_lints()?;
+        Ok(conf)
+    }
+
+    /// Look up the configuration file from `dir` and read it, or use the default configuration if
+    /// there is none.
+    pub fn lookup_and_read(dir: &Path) -> Result<Self, ConfError> {
+        match lookup_conf_file(dir)? {
+            Some(path) => Self::read(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Lint names may be written with or without the `rpl::` prefix, but not both.
+    fn check_lints(&self) -> Result<(), ConfError> {
+        let mut seen = Vec::with_capacity(self.lints.len());
+        for lint in self.lints.keys() {
+            let lint = qualified_name(lint);
+            if seen.contains(&lint) {
+                return Err(ConfError::DuplicatedLint {
+                    path: self.file.clone().unwrap_or_default(),
+                    lint,
+                });
+            }
+            seen.push(lint);
+        }
+        Ok(())
+    }
+
+    /// The directory paths in this configuration are relative to.
+    pub fn root(&self) -> Option<&Path> {
+        self.file.as_deref().and_then(Path::parent)
+    }
+
+    /// Resolve `path` in this configuration against [`Conf::root`].
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        match self.root() {
+            Some(root) => root.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// The rustc flags setting the lint levels in this configuration.
+    pub fn lint_level_args(&self) -> Vec<String> {
+        self.lints
+            .iter()
+            .flat_map(|(lint, level)| [level.flag().to_owned(), qualified_name(lint)])
+            .collect()
+    }
+
+    /// Whether the detector named by its module path, e.g. `inline::cve_2020_35873`, should run.
+    pub fn is_detector_enabled(&self, name: &str) -> bool {
+        let mode_enabled = match self.mode {
+            Mode::Both => true,
+            Mode::Inline => !name.starts_with("normal::"),
+            Mode::Normal => !name.starts_with("inline::"),
+        };
+        let selects = |selector: &String| selects_detector(selector, name);
+        mode_enabled
+            && (self.detectors.enable.is_empty() || self.detectors.enable.iter().any(selects))
+            && !self.detectors.disable.iter().any(selects)
+    }
+
+    /// Whether `path` matches any of the exclude globs. Relative paths are relative to
+    /// [`Conf::root`], and absolute paths outside of it are never excluded.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        let path = match self.root() {
+            Some(root) if path.is_absolute() => match path.strip_prefix(root) {
+                Ok(path) => path,
+                Err(_) => return false,
+            },
+            _ => path,
+        };
+        let Some(path) = path.to_str() else {
+            return false;
+        };
+        let path = path.replace('\\', "/");
+        self.exclude.iter().any(|glob| glob_match(glob, &path))
+    }
+}
+
+/// Qualify a lint or lint group name with the `rpl::` tool prefix, if it is not.
+pub fn qualified_name(name: &str) -> String {
+    if name.starts_with("rpl::") {
+        name.to_owned()
+    } else {
+        format!("rpl::{name}")
+    }
+}
+
+/// A selector selects a detector if it is a prefix of the module path of the detector, or the
+/// module path without the leading mode.
+fn selects_detector(selector: &str, name: &str) -> bool {
+    let is_prefix_of = |name: &str| {
+        name.strip_prefix(selector)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    };
+    is_prefix_of(name) || name.split_once("::").is_some_and(|(_mode, rest)| is_prefix_of(rest))
+}
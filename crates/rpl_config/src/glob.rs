@@ -0,0 +1,39 @@
+/// Match a `/`-separated `path` against a glob `pattern`.
+///
+/// - `**` as a whole path segment matches any number of segments, including none;
+/// - `*` matches any characters in a segment;
+/// - `?` matches any single character in a segment;
+/// - a trailing `/` is ignored, so `vendor/` is the same as `vendor`;
+/// - a pattern also matches everything under the paths it matches, so `vendor` excludes
+///   `vendor/foo/src/lib.rs`.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_end_matches('/');
+    let pattern = pattern.split('/').filter(|seg| !seg.is_empty()).collect::<Vec<_>>();
+    let path = path.split('/').filter(|seg| !seg.is_empty()).collect::<Vec<_>>();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern {
+        // The path is under a matched directory.
+        [] => true,
+        ["**", rest @ ..] => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        [seg, rest @ ..] => match path {
+            [] => false,
+            [name, path @ ..] => {
+                let seg = seg.chars().collect::<Vec<_>>();
+                let name = name.chars().collect::<Vec<_>>();
+                match_segment(&seg, &name) && match_segments(rest, path)
+            },
+        },
+    }
+}
+
+fn match_segment(pattern: &[char], name: &[char]) -> bool {
+    match pattern {
+        [] => name.is_empty(),
+        ['*', rest @ ..] => (0..=name.len()).any(|skip| match_segment(rest, &name[skip..])),
+        ['?', rest @ ..] => !name.is_empty() && match_segment(rest, &name[1..]),
+        [c, rest @ ..] => name.first() == Some(c) && match_segment(rest, &name[1..]),
+    }
+}
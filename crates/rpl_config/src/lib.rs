@@ -0,0 +1,55 @@
+//! Workspace configuration of RPL, read from `rpl.toml` or the `[workspace.metadata.rpl]` section
+//! of the workspace `Cargo.toml`.
+//!
+//! ```toml
+//! # Detectors to run, named by their module paths, e.g. `inline::cve_2020_35873`,
+//! # `cve_2020_35873` (both modes) or `normal` (a whole mode). Empty means all of them.
+//! detectors.enable = []
+//! detectors.disable = ["cve_2021_27376"]
+//! # Run only `inline`, only `normal`, or `both` kinds of detectors.
+//! mode = "both"
+//!
+//! # Paths to skip, as globs relative to this file.
+//! exclude = ["src/generated/**", "vendor", "**/tests/**"]
+//! # `human` or `json`.
+//! output-format = "human"
+//! # Known findings that are not reported again, see `cargo rpl --update-baseline`.
+//! baseline = "rpl-baseline.json"
+//!
+//! # Lint levels, applied before the ones passed after `cargo rpl --`.
+//! [lints]
+//! use_after_drop = "deny"
+//! private_function_marked_inline = "allow"
+//! ```
+
+mod audit;
+mod baseline;
+mod conf;
+mod glob;
+mod version;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::OnceLock;
+
+pub use audit::{AUDIT_DIR_ENV, CrateSummary, UNSAFE_HEAVY_THRESHOLD, UnsafeStats};
+pub use baseline::{Baseline, BaselineEntry};
+pub use conf::{
+    CONF_DIR_ENV, Conf, ConfError, Detectors, LintLevel, Mode, OutputFormat, lookup_conf_file, qualified_name,
+};
+pub use glob::glob_match;
+pub use version::{CRATE_VERSIONS_ENV, VersionReq, VersionReqError, crate_versions};
+
+static CONF: OnceLock<Conf> = OnceLock::new();
+
+/// Set the configuration of the current process. It can only be set once, and later calls are
+/// ignored.
+pub fn init(conf: Conf) {
+    _ = CONF.set(conf);
+}
+
+/// The configuration of the current process, or the default one if [`init`] is not called.
+pub fn conf() -> &'static Conf {
+    CONF.get_or_init(Conf::default)
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use crate::*;
+
+fn parse(toml: &str) -> Conf {
+    toml::from_str(toml).unwrap()
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("src/generated/**", "src/generated/foo.rs"));
+    assert!(glob_match("src/generated/**", "src/generated/a/b/foo.rs"));
+    assert!(!glob_match("src/generated/**", "src/lib.rs"));
+    assert!(glob_match("vendor", "vendor/foo/src/lib.rs"));
+    assert!(glob_match("vendor/", "vendor/foo/src/lib.rs"));
+    assert!(!glob_match("vendor", "src/vendor/lib.rs"));
+    assert!(glob_match("**/tests/**", "tests/ui.rs"));
+    assert!(glob_match("**/tests/**", "crates/foo/tests/ui.rs"));
+    assert!(!glob_match("**/tests/**", "crates/foo/src/tests.rs"));
+    assert!(glob_match("**/*_generated.rs", "src/a/b_generated.rs"));
+    assert!(glob_match("src/?.rs", "src/a.rs"));
+    assert!(!glob_match("src/?.rs", "src/ab.rs"));
+    assert!(!glob_match("src/*.rs", "src/a/b.rs"));
+}
+
+#[test]
+fn test_detectors() {
+    let default = Conf::default();
+    assert!(default.is_detector_enabled("inline::cve_2020_35873"));
+    assert!(default.is_detector_enabled("normal::cve_2018_21000::t_to_u8"));
+
+    let conf = parse(
+        r#"
+        detectors.disable = ["cve_2018_21000", "inline::cve_2020_35907"]
+        "#,
+    );
+    assert!(!conf.is_detector_enabled("inline::cve_2018_21000::t_to_u8"));
+    assert!(!conf.is_detector_enabled("normal::cve_2018_21000::u8_to_t"));
+    assert!(!conf.is_detector_enabled("inline::cve_2020_35907"));
+    assert!(conf.is_detector_enabled("normal::cve_2020_35907"));
+    assert!(conf.is_detector_enabled("normal::cve_2018_21000_foo"));
+
+    let conf = parse(
+        r#"
+        detectors.enable = ["normal", "inline::alloc_unchecked"]
+        detectors.disable = ["normal::alloc_unchecked"]
+        "#,
+    );
+    assert!(conf.is_detector_enabled("normal::manually_drop"));
+    assert!(conf.is_detector_enabled("inline::alloc_unchecked"));
+    assert!(!conf.is_detector_enabled("normal::alloc_unchecked"));
+    assert!(!conf.is_detector_enabled("inline::cve_2020_35873"));
+}
+
+#[test]
+fn test_mode() {
+    let conf = parse(r#"mode = "normal""#);
+    assert!(conf.is_detector_enabled("normal::cve_2020_35907"));
+    assert!(!conf.is_detector_enabled("inline::cve_2020_35907"));
+    assert!(conf.is_detector_enabled("others::private_or_generic_function_marked_inline"));
+}
+
+#[test]
+fn test_lint_level_args() {
+    let conf = parse(
+        r#"
+        [lints]
+        use_after_drop = "deny"
+        "rpl::private_function_marked_inline" = "allow"
+        "#,
+    );
+    assert_eq!(
+        conf.lint_level_args(),
+        ["-A", "rpl::private_function_marked_inline", "-D", "rpl::use_after_drop"]
+    );
+}
+
+#[test]
+fn test_unknown_field() {
+    assert!(toml::from_str::<Conf>("unknown = 1").is_err());
+    assert!(toml::from_str::<Conf>(r#"mode = "all""#).is_err());
+}
+
+#[test]
+fn test_exclude() {
+    let mut conf = parse(r#"exclude = ["src/generated/**"]"#);
+    assert!(conf.is_excluded(Path::new("src/generated/foo.rs")));
+    assert!(!conf.is_excluded(Path::new("src/lib.rs")));
+    conf.file = Some("/workspace/rpl.toml".into());
+    assert!(conf.is_excluded(Path::new("/workspace/src/generated/foo.rs")));
+    assert!(!conf.is_excluded(Path::new("/elsewhere/src/generated/foo.rs")));
+}
+
+#[test]
+fn test_baseline() {
+    let diag = serde_json::json!({
+        "message": "use a pointer from `Vec<u8>` after dropped",
+        "code": { "code": "rpl::use_after_drop", "explanation": null },
+        "level": "error",
+        "spans": [
+            { "file_name": "src/other.rs", "is_primary": false },
+            { "file_name": "src/lib.rs", "is_primary": true },
+        ],
+    });
+    let entry = BaselineEntry::from_diagnostic(&diag).unwrap();
+    assert_eq!(entry.lint, "rpl::use_after_drop");
+    assert_eq!(entry.file, "src/lib.rs");
+
+    let rustc_diag = serde_json::json!({
+        "message": "unused variable: `x`",
+        "code": { "code": "unused_variables", "explanation": null },
+        "spans": [{ "file_name": "src/lib.rs", "is_primary": true }],
+    });
+    assert_eq!(BaselineEntry::from_diagnostic(&rustc_diag), None);
+
+    let mut baseline = Baseline::default();
+    baseline.insert(entry.clone());
+    assert!(baseline.remove(&entry));
+    assert!(!baseline.remove(&entry));
+    assert!(baseline.is_empty());
+}
+
+#[test]
+fn test_version_req() {
+    let req = VersionReq::parse("< 0.8.7").unwrap();
+    assert!(req.matches("0.8.6"));
+    assert!(req.matches("0.7.0"));
+    assert!(!req.matches("0.8.7"));
+    assert!(!req.matches("1.0.0"));
+    assert_eq!(req.to_string(), "<0.8.7");
+
+    let req = VersionReq::parse(">=0.8.0, <0.8.7").unwrap();
+    assert!(req.matches("0.8.0"));
+    assert!(req.matches("0.8.6-beta.1"));
+    assert!(!req.matches("0.7.9"));
+
+    let req = VersionReq::parse("2.1").unwrap();
+    assert!(req.matches("2.3.0"));
+    assert!(!req.matches("2.0.9"));
+    assert!(!req.matches("3.0.0"));
+    assert!(!req.matches("not a version"));
+
+    assert_eq!(VersionReq::parse(">="), Err(VersionReqError::Empty));
+    assert_eq!(
+        VersionReq::parse("< 0.x"),
+        Err(VersionReqError::InvalidVersion("0.x".to_owned()))
+    );
+}
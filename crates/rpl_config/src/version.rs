@@ -0,0 +1,125 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use rustc_semver::RustcVersion;
+
+/// Name of the environment variable of the resolved versions of the packages in the dependency
+/// graph, set by `cargo rpl` as comma-separated `name@version`s from `cargo metadata`.
+pub const CRATE_VERSIONS_ENV: &str = "RPL_CRATE_VERSIONS";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VersionReqError {
+    #[error("empty version requirement")]
+    Empty,
+    #[error("invalid version `{0}` in version requirement")]
+    InvalidVersion(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Caret,
+}
+
+/// A requirement on the version of a crate, as comma-separated comparators that must all be met,
+/// e.g. `>= 0.8.0, < 0.8.7`.
+///
+/// Each comparator is one of `=`, `>`, `>=`, `<`, `<=` and `^` followed by a version, and a
+/// version without an operator is a caret requirement, as in `Cargo.toml`. Pre-release and build
+/// metadata of the versions are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<(Op, RustcVersion)>,
+}
+
+fn parse_version(version: &str) -> Option<RustcVersion> {
+    let version = version.trim();
+    let version = version.split(['-', '+']).next()?;
+    RustcVersion::parse(version).ok()
+}
+
+impl VersionReq {
+    pub fn parse(req: &str) -> Result<Self, VersionReqError> {
+        let comparators = req
+            .split(',')
+            .map(|comparator| {
+                let comparator = comparator.trim();
+                let (op, version) = [
+                    (">=", Op::GreaterEq),
+                    ("<=", Op::LessEq),
+                    (">", Op::Greater),
+                    ("<", Op::Less),
+                    ("=", Op::Exact),
+                    ("^", Op::Caret),
+                ]
+                .into_iter()
+                .find_map(|(prefix, op)| Some((op, comparator.strip_prefix(prefix)?)))
+                .unwrap_or((Op::Caret, comparator));
+                if version.trim().is_empty() {
+                    return Err(VersionReqError::Empty);
+                }
+                let version =
+                    parse_version(version).ok_or_else(|| VersionReqError::InvalidVersion(version.trim().to_owned()))?;
+                Ok((op, version))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { comparators })
+    }
+
+    /// Whether `version` meets this requirement, or `false` if it is not a valid version.
+    pub fn matches(&self, version: &str) -> bool {
+        let Some(version) = parse_version(version) else {
+            return false;
+        };
+        self.comparators.iter().all(|&(op, req)| match op {
+            Op::Exact => version == req,
+            Op::Greater => version > req,
+            Op::GreaterEq => version >= req,
+            Op::Less => version < req,
+            Op::LessEq => version <= req,
+            Op::Caret => version.meets(req),
+        })
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (op, version)) in self.comparators.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let op = match op {
+                Op::Exact => "=",
+                Op::Greater => ">",
+                Op::GreaterEq => ">=",
+                Op::Less => "<",
+                Op::LessEq => "<=",
+                Op::Caret => "^",
+            };
+            write!(f, "{op}{version}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The resolved versions of the package `name`, from [`CRATE_VERSIONS_ENV`]. There may be more
+/// than one due to multiple major versions, and none if `cargo rpl` is not used.
+pub fn crate_versions(name: &str) -> impl Iterator<Item = &'static str> {
+    static CRATE_VERSIONS: OnceLock<Vec<(String, String)>> = OnceLock::new();
+    CRATE_VERSIONS
+        .get_or_init(|| {
+            std::env::var(CRATE_VERSIONS_ENV)
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|package| package.split_once('@'))
+                .map(|(name, version)| (name.to_owned(), version.to_owned()))
+                .collect()
+        })
+        .iter()
+        .filter(move |(package, _)| package == name)
+        .map(|(_, version)| version.as_str())
+}
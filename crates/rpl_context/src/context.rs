@@ -143,6 +143,49 @@ impl<'pcx> PatCtxt<'pcx> {
     pub fn mk_raw_ptr_ty(self, ty: Ty<'pcx>, mutability: mir::Mutability) -> Ty<'pcx> {
         self.mk_ty(TyKind::RawPtr(ty, mutability))
     }
+    pub fn mk_fn_ptr_ty(self, inputs: &[Ty<'pcx>], output: Ty<'pcx>, safety: hir::Safety, abi: &str) -> Ty<'pcx> {
+        let abi = abi
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid ABI \"{abi}\", expect one of {:?}", rustc_abi::all_names()));
+        self.mk_ty(TyKind::FnPtr(pat::FnSig {
+            inputs: self.mk_slice(inputs),
+            output,
+            safety,
+            abi,
+        }))
+    }
+    pub fn mk_trait_bound(self, path_with_args: pat::PathWithArgs<'pcx>) -> pat::TraitBound<'pcx> {
+        pat::TraitBound {
+            trait_ref: pat::TraitRef::Path(path_with_args),
+            output: None,
+        }
+    }
+    /// Make a bound like `Fn(A, B) -> C`, from the trait path `Fn`, the inputs `A, B` and the
+    /// output `C`.
+    pub fn mk_fn_trait_bound(
+        self,
+        path_with_args: pat::PathWithArgs<'pcx>,
+        inputs: &[Ty<'pcx>],
+        output: Ty<'pcx>,
+    ) -> pat::TraitBound<'pcx> {
+        let args = self.mk_generic_args(&[self.mk_tuple_ty(inputs).into()]);
+        pat::TraitBound {
+            trait_ref: pat::TraitRef::Path(pat::PathWithArgs {
+                path: path_with_args.path,
+                args,
+            }),
+            output: Some(output),
+        }
+    }
+    pub fn mk_dyn_ty(self, bounds: &[pat::TraitBound<'pcx>], region: pat::RegionKind) -> Ty<'pcx> {
+        self.mk_ty(TyKind::Dynamic(self.mk_slice(bounds), region))
+    }
+    pub fn mk_impl_trait_ty(self, bounds: &[pat::TraitBound<'pcx>]) -> Ty<'pcx> {
+        self.mk_ty(TyKind::ImplTrait(self.mk_slice(bounds)))
+    }
+    pub fn mk_never_ty(self) -> Ty<'pcx> {
+        self.mk_ty(TyKind::Never)
+    }
     pub fn mk_fn(self, path_with_args: pat::PathWithArgs<'pcx>) -> Ty<'pcx> {
         self.mk_path_ty(path_with_args)
     }
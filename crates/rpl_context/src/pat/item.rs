@@ -1,10 +1,13 @@
 use derive_more::Debug;
+use rpl_config::VersionReq;
+use rustc_abi::ExternAbi;
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_hir::Safety;
 use rustc_middle::mir;
 use rustc_span::Symbol;
 use rustc_span::symbol::kw;
 
-use super::{MetaVars, MirPattern, Path, Ty};
+use super::{MetaVars, MirPattern, RegionKind, RegionVar, RegionVarIdx, TraitBound, TraitRef, Ty, TyVar, TyVarIdx};
 
 #[derive(Debug)]
 #[debug("${name}")]
@@ -30,16 +33,34 @@ pub struct Field<'pcx> {
     pub ty: Ty<'pcx>,
 }
 
+/// An `impl` pattern, such as `impl std::marker::Sync for $T where $T: !std::marker::Send {}`.
 pub struct Impl<'pcx> {
     pub meta: MetaVars<'pcx>,
-    #[expect(dead_code)]
-    ty: Ty<'pcx>,
-    #[expect(dead_code)]
-    trait_id: Option<Path<'pcx>>,
+    pub ty: Ty<'pcx>,
+    /// The trait implemented, or `None` for an inherent `impl`.
+    pub trait_ref: Option<TraitRef<'pcx>>,
+    pub predicates: Vec<WherePredicate<'pcx>>,
     #[expect(dead_code)]
     fns: FxHashMap<Symbol, Fn<'pcx>>,
 }
 
+/// A predicate in the `where` clause of a `fn` or `impl` pattern, such as `$T: !std::marker::Send`,
+/// which is evaluated in the typing environment of the matched item.
+#[derive(Clone, Copy)]
+pub struct WherePredicate<'pcx> {
+    pub ty_var: TyVarIdx,
+    pub bound: WhereBound<'pcx>,
+    /// Whether the bound must be implied by the item, or must not if `false`.
+    pub holds: bool,
+}
+
+#[derive(Clone, Copy)]
+pub enum WhereBound<'pcx> {
+    Trait(TraitBound<'pcx>),
+    /// A region bound, where only `'static` is supported.
+    Outlives(RegionKind),
+}
+
 #[derive(Default)]
 pub struct Fns<'pcx> {
     fns: FxHashMap<Symbol, Fn<'pcx>>,
@@ -53,6 +74,50 @@ pub struct Fn<'pcx> {
     pub params: Params<'pcx>,
     pub ret: Option<Ty<'pcx>>,
     pub body: Option<FnBody<'pcx>>,
+    pub applies_to: Option<AppliesTo>,
+    pub constraints: FnConstraints,
+    /// The `where` clause, checked when the signature is matched.
+    pub predicates: Vec<WherePredicate<'pcx>>,
+}
+
+/// The constraints on the function matched by a `fn` pattern, from its qualifiers such as
+/// `pub unsafe extern "C" fn` and `#[constraints(generic, !trait_method, attr(inline))]`.
+///
+/// `None` means no constraint.
+#[derive(Default)]
+pub struct FnConstraints {
+    /// Whether the function is public, from `pub` or `pub(crate)`.
+    pub public: Option<bool>,
+    pub constness: Option<bool>,
+    pub safety: Option<Safety>,
+    pub abi: Option<ExternAbi>,
+    pub generic: Option<bool>,
+    /// Whether the function is declared in a trait, or in an implementation of a trait.
+    pub trait_method: Option<bool>,
+    /// The attributes the function must have, or must not have if `false`, with the words their
+    /// arguments must contain, such as `never` in `#[inline(never)]`.
+    pub attrs: Vec<(Vec<Symbol>, Vec<Symbol>, bool)>,
+    /// The relations between the region variables in the signature, which hold, or must not
+    /// hold if `false`. Unlike the other constraints, they are checked when the signature is
+    /// matched.
+    pub regions: Vec<(RegionConstraint, bool)>,
+}
+
+/// A relation between the region variables of a `fn` pattern.
+#[derive(Clone, Copy)]
+pub enum RegionConstraint {
+    /// `outlives($'a, $'b)`, `$'a` outlives `$'b`.
+    Outlives(RegionVarIdx, RegionVarIdx),
+    /// `unbounded($'a)`, `$'a` is not `'static` and not tied to any input of the function.
+    Unbounded(RegionVarIdx),
+}
+
+/// The versions of a crate a pattern applies to, from
+/// `#[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]`.
+pub struct AppliesTo {
+    /// The package name of the crate, which may contain `-`.
+    pub krate: Symbol,
+    pub version: VersionReq,
 }
 
 #[derive(Default)]
@@ -158,6 +223,28 @@ impl<'pcx> Variant<'pcx> {
     }
 }
 
+impl<'pcx> Impl<'pcx> {
+    pub(crate) fn new(ty: Ty<'pcx>) -> Self {
+        Self {
+            meta: MetaVars::default(),
+            ty,
+            trait_ref: None,
+            predicates: Vec::new(),
+            fns: FxHashMap::default(),
+        }
+    }
+    pub fn set_self_ty(&mut self, ty: Ty<'pcx>) {
+        self.ty = ty;
+    }
+    pub fn set_trait(&mut self, trait_ref: TraitRef<'pcx>) {
+        self.trait_ref = Some(trait_ref);
+    }
+    pub fn add_where_predicate(&mut self, ty_var: TyVar, bound: WhereBound<'pcx>, holds: bool) {
+        let ty_var = ty_var.idx;
+        self.predicates.push(WherePredicate { ty_var, bound, holds });
+    }
+}
+
 impl<'pcx> Fns<'pcx> {
     pub fn get_fn_pat(&self, name: Symbol) -> Option<&Fn<'pcx>> {
         self.fn_pats.get(&name)
@@ -182,14 +269,64 @@ impl<'pcx> Fn<'pcx> {
             params: Params::default(),
             ret: None,
             body: None,
+            applies_to: None,
+            constraints: FnConstraints::default(),
+            predicates: Vec::new(),
         }
     }
+    pub fn add_where_predicate(&mut self, ty_var: TyVar, bound: WhereBound<'pcx>, holds: bool) {
+        let ty_var = ty_var.idx;
+        self.predicates.push(WherePredicate { ty_var, bound, holds });
+    }
     pub fn set_ret_ty(&mut self, ty: Ty<'pcx>) {
         self.ret = Some(ty);
     }
     pub fn set_body(&mut self, body: FnBody<'pcx>) {
         self.body = Some(body);
     }
+    /// Restrict this pattern to the versions of `krate` meeting `version`, which is checked to be a
+    /// valid version requirement when the pattern is expanded.
+    pub fn set_applies_to(&mut self, krate: Symbol, version: &str) {
+        let version = VersionReq::parse(version).unwrap_or_else(|err| panic!("{err}: `{version}`"));
+        self.applies_to = Some(AppliesTo { krate, version });
+    }
+    pub fn set_public(&mut self, public: bool) {
+        self.constraints.public = Some(public);
+    }
+    pub fn set_const(&mut self) {
+        self.constraints.constness = Some(true);
+    }
+    pub fn set_safety(&mut self, safety: Safety) {
+        self.constraints.safety = Some(safety);
+    }
+    /// Restrict this pattern to functions of the ABI named by `abi`, which is checked to be valid
+    /// when the pattern is expanded.
+    pub fn set_abi(&mut self, abi: &str) {
+        let abi = abi.parse().unwrap_or_else(|_| panic!("invalid ABI \"{abi}\""));
+        self.constraints.abi = Some(abi);
+    }
+    pub fn set_generic(&mut self, generic: bool) {
+        self.constraints.generic = Some(generic);
+    }
+    pub fn set_trait_method(&mut self, trait_method: bool) {
+        self.constraints.trait_method = Some(trait_method);
+    }
+    pub fn add_attr_constraint(&mut self, path: &[&str], present: bool) {
+        self.add_attr_constraint_with_args(path, &[], present);
+    }
+    pub fn add_attr_constraint_with_args(&mut self, path: &[&str], args: &[&str], present: bool) {
+        let path = path.iter().copied().map(Symbol::intern).collect();
+        let args = args.iter().copied().map(Symbol::intern).collect();
+        self.constraints.attrs.push((path, args, present));
+    }
+    pub fn add_outlives_constraint(&mut self, longer: RegionVar, shorter: RegionVar, holds: bool) {
+        let constraint = RegionConstraint::Outlives(longer.idx, shorter.idx);
+        self.constraints.regions.push((constraint, holds));
+    }
+    pub fn add_unbounded_constraint(&mut self, region: RegionVar, holds: bool) {
+        let constraint = RegionConstraint::Unbounded(region.idx);
+        self.constraints.regions.push((constraint, holds));
+    }
     // FIXME: remove this when all kinds of patterns are implemented
     pub fn expect_mir_body(&self) -> &'pcx MirPattern<'pcx> {
         match self.body {
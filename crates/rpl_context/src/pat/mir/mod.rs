@@ -3,7 +3,7 @@ use std::fmt::{self, Debug};
 use std::ops::Index;
 
 use either::Either;
-use rustc_abi::FieldIdx;
+use rustc_abi::{FieldIdx, VariantIdx};
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_hir::Target;
 use rustc_index::IndexVec;
@@ -98,16 +98,40 @@ impl<'pcx> BasicBlockData<'pcx> {
     fn set_goto(&mut self, block: BasicBlock) {
         match &mut self.terminator {
             None => self.terminator = Some(TerminatorKind::Goto(block)),
-            Some(TerminatorKind::Call { target, .. } | TerminatorKind::Drop { target, .. }) => *target = block,
+            Some(
+                TerminatorKind::Call { target, .. }
+                | TerminatorKind::Drop { target, .. }
+                | TerminatorKind::Assert { target, .. }
+                | TerminatorKind::InlineAsm { target, .. }
+                | TerminatorKind::Yield { resume: target, .. },
+            ) => *target = block,
             // Here the `goto ?bb` terminator comes from `break` or `continue`,
             // plus the `return` terminator, are all skipped because thay are
             // abnormal control flows.
-            Some(TerminatorKind::Goto(_) | TerminatorKind::Return) => {},
+            Some(
+                TerminatorKind::Goto(_)
+                | TerminatorKind::Return
+                | TerminatorKind::TailCall { .. }
+                | TerminatorKind::CoroutineDrop
+                | TerminatorKind::UnwindResume
+                | TerminatorKind::Unreachable,
+            ) => {},
             Some(terminator @ (TerminatorKind::SwitchInt { .. } | TerminatorKind::PatEnd)) => {
                 panic!("expect `{:?}`, but found `{terminator:?}`", TerminatorKind::Goto(block));
             },
         }
     }
+    fn set_unwind(&mut self, block: BasicBlock) {
+        match &mut self.terminator {
+            Some(
+                TerminatorKind::Call { unwind, .. }
+                | TerminatorKind::Drop { unwind, .. }
+                | TerminatorKind::Assert { unwind, .. },
+            ) => *unwind = Some(block),
+            None => panic!("unwinding terminator not set"),
+            Some(terminator) => panic!("expect `call`, `drop` or `assert` terminator, but found `{terminator:?}`"),
+        }
+    }
     fn set_switch_targets(&mut self, switch_targets: SwitchTargets) {
         match &mut self.terminator {
             Some(TerminatorKind::SwitchInt { targets, .. }) => *targets = switch_targets,
@@ -312,6 +336,19 @@ impl<'pcx> PlaceTy<'pcx> {
 
 pub enum StatementKind<'pcx> {
     Assign(Place<'pcx>, Rvalue<'pcx>),
+    SetDiscriminant(Place<'pcx>, VariantIdx),
+    Deinit(Place<'pcx>),
+    Intrinsic(NonDivergingIntrinsic<'pcx>),
+}
+
+/// See [`mir::NonDivergingIntrinsic`].
+pub enum NonDivergingIntrinsic<'pcx> {
+    Assume(Operand<'pcx>),
+    CopyNonOverlapping {
+        src: Operand<'pcx>,
+        dst: Operand<'pcx>,
+        count: Operand<'pcx>,
+    },
 }
 
 #[derive(Default)]
@@ -320,6 +357,36 @@ pub struct SwitchTargets {
     pub otherwise: Option<BasicBlock>,
 }
 
+/// The kind of the message of an [`TerminatorKind::Assert`], see [`mir::AssertKind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssertKind {
+    BoundsCheck,
+    Overflow,
+    OverflowNeg,
+    DivisionByZero,
+    RemainderByZero,
+    ResumedAfterReturn,
+    ResumedAfterPanic,
+    MisalignedPointerDereference,
+    NullPointerDereference,
+}
+
+impl AssertKind {
+    pub fn matches<O>(self, msg: &mir::AssertKind<O>) -> bool {
+        self == match msg {
+            mir::AssertKind::BoundsCheck { .. } => Self::BoundsCheck,
+            mir::AssertKind::Overflow(..) => Self::Overflow,
+            mir::AssertKind::OverflowNeg(_) => Self::OverflowNeg,
+            mir::AssertKind::DivisionByZero(_) => Self::DivisionByZero,
+            mir::AssertKind::RemainderByZero(_) => Self::RemainderByZero,
+            mir::AssertKind::ResumedAfterReturn(_) => Self::ResumedAfterReturn,
+            mir::AssertKind::ResumedAfterPanic(_) => Self::ResumedAfterPanic,
+            mir::AssertKind::MisalignedPointerDereference { .. } => Self::MisalignedPointerDereference,
+            mir::AssertKind::NullPointerDereference => Self::NullPointerDereference,
+        }
+    }
+}
+
 pub enum TerminatorKind<'pcx> {
     SwitchInt {
         operand: Operand<'pcx>,
@@ -331,11 +398,38 @@ pub enum TerminatorKind<'pcx> {
         args: List<Operand<'pcx>>,
         destination: Option<Place<'pcx>>,
         target: BasicBlock,
+        unwind: Option<BasicBlock>,
+    },
+    TailCall {
+        func: Operand<'pcx>,
+        args: List<Operand<'pcx>>,
     },
     Drop {
         place: Place<'pcx>,
         target: BasicBlock,
+        unwind: Option<BasicBlock>,
     },
+    /// `assert(!move $cond, BoundsCheck)`, where `msg` is `None` for any kind of messages.
+    Assert {
+        cond: Operand<'pcx>,
+        expected: bool,
+        msg: Option<AssertKind>,
+        target: BasicBlock,
+        unwind: Option<BasicBlock>,
+    },
+    /// `asm!("nop")`, where `template` is `None` for any template.
+    InlineAsm {
+        template: Option<Symbol>,
+        target: BasicBlock,
+    },
+    /// A suspension point of a coroutine, i.e. a `yield` or an `.await`.
+    Yield {
+        value: Operand<'pcx>,
+        resume: BasicBlock,
+    },
+    CoroutineDrop,
+    UnwindResume,
+    Unreachable,
     Return,
     /// Pattern ends here
     PatEnd,
@@ -350,14 +444,37 @@ pub enum Rvalue<'pcx> {
     Len(Place<'pcx>),
     Cast(mir::CastKind, Operand<'pcx>, Ty<'pcx>),
     BinaryOp(mir::BinOp, Box<[Operand<'pcx>; 2]>),
+    /// `Arith(copy $a, copy $b)`, any of the arithmetic operations, see [`is_arith_op`].
+    ArithOp(Box<[Operand<'pcx>; 2]>),
     NullaryOp(mir::NullOp<'pcx>, Ty<'pcx>),
     UnaryOp(mir::UnOp, Operand<'pcx>),
     Discriminant(Place<'pcx>),
     Aggregate(AggKind<'pcx>, List<Operand<'pcx>>),
+    /// `None` for any `#[thread_local]` static.
+    ThreadLocalRef(Option<PathWithArgs<'pcx>>),
     ShallowInitBox(Operand<'pcx>, Ty<'pcx>),
     CopyForDeref(Place<'pcx>),
 }
 
+/// Whether `op` is matched by an [`Rvalue::ArithOp`], i.e. `Add`, `Sub`, `Mul`, `Div` or `Rem`,
+/// including the unchecked ones and the ones with overflow checks.
+pub fn is_arith_op(op: mir::BinOp) -> bool {
+    use mir::BinOp::*;
+    matches!(
+        op,
+        Add | AddUnchecked
+            | AddWithOverflow
+            | Sub
+            | SubUnchecked
+            | SubWithOverflow
+            | Mul
+            | MulUnchecked
+            | MulWithOverflow
+            | Div
+            | Rem
+    )
+}
+
 //FIXME: Add a new variant for `Copy` or `Move` a value that is a `Copy` type.
 #[derive(Clone)]
 pub enum Operand<'pcx> {
@@ -396,6 +513,10 @@ pub enum AggKind<'pcx> {
     Tuple,
     Adt(PathWithArgs<'pcx>, AggAdtKind),
     RawPtr(Ty<'pcx>, mir::Mutability),
+    /// Any closure, with its captured values as the operands.
+    Closure,
+    /// Any coroutine, with its upvars as the operands.
+    Coroutine,
 }
 
 #[derive(Clone, Copy)]
@@ -507,6 +628,18 @@ impl<'pcx> MirPatternBuilder<'pcx> {
     pub fn mk_assign(&mut self, place: impl Into<Place<'pcx>>, rvalue: Rvalue<'pcx>) -> Location {
         self.mk_statement(StatementKind::Assign(place.into(), rvalue))
     }
+    pub fn mk_set_discriminant(&mut self, place: impl Into<Place<'pcx>>, variant_index: u32) -> Location {
+        self.mk_statement(StatementKind::SetDiscriminant(
+            place.into(),
+            VariantIdx::from_u32(variant_index),
+        ))
+    }
+    pub fn mk_deinit(&mut self, place: impl Into<Place<'pcx>>) -> Location {
+        self.mk_statement(StatementKind::Deinit(place.into()))
+    }
+    pub fn mk_intrinsic(&mut self, intrinsic: NonDivergingIntrinsic<'pcx>) -> Location {
+        self.mk_statement(StatementKind::Intrinsic(intrinsic))
+    }
     pub fn mk_fn_call(
         &mut self,
         func: Operand<'pcx>,
@@ -533,12 +666,66 @@ impl<'pcx> MirPatternBuilder<'pcx> {
             args,
             destination,
             target,
+            unwind: None,
         })
     }
+    pub fn mk_tail_call(&mut self, func: Operand<'pcx>, args: List<Operand<'pcx>>) -> Location {
+        self.new_block_if_terminated();
+        self.set_terminator(TerminatorKind::TailCall { func, args })
+    }
     pub fn mk_drop(&mut self, place: impl Into<Place<'pcx>>) -> Location {
         let target = self.next_block();
         let place = place.into();
-        self.set_terminator(TerminatorKind::Drop { place, target })
+        self.set_terminator(TerminatorKind::Drop {
+            place,
+            target,
+            unwind: None,
+        })
+    }
+    pub fn mk_assert(&mut self, cond: Operand<'pcx>, expected: bool, msg: Option<AssertKind>) -> Location {
+        let target = self.next_block();
+        self.set_terminator(TerminatorKind::Assert {
+            cond,
+            expected,
+            msg,
+            target,
+            unwind: None,
+        })
+    }
+    pub fn mk_inline_asm(&mut self, template: Option<&str>) -> Location {
+        let target = self.next_block();
+        let template = template.map(Symbol::intern);
+        self.set_terminator(TerminatorKind::InlineAsm { template, target })
+    }
+    /// Build the unwind path of the last `call`, `drop` or `assert` terminator, which ends with
+    /// `unwind_resume` or the end of the pattern.
+    pub fn mk_unwind(&mut self, f: impl FnOnce(&mut MirPatternBuilder<'pcx>)) {
+        let block = self.current;
+        // The target of the terminator is the next block, see `next_block`.
+        let target = self.pattern.basic_blocks.push(BasicBlockData::default());
+        let unwind = self.pattern.basic_blocks.push(BasicBlockData::default());
+        self.pattern.basic_blocks[block].set_unwind(unwind);
+        self.current = unwind;
+        f(self);
+        self.new_block_if_terminated();
+        self.pattern.basic_blocks[self.current].set_terminator(TerminatorKind::PatEnd);
+        self.current = target;
+    }
+    pub fn mk_unwind_resume(&mut self) -> Location {
+        self.new_block_if_terminated();
+        self.set_terminator(TerminatorKind::UnwindResume)
+    }
+    pub fn mk_unreachable(&mut self) -> Location {
+        self.new_block_if_terminated();
+        self.set_terminator(TerminatorKind::Unreachable)
+    }
+    pub fn mk_yield(&mut self, value: Operand<'pcx>) -> Location {
+        let resume = self.next_block();
+        self.set_terminator(TerminatorKind::Yield { value, resume })
+    }
+    pub fn mk_coroutine_drop(&mut self) -> Location {
+        self.new_block_if_terminated();
+        self.set_terminator(TerminatorKind::CoroutineDrop)
     }
     pub fn mk_switch_int(&mut self, operand: Operand<'pcx>, f: impl FnOnce(SwitchIntBuilder<'_, 'pcx>)) -> Location {
         self.new_block_if_terminated();
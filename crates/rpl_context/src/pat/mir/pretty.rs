@@ -58,6 +58,15 @@ impl fmt::Debug for StatementKind<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Assign(place, rvalue) => write!(f, "{place:?} = {rvalue:?}"),
+            Self::SetDiscriminant(place, variant_index) => write!(f, "discriminant({place:?}) = {variant_index:?}"),
+            Self::Deinit(place) => write!(f, "Deinit({place:?})"),
+            Self::Intrinsic(NonDivergingIntrinsic::Assume(cond)) => write!(f, "assume({cond:?})"),
+            Self::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping { src, dst, count }) => {
+                write!(
+                    f,
+                    "copy_nonoverlapping(src = {src:?}, dst = {dst:?}, count = {count:?})"
+                )
+            },
         }
     }
 }
@@ -79,6 +88,13 @@ fn fmt_list<T>(
     f.write_str(end)
 }
 
+fn fmt_unwind(f: &mut fmt::Formatter<'_>, unwind: &Option<BasicBlock>) -> fmt::Result {
+    match unwind {
+        Some(unwind) => write!(f, ", unwind {unwind:?}"),
+        None => Ok(()),
+    }
+}
+
 impl fmt::Debug for TerminatorKind<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -87,6 +103,7 @@ impl fmt::Debug for TerminatorKind<'_> {
                 args,
                 destination,
                 target,
+                unwind,
             } => {
                 if let Some(destination) = destination {
                     write!(f, "{destination:?} = ")?
@@ -94,11 +111,41 @@ impl fmt::Debug for TerminatorKind<'_> {
                 func.fmt_fn_operand(f)?;
                 write!(f, "(")?;
                 fmt_list(f, args, ")", fmt::Debug::fmt)?;
-                write!(f, " -> {target:?}")
+                write!(f, " -> {target:?}")?;
+                fmt_unwind(f, unwind)
+            },
+            TerminatorKind::TailCall { func, args } => {
+                write!(f, "become ")?;
+                func.fmt_fn_operand(f)?;
+                write!(f, "(")?;
+                fmt_list(f, args, ")", fmt::Debug::fmt)
+            },
+            TerminatorKind::Drop { place, target, unwind } => {
+                write!(f, "drop({place:?}) -> {target:?}")?;
+                fmt_unwind(f, unwind)
             },
-            TerminatorKind::Drop { place, target } => {
-                write!(f, "drop({place:?}) -> {target:?}")
+            TerminatorKind::Assert {
+                cond,
+                expected,
+                msg,
+                target,
+                unwind,
+            } => {
+                let not = if *expected { "" } else { "!" };
+                match msg {
+                    Some(msg) => write!(f, "assert({not}{cond:?}, {msg:?}) -> {target:?}")?,
+                    None => write!(f, "assert({not}{cond:?}, ..) -> {target:?}")?,
+                }
+                fmt_unwind(f, unwind)
+            },
+            TerminatorKind::InlineAsm { template, target } => match template {
+                Some(template) => write!(f, "asm!({template:?}) -> {target:?}"),
+                None => write!(f, "asm!(..) -> {target:?}"),
             },
+            TerminatorKind::Yield { value, resume } => write!(f, "yield({value:?}) -> {resume:?}"),
+            TerminatorKind::CoroutineDrop => f.write_str("coroutine_drop"),
+            TerminatorKind::UnwindResume => f.write_str("unwind_resume"),
+            TerminatorKind::Unreachable => f.write_str("unreachable"),
             TerminatorKind::SwitchInt { operand, targets } => write!(f, "switchInt({operand:?}) -> {targets:?}"),
             TerminatorKind::Goto(basic_block) => write!(f, "goto {basic_block:?}"),
             TerminatorKind::Return => f.write_str("return"),
@@ -131,10 +178,13 @@ impl fmt::Debug for Rvalue<'_> {
             Self::Len(place) => f.debug_tuple("Len").field(place).finish(),
             Self::Cast(cast_kind, operand, ty) => write!(f, "{operand:?} as {ty:?} ({cast_kind:?})"),
             Self::BinaryOp(op, box [lhs, rhs]) => write!(f, "{op:?}({lhs:?}, {rhs:?})"),
+            Self::ArithOp(box [lhs, rhs]) => write!(f, "Arith({lhs:?}, {rhs:?})"),
             Self::NullaryOp(op, ty) => write!(f, "{op:?}({ty:?})"),
             Self::UnaryOp(op, operand) => write!(f, "{op:?}({operand:?})"),
             Self::Discriminant(place) => f.debug_tuple("discriminant").field(place).finish(),
             Self::Aggregate(agg_kind, operands) => format_aggregate(agg_kind, operands, f),
+            Self::ThreadLocalRef(Some(path_with_args)) => write!(f, "ThreadLocalRef({path_with_args:?})"),
+            Self::ThreadLocalRef(None) => write!(f, "ThreadLocalRef(_)"),
             Self::ShallowInitBox(operand, ty) => write!(f, "Box< {ty:?} >({operand:?})"),
             Self::CopyForDeref(place) => write!(f, "&(*{place:?})"),
         }
@@ -179,6 +229,14 @@ fn format_aggregate<'pcx>(
             write!(f, "*{} {ty:?} from (", mutability.ptr_str())?;
             fmt_list(f, operands, ")", fmt::Debug::fmt)
         },
+        AggKind::Closure => {
+            f.write_str("closure(")?;
+            fmt_list(f, operands, ")", fmt::Debug::fmt)
+        },
+        AggKind::Coroutine => {
+            f.write_str("coroutine(")?;
+            fmt_list(f, operands, ")", fmt::Debug::fmt)
+        },
     }
 }
 
@@ -171,7 +171,7 @@ pub trait PatternVisitor<'pcx>: Sized {
                 self.visit_operand(operand, location);
                 self.visit_ty(ty);
             },
-            Rvalue::BinaryOp(_op, box [lhs, rhs]) => {
+            Rvalue::BinaryOp(_, box [lhs, rhs]) | Rvalue::ArithOp(box [lhs, rhs]) => {
                 self.visit_operand(lhs, location);
                 self.visit_operand(rhs, location);
             },
@@ -179,6 +179,7 @@ pub trait PatternVisitor<'pcx>: Sized {
             Rvalue::Aggregate(_agg_kind, operands) => operands
                 .iter()
                 .for_each(|operand| self.visit_operand(operand, location)),
+            Rvalue::ThreadLocalRef(_) => {},
         }
     }
     fn super_operand(&mut self, operand: &Operand<'pcx>, location: Location) {
@@ -207,6 +208,24 @@ pub trait PatternVisitor<'pcx>: Sized {
                 self.visit_place(place, store, location);
                 self.visit_rvalue(rvalue, location);
             },
+            StatementKind::SetDiscriminant(place, _) => self.visit_place(
+                place,
+                PlaceContext::MutatingUse(MutatingUseContext::SetDiscriminant),
+                location,
+            ),
+            StatementKind::Deinit(place) => {
+                self.visit_place(place, PlaceContext::MutatingUse(MutatingUseContext::Deinit), location)
+            },
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(ref cond)) => self.visit_operand(cond, location),
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping {
+                ref src,
+                ref dst,
+                ref count,
+            }) => {
+                self.visit_operand(src, location);
+                self.visit_operand(dst, location);
+                self.visit_operand(count, location);
+            },
         }
     }
     fn super_terminator(&mut self, terminator: &TerminatorKind<'pcx>, location: Location) {
@@ -216,6 +235,7 @@ pub trait PatternVisitor<'pcx>: Sized {
                 ref args,
                 destination,
                 target: _,
+                unwind: _,
             } => {
                 self.visit_operand(func, location);
                 for arg in args {
@@ -229,9 +249,16 @@ pub trait PatternVisitor<'pcx>: Sized {
                     );
                 }
             },
-            TerminatorKind::Drop { place, target: _ } => {
+            TerminatorKind::TailCall { ref func, ref args } => {
+                self.visit_operand(func, location);
+                for arg in args {
+                    self.visit_operand(arg, location);
+                }
+            },
+            TerminatorKind::Drop { place, .. } => {
                 self.visit_place(place, PlaceContext::MutatingUse(MutatingUseContext::Drop), location)
             },
+            TerminatorKind::Assert { ref cond, .. } => self.visit_operand(cond, location),
             TerminatorKind::SwitchInt {
                 ref operand,
                 ref targets,
@@ -239,7 +266,14 @@ pub trait PatternVisitor<'pcx>: Sized {
                 self.visit_operand(operand, location);
                 self.visit_switch_targets(targets, location);
             },
-            TerminatorKind::Goto(_) | TerminatorKind::Return | TerminatorKind::PatEnd => {},
+            TerminatorKind::Yield { ref value, resume: _ } => self.visit_operand(value, location),
+            TerminatorKind::Goto(_)
+            | TerminatorKind::InlineAsm { .. }
+            | TerminatorKind::CoroutineDrop
+            | TerminatorKind::UnwindResume
+            | TerminatorKind::Unreachable
+            | TerminatorKind::Return
+            | TerminatorKind::PatEnd => {},
         }
     }
 }
@@ -270,6 +304,24 @@ impl<'pcx> PatternSuperVisitable<'pcx> for Ty<'pcx> {
                 vis.visit_ty(ty);
             },
             &TyKind::RawPtr(ty, _) => vis.visit_ty(ty),
+            TyKind::FnPtr(sig) => {
+                sig.inputs.iter().for_each(|&ty| vis.visit_ty(ty));
+                vis.visit_ty(sig.output);
+            },
+            TyKind::Dynamic(bounds, _) | TyKind::ImplTrait(bounds) => {
+                for bound in bounds.iter() {
+                    match bound.trait_ref {
+                        TraitRef::Path(PathWithArgs { ref path, args }) => {
+                            vis.visit_path(path);
+                            vis.visit_generic_args(args);
+                        },
+                        TraitRef::Def(_, args) => vis.visit_generic_args(args),
+                    }
+                    if let Some(output) = bound.output {
+                        vis.visit_ty(output);
+                    }
+                }
+            },
             &TyKind::Path(PathWithArgs { ref path, args }) => {
                 vis.visit_path(path);
                 vis.visit_generic_args(args);
@@ -285,6 +337,7 @@ impl<'pcx> PatternSuperVisitable<'pcx> for Ty<'pcx> {
             | TyKind::Str
             | TyKind::Bool
             | TyKind::Char
+            | TyKind::Never
             | TyKind::Any => {},
             &TyKind::AdtPat(adt_pat) => vis.visit_adt_pat(adt_pat),
         }
@@ -18,14 +18,14 @@ pub struct MetaVars<'pcx> {
     pub ty_vars: IndexVec<TyVarIdx, TyVar>,
     pub place_vars: IndexVec<PlaceVarIdx, PlaceVar<'pcx>>,
     pub const_vars: IndexVec<ConstVarIdx, ConstVar<'pcx>>,
+    pub region_vars: IndexVec<RegionVarIdx, RegionVar>,
 }
 
 pub struct Pattern<'pcx> {
     pub pcx: PatCtxt<'pcx>,
     pub adts: FxHashMap<Symbol, Adt<'pcx>>,
     pub fns: Fns<'pcx>,
-    #[expect(dead_code)]
-    impls: Vec<Impl<'pcx>>,
+    pub impls: Vec<Impl<'pcx>>,
 }
 
 impl<'pcx> MetaVars<'pcx> {
@@ -47,6 +47,12 @@ impl<'pcx> MetaVars<'pcx> {
         self.const_vars.push(const_var);
         const_var
     }
+    pub fn new_region_var(&mut self) -> RegionVar {
+        let idx = self.region_vars.next_index();
+        let region_var = RegionVar { idx };
+        self.region_vars.push(region_var);
+        region_var
+    }
 }
 
 impl<'pcx> Pattern<'pcx> {
@@ -65,6 +71,11 @@ impl<'pcx> Pattern<'pcx> {
     pub fn new_enum(&mut self, name: Symbol) -> &mut Adt<'pcx> {
         self.adts.entry(name).or_insert_with(|| Adt::new_enum(name))
     }
+    /// Add an `impl` pattern, whose self type is `_` until set by [`Impl::set_self_ty`].
+    pub fn new_impl(&mut self) -> &mut Impl<'pcx> {
+        self.impls.push(Impl::new(self.pcx.mk_any_ty()));
+        self.impls.last_mut().unwrap()
+    }
     pub fn get_adt(&self, name: Symbol) -> Option<&Adt<'pcx>> {
         self.adts.get(&name)
     }
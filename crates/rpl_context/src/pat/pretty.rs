@@ -75,6 +75,20 @@ impl fmt::Debug for TyKind<'_> {
             Self::Ref(region, ty, mir::Mutability::Not) => write!(f, "&{region} {ty:?}"),
             Self::Ref(region, ty, mir::Mutability::Mut) => write!(f, "&{region}mut {ty:?}"),
             Self::RawPtr(ty, mutability) => write!(f, "*{} {ty:?}", mutability.ptr_str()),
+            Self::FnPtr(sig) => sig.fmt(f),
+            Self::Dynamic(bounds, region) => {
+                f.write_str("dyn ")?;
+                fmt_bounds(bounds, f)?;
+                match region {
+                    RegionKind::ReAny => Ok(()),
+                    RegionKind::ReStatic => f.write_str(" + 'static"),
+                    RegionKind::ReVar(region_var) => write!(f, " + {region_var:?}"),
+                }
+            },
+            Self::ImplTrait(bounds) => {
+                f.write_str("impl ")?;
+                fmt_bounds(bounds, f)
+            },
             Self::Path(path_with_args) => path_with_args.fmt_as_ty(f),
             Self::Def(def_id, args) => write!(f, "{def_id:?}{args:?}"),
             Self::Uint(uint) => uint.fmt(f),
@@ -83,12 +97,65 @@ impl fmt::Debug for TyKind<'_> {
             Self::Bool => f.write_str("bool"),
             Self::Str => f.write_str("str"),
             Self::Char => f.write_str("char"),
+            Self::Never => f.write_str("!"),
             Self::AdtPat(adt_var) => write!(f, "${adt_var}"),
             Self::Any => f.write_str("_"),
         }
     }
 }
 
+fn fmt_bounds(bounds: &[TraitBound<'_>], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, bound) in bounds.iter().enumerate() {
+        if i > 0 {
+            f.write_str(" + ")?;
+        }
+        fmt::Debug::fmt(bound, f)?;
+    }
+    Ok(())
+}
+
+impl fmt::Debug for FnSig<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let FnSig {
+            inputs,
+            output,
+            safety,
+            abi,
+        } = self;
+        f.write_str(safety.prefix_str())?;
+        if *abi != rustc_abi::ExternAbi::Rust {
+            write!(f, "extern \"{}\" ", abi.as_str())?;
+        }
+        f.write_str("fn(")?;
+        for (i, input) in inputs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            input.fmt(f)?;
+        }
+        write!(f, ") -> {output:?}")
+    }
+}
+
+impl fmt::Debug for TraitRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path_with_args) => path_with_args.fmt_as_ty(f),
+            Self::Def(def_id, args) => write!(f, "{def_id:?}{args:?}"),
+        }
+    }
+}
+
+impl fmt::Debug for TraitBound<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.trait_ref.fmt(f)?;
+        if let Some(output) = self.output {
+            write!(f, " -> {output:?}")?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Debug for GenericArgsRef<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.0 {
@@ -124,6 +191,7 @@ impl fmt::Debug for RegionKind {
         match self {
             Self::ReStatic => f.write_str("'static"),
             Self::ReAny => f.write_str("'_"),
+            Self::ReVar(region_var) => region_var.fmt(f),
         }
     }
 }
@@ -133,6 +201,7 @@ impl fmt::Display for RegionKind {
         match self {
             Self::ReStatic => f.write_str("'static"),
             Self::ReAny => Ok(()),
+            Self::ReVar(region_var) => write!(f, "{region_var:?}"),
         }
     }
 }
@@ -160,6 +229,12 @@ impl fmt::Display for TyVar {
     }
 }
 
+impl fmt::Debug for RegionVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{:?}", self.idx)
+    }
+}
+
 impl fmt::Debug for ConstVar<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({:?}: {:?})", self.idx, self.ty)
@@ -1,8 +1,9 @@
 use std::fmt::Debug;
 
+use rustc_abi::ExternAbi;
 use rustc_data_structures::packed::Pu128;
 use rustc_hir::def_id::DefId;
-use rustc_hir::{LangItem, PrimTy};
+use rustc_hir::{LangItem, PrimTy, Safety};
 use rustc_middle::mir;
 use rustc_middle::ty::{self, TyCtxt};
 use rustc_span::Symbol;
@@ -27,6 +28,11 @@ rustc_index::newtype_index! {
     pub struct PlaceVarIdx {}
 }
 
+rustc_index::newtype_index! {
+    #[debug_format = "?R{}"]
+    pub struct RegionVarIdx {}
+}
+
 // FIXME: Use interning for the types
 #[derive(Clone, Copy)]
 #[rustc_pass_by_value]
@@ -37,8 +43,13 @@ impl<'pcx> Ty<'pcx> {
         self.0
     }
     //FIXME: this may breaks uniqueness of `Ty`
-    pub fn from_ty_lossy(pcx: PatCtxt<'pcx>, ty: ty::Ty<'_>, args: GenericArgsRef<'pcx>) -> Option<Self> {
-        Some(pcx.mk_ty(TyKind::from_ty_lossy(pcx, ty, args)?))
+    pub fn from_ty_lossy<'tcx>(
+        pcx: PatCtxt<'pcx>,
+        tcx: TyCtxt<'tcx>,
+        ty: ty::Ty<'tcx>,
+        args: GenericArgsRef<'pcx>,
+    ) -> Option<Self> {
+        Some(pcx.mk_ty(TyKind::from_ty_lossy(pcx, tcx, ty, args)?))
     }
     pub fn from_prim_ty(pcx: PatCtxt<'pcx>, ty: PrimTy) -> Self {
         pcx.mk_ty(TyKind::from(ty))
@@ -52,6 +63,42 @@ impl<'pcx> Ty<'pcx> {
 pub enum RegionKind {
     ReAny,
     ReStatic,
+    /// A region variable `$'a`, matching the same region wherever it appears.
+    ReVar(RegionVar),
+}
+
+impl RegionKind {
+    pub fn from_region_lossy(region: ty::Region<'_>) -> Self {
+        if region.is_static() {
+            Self::ReStatic
+        } else {
+            Self::ReAny
+        }
+    }
+}
+
+/// The signature of a function pointer, such as `unsafe extern "C" fn(*mut c_void) -> i32`.
+#[derive(Clone, Copy)]
+pub struct FnSig<'pcx> {
+    pub inputs: &'pcx [Ty<'pcx>],
+    pub output: Ty<'pcx>,
+    pub safety: Safety,
+    pub abi: ExternAbi,
+}
+
+#[derive(Clone, Copy)]
+pub enum TraitRef<'pcx> {
+    Path(PathWithArgs<'pcx>),
+    /// A trait resolved from a real type, with its generic arguments other than `Self`.
+    Def(DefId, GenericArgsRef<'pcx>),
+}
+
+/// A trait bound in `dyn Trait` or `impl Trait`.
+#[derive(Clone, Copy)]
+pub struct TraitBound<'pcx> {
+    pub trait_ref: TraitRef<'pcx>,
+    /// The `C` in `Fn(A, B) -> C`, where `(A, B)` is the only generic argument of `trait_ref`.
+    pub output: Option<Ty<'pcx>>,
 }
 
 #[derive(Clone, Copy)]
@@ -63,7 +110,13 @@ pub enum TyKind<'pcx> {
     Tuple(&'pcx [Ty<'pcx>]),
     Ref(RegionKind, Ty<'pcx>, mir::Mutability),
     RawPtr(Ty<'pcx>, mir::Mutability),
+    FnPtr(FnSig<'pcx>),
+    /// `dyn Trait1 + Trait2 + 'r`
+    Dynamic(&'pcx [TraitBound<'pcx>], RegionKind),
+    /// `impl Trait1 + Trait2`, matching any type that implements all the traits.
+    ImplTrait(&'pcx [TraitBound<'pcx>]),
     Path(PathWithArgs<'pcx>),
+    /// An ADT, a foreign type, a function item, a closure or a coroutine.
     Def(DefId, GenericArgsRef<'pcx>),
     Uint(ty::UintTy),
     Int(ty::IntTy),
@@ -71,6 +124,7 @@ pub enum TyKind<'pcx> {
     Bool,
     Str,
     Char,
+    Never,
     Any,
 }
 
@@ -88,44 +142,90 @@ impl From<PrimTy> for TyKind<'_> {
 }
 
 impl<'pcx> TyKind<'pcx> {
-    //FIXME: this is incomplete
     //FIXME: add a new `TyKind` for resolved types, just like `rustc_middle::ty::TyKind`
     //FIXME: this may breaks uniqueness of `Ty`
-    pub fn from_ty_lossy(pcx: PatCtxt<'pcx>, ty: ty::Ty<'_>, args: GenericArgsRef<'pcx>) -> Option<Self> {
+    pub fn from_ty_lossy<'tcx>(
+        pcx: PatCtxt<'pcx>,
+        tcx: TyCtxt<'tcx>,
+        ty: ty::Ty<'tcx>,
+        args: GenericArgsRef<'pcx>,
+    ) -> Option<Self> {
         fn require_empty(args: GenericArgsRef<'_>) -> Option<GenericArgsRef<'_>> {
             if args.is_empty() { Some(args) } else { None }
         }
-        Some(match ty.kind() {
+        let from_ty = |ty| Ty::from_ty_lossy(pcx, tcx, ty, require_empty(args)?);
+        Some(match *ty.kind() {
             ty::TyKind::Bool => Self::Bool,
             ty::TyKind::Char => Self::Char,
-            ty::TyKind::Int(int_ty) => Self::Int(*int_ty),
-            ty::TyKind::Uint(uint_ty) => Self::Uint(*uint_ty),
-            ty::TyKind::Float(float_ty) => Self::Float(*float_ty),
+            ty::TyKind::Int(int_ty) => Self::Int(int_ty),
+            ty::TyKind::Uint(uint_ty) => Self::Uint(uint_ty),
+            ty::TyKind::Float(float_ty) => Self::Float(float_ty),
             ty::TyKind::Adt(def, _) => Self::Def(def.did(), args),
-            ty::TyKind::Foreign(def_id) => Self::Def(*def_id, args),
+            ty::TyKind::Foreign(def_id) => Self::Def(def_id, args),
             ty::TyKind::Str => Self::Str,
-            ty::TyKind::Array(_, _) => None?, //FIXME
-            ty::TyKind::Pat(_, _) => None?,   //FIXME
-            ty::TyKind::Slice(ty) => Self::Slice(pcx.mk_ty(Self::from_ty_lossy(pcx, *ty, require_empty(args)?)?)),
-            ty::TyKind::RawPtr(ty, mutability) => Self::RawPtr(
-                pcx.mk_ty(Self::from_ty_lossy(pcx, *ty, require_empty(args)?)?),
-                *mutability,
-            ),
-            ty::TyKind::Ref(_, _, _) => None?,           //FIXME
-            ty::TyKind::FnDef(_, _) => None?,            //FIXME
-            ty::TyKind::FnPtr(_, _) => None?,            //FIXME
-            ty::TyKind::Dynamic(_, _, _) => None?,       //FIXME
-            ty::TyKind::Closure(_, _) => None?,          //FIXME
-            ty::TyKind::CoroutineClosure(_, _) => None?, //FIXME
-            ty::TyKind::Coroutine(_, _) => None?,        //FIXME
-            ty::TyKind::CoroutineWitness(_, _) => None?, //FIXME
-            ty::TyKind::Never => None?,                  //FIXME
-            ty::TyKind::Tuple(_) => None?,               //FIXME
-            ty::TyKind::Alias(_, _) => None?,            //FIXME
-            ty::TyKind::Param(_) => None?,               //FIXME
-            ty::TyKind::Bound(_, _) => None?,            //FIXME
-            ty::TyKind::Placeholder(_) => None?,         //FIXME
-            ty::TyKind::Infer(_) => None?,               //FIXME
+            ty::TyKind::Array(ty, len) => Self::Array(from_ty(ty)?, Const::from_const_lossy(tcx, len)?),
+            ty::TyKind::Pat(_, _) => None?, //FIXME
+            ty::TyKind::Slice(ty) => Self::Slice(from_ty(ty)?),
+            ty::TyKind::RawPtr(ty, mutability) => Self::RawPtr(from_ty(ty)?, mutability),
+            ty::TyKind::Ref(region, ty, mutability) => {
+                Self::Ref(RegionKind::from_region_lossy(region), from_ty(ty)?, mutability)
+            },
+            ty::TyKind::FnPtr(sig_tys, hdr) => {
+                let sig = sig_tys.with(hdr).skip_binder();
+                let inputs = sig.inputs().iter().map(|&ty| from_ty(ty)).collect::<Option<Vec<_>>>()?;
+                Self::FnPtr(FnSig {
+                    inputs: pcx.mk_slice(&inputs),
+                    output: from_ty(sig.output())?,
+                    safety: sig.safety,
+                    abi: sig.abi,
+                })
+            },
+            ty::TyKind::Dynamic(preds, region, ty::Dyn) => {
+                require_empty(args)?;
+                let output = match preds
+                    .projection_bounds()
+                    .map(|proj| proj.skip_binder())
+                    .find(|proj| tcx.is_lang_item(proj.def_id, LangItem::FnOnceOutput))
+                {
+                    Some(proj) => Some(from_ty(proj.term.as_type()?)?),
+                    None => None,
+                };
+                let principal = match preds.principal() {
+                    Some(trait_ref) => {
+                        let trait_ref = trait_ref.skip_binder();
+                        Some(TraitBound {
+                            trait_ref: TraitRef::Def(
+                                trait_ref.def_id,
+                                GenericArgsRef::from_args_lossy(pcx, tcx, trait_ref.args)?,
+                            ),
+                            output: output.filter(|_| tcx.is_fn_trait(trait_ref.def_id)),
+                        })
+                    },
+                    None => None,
+                };
+                let auto_traits = preds.auto_traits().map(|def_id| TraitBound {
+                    trait_ref: TraitRef::Def(def_id, GenericArgsRef(&[])),
+                    output: None,
+                });
+                let bounds = principal.into_iter().chain(auto_traits).collect::<Vec<_>>();
+                Self::Dynamic(pcx.mk_slice(&bounds), RegionKind::from_region_lossy(region))
+            },
+            ty::TyKind::Dynamic(_, _, ty::DynStar) => None?,
+            ty::TyKind::FnDef(def_id, _)
+            | ty::TyKind::Closure(def_id, _)
+            | ty::TyKind::CoroutineClosure(def_id, _)
+            | ty::TyKind::Coroutine(def_id, _) => Self::Def(def_id, args),
+            ty::TyKind::CoroutineWitness(_, _) => None?,
+            ty::TyKind::Never => Self::Never,
+            ty::TyKind::Tuple(tys) => {
+                let tys = tys.iter().map(from_ty).collect::<Option<Vec<_>>>()?;
+                Self::Tuple(pcx.mk_slice(&tys))
+            },
+            ty::TyKind::Alias(_, _) => None?,    //FIXME
+            ty::TyKind::Param(_) => None?,       //FIXME
+            ty::TyKind::Bound(_, _) => None?,    //FIXME
+            ty::TyKind::Placeholder(_) => None?, //FIXME
+            ty::TyKind::Infer(_) => None?,       //FIXME
             ty::TyKind::Error(_) => None?,
             ty::TyKind::UnsafeBinder(_) => None?,
         })
@@ -189,6 +289,27 @@ impl<'pcx> From<(Ty<'pcx>, &str)> for Path<'pcx> {
 #[derive(Clone, Copy)]
 pub struct GenericArgsRef<'pcx>(pub &'pcx [GenericArgKind<'pcx>]);
 
+impl<'pcx> GenericArgsRef<'pcx> {
+    //FIXME: this may breaks uniqueness of `Ty`
+    pub fn from_args_lossy<'tcx>(
+        pcx: PatCtxt<'pcx>,
+        tcx: TyCtxt<'tcx>,
+        args: &'tcx [ty::GenericArg<'tcx>],
+    ) -> Option<Self> {
+        let args = args
+            .iter()
+            .map(|arg| {
+                Some(match arg.unpack() {
+                    ty::GenericArgKind::Lifetime(region) => RegionKind::from_region_lossy(region).into(),
+                    ty::GenericArgKind::Type(ty) => Ty::from_ty_lossy(pcx, tcx, ty, GenericArgsRef(&[]))?.into(),
+                    ty::GenericArgKind::Const(konst) => Const::from_const_lossy(tcx, konst)?.into(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(GenericArgsRef(pcx.mk_slice(&args)))
+    }
+}
+
 impl<'pcx> std::ops::Deref for GenericArgsRef<'pcx> {
     type Target = [GenericArgKind<'pcx>];
 
@@ -295,6 +416,21 @@ pub enum Const<'pcx> {
     Value(IntValue),
 }
 
+impl Const<'_> {
+    /// Convert an evaluated integral constant, such as the length of an array.
+    pub fn from_const_lossy<'tcx>(tcx: TyCtxt<'tcx>, konst: ty::Const<'tcx>) -> Option<Self> {
+        let value = konst.try_to_value()?;
+        let bits = value.try_to_bits(tcx, ty::TypingEnv::fully_monomorphized())?;
+        let ty = match *value.ty.kind() {
+            ty::Int(int_ty) => IntTy::Int(int_ty),
+            ty::Uint(uint_ty) => IntTy::Uint(uint_ty),
+            ty::Bool => IntTy::Bool,
+            _ => return None,
+        };
+        Some(Const::Value(IntValue { value: Pu128(bits), ty }))
+    }
+}
+
 impl<'pcx> From<ConstVar<'pcx>> for Const<'pcx> {
     fn from(konst: ConstVar<'pcx>) -> Self {
         Const::ConstVar(konst)
@@ -307,6 +443,11 @@ pub struct TyVar {
     pub pred: Option<TyPred>,
 }
 
+#[derive(Clone, Copy)]
+pub struct RegionVar {
+    pub idx: RegionVarIdx,
+}
+
 #[derive(Clone, Copy)]
 pub struct PlaceVar<'pcx> {
     pub idx: PlaceVarIdx,
@@ -0,0 +1,68 @@
+use rpl_config::UnsafeStats;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, FnKind, Visitor};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+/// Count the usage of `unsafe` in the local crate, for `cargo rpl --audit-deps`.
+pub fn unsafe_stats(tcx: TyCtxt<'_>) -> UnsafeStats {
+    let mut visitor = UnsafeVisitor {
+        tcx,
+        stats: UnsafeStats::default(),
+    };
+    tcx.hir().walk_toplevel_module(&mut visitor);
+    visitor.stats.lines = tcx
+        .sess
+        .source_map()
+        .files()
+        .iter()
+        .filter(|file| !file.is_imported() && file.name.is_real())
+        .map(|file| file.count_lines())
+        .sum();
+    visitor.stats
+}
+
+struct UnsafeVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    stats: UnsafeStats,
+}
+
+impl<'tcx> Visitor<'tcx> for UnsafeVisitor<'tcx> {
+    type NestedFilter = nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        match item.kind {
+            hir::ItemKind::Impl(impl_) if impl_.safety.is_unsafe() => self.stats.unsafe_impls += 1,
+            hir::ItemKind::Trait(_, safety, ..) if safety.is_unsafe() => self.stats.unsafe_traits += 1,
+            _ => {},
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_fn(
+        &mut self,
+        kind: FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if kind.header().is_some_and(hir::FnHeader::is_unsafe) {
+            self.stats.unsafe_fns += 1;
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+
+    fn visit_block(&mut self, block: &'tcx hir::Block<'tcx>) {
+        if let hir::BlockCheckMode::UnsafeBlock(hir::UnsafeSource::UserProvided) = block.rules {
+            self.stats.unsafe_blocks += 1;
+        }
+        intravisit::walk_block(self, block);
+    }
+}
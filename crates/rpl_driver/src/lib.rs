@@ -3,21 +3,31 @@
 extern crate rustc_data_structures;
 extern crate rustc_errors;
 extern crate rustc_fluent_macro;
+extern crate rustc_hir;
 extern crate rustc_interface;
+extern crate rustc_lint;
 extern crate rustc_lint_defs;
 extern crate rustc_middle;
 extern crate rustc_span;
 
 rustc_fluent_macro::fluent_messages! { "../messages.en.ftl" }
 
-use rpl_context::PatCtxt;
-use rustc_lint_defs::RegisteredTools;
+mod audit;
+#[cfg(test)]
+mod tests;
+
+pub use audit::unsafe_stats;
+
+use rpl_context::{PatCtxt, PatternCtxt};
+use rustc_lint::{LateContext, LateLintPass, LintStore};
+use rustc_lint_defs::{RegisteredTools, declare_lint_pass};
 use rustc_middle::ty::TyCtxt;
 use rustc_middle::util::Providers;
 use rustc_span::symbol::Ident;
 
 pub fn provide(providers: &mut Providers) {
     providers.registered_tools = registered_tools;
+    rpl_mir_transform::provide_coroutine_bodies(providers);
 }
 
 fn registered_tools(tcx: TyCtxt<'_>, (): ()) -> RegisteredTools {
@@ -26,6 +36,23 @@ fn registered_tools(tcx: TyCtxt<'_>, (): ()) -> RegisteredTools {
     registered_tools
 }
 
+/// Register all RPL lints and lint groups, see [`rpl_patterns::register_lints`], and the lint pass
+/// running the detectors.
+pub fn register_lints(store: &mut LintStore) {
+    rpl_patterns::register_lints(store);
+    store.register_late_pass(|_| Box::new(RplLintPass));
+}
+
+declare_lint_pass!(RplLintPass => []);
+
+// Run the detectors in the lint checking of rustc, before lint expectations are checked, so that
+// `#[expect(rpl::...)]` is fulfilled by RPL lints.
+impl<'tcx> LateLintPass<'tcx> for RplLintPass {
+    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
+        PatternCtxt::entered(|pcx| check_crate(cx.tcx, pcx));
+    }
+}
+
 pub fn check_crate(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>) {
     _ = tcx.hir_crate_items(()).par_items(|item_id| {
         rpl_patterns::check_item(tcx, pcx, item_id);
@@ -0,0 +1,84 @@
+extern crate rustc_driver;
+
+use rpl_config::UnsafeStats;
+use rustc_driver::Compilation;
+use rustc_interface::interface;
+use rustc_middle::ty::TyCtxt;
+
+use crate::unsafe_stats;
+
+struct UnsafeStatsCallbacks(Option<UnsafeStats>);
+
+impl rustc_driver::Callbacks for UnsafeStatsCallbacks {
+    fn after_analysis(&mut self, _compiler: &interface::Compiler, tcx: TyCtxt<'_>) -> Compilation {
+        self.0 = Some(unsafe_stats(tcx));
+        Compilation::Stop
+    }
+}
+
+/// Compile `source` as a library crate, and count its usage of `unsafe`.
+fn unsafe_stats_of(name: &str, source: &str) -> UnsafeStats {
+    let dir = std::env::temp_dir().join(format!("rpl_driver-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("lib.rs");
+    std::fs::write(&path, source).unwrap();
+    let args = [
+        "rustc",
+        path.to_str().unwrap(),
+        "--crate-type=lib",
+        "--edition=2021",
+        "--out-dir",
+        dir.to_str().unwrap(),
+    ]
+    .map(ToString::to_string);
+    let mut callbacks = UnsafeStatsCallbacks(None);
+    rustc_driver::run_compiler(&args, &mut callbacks);
+    std::fs::remove_dir_all(&dir).unwrap();
+    callbacks.0.unwrap()
+}
+
+#[test]
+fn test_unsafe_stats() {
+    let stats = unsafe_stats_of(
+        "unsafe_stats",
+        r#"
+pub unsafe trait Zeroable {}
+
+unsafe impl Zeroable for u8 {}
+
+pub struct Foo(u8);
+
+impl Clone for Foo {
+    fn clone(&self) -> Self {
+        Foo(unsafe { *(&raw const self.0) })
+    }
+}
+
+impl Foo {
+    pub unsafe fn get_unchecked(s: &[u8], i: usize) -> u8 {
+        unsafe { *s.get_unchecked(i) }
+    }
+}
+
+pub unsafe fn read(p: *const u8) -> u8 {
+    // Unsafe blocks in closures are counted as well.
+    let f = || unsafe { *p };
+    f()
+}
+
+pub fn safe(v: &[u8]) -> Vec<u8> {
+    v.to_vec()
+}
+"#,
+    );
+    assert_eq!(
+        stats,
+        UnsafeStats {
+            unsafe_blocks: 3,
+            unsafe_fns: 2,
+            unsafe_impls: 1,
+            unsafe_traits: 1,
+            lines: 28,
+        }
+    );
+}
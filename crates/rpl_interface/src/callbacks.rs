@@ -1,5 +1,8 @@
-use rpl_context::PatternCtxt;
+use std::path::{Path, PathBuf};
+
+use rpl_config::CrateSummary;
 // use rpl_middle::ty::RplConfig;
+use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_interface::interface;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::parse::ParseSess;
@@ -16,12 +19,28 @@ fn track_rpl_args(psess: &mut ParseSess, args_env_var: &Option<String>) {
     ));
 }
 
-#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+/// Track an environment variable read by RPL at runtime, e.g. [`rpl_config::CONF_DIR_ENV`], so
+/// that cargo will re-run RPL when it changes
+fn track_env_var(psess: &mut ParseSess, var: &str) {
+    let value = std::env::var(var).ok();
+    psess
+        .env_depinfo
+        .get_mut()
+        .insert((Symbol::intern(var), value.as_deref().map(Symbol::intern)));
+}
+
 /// Track files that may be accessed at runtime in `file_depinfo` so that cargo will re-run RPL
 /// when any of them are modified
 fn track_files(psess: &mut ParseSess) {
     let file_depinfo = psess.file_depinfo.get_mut();
 
+    // Track the configuration file so that edits to it trigger re-analysis
+    if let Some(conf_file) = &rpl_config::conf().file
+        && let Some(conf_file) = conf_file.to_str()
+    {
+        file_depinfo.insert(Symbol::intern(conf_file));
+    }
+
     // During development track the `rpl-driver` executable so that cargo will re-run RPL
     // whenever it is rebuilt
     #[cfg(debug_assertions)]
@@ -58,11 +77,37 @@ impl rustc_driver::Callbacks for DefaultCallbacks {}
 
 pub struct RplCallbacks {
     rpl_args_var: Option<String>,
+    audit_dir: Option<PathBuf>,
 }
 
 impl RplCallbacks {
     pub fn new(rpl_args_var: Option<String>) -> Self {
-        Self { rpl_args_var }
+        Self {
+            rpl_args_var,
+            audit_dir: None,
+        }
+    }
+
+    /// Write the summary of the crate to `audit_dir` after analysis, for `cargo rpl --audit-deps`.
+    pub fn audit_to(self, audit_dir: PathBuf) -> Self {
+        Self {
+            audit_dir: Some(audit_dir),
+            ..self
+        }
+    }
+}
+
+fn write_audit_summary(tcx: TyCtxt<'_>, audit_dir: &Path) {
+    let env = |var| std::env::var(var).unwrap_or_default();
+    let summary = CrateSummary {
+        package: env("CARGO_PKG_NAME"),
+        version: env("CARGO_PKG_VERSION"),
+        krate: tcx.crate_name(LOCAL_CRATE).to_string(),
+        extra_filename: tcx.sess.opts.cg.extra_filename.clone(),
+        stats: rpl_driver::unsafe_stats(tcx),
+    };
+    if let Err(err) = summary.write(audit_dir) {
+        tcx.dcx().warn(format!("failed to write the RPL audit summary: {err}"));
     }
 }
 
@@ -70,15 +115,16 @@ impl rustc_driver::Callbacks for RplCallbacks {
     // JUSTIFICATION: necessary in RPL driver to set `mir_opt_level`
     #[allow(rustc::bad_opt_access)]
     fn config(&mut self, config: &mut interface::Config) {
-        // let previous = config.register_lints.take();
+        let previous = config.register_lints.take();
         let rpl_args_var = self.rpl_args_var.take();
         config.psess_created = Some(Box::new(move |psess| {
             track_rpl_args(psess, &rpl_args_var);
+            track_env_var(psess, rpl_config::CONF_DIR_ENV);
+            track_env_var(psess, rpl_config::CRATE_VERSIONS_ENV);
             track_files(psess);
         }));
         config.locale_resources = crate::default_locale_resources();
 
-        /*
         config.register_lints = Some(Box::new(move |sess, lint_store| {
             // technically we're ~guaranteed that this is none but might as well call anything that
             // is there already. Certainly it can't hurt.
@@ -88,7 +134,6 @@ impl rustc_driver::Callbacks for RplCallbacks {
 
             rpl_driver::register_lints(lint_store);
         }));
-        */
 
         config.override_queries = Some(|_sess, providers| {
             rpl_driver::provide(providers);
@@ -124,7 +169,10 @@ impl rustc_driver::Callbacks for RplCallbacks {
     }
 
     fn after_analysis(&mut self, _compiler: &interface::Compiler, tcx: TyCtxt<'_>) -> rustc_driver::Compilation {
-        PatternCtxt::entered(|pcx| rpl_driver::check_crate(tcx, pcx));
+        // RPL patterns are checked in the lint pass registered by `rpl_driver::register_lints`.
+        if let Some(audit_dir) = &self.audit_dir {
+            write_audit_summary(tcx, audit_dir);
+        }
         /*
         queries.global_ctxt().unwrap().enter(|tcx| {
             let mut lint_store = LaterLintStore::new();
@@ -6,6 +6,7 @@ extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_driver_impl;
 extern crate rustc_errors;
+extern crate rustc_hir;
 extern crate rustc_interface;
 extern crate rustc_middle;
 extern crate rustc_session;
@@ -0,0 +1,32 @@
+//! Restrict patterns to the affected versions of a crate, see `#[applies_to]`.
+
+use rpl_context::pat;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Symbol;
+
+use crate::resolve::find_crates;
+
+/// The version of the crate named in `applies_to` that meets its version requirement, or `None`
+/// if the pattern does not apply.
+///
+/// The crate is either the local crate, with the version from `CARGO_PKG_VERSION`, or a direct
+/// dependency found by [`find_crates`], with the versions resolved by cargo from
+/// [`rpl_config::crate_versions`]. The pattern never applies if the version is unknown, e.g. when
+/// not run by cargo.
+#[instrument(level = "debug", skip(tcx, applies_to), fields(krate = ?applies_to.krate, version = %applies_to.version), ret)]
+pub fn applied_version(tcx: TyCtxt<'_>, applies_to: &pat::AppliesTo) -> Option<String> {
+    let package = applies_to.krate.as_str();
+    let crate_name = Symbol::intern(&package.replace('-', "_"));
+    if tcx.crate_name(LOCAL_CRATE) == crate_name {
+        return std::env::var("CARGO_PKG_VERSION")
+            .ok()
+            .filter(|version| applies_to.version.matches(version));
+    }
+    if find_crates(tcx, crate_name).is_empty() {
+        return None;
+    }
+    rpl_config::crate_versions(package)
+        .find(|version| applies_to.version.matches(version))
+        .map(str::to_owned)
+}
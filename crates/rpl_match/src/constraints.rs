@@ -0,0 +1,56 @@
+//! Restrict `fn` patterns to functions with the given qualifiers and attributes, see
+//! [`pat::FnConstraints`].
+
+use rpl_context::pat;
+use rustc_abi::ExternAbi;
+use rustc_hir::Safety;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+
+/// Whether the function `def_id` meets the constraints of a `fn` pattern.
+///
+/// Coroutines, i.e. the bodies of `async fn`s and `async` blocks, are checked as the function
+/// they are defined in. Other closures are taken as safe functions of the Rust ABI, which are
+/// never trait methods.
+#[instrument(level = "debug", skip(tcx, constraints), ret)]
+pub fn satisfies_fn_constraints(tcx: TyCtxt<'_>, def_id: DefId, constraints: &pat::FnConstraints) -> bool {
+    let def_id = if tcx.is_coroutine(def_id) {
+        tcx.typeck_root_def_id(def_id)
+    } else {
+        def_id
+    };
+    let pat::FnConstraints {
+        public,
+        constness,
+        safety,
+        abi,
+        generic,
+        trait_method,
+        attrs,
+        // Checked when the signature is matched, see `MatchFnCtxt::match_fn`.
+        regions: _,
+    } = constraints;
+    let sig = matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn).then(|| tcx.fn_sig(def_id).skip_binder());
+    public.is_none_or(|public| tcx.visibility(def_id).is_public() == public)
+        && constness.is_none_or(|constness| tcx.is_const_fn(def_id) == constness)
+        && safety.is_none_or(|safety| sig.map_or(Safety::Safe, |sig| sig.safety()) == safety)
+        && abi.is_none_or(|abi| sig.map_or(ExternAbi::Rust, |sig| sig.abi()) == abi)
+        && generic.is_none_or(|generic| tcx.generics_of(def_id).requires_monomorphization(tcx) == generic)
+        && trait_method.is_none_or(|trait_method| is_trait_method(tcx, def_id) == trait_method)
+        && attrs.iter().all(|(path, args, present)| {
+            tcx.get_attrs_by_path(def_id, path).any(|attr| {
+                args.iter().all(|&arg| {
+                    attr.meta_item_list()
+                        .is_some_and(|items| items.iter().any(|item| item.has_name(arg)))
+                })
+            }) == *present
+        })
+}
+
+fn is_trait_method(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    tcx.trait_of_item(def_id).is_some()
+        || tcx
+            .impl_of_method(def_id)
+            .is_some_and(|impl_id| tcx.trait_id_of_impl(impl_id).is_some())
+}
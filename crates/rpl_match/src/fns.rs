@@ -1,13 +1,14 @@
 use std::iter::zip;
 
 use rpl_context::{PatCtxt, pat};
+use rustc_data_structures::fx::FxIndexSet;
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::{self, TyCtxt};
 
 use crate::MatchTyCtxt;
 
 pub struct MatchFnCtxt<'a, 'pcx, 'tcx> {
-    ty: MatchTyCtxt<'pcx, 'tcx>,
+    pub ty: MatchTyCtxt<'pcx, 'tcx>,
     fn_pat: &'a pat::Fn<'pcx>,
 }
 
@@ -25,19 +26,105 @@ impl<'a, 'pcx, 'tcx> MatchFnCtxt<'a, 'pcx, 'tcx> {
     #[instrument(level = "info", skip_all, fields(fn_pat = %self.fn_pat, fn_did = ?fn_did.into()), ret)]
     pub fn match_fn(&self, fn_did: impl Into<DefId> + Copy) -> bool {
         let fn_did = fn_did.into();
+        crate::satisfies_fn_constraints(self.ty.tcx, fn_did, &self.fn_pat.constraints)
+            && self.match_fn_sig(fn_did)
+            && self.ty.match_where_predicates(
+                &self.fn_pat.predicates,
+                ty::TypingEnv::non_body_analysis(self.ty.tcx, fn_did),
+            )
+    }
+
+    /// Match the signature and the region constraints, but not the `where` predicates, so that
+    /// the type variables only appearing in the body can be bounded as well.
+    pub fn match_fn_sig(&self, fn_did: DefId) -> bool {
+        let tcx = self.ty.tcx;
         let poly_fn_sig = match self.ty.tcx.type_of(fn_did).instantiate_identity().kind() {
-            ty::FnDef(..) => self.ty.tcx.fn_sig(fn_did).instantiate_identity(),
+            ty::FnDef(..) => tcx.fn_sig(fn_did).instantiate_identity(),
             ty::Closure(_, args) => args.as_closure().sig(),
-            _ => unimplemented!(),
+            // Like closures, the inputs of async closures are tupled.
+            ty::CoroutineClosure(_, args) => args.as_coroutine_closure().coroutine_closure_sig().map_bound(|sig| {
+                tcx.mk_fn_sig(
+                    [sig.tupled_inputs_ty],
+                    sig.return_ty,
+                    sig.c_variadic,
+                    sig.safety,
+                    sig.abi,
+                )
+            }),
+            // The bodies of `async fn`s, `async` blocks and `async` closures have no signatures of their own.
+            ty::Coroutine(..) => return false,
+            kind => unreachable!("unexpected type of a function body: {kind:?}"),
         };
         let fn_sig = self.ty.tcx.liberate_late_bound_regions(fn_did, poly_fn_sig);
         info!(?fn_sig);
-        (self.fn_pat.params.len() <= fn_sig.inputs().len() || self.fn_pat.params.non_exhaustive)
+        (self.fn_pat.params.len() == fn_sig.inputs().len()
+            || self.fn_pat.params.non_exhaustive && self.fn_pat.params.len() <= fn_sig.inputs().len())
             && zip(self.fn_pat.params.iter(), fn_sig.inputs())
                 .all(|(param_pat, &param_ty)| self.match_param(param_pat, param_ty))
             && self
                 .ty
                 .match_ty(self.fn_pat.ret.unwrap_or(self.ty.pcx.mk_unit_ty()), fn_sig.output())
+            && self.match_region_constraints(fn_did, fn_sig.inputs())
+    }
+
+    /// Each region variable must be matched with a single region, and the regions must satisfy the
+    /// region constraints of the pattern. A constraint on an unmatched region variable never holds.
+    fn match_region_constraints(&self, fn_did: DefId, inputs: &[ty::Ty<'tcx>]) -> bool {
+        if self.ty.region_vars.iter().any(|regions| regions.borrow().len() > 1) {
+            return false;
+        }
+        let region = |region_var: pat::RegionVarIdx| self.ty.region_vars[region_var].borrow().first().copied();
+        self.fn_pat.constraints.regions.iter().all(|&(constraint, holds)| {
+            let satisfied = match constraint {
+                pat::RegionConstraint::Outlives(longer, shorter) => match (region(longer), region(shorter)) {
+                    (Some(longer), Some(shorter)) => self.region_outlives(fn_did, longer, shorter),
+                    _ => return false,
+                },
+                pat::RegionConstraint::Unbounded(region_var) => match region(region_var) {
+                    Some(region) => !self.is_tied_to_inputs(fn_did, region, inputs),
+                    None => return false,
+                },
+            };
+            satisfied == holds
+        })
+    }
+
+    /// Whether `region` is `'static`, appears in an input, or is outlived by a region of an input.
+    fn is_tied_to_inputs(&self, fn_did: DefId, region: ty::Region<'tcx>, inputs: &[ty::Ty<'tcx>]) -> bool {
+        region.is_static()
+            || inputs
+                .iter()
+                .flat_map(|ty| ty.walk())
+                .filter_map(|arg| arg.as_region())
+                .any(|input| !input.is_static() && self.region_outlives(fn_did, input, region))
+    }
+
+    /// Whether `longer` outlives `shorter`, following the explicit bounds such as `'a: 'b` on the
+    /// function. The bounds implied by the types of the inputs are not taken into account.
+    fn region_outlives(&self, fn_did: DefId, longer: ty::Region<'tcx>, shorter: ty::Region<'tcx>) -> bool {
+        let bounds = self
+            .ty
+            .tcx
+            .param_env(fn_did)
+            .caller_bounds()
+            .iter()
+            .filter_map(|clause| clause.as_region_outlives_clause()?.no_bound_vars())
+            .collect::<Vec<_>>();
+        let mut outlived = FxIndexSet::from_iter([longer]);
+        let mut index = 0;
+        while let Some(&region) = outlived.get_index(index) {
+            if region == shorter || region.is_static() {
+                return true;
+            }
+            let next = bounds
+                .iter()
+                .filter(|&&ty::OutlivesPredicate(a, _)| a == region)
+                .map(|&ty::OutlivesPredicate(_, b)| b)
+                .collect::<Vec<_>>();
+            outlived.extend(next);
+            index += 1;
+        }
+        false
     }
 
     fn match_param(&self, param_pat: &pat::Param<'pcx>, ty: ty::Ty<'tcx>) -> bool {
@@ -0,0 +1,53 @@
+use rpl_context::{PatCtxt, pat};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{self, TyCtxt};
+
+use crate::MatchTyCtxt;
+
+pub struct MatchImplCtxt<'a, 'pcx, 'tcx> {
+    ty: MatchTyCtxt<'pcx, 'tcx>,
+    impl_pat: &'a pat::Impl<'pcx>,
+}
+
+impl<'a, 'pcx, 'tcx> MatchImplCtxt<'a, 'pcx, 'tcx> {
+    pub fn new(
+        tcx: TyCtxt<'tcx>,
+        pcx: PatCtxt<'pcx>,
+        pat: &'pcx pat::Pattern<'pcx>,
+        impl_pat: &'a pat::Impl<'pcx>,
+    ) -> Self {
+        let ty = MatchTyCtxt::new(tcx, pcx, ty::TypingEnv::fully_monomorphized(), pat, &impl_pat.meta);
+        Self { ty, impl_pat }
+    }
+
+    /// Match the trait, the self type and the `where` clause of the `impl` item `impl_did`, where
+    /// negative implementations are never matched.
+    #[instrument(level = "info", skip_all, fields(impl_did = ?impl_did.into()), ret)]
+    pub fn match_impl(&self, impl_did: impl Into<DefId> + Copy) -> bool {
+        let impl_did = impl_did.into();
+        let tcx = self.ty.tcx;
+        if !matches!(tcx.def_kind(impl_did), DefKind::Impl { .. })
+            || tcx.impl_polarity(impl_did) != ty::ImplPolarity::Positive
+        {
+            return false;
+        }
+        let trait_matched = match (self.impl_pat.trait_ref, tcx.impl_trait_ref(impl_did)) {
+            (None, None) => true,
+            (Some(trait_pat), Some(trait_ref)) => {
+                let trait_ref = trait_ref.instantiate_identity();
+                self.ty
+                    .match_trait_ref(trait_pat, trait_ref.def_id, &trait_ref.args[1..])
+            },
+            (None, Some(_)) | (Some(_), None) => false,
+        };
+        trait_matched
+            && self
+                .ty
+                .match_ty(self.impl_pat.ty, tcx.type_of(impl_did).instantiate_identity())
+            && self.ty.match_where_predicates(
+                &self.impl_pat.predicates,
+                ty::TypingEnv::non_body_analysis(tcx, impl_did),
+            )
+    }
+}
@@ -8,20 +8,28 @@ extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_hir;
 extern crate rustc_index;
+extern crate rustc_infer;
 extern crate rustc_middle;
 extern crate rustc_span;
+extern crate rustc_trait_selection;
 #[macro_use]
 extern crate tracing;
 
 mod adt;
+mod applies_to;
+mod constraints;
 mod counted;
 mod fns;
+mod impls;
 mod place;
 pub(crate) mod resolve;
 mod ty;
 
 pub use adt::{AdtMatch, Candidates, MatchAdtCtxt};
+pub use applies_to::applied_version;
+pub use constraints::satisfies_fn_constraints;
 pub use counted::CountedMatch;
 pub use fns::MatchFnCtxt;
+pub use impls::MatchImplCtxt;
 pub use place::MatchPlaceCtxt;
 pub use ty::MatchTyCtxt;
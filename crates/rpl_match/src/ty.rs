@@ -2,15 +2,19 @@ use std::cell::RefCell;
 use std::iter::zip;
 
 use rpl_context::{PatCtxt, pat};
+use rustc_abi::ExternAbi;
 use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_hir::LangItem;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_hir::definitions::DefPathData;
 use rustc_index::IndexVec;
+use rustc_infer::infer::{DefineOpaqueTypes, TyCtxtInferExt};
+use rustc_infer::traits::ObligationCause;
 use rustc_middle::mir;
-use rustc_middle::ty::{self, TyCtxt, ValTreeKind};
-use rustc_span::Symbol;
+use rustc_middle::ty::{self, TyCtxt};
 use rustc_span::symbol::kw;
+use rustc_span::{DUMMY_SP, Symbol};
 
 use crate::resolve::{self, PatItemKind, lang_item_res, ty_res};
 use crate::{AdtMatch, MatchAdtCtxt};
@@ -22,6 +26,7 @@ pub struct MatchTyCtxt<'pcx, 'tcx> {
     pub typing_env: ty::TypingEnv<'tcx>,
     pub const_vars: IndexVec<pat::ConstVarIdx, RefCell<FxIndexSet<mir::Const<'tcx>>>>,
     pub ty_vars: IndexVec<pat::TyVarIdx, RefCell<FxIndexSet<ty::Ty<'tcx>>>>,
+    pub region_vars: IndexVec<pat::RegionVarIdx, RefCell<FxIndexSet<ty::Region<'tcx>>>>,
     pub adt_matches: RefCell<FxHashMap<Symbol, FxHashMap<DefId, AdtMatch<'tcx>>>>,
 }
 
@@ -40,6 +45,7 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
             typing_env,
             ty_vars: IndexVec::from_elem(RefCell::new(FxIndexSet::default()), &meta.ty_vars),
             const_vars: IndexVec::from_elem(RefCell::new(FxIndexSet::default()), &meta.const_vars),
+            region_vars: IndexVec::from_elem(RefCell::new(FxIndexSet::default()), &meta.region_vars),
             adt_matches: Default::default(),
         }
     }
@@ -68,6 +74,13 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
             (pat::TyKind::RawPtr(ty_pat, mutability_pat), ty::RawPtr(ty, mutblty)) => {
                 mutability_pat == mutblty && self.match_ty(ty_pat, ty)
             },
+            (pat::TyKind::FnPtr(sig_pat), ty::FnPtr(sig_tys, hdr)) => {
+                self.match_fn_sig(sig_pat, sig_tys.with(hdr).skip_binder())
+            },
+            (pat::TyKind::Dynamic(bounds, region_pat), ty::Dynamic(preds, region, ty::Dyn)) => {
+                self.match_region(region_pat, region) && self.match_dyn(bounds, preds)
+            },
+            (pat::TyKind::ImplTrait(bounds), _) => bounds.iter().all(|&bound| self.match_impl_trait(bound, ty)),
             (pat::TyKind::Uint(ty_pat), ty::Uint(ty)) => ty_pat == ty,
             (pat::TyKind::Int(ty_pat), ty::Int(ty)) => ty_pat == ty,
             (pat::TyKind::Float(ty_pat), ty::Float(ty)) => ty_pat == ty,
@@ -85,6 +98,15 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
             (pat::TyKind::Def(def_id_pat, args_pat), ty::FnDef(def_id, args)) => {
                 self.match_generic_args(&args_pat, args, self.tcx.generics_of(def_id)) && def_id_pat == def_id
             },
+            // The generic arguments of closures and coroutines are synthesized, so only the definitions are
+            // compared.
+            (
+                pat::TyKind::Def(def_id_pat, _),
+                ty::Foreign(def_id)
+                | ty::Closure(def_id, _)
+                | ty::CoroutineClosure(def_id, _)
+                | ty::Coroutine(def_id, _),
+            ) => def_id_pat == def_id,
             (pat::TyKind::Path(path_with_args), _) => {
                 //FIXME: generics args are ignored.
                 match path_with_args.path {
@@ -113,6 +135,9 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
             //         && self.match_generic_args(args, alias.args)
             // },
             (pat::TyKind::Bool, ty::Bool) => true,
+            (pat::TyKind::Str, ty::Str) => true,
+            (pat::TyKind::Char, ty::Char) => true,
+            (pat::TyKind::Never, ty::Never) => true,
             (pat::TyKind::Any, _) => true,
             (
                 pat::TyKind::TyVar(_)
@@ -122,13 +147,16 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
                 | pat::TyKind::Tuple(_)
                 | pat::TyKind::Ref(..)
                 | pat::TyKind::RawPtr(..)
+                | pat::TyKind::FnPtr(_)
+                | pat::TyKind::Dynamic(..)
                 | pat::TyKind::Uint(_)
                 | pat::TyKind::Int(_)
                 | pat::TyKind::Float(_)
                 | pat::TyKind::Def(_, _)
                 | pat::TyKind::Bool
                 | pat::TyKind::Str
-                | pat::TyKind::Char,
+                | pat::TyKind::Char
+                | pat::TyKind::Never,
                 ty::Bool
                 | ty::Char
                 | ty::Int(_)
@@ -173,15 +201,10 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
     pub fn match_const(&self, konst_pat: pat::Const<'pcx>, konst: ty::Const<'tcx>) -> bool {
         match (konst_pat, konst.kind()) {
             (pat::Const::ConstVar(const_var), _) => self.match_ty_const_var(const_var, konst),
-            //(pat::Const::Value(_value_pat), ty::Value(_ty, ty::ValTree::Leaf(_value))) => todo!(),
-            (
-                pat::Const::Value(_value_pat),
-                ty::ConstKind::Value(ty::Value {
-                    ty: _ty,
-                    valtree: _valtree,
-                }),
-            ) if matches!(*_valtree, ValTreeKind::Leaf(_val)) => {
-                todo!()
+            (pat::Const::Value(value_pat), ty::ConstKind::Value(value)) => {
+                value.valtree.try_to_scalar_int().is_some_and(|value| {
+                    value_pat.normalize(self.tcx.data_layout.pointer_size.bytes()) == value.to_bits_unchecked()
+                })
             },
             (
                 // pat::Const::ConstVar(_)
@@ -191,7 +214,6 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
                 | ty::ConstKind::Bound(..)
                 | ty::ConstKind::Placeholder(_)
                 | ty::ConstKind::Unevaluated(_)
-                | ty::ConstKind::Value(..)
                 | ty::ConstKind::Error(_)
                 | ty::ConstKind::Expr(_),
             ) => false,
@@ -223,13 +245,286 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
     }
 
     #[instrument(level = "trace", skip(self), ret)]
-    pub fn match_region(&self, pat: pat::RegionKind, region: ty::Region<'tcx>) -> bool {
-        matches!(
-            (pat, region.kind()),
-            (pat::RegionKind::ReStatic, ty::RegionKind::ReStatic) | (pat::RegionKind::ReAny, _)
+    fn match_fn_sig(&self, sig_pat: pat::FnSig<'pcx>, sig: ty::FnSig<'tcx>) -> bool {
+        sig_pat.safety == sig.safety
+            && sig_pat.abi == sig.abi
+            && !sig.c_variadic
+            && sig_pat.inputs.len() == sig.inputs().len()
+            && zip(sig_pat.inputs, sig.inputs()).all(|(&ty_pat, &ty)| self.match_ty(ty_pat, ty))
+            && self.match_ty(sig_pat.output, sig.output())
+    }
+
+    /// Match the bounds of `dyn Trait1 + Trait2`, where each trait of the trait object must be
+    /// matched by exactly one bound.
+    #[instrument(level = "trace", skip(self), ret)]
+    fn match_dyn(
+        &self,
+        bounds: &[pat::TraitBound<'pcx>],
+        preds: &'tcx ty::List<ty::PolyExistentialPredicate<'tcx>>,
+    ) -> bool {
+        bounds.len() == usize::from(preds.principal().is_some()) + preds.auto_traits().count()
+            && bounds.iter().all(|&bound| self.match_dyn_bound(bound, preds))
+    }
+
+    fn match_dyn_bound(
+        &self,
+        bound: pat::TraitBound<'pcx>,
+        preds: &'tcx ty::List<ty::PolyExistentialPredicate<'tcx>>,
+    ) -> bool {
+        let output = || {
+            preds
+                .projection_bounds()
+                .map(|proj| proj.skip_binder())
+                .find(|proj| self.tcx.is_lang_item(proj.def_id, LangItem::FnOnceOutput))
+                .and_then(|proj| proj.term.as_type())
+        };
+        preds.principal().is_some_and(|trait_ref| {
+            let trait_ref = trait_ref.skip_binder();
+            self.match_trait_ref(bound.trait_ref, trait_ref.def_id, trait_ref.args)
+                && self.match_fn_output(bound, output)
+        }) || bound.output.is_none()
+            && preds
+                .auto_traits()
+                .any(|def_id| self.match_trait_ref(bound.trait_ref, def_id, ty::List::empty()))
+    }
+
+    /// Match `impl Trait`, i.e., whether `ty` implements the trait of `bound`.
+    #[instrument(level = "trace", skip(self), ret)]
+    fn match_impl_trait(&self, bound: pat::TraitBound<'pcx>, ty: ty::Ty<'tcx>) -> bool {
+        match *ty.kind() {
+            ty::Alias(ty::Opaque, alias) => {
+                let clauses = self
+                    .tcx
+                    .item_bounds(alias.def_id)
+                    .iter_instantiated(self.tcx, alias.args)
+                    .collect::<Vec<_>>();
+                let output = || {
+                    clauses
+                        .iter()
+                        .filter_map(|clause| clause.as_projection_clause())
+                        .map(|proj| proj.skip_binder())
+                        .find(|proj| self.tcx.is_lang_item(proj.def_id(), LangItem::FnOnceOutput))
+                        .and_then(|proj| proj.term.as_type())
+                };
+                clauses
+                    .iter()
+                    .filter_map(|clause| clause.as_trait_clause())
+                    .any(|pred| {
+                        let trait_ref = pred.skip_binder().trait_ref;
+                        self.match_trait_ref(bound.trait_ref, trait_ref.def_id, &trait_ref.args[1..])
+                    })
+                    && self.match_fn_output(bound, output)
+            },
+            ty::Dynamic(preds, _, ty::Dyn) => self.match_dyn_bound(bound, preds),
+            _ => self
+                .resolve_trait(bound.trait_ref)
+                .into_iter()
+                .any(|trait_def_id| self.match_impl(bound, trait_def_id, ty)),
+        }
+    }
+
+    fn match_impl(&self, bound: pat::TraitBound<'pcx>, trait_def_id: DefId, ty: ty::Ty<'tcx>) -> bool {
+        if let Some(kind) = self.tcx.fn_trait_kind_from_def_id(trait_def_id) {
+            return self.fn_trait_sig(ty, kind).is_some_and(|(inputs, output)| {
+                self.match_trait_ref(bound.trait_ref, trait_def_id, &[inputs.into()])
+                    && self.match_fn_output(bound, || Some(output))
+            });
+        }
+        let args_pat = match bound.trait_ref {
+            pat::TraitRef::Path(path_with_args) => path_with_args.args,
+            pat::TraitRef::Def(_, args) => args,
+        };
+        if args_pat.is_empty() {
+            return self.implements_non_generic_trait(ty, trait_def_id, self.typing_env);
+        }
+        let infcx = self.tcx.infer_ctxt().build(self.typing_env.typing_mode);
+        let mut matched = false;
+        self.tcx.for_each_relevant_impl(trait_def_id, ty, |impl_def_id| {
+            if matched {
+                return;
+            }
+            let Some(trait_ref) = self.tcx.impl_trait_ref(impl_def_id) else {
+                return;
+            };
+            // Unify the self type of the implementation with `ty`, so that the arguments of the trait are
+            // instantiated accordingly, e.g. `T` of `impl<T> From<T> for Wrapper<T>` with `Wrapper<u8>`.
+            matched = infcx.probe(|_| {
+                let impl_args = infcx.fresh_args_for_item(DUMMY_SP, impl_def_id);
+                let trait_ref = trait_ref.instantiate(self.tcx, impl_args);
+                infcx
+                    .at(&ObligationCause::dummy(), self.typing_env.param_env)
+                    .eq(DefineOpaqueTypes::No, trait_ref.self_ty(), ty)
+                    .is_ok()
+                    && self.match_trait_ref(
+                        bound.trait_ref,
+                        trait_def_id,
+                        &infcx.resolve_vars_if_possible(trait_ref).args[1..],
+                    )
+            });
+        });
+        matched
+    }
+
+    /// Whether `ty` implements the trait of `trait_def_id`. Generic traits are rejected, as their
+    /// arguments are unknown, just like `check_where_clause` of `rpl_pat_expand` rejects them in
+    /// `where` bounds.
+    fn implements_non_generic_trait(
+        &self,
+        ty: ty::Ty<'tcx>,
+        trait_def_id: DefId,
+        typing_env: ty::TypingEnv<'tcx>,
+    ) -> bool {
+        // The only generic parameter of a non-generic trait is `Self`.
+        if self.tcx.generics_of(trait_def_id).count() != 1 {
+            return false;
+        }
+        let infcx = self.tcx.infer_ctxt().build(typing_env.typing_mode);
+        rustc_trait_selection::traits::type_known_to_meet_bound_modulo_regions(
+            &infcx,
+            typing_env.param_env,
+            ty,
+            trait_def_id,
         )
     }
 
+    /// Whether the types matched by the type variables satisfy the `where` predicates, in the
+    /// typing environment of the item that the predicates are on. A predicate on an unmatched
+    /// type variable never holds.
+    #[instrument(level = "debug", skip(self, predicates), ret)]
+    pub fn match_where_predicates(
+        &self,
+        predicates: &[pat::WherePredicate<'pcx>],
+        typing_env: ty::TypingEnv<'tcx>,
+    ) -> bool {
+        predicates.iter().all(|predicate| {
+            let tys = self.ty_vars[predicate.ty_var].borrow();
+            !tys.is_empty()
+                && tys
+                    .iter()
+                    .all(|&ty| self.match_where_predicate(predicate, ty, typing_env))
+        })
+    }
+
+    /// Whether `ty`, matched by the type variable of `predicate`, satisfies the predicate.
+    pub fn match_where_predicate(
+        &self,
+        predicate: &pat::WherePredicate<'pcx>,
+        ty: ty::Ty<'tcx>,
+        typing_env: ty::TypingEnv<'tcx>,
+    ) -> bool {
+        self.satisfies_where_bound(predicate.bound, ty, typing_env) == predicate.holds
+    }
+
+    fn satisfies_where_bound(
+        &self,
+        bound: pat::WhereBound<'pcx>,
+        ty: ty::Ty<'tcx>,
+        typing_env: ty::TypingEnv<'tcx>,
+    ) -> bool {
+        match bound {
+            pat::WhereBound::Trait(bound) => self
+                .resolve_trait(bound.trait_ref)
+                .into_iter()
+                .any(|trait_def_id| self.implements_non_generic_trait(ty, trait_def_id, typing_env)),
+            pat::WhereBound::Outlives(pat::RegionKind::ReStatic) => self.outlives_static(ty, typing_env),
+            pat::WhereBound::Outlives(region) => unreachable!("unsupported region bound {region:?}"),
+        }
+    }
+
+    /// Whether `ty: 'static` is implied by the bounds in `typing_env`, including those of the
+    /// supertraits, such as `T: std::any::Any`.
+    fn outlives_static(&self, ty: ty::Ty<'tcx>, typing_env: ty::TypingEnv<'tcx>) -> bool {
+        let clauses =
+            rustc_infer::traits::util::elaborate(self.tcx, typing_env.param_env.caller_bounds()).collect::<Vec<_>>();
+        let ty_outlives_static = |ty: ty::Ty<'tcx>| {
+            clauses
+                .iter()
+                .filter_map(|clause| clause.as_type_outlives_clause()?.no_bound_vars())
+                .any(|ty::OutlivesPredicate(bounded, bound)| bounded == ty && bound.is_static())
+        };
+        let region_outlives_static = |region: ty::Region<'tcx>| {
+            region.is_static()
+                || clauses
+                    .iter()
+                    .filter_map(|clause| clause.as_region_outlives_clause()?.no_bound_vars())
+                    .any(|ty::OutlivesPredicate(bounded, bound)| bounded == region && bound.is_static())
+        };
+        // The components of other types are visited by `walk`.
+        ty.walk().all(|arg| match arg.unpack() {
+            ty::GenericArgKind::Lifetime(region) => region_outlives_static(region),
+            ty::GenericArgKind::Type(ty) if let ty::Param(_) = ty.kind() => ty_outlives_static(ty),
+            ty::GenericArgKind::Type(_) | ty::GenericArgKind::Const(_) => true,
+        })
+    }
+
+    /// The inputs (as a tuple) and the output of a closure, a function item or a function pointer
+    /// that implements the function trait of `kind`.
+    fn fn_trait_sig(&self, ty: ty::Ty<'tcx>, kind: ty::ClosureKind) -> Option<(ty::Ty<'tcx>, ty::Ty<'tcx>)> {
+        let sig = match *ty.kind() {
+            ty::Closure(_, args) => {
+                let closure = args.as_closure();
+                let sig = closure.sig().skip_binder();
+                return closure.kind().extends(kind).then(|| (sig.inputs()[0], sig.output()));
+            },
+            ty::FnDef(def_id, args) => self.tcx.fn_sig(def_id).instantiate(self.tcx, args).skip_binder(),
+            ty::FnPtr(sig_tys, hdr) => sig_tys.with(hdr).skip_binder(),
+            _ => return None,
+        };
+        (sig.safety.is_safe() && sig.abi == ExternAbi::Rust && !sig.c_variadic)
+            .then(|| (ty::Ty::new_tup(self.tcx, sig.inputs()), sig.output()))
+    }
+
+    fn match_fn_output(&self, bound: pat::TraitBound<'pcx>, output: impl FnOnce() -> Option<ty::Ty<'tcx>>) -> bool {
+        bound
+            .output
+            .is_none_or(|output_pat| output().is_some_and(|output| self.match_ty(output_pat, output)))
+    }
+
+    /// Match a trait and its generic arguments other than `Self`.
+    #[instrument(level = "trace", skip(self), ret)]
+    pub fn match_trait_ref(
+        &self,
+        trait_ref: pat::TraitRef<'pcx>,
+        def_id: DefId,
+        args: &[ty::GenericArg<'tcx>],
+    ) -> bool {
+        let (matched, args_pat) = match trait_ref {
+            pat::TraitRef::Path(path_with_args) => (self.match_path(path_with_args.path, def_id), path_with_args.args),
+            pat::TraitRef::Def(def_id_pat, args_pat) => (def_id_pat == def_id, args_pat),
+        };
+        matched
+            && args_pat.len() == args.len()
+            && zip(args_pat.iter(), args).all(|(&arg_pat, &arg)| self.match_generic_arg(arg_pat, arg))
+    }
+
+    fn resolve_trait(&self, trait_ref: pat::TraitRef<'pcx>) -> Vec<DefId> {
+        match trait_ref {
+            pat::TraitRef::Path(pat::PathWithArgs { path, .. }) => match path {
+                pat::Path::Item(path) => resolve::def_path_res(self.tcx, path.0, PatItemKind::Trait)
+                    .into_iter()
+                    .filter_map(|res| res.opt_def_id())
+                    .collect(),
+                pat::Path::LangItem(item) => self.tcx.lang_items().get(item).into_iter().collect(),
+                pat::Path::TypeRelative(..) => Vec::new(),
+            },
+            pat::TraitRef::Def(def_id, _) => vec![def_id],
+        }
+    }
+
+    #[instrument(level = "trace", skip(self), ret)]
+    pub fn match_region(&self, pat: pat::RegionKind, region: ty::Region<'tcx>) -> bool {
+        match (pat, region.kind()) {
+            // Regions are erased in MIR bodies, where nothing is known about them.
+            (pat::RegionKind::ReVar(_), ty::RegionKind::ReErased) => true,
+            (pat::RegionKind::ReVar(region_var), _) => {
+                self.region_vars[region_var.idx].borrow_mut().insert(region);
+                true
+            },
+            (pat::RegionKind::ReStatic, ty::RegionKind::ReStatic) | (pat::RegionKind::ReAny, _) => true,
+            _ => false,
+        }
+    }
+
     /// Match type path
     #[instrument(level = "trace", skip(self), ret)]
     pub fn match_path_with_args(
@@ -272,17 +567,10 @@ impl<'pcx, 'tcx> MatchTyCtxt<'pcx, 'tcx> {
         };
         let res = resolve::def_path_res(self.tcx, path.0, kind);
         trace!(?res);
-        let mut res = res.into_iter().filter_map(|res| match res {
-            Res::Def(_, id) => Some(id),
-            _ => None,
-        });
-        let pat_id = if let Some(id) = res.next() { id } else { return false };
-        // FIXME: there should be at most one item matching specific item kind
-        assert!(res.next().is_none());
-
-        trace!(?pat_id, ?def_id);
-
-        pat_id == def_id
+        // There may be more than one item of the same kind, e.g. `fetch_add` in both
+        // `impl AtomicCell<u32>` and `impl AtomicCell<u64>`, which are told apart by the types.
+        res.into_iter()
+            .any(|res| matches!(res, Res::Def(_, id) if id == def_id))
     }
 
     pub fn match_item_path(&self, path: pat::ItemPath<'pcx>, def_id: DefId) -> Option<&[Symbol]> {
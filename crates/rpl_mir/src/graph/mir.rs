@@ -44,6 +44,19 @@ impl<'tcx> Visitor<'tcx> for BlockDataDepGraphVisitor<'_, mir::Local> {
     fn visit_local(&mut self, local: mir::Local, pcx: PlaceContext, location: mir::Location) {
         self.graph.access_local(local, pcx, location.statement_index);
     }
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: mir::Location) {
+        // Access the operands of `Gt` and `Ge` in the order of `Lt` and `Le` that they are unified
+        // into, so that they line up with the ones in patterns, see
+        // `rpl_mir_transform::unified_comparison`.
+        if let &mir::Rvalue::BinaryOp(op, box (ref lhs, ref rhs)) = rvalue
+            && rpl_mir_transform::unified_comparison(op).is_some()
+        {
+            self.visit_operand(rhs, location);
+            self.visit_operand(lhs, location);
+        } else {
+            self.super_rvalue(rvalue, location);
+        }
+    }
     fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: mir::Location) {
         self.super_statement(statement, location);
         self.graph.update_deps(location.statement_index);
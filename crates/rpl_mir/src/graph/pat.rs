@@ -46,6 +46,17 @@ impl<'tcx> PatternVisitor<'tcx> for BlockDataDepGraphVisitor<'_, pat::Local> {
     fn visit_local(&mut self, local: pat::Local, pcx: PlaceContext, location: pat::Location) {
         self.graph.access_local(local, pcx, location.statement_index);
     }
+    fn visit_rvalue(&mut self, rvalue: &pat::Rvalue<'tcx>, location: pat::Location) {
+        // See `visit_rvalue` of the MIR graph.
+        if let &pat::Rvalue::BinaryOp(op, box [ref lhs, ref rhs]) = rvalue
+            && rpl_mir_transform::unified_comparison(op).is_some()
+        {
+            self.visit_operand(rhs, location);
+            self.visit_operand(lhs, location);
+        } else {
+            self.super_rvalue(rvalue, location);
+        }
+    }
     fn visit_statement(&mut self, statement: &pat::StatementKind<'tcx>, location: pat::Location) {
         self.super_statement(statement, location);
         self.graph.update_deps(location.statement_index);
@@ -61,11 +72,24 @@ pub fn normalized_terminator_edges(
     terminator: Option<&pat::TerminatorKind<'_>>,
     pointer_bytes: u64,
 ) -> PatTerminatorEdges {
-    use pat::TerminatorKind::{Call, Drop, Goto, PatEnd, Return, SwitchInt};
+    use pat::TerminatorKind::{
+        Assert, Call, CoroutineDrop, Drop, Goto, InlineAsm, PatEnd, Return, SwitchInt, TailCall, Unreachable,
+        UnwindResume, Yield,
+    };
     match terminator {
-        None | Some(Return | PatEnd) => TerminatorEdges::None,
-        Some(&Goto(target) | &Drop { target, .. }) => TerminatorEdges::Single(target),
-        Some(&Call { target, .. }) => TerminatorEdges::AssignOnReturn {
+        None | Some(Return | TailCall { .. } | CoroutineDrop | UnwindResume | Unreachable | PatEnd) => {
+            TerminatorEdges::None
+        },
+        Some(&Goto(target) | &Yield { resume: target, .. }) => TerminatorEdges::Single(target),
+        Some(&Drop { target, unwind, .. } | &Assert { target, unwind, .. }) => match unwind {
+            Some(unwind) => TerminatorEdges::Double(target, unwind),
+            None => TerminatorEdges::Single(target),
+        },
+        Some(&Call { target, unwind, .. }) => TerminatorEdges::AssignOnReturn {
+            return_: Box::new([target]),
+            cleanup: unwind,
+        },
+        Some(&InlineAsm { target, .. }) => TerminatorEdges::AssignOnReturn {
             return_: Box::new([target]),
             cleanup: None,
         },
@@ -42,6 +42,7 @@ use rpl_context::PatCtxt;
 use rpl_match::{Candidates, MatchFnCtxt, MatchPlaceCtxt, MatchTyCtxt};
 use rpl_mir_graph::TerminatorEdges;
 use rustc_abi::{FieldIdx, VariantIdx};
+use rustc_ast::InlineAsmTemplatePiece;
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_hash::FxHashMap;
 use rustc_hir::def::CtorKind;
@@ -111,7 +112,23 @@ impl<'a, 'pcx, 'tcx> CheckMirCtxt<'a, 'pcx, 'tcx> {
         }
     }
     pub fn check(&self) -> Vec<Matched<'tcx>> {
-        matches::matches(self)
+        // Patterns with `#[applies_to]` only match the affected versions of the crate.
+        if let Some(applies_to) = &self.fn_pat.applies_to
+            && rpl_match::applied_version(self.ty.tcx, applies_to).is_none()
+        {
+            return Vec::new();
+        }
+        if !rpl_match::satisfies_fn_constraints(self.ty.tcx, self.body.source.def_id(), &self.fn_pat.constraints) {
+            return Vec::new();
+        }
+        // Regions are erased in MIR bodies, so the region variables are matched in the signature,
+        // as well as the type variables bounded in the `where` clause that appear in it.
+        if (!self.fn_pat.meta.region_vars.is_empty() || !self.fn_pat.predicates.is_empty()) && !self.match_fn_sig() {
+            return Vec::new();
+        }
+        let mut matches = matches::matches(self);
+        matches.retain(|matched| self.match_where_predicates(matched));
+        matches
     }
     /*
     pub fn check(&self) {
@@ -356,6 +373,8 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                     _ => false,
                 }
             },
+            // The `(_3.1: bool)` of `assert(!move (_3.1: bool), ..)` after an `AddWithOverflow`.
+            (_, ty::Tuple(_), pat::PlaceElem::Field(Unnamed(idx_pat)), Field(idx, _)) => idx_pat == idx,
             (_, _, pat::PlaceElem::Index(local_pat), Index(local)) => self.match_local(local_pat, local),
             (
                 _,
@@ -595,9 +614,40 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                 &mir::StatementKind::Assign(box (place, ref rvalue)),
             ) => self.match_rvalue(rvalue_pat, rvalue) && self.match_place(place_pat, place),
             (
-                pat::StatementKind::Assign(..),
-                // mir::StatementKind::Assign(..)
-                mir::StatementKind::FakeRead(..)
+                &pat::StatementKind::SetDiscriminant(place_pat, variant_index_pat),
+                &mir::StatementKind::SetDiscriminant {
+                    box place,
+                    variant_index,
+                },
+            ) => variant_index_pat == variant_index && self.match_place(place_pat, place),
+            (&pat::StatementKind::Deinit(place_pat), &mir::StatementKind::Deinit(box place)) => {
+                self.match_place(place_pat, place)
+            },
+            (
+                pat::StatementKind::Intrinsic(pat::NonDivergingIntrinsic::Assume(cond_pat)),
+                mir::StatementKind::Intrinsic(box mir::NonDivergingIntrinsic::Assume(cond)),
+            ) => self.match_operand(cond_pat, cond),
+            (
+                pat::StatementKind::Intrinsic(pat::NonDivergingIntrinsic::CopyNonOverlapping {
+                    src: src_pat,
+                    dst: dst_pat,
+                    count: count_pat,
+                }),
+                mir::StatementKind::Intrinsic(box mir::NonDivergingIntrinsic::CopyNonOverlapping(
+                    mir::CopyNonOverlapping { src, dst, count },
+                )),
+            ) => {
+                self.match_operand(src_pat, src)
+                    && self.match_operand(dst_pat, dst)
+                    && self.match_operand(count_pat, count)
+            },
+            (
+                pat::StatementKind::Assign(..)
+                | pat::StatementKind::SetDiscriminant(..)
+                | pat::StatementKind::Deinit(_)
+                | pat::StatementKind::Intrinsic(_),
+                mir::StatementKind::Assign(..)
+                | mir::StatementKind::FakeRead(..)
                 | mir::StatementKind::SetDiscriminant { .. }
                 | mir::StatementKind::Deinit(_)
                 | mir::StatementKind::StorageLive(_)
@@ -651,6 +701,7 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                     args: ref args_pat,
                     target: _,
                     destination: destination_pat,
+                    unwind: _,
                 },
                 &mir::TerminatorKind::Call {
                     ref func,
@@ -664,16 +715,60 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                     && self.match_spanned_operands(args_pat, args)
                     && destination_pat.is_none_or(|destination_pat| self.match_place(destination_pat, destination))
             },
+            (
+                pat::TerminatorKind::TailCall {
+                    func: func_pat,
+                    args: args_pat,
+                },
+                mir::TerminatorKind::TailCall { func, box args, .. },
+            ) => self.match_operand(func_pat, func) && self.match_spanned_operands(args_pat, args),
             (
                 &pat::TerminatorKind::Drop {
                     place: place_pat,
                     target: _,
+                    unwind: _,
                 },
                 &mir::TerminatorKind::Drop { place, target: _, .. },
             ) => self.match_place(place_pat, place),
+            (
+                &pat::TerminatorKind::Assert {
+                    cond: ref cond_pat,
+                    expected: expected_pat,
+                    msg: msg_pat,
+                    ..
+                },
+                &mir::TerminatorKind::Assert {
+                    ref cond,
+                    expected,
+                    box ref msg,
+                    ..
+                },
+            ) => {
+                expected_pat == expected
+                    && msg_pat.is_none_or(|msg_pat| msg_pat.matches(msg))
+                    && self.match_operand(cond_pat, cond)
+            },
+            (
+                &pat::TerminatorKind::InlineAsm {
+                    template: template_pat,
+                    target: _,
+                },
+                &mir::TerminatorKind::InlineAsm { template, .. },
+            ) => template_pat
+                .is_none_or(|template_pat| template_pat.as_str() == InlineAsmTemplatePiece::to_string(template)),
+            (
+                pat::TerminatorKind::Yield {
+                    value: value_pat,
+                    resume: _,
+                },
+                mir::TerminatorKind::Yield { value, .. },
+            ) => self.match_operand(value_pat, value),
             // Trivial matches, do not need to print
             (pat::TerminatorKind::Goto(_), mir::TerminatorKind::Goto { .. })
             | (pat::TerminatorKind::Return, mir::TerminatorKind::Return)
+            | (pat::TerminatorKind::CoroutineDrop, mir::TerminatorKind::CoroutineDrop)
+            | (pat::TerminatorKind::UnwindResume, mir::TerminatorKind::UnwindResume)
+            | (pat::TerminatorKind::Unreachable, mir::TerminatorKind::Unreachable)
             | (pat::TerminatorKind::PatEnd, _) => return true,
             (
                 pat::TerminatorKind::SwitchInt { operand, targets: _ },
@@ -683,7 +778,14 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                 pat::TerminatorKind::SwitchInt { .. }
                 | pat::TerminatorKind::Goto(_)
                 | pat::TerminatorKind::Call { .. }
+                | pat::TerminatorKind::TailCall { .. }
                 | pat::TerminatorKind::Drop { .. }
+                | pat::TerminatorKind::Assert { .. }
+                | pat::TerminatorKind::InlineAsm { .. }
+                | pat::TerminatorKind::Yield { .. }
+                | pat::TerminatorKind::CoroutineDrop
+                | pat::TerminatorKind::UnwindResume
+                | pat::TerminatorKind::Unreachable
                 | pat::TerminatorKind::Return,
                 // | pat::TerminatorKind::PatEnd,
                 mir::TerminatorKind::Goto { .. }
@@ -784,8 +886,15 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                 &mir::Rvalue::Cast(cast_kind, ref operand, ty),
             ) => cast_kind_pat == cast_kind && self.match_operand(operand_pat, operand) && self.ty.match_ty(ty_pat, ty),
             (pat::Rvalue::BinaryOp(op_pat, box [lhs_pat, rhs_pat]), mir::Rvalue::BinaryOp(op, box (lhs, rhs))) => {
+                // Compare `Gt` and `Ge` as `Lt` and `Le` with the operands swapped on both sides, as
+                // the MIR may or may not have been through `unify_comparison`.
+                let (op_pat, lhs_pat, rhs_pat) = unify_comparison(*op_pat, lhs_pat, rhs_pat);
+                let (op, lhs, rhs) = unify_comparison(*op, lhs, rhs);
                 op_pat == op && self.match_operand(lhs_pat, lhs) && self.match_operand(rhs_pat, rhs)
             },
+            (pat::Rvalue::ArithOp(box [lhs_pat, rhs_pat]), mir::Rvalue::BinaryOp(op, box (lhs, rhs))) => {
+                pat::is_arith_op(*op) && self.match_operand(lhs_pat, lhs) && self.match_operand(rhs_pat, rhs)
+            },
             (&pat::Rvalue::NullaryOp(op_pat, ty_pat), &mir::Rvalue::NullaryOp(op, ty)) => {
                 op_pat == op && self.ty.match_ty(ty_pat, ty)
             },
@@ -795,6 +904,8 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
             (pat::Rvalue::Aggregate(agg_kind_pat, operands_pat), mir::Rvalue::Aggregate(box agg_kind, operands)) => {
                 self.match_aggregate(agg_kind_pat, operands_pat, agg_kind, operands)
             },
+            (&pat::Rvalue::ThreadLocalRef(path_with_args), &mir::Rvalue::ThreadLocalRef(def_id)) => path_with_args
+                .is_none_or(|path_with_args| self.ty.match_path_with_args(path_with_args, def_id, ty::List::empty())),
             (&pat::Rvalue::ShallowInitBox(ref operand_pat, ty_pat), &mir::Rvalue::ShallowInitBox(ref operand, ty)) => {
                 self.match_operand(operand_pat, operand) && self.ty.match_ty(ty_pat, ty)
             },
@@ -807,10 +918,12 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                 | pat::Rvalue::Len(_)
                 | pat::Rvalue::Cast(..)
                 | pat::Rvalue::BinaryOp(..)
+                | pat::Rvalue::ArithOp(..)
                 | pat::Rvalue::NullaryOp(..)
                 | pat::Rvalue::UnaryOp(..)
                 | pat::Rvalue::Discriminant(_)
                 | pat::Rvalue::Aggregate(..)
+                | pat::Rvalue::ThreadLocalRef(_)
                 | pat::Rvalue::ShallowInitBox(..)
                 | pat::Rvalue::CopyForDeref(_),
                 mir::Rvalue::Use(_)
@@ -872,6 +985,54 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
         matched
     }
 
+    /// The function, or the function a coroutine is defined in, whose signature and `where`
+    /// clause are matched.
+    fn fn_def_id(&self) -> DefId {
+        let def_id = self.body.source.def_id();
+        if self.ty.tcx.is_coroutine(def_id) {
+            self.ty.tcx.typeck_root_def_id(def_id)
+        } else {
+            def_id
+        }
+    }
+
+    /// Match the signature of the function against the signature of the pattern.
+    ///
+    /// The types matched by the type variables in the signature become candidates of the type
+    /// variables in the body, so a type variable that only appears in the signature can be matched.
+    /// The `where` predicates on the type variables matched here are checked here, and the others
+    /// are checked in [`Self::match_where_predicates`] after the body is matched.
+    fn match_fn_sig(&self) -> bool {
+        let def_id = self.fn_def_id();
+        let fn_cx = MatchFnCtxt::new(self.ty.tcx, self.ty.pcx, self.ty.pat, self.fn_pat);
+        if !fn_cx.match_fn_sig(def_id) {
+            return false;
+        }
+        let typing_env = ty::TypingEnv::non_body_analysis(self.ty.tcx, def_id);
+        if !self.fn_pat.predicates.iter().all(|predicate| {
+            fn_cx.ty.ty_vars[predicate.ty_var]
+                .borrow()
+                .iter()
+                .all(|&ty| fn_cx.ty.match_where_predicate(predicate, ty, typing_env))
+        }) {
+            return false;
+        }
+        for (candidates, tys) in std::iter::zip(&self.ty.ty_vars, fn_cx.ty.ty_vars) {
+            candidates.borrow_mut().extend(tys.into_inner());
+        }
+        true
+    }
+
+    /// Whether the types matched in the body satisfy the `where` predicates, such as the type of
+    /// an iterator local bounded by `$I: std::iter::ExactSizeIterator`.
+    fn match_where_predicates(&self, matched: &Matched<'tcx>) -> bool {
+        let typing_env = ty::TypingEnv::non_body_analysis(self.ty.tcx, self.fn_def_id());
+        self.fn_pat.predicates.iter().all(|predicate| {
+            self.ty
+                .match_where_predicate(predicate, matched.ty_vars[predicate.ty_var], typing_env)
+        })
+    }
+
     fn match_fn_pat(&self, fn_pat: Symbol, fn_did: DefId) -> bool {
         let fn_pat = self
             .ty
@@ -990,8 +1151,17 @@ impl<'pcx, 'tcx> CheckMirCtxt<'_, 'pcx, 'tcx> {
                     && mutability_pat == mutability
                     && self.match_operands(operands_pat, &operands.raw)
             },
+            (pat::AggKind::Closure, mir::AggregateKind::Closure(..))
+            | (pat::AggKind::Coroutine, mir::AggregateKind::Coroutine(..)) => {
+                self.match_operands(operands_pat, &operands.raw)
+            },
             (
-                pat::AggKind::Array | pat::AggKind::Tuple | pat::AggKind::Adt(..) | pat::AggKind::RawPtr(..),
+                pat::AggKind::Array
+                | pat::AggKind::Tuple
+                | pat::AggKind::Adt(..)
+                | pat::AggKind::RawPtr(..)
+                | pat::AggKind::Closure
+                | pat::AggKind::Coroutine,
                 mir::AggregateKind::Array(_)
                 | mir::AggregateKind::Tuple
                 | mir::AggregateKind::Adt(..)
@@ -1066,3 +1236,12 @@ impl<'pcx> CheckMirCtxt<'_, 'pcx, '_> {
         // self.body.local_decls[place.local].ty
     }
 }
+
+/// Turn `Gt(lhs, rhs)` and `Ge(lhs, rhs)` into `Lt(rhs, lhs)` and `Le(rhs, lhs)`, see
+/// [`rpl_mir_transform::unified_comparison`].
+fn unify_comparison<T>(op: mir::BinOp, lhs: T, rhs: T) -> (mir::BinOp, T, T) {
+    match rpl_mir_transform::unified_comparison(op) {
+        Some(op) => (op, rhs, lhs),
+        None => (op, lhs, rhs),
+    }
+}
@@ -787,6 +787,7 @@ impl<'a, 'pcx, 'tcx> MatchCtxt<'a, 'pcx, 'tcx> {
                 AssignOnReturn { box return_, cleanup },
             ) => {
                 return_pat.len() == return_.len()
+                    && (cleanup_pat.is_none() || cleanup.is_some())
                     && core::iter::zip(return_pat, return_)
                         .chain(cleanup_pat.as_ref().zip(cleanup.as_ref()))
                         .all(|(&bb_pat, &bb)| self.match_block_starts_with(bb_pat, bb))
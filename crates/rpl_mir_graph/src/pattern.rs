@@ -53,10 +53,22 @@ impl<'a> PatControlFlowGraph<'a> {
 
 impl<'a> PatTerminatorEdges<'a> {
     fn from_normalized(terminator: Option<&'a pat::TerminatorKind<'_>>, pointer_bytes: usize) -> Self {
-        use pat::TerminatorKind::{Call, Drop, Goto, PatEnd, Return, SwitchInt};
+        use pat::TerminatorKind::{
+            Assert, Call, CoroutineDrop, Drop, Goto, InlineAsm, PatEnd, Return, SwitchInt, TailCall, Unreachable,
+            UnwindResume, Yield,
+        };
         match terminator {
-            None | Some(Return | PatEnd) => TerminatorEdges::None,
-            Some(&Goto(target) | &Call { target, .. } | &Drop { target, .. }) => TerminatorEdges::Single(target),
+            None | Some(Return | TailCall { .. } | CoroutineDrop | UnwindResume | Unreachable | PatEnd) => {
+                TerminatorEdges::None
+            },
+            Some(
+                &Goto(target)
+                | &Call { target, .. }
+                | &Drop { target, .. }
+                | &Assert { target, .. }
+                | &InlineAsm { target, .. }
+                | &Yield { resume: target, .. },
+            ) => TerminatorEdges::Single(target),
             Some(SwitchInt { targets, .. }) => {
                 TerminatorEdges::SwitchInt(PatSwitchTargets::from_normalized(targets, pointer_bytes))
             },
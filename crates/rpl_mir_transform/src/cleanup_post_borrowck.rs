@@ -0,0 +1,38 @@
+use rustc_middle::mir::coverage::CoverageKind;
+use rustc_middle::mir::{Body, BorrowKind, CastKind, Rvalue, StatementKind, TerminatorKind};
+use rustc_middle::ty::adjustment::PointerCoercion;
+
+/// A copy of the private `CleanupPostBorrowck` pass of `rustc_mir_transform`, plus the removal of
+/// `PlaceMention`s, which turns built MIR into the shape the detectors expect from optimized MIR.
+///
+/// See <https://doc.rust-lang.org/nightly/nightly-rustc/src/rustc_mir_transform/cleanup_post_borrowck.rs.html>
+pub(crate) fn cleanup_post_borrowck(body: &mut Body<'_>) {
+    for block in body.basic_blocks.as_mut() {
+        for statement in &mut block.statements {
+            match statement.kind {
+                StatementKind::AscribeUserType(..)
+                | StatementKind::Assign(box (_, Rvalue::Ref(_, BorrowKind::Fake(_), _)))
+                | StatementKind::Coverage(CoverageKind::BlockMarker { .. } | CoverageKind::SpanMarker)
+                | StatementKind::FakeRead(..)
+                | StatementKind::PlaceMention(..) => statement.make_nop(),
+                StatementKind::Assign(box (
+                    _,
+                    Rvalue::Cast(
+                        ref mut cast_kind @ CastKind::PointerCoercion(
+                            PointerCoercion::ArrayToPointer | PointerCoercion::MutToConstPointer,
+                            _,
+                        ),
+                        ..,
+                    ),
+                )) => *cast_kind = CastKind::PtrToPtr,
+                _ => {},
+            }
+        }
+        let terminator = block.terminator_mut();
+        if let TerminatorKind::FalseEdge { real_target, .. } | TerminatorKind::FalseUnwind { real_target, .. } =
+            terminator.kind
+        {
+            terminator.kind = TerminatorKind::Goto { target: real_target };
+        }
+    }
+}
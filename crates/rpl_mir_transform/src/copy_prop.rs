@@ -0,0 +1,136 @@
+use rustc_index::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, MutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{Body, Local, Location, Operand, Place, Rvalue, Statement, StatementKind};
+use rustc_middle::ty::TyCtxt;
+
+/// A simplified `CopyProp` pass of `rustc_mir_transform`, which only runs on optimized MIR.
+///
+/// For a temporary assigned once by `_t = copy _s` (or `move _s`), where `_s` is assigned at most
+/// once and never mutably borrowed, replace the uses of `_t` with `_s`. For an example, it turns
+///
+/// ```ignore (MIR)
+/// _5 = Option::<*const u8>::unwrap(move _6);
+/// _31 = copy _5;
+/// _30 = std::slice::from_raw_parts::<'_, u8>(move _31, move _32);
+/// ```
+///
+/// into:
+///
+/// ```ignore (MIR)
+/// _5 = Option::<*const u8>::unwrap(move _6);
+/// _30 = std::slice::from_raw_parts::<'_, u8>(copy _5, move _32);
+/// ```
+pub(crate) fn copy_prop<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+    let mut defs = LocalDefs {
+        stores: IndexVec::from_elem(0, &body.local_decls),
+        mutated: IndexVec::from_elem(false, &body.local_decls),
+    };
+    defs.visit_body(body);
+    let is_ssa = |local: Local| {
+        let assigned_on_entry = local.as_usize() <= body.arg_count;
+        !defs.mutated[local] && defs.stores[local] == usize::from(!assigned_on_entry)
+    };
+
+    // The local each temporary is a copy of, and whether it is moved from the local.
+    let mut copies: IndexVec<Local, Option<(Local, bool)>> = IndexVec::from_elem(None, &body.local_decls);
+    for block in body.basic_blocks.iter() {
+        for statement in &block.statements {
+            if let StatementKind::Assign(box (dest, Rvalue::Use(Operand::Copy(src) | Operand::Move(src)))) =
+                &statement.kind
+                && let Some(dest) = dest.as_local()
+                && let Some(src) = src.as_local()
+                && dest.as_usize() > body.arg_count
+                && !body.local_decls[dest].is_user_variable()
+                && is_ssa(dest)
+                && is_ssa(src)
+            {
+                let moved = matches!(
+                    statement.kind,
+                    StatementKind::Assign(box (_, Rvalue::Use(Operand::Move(_))))
+                );
+                // Follow the chain of copies, `_t1 = copy _s; _t2 = move _t1;`.
+                copies[dest] = Some(match copies[src] {
+                    Some((src, moved_src)) => (src, moved_src && moved),
+                    None => (src, moved),
+                });
+            }
+        }
+    }
+
+    Replacer { tcx, copies }.visit_body_preserves_cfg(body);
+}
+
+struct LocalDefs {
+    stores: IndexVec<Local, usize>,
+    mutated: IndexVec<Local, bool>,
+}
+
+impl Visitor<'_> for LocalDefs {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, _location: Location) {
+        match context {
+            PlaceContext::MutatingUse(
+                MutatingUseContext::Store
+                | MutatingUseContext::Call
+                | MutatingUseContext::Yield
+                | MutatingUseContext::AsmOutput,
+            ) => self.stores[local] += 1,
+            PlaceContext::MutatingUse(
+                MutatingUseContext::Borrow
+                | MutatingUseContext::RawBorrow
+                | MutatingUseContext::Projection
+                | MutatingUseContext::SetDiscriminant
+                | MutatingUseContext::Deinit,
+            ) => self.mutated[local] = true,
+            _ => {},
+        }
+    }
+
+    fn visit_place(&mut self, place: &Place<'_>, context: PlaceContext, location: Location) {
+        // Assigning to a projection, `_1.0 = ..`, mutates `_1` instead of defining it.
+        let context = match context {
+            PlaceContext::MutatingUse(_) if !place.projection.is_empty() => {
+                PlaceContext::MutatingUse(MutatingUseContext::Projection)
+            },
+            context => context,
+        };
+        self.visit_local(place.local, context, location);
+    }
+}
+
+struct Replacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    copies: IndexVec<Local, Option<(Local, bool)>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = *operand
+            && let Some((_, false)) = self.copies[place.local]
+        {
+            *operand = Operand::Copy(place);
+        }
+        self.super_operand(operand, location);
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _context: PlaceContext, _location: Location) {
+        if let Some((src, _)) = self.copies[*local] {
+            *local = src;
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::Assign(box (dest, _)) if dest.as_local().is_some_and(|dest| self.copies[dest].is_some()) => {
+                statement.make_nop();
+            },
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) if self.copies[local].is_some() => {
+                statement.make_nop();
+            },
+            _ => self.super_statement(statement, location),
+        }
+    }
+}
@@ -1,9 +1,12 @@
 #![feature(rustc_private)]
 #![feature(box_patterns)]
+#![feature(let_chains)]
 
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_hir;
+extern crate rustc_index;
+extern crate rustc_interface;
 extern crate rustc_middle;
 extern crate rustc_mir_build;
 extern crate rustc_mir_transform;
@@ -18,12 +21,65 @@ use rustc_middle::ty::{self, TyCtxt};
 use rustc_middle::util::Providers;
 use rustc_mir_transform::run_analysis_to_runtime_passes;
 
+mod cleanup_post_borrowck;
+mod copy_prop;
 mod unify_comparison;
 
+pub use unify_comparison::unified_comparison;
+
 pub fn provide(providers: &mut Providers) {
     providers.queries.mir_drops_elaborated_and_const_checked = mir_drops_elaborated_and_const_checked;
 }
 
+/// Keep the built MIR of coroutines for [`coroutine_body`], before it is stolen to be lowered
+/// into a state machine. The MIR of all functions, including the one for codegen, is still left
+/// to the default providers.
+///
+/// Coroutines never have MIR for CTFE, so their `mir_for_ctfe` is taken over to cache the MIR
+/// to match against, which is computed by `mir_drops_elaborated_and_const_checked` right before
+/// it steals the built MIR.
+pub fn provide_coroutine_bodies(providers: &mut Providers) {
+    providers.queries.mir_drops_elaborated_and_const_checked = |tcx, def| {
+        if tcx.is_coroutine(def.to_def_id()) {
+            tcx.ensure_done().mir_for_ctfe(def);
+        }
+        (rustc_interface::DEFAULT_QUERY_PROVIDERS
+            .queries
+            .mir_drops_elaborated_and_const_checked)(tcx, def)
+    };
+    providers.queries.mir_for_ctfe = |tcx, def| {
+        if !tcx.is_coroutine(def.to_def_id()) {
+            return (rustc_interface::DEFAULT_QUERY_PROVIDERS.queries.mir_for_ctfe)(tcx, def);
+        }
+        let mut body = tcx.mir_promoted(def).0.borrow().clone();
+        cleanup_post_borrowck::cleanup_post_borrowck(&mut body);
+        copy_prop::copy_prop(tcx, &mut body);
+        unify_comparison::unify_comparison(tcx, &mut body);
+        tcx.arena.alloc(body)
+    };
+}
+
+/// The MIR of `def` to match patterns against.
+///
+/// This is the optimized MIR, except for coroutines (the bodies of `async fn`s, `async` blocks
+/// and `async` closures), see [`coroutine_body`].
+pub fn mir_for_matching(tcx: TyCtxt<'_>, def: LocalDefId) -> &Body<'_> {
+    if tcx.is_coroutine(def.to_def_id()) {
+        coroutine_body(tcx, def)
+    } else {
+        tcx.optimized_mir(def)
+    }
+}
+
+/// The MIR of coroutine `def` before it is lowered into a state machine, so that its locals live
+/// across `.await`s, and its suspension points are kept as `Yield` terminators.
+///
+/// This requires [`provide_coroutine_bodies`] to be registered, and is computed once per
+/// coroutine.
+pub fn coroutine_body(tcx: TyCtxt<'_>, def: LocalDefId) -> &Body<'_> {
+    tcx.mir_for_ctfe(def)
+}
+
 /// See <https://doc.rust-lang.org/nightly/nightly-rustc/src/rustc_mir_transform/lib.rs.html#479-554>
 ///
 /// This is not the most suitable query to override, but it's the only one that is easy to work
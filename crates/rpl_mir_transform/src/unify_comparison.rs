@@ -30,15 +30,22 @@ use rustc_middle::ty::TyCtxt;
 pub(crate) fn unify_comparison(_tcx: TyCtxt<'_>, body: &mut Body<'_>) {
     for block in body.basic_blocks_mut() {
         for statement in &mut block.statements {
-            if let StatementKind::Assign(box (_, Rvalue::BinaryOp(op, box (lhs, rhs)))) = &mut statement.kind {
-                *op = match op {
-                    BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le => continue,
-                    BinOp::Gt => BinOp::Lt,
-                    BinOp::Ge => BinOp::Le,
-                    _ => continue,
-                };
+            if let StatementKind::Assign(box (_, Rvalue::BinaryOp(op, box (lhs, rhs)))) = &mut statement.kind
+                && let Some(unified) = unified_comparison(*op)
+            {
+                *op = unified;
                 swap(lhs, rhs);
             }
         }
     }
 }
+
+/// The comparison that `op` is turned into by [`unify_comparison`], with the operands swapped,
+/// or `None` if `op` is kept as is.
+pub fn unified_comparison(op: BinOp) -> Option<BinOp> {
+    match op {
+        BinOp::Gt => Some(BinOp::Lt),
+        BinOp::Ge => Some(BinOp::Le),
+        _ => None,
+    }
+}
@@ -1,20 +1,49 @@
 use crate::SymbolTable;
-use crate::symbol_table::{CheckError, Enum, ExportKind, FnInner, ImplInner, MetaTable, Variant};
+use crate::module::Module;
+use crate::symbol_table::{
+    CheckError, Enum, ExportKind, FnInner, FragmentInner, FragmentTable, ImplInner, MetaTable, SymbolKind, TypeTable,
+    Variant,
+};
+use proc_macro2::{TokenStream, TokenTree};
 use quote::ToTokens;
 use rpl_pat_syntax::*;
 use rustc_span::Symbol;
-use syn::Ident;
 use syn::parse::Parse;
+use syn::punctuated::Punctuated;
+use syn::{Ident, Token};
 
-pub(crate) fn check_pattern(pattern: &Pattern) -> syn::Result<SymbolTable<'_>> {
+/// The kinds of assert messages, see `rpl_context::pat::AssertKind`.
+const ASSERT_KINDS: &[&str] = &[
+    "BoundsCheck",
+    "Overflow",
+    "OverflowNeg",
+    "DivisionByZero",
+    "RemainderByZero",
+    "ResumedAfterReturn",
+    "ResumedAfterPanic",
+    "MisalignedPointerDereference",
+    "NullPointerDereference",
+];
+
+pub(crate) fn check_pattern<'pat>(
+    pattern: &'pat Pattern,
+    modules: &'pat [Module<'pat>],
+) -> syn::Result<SymbolTable<'pat>> {
     rustc_span::create_session_if_not_set_then(rustc_span::edition::LATEST_STABLE_EDITION, |_| {
-        CheckCtxt::default().check_pattern(pattern)
+        let mut cx = CheckCtxt::default();
+        for Module { ident, pattern } in modules {
+            cx.fragments
+                .add_module(ident, CheckCtxt::default().check_module(pattern)?)?;
+        }
+        cx.check_pattern(pattern)
     })
 }
 
 #[derive(Default)]
 struct CheckCtxt<'pat> {
     symbols: SymbolTable<'pat>,
+    types: TypeTable<'pat>,
+    fragments: FragmentTable<'pat>,
 }
 
 struct CheckFnCtxt<'a, 'pat> {
@@ -22,6 +51,10 @@ struct CheckFnCtxt<'a, 'pat> {
     meta_table: &'a mut MetaTable<'pat>,
     impl_def: Option<&'a mut ImplInner<'pat>>,
     fn_def: &'a mut FnInner<'pat>,
+    types: &'a TypeTable<'pat>,
+    fragments: &'a FragmentTable<'pat>,
+    /// The generic parameters of the `type` or `fragment` item being checked.
+    type_params: Option<&'pat TypeGenerics>,
 }
 
 struct CheckVariantCtxt<'a, 'pat> {
@@ -37,21 +70,72 @@ struct CheckEnumCtxt<'a, 'pat> {
 
 impl<'pat> CheckCtxt<'pat> {
     fn check_pattern(mut self, pattern: &'pat Pattern) -> syn::Result<SymbolTable<'pat>> {
-        for item in &pattern.items {
-            self.check_item(item)?;
+        // `type` items are checked first, so that they can be used by all `fn` patterns, and then
+        // the `fragment` items, which can be used by all `fn` patterns and other fragments.
+        let mut items = pattern.items.iter().collect::<Vec<_>>();
+        items.sort_by_key(|item| match item.kind {
+            ItemKind::Type(_) => 0,
+            ItemKind::Fragment(_) => 1,
+            _ => 2,
+        });
+        let fragments = items
+            .iter()
+            .filter_map(|item| match &item.kind {
+                ItemKind::Fragment(fragment) => Some(fragment),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for item in &items {
+            self.check_item(item, &fragments)?;
         }
+        self.symbols.set_types(self.types);
+        self.symbols.set_fragments(self.fragments);
         Ok(self.symbols)
     }
-    fn check_item(&mut self, item: &'pat Item) -> syn::Result<()> {
+    fn check_module(mut self, pattern: &'pat Pattern) -> syn::Result<FragmentTable<'pat>> {
+        let fragments = pattern
+            .items
+            .iter()
+            .filter_map(|item| match &item.kind {
+                ItemKind::Fragment(fragment) => Some(fragment),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for item in &pattern.items {
+            self.check_item(item, &fragments)?;
+        }
+        Ok(self.fragments)
+    }
+    fn check_item(&mut self, item: &'pat Item, fragments: &[&'pat syntax::Fragment]) -> syn::Result<()> {
+        if let Some(applies_to) = &item.applies_to {
+            check_applies_to(applies_to, &item.kind)?;
+        }
+        if let Some(constraints) = &item.constraints {
+            check_constraints(constraints, &item.kind)?;
+        }
         let meta = item.meta.as_ref();
         match &item.kind {
-            ItemKind::Fn(fn_pat) => self.check_fn(meta, fn_pat),
+            ItemKind::Fn(fn_pat) => self.check_fn(meta, item.constraints.as_ref(), fn_pat),
+            ItemKind::Type(type_decl) => self.check_type_item(meta, type_decl),
+            ItemKind::Fragment(fragment) => {
+                if let Some(meta) = meta {
+                    return Err(syn::Error::new_spanned(meta, CheckError::MetaOnItem("fragment")));
+                }
+                self.check_fragment(fragment, fragments, &mut Vec::new())
+            },
+            // Pattern modules are loaded and checked before the pattern.
+            ItemKind::Mod(_) => Ok(()),
             ItemKind::Struct(struct_pat) => self.check_struct(meta, struct_pat),
             ItemKind::Enum(enum_pat) => self.check_enum(meta, enum_pat),
-            ItemKind::Impl(_impl_pat) => todo!(), // self.check_impl(impl_pat),
+            ItemKind::Impl(impl_pat) => self.check_impl(meta, impl_pat),
         }
     }
-    fn check_fn(&mut self, meta: Option<&'pat Meta>, fn_pat: &'pat FnPat) -> syn::Result<()> {
+    fn check_fn(
+        &mut self,
+        meta: Option<&'pat Meta>,
+        constraints: Option<&'pat Constraints>,
+        fn_pat: &'pat FnPat,
+    ) -> syn::Result<()> {
         let fn_def = self.symbols.add_fn(&fn_pat.sig.ident, None)?;
         let meta_table = &mut fn_def.meta;
         let fn_def = &mut fn_def.inner;
@@ -60,8 +144,90 @@ impl<'pat> CheckCtxt<'pat> {
             impl_def: None,
             meta_table,
             fn_def,
+            types: &self.types,
+            fragments: &self.fragments,
+            type_params: None,
         }
-        .check_fn(fn_pat)
+        .check_fn(constraints, fn_pat)
+    }
+    fn check_type_item(&mut self, meta: Option<&'pat Meta>, type_decl: &'pat TypeDecl) -> syn::Result<()> {
+        if let Some(meta) = meta {
+            return Err(syn::Error::new_spanned(meta, CheckError::MetaOnItem("type")));
+        }
+        CheckFnCtxt {
+            meta: None,
+            impl_def: None,
+            meta_table: &mut MetaTable::default(),
+            fn_def: &mut FnInner::new(type_decl.ident.span(), None),
+            types: &self.types,
+            fragments: &self.fragments,
+            type_params: type_decl.generics.as_ref(),
+        }
+        .check_type_item(type_decl)?;
+        self.types.add_type(type_decl)
+    }
+    /// Checks a fragment after the fragments used by it, which are declared in `fragments`, and
+    /// reports a cycle if it is already on the `stack` of fragments being checked.
+    fn check_fragment(
+        &mut self,
+        fragment: &'pat syntax::Fragment,
+        fragments: &[&'pat syntax::Fragment],
+        stack: &mut Vec<&'pat Ident>,
+    ) -> syn::Result<()> {
+        let ident = &fragment.ident;
+        if self.fragments.contains_fragment(ident) {
+            return Ok(());
+        }
+        if let Some(pos) = stack.iter().position(|&used| used == ident) {
+            let cycle = stack[pos..]
+                .iter()
+                .chain([&ident])
+                .map(|ident| format!("`{ident}`"))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(syn::Error::new(ident.span(), CheckError::FragmentCycle(ident, cycle)));
+        }
+        stack.push(ident);
+        for decl in &fragment.body.mir.declarations {
+            if let Declaration::FragmentUse(FragmentUse {
+                module: None, ident, ..
+            }) = decl
+                && let Some(used) = fragments.iter().find(|fragment| &fragment.ident == ident)
+            {
+                self.check_fragment(used, fragments, stack)?;
+            }
+        }
+        stack.pop();
+
+        let mut meta_table = MetaTable::default();
+        let mut fn_def = FnInner::new(ident.span(), None);
+        let mut cx = CheckFnCtxt {
+            meta: None,
+            impl_def: None,
+            meta_table: &mut meta_table,
+            fn_def: &mut fn_def,
+            types: &self.types,
+            fragments: &self.fragments,
+            type_params: fragment.generics.as_ref(),
+        };
+        cx.check_fragment(fragment)?;
+        self.fragments.add_fragment(FragmentInner { fragment, fn_def }.into())
+    }
+    fn check_impl(&mut self, meta: Option<&'pat Meta>, impl_pat: &'pat syntax::Impl) -> syn::Result<()> {
+        if let Some(item) = impl_pat.items.first() {
+            return Err(syn::Error::new_spanned(item, CheckError::ImplItemsNotSupported));
+        }
+        let impl_def = self.symbols.add_impl(impl_pat);
+        CheckFnCtxt {
+            meta,
+            meta_table: &mut impl_def.meta,
+            impl_def: Some(&mut impl_def.inner),
+            fn_def: &mut FnInner::new(impl_pat.brace.span.join(), Some(&impl_pat.ty)),
+            types: &self.types,
+            fragments: &self.fragments,
+            type_params: None,
+        }
+        .check_impl(impl_pat)
     }
     fn check_struct(&mut self, meta: Option<&'pat Meta>, struct_pat: &'pat Struct) -> syn::Result<()> {
         let struct_def = self.symbols.add_struct(&struct_pat.ident)?;
@@ -81,20 +247,227 @@ impl<'pat> CheckCtxt<'pat> {
     }
 }
 
+fn check_applies_to(applies_to: &AppliesTo, kind: &ItemKind) -> syn::Result<()> {
+    let AppliesToItem { krate, version, .. } = &applies_to.inner;
+    if !matches!(kind, ItemKind::Fn(_)) {
+        return Err(syn::Error::new_spanned(applies_to, CheckError::AppliesToNonFn));
+    }
+    if krate.value().trim().is_empty() {
+        return Err(syn::Error::new_spanned(krate, CheckError::AppliesToEmptyCrate));
+    }
+    rpl_config::VersionReq::parse(&version.value())
+        .map_err(|err| syn::Error::new_spanned(version, CheckError::AppliesToInvalidVersion(err)))?;
+    Ok(())
+}
+
+fn check_constraints(constraints: &Constraints, kind: &ItemKind) -> syn::Result<()> {
+    if !matches!(kind, ItemKind::Fn(_)) {
+        return Err(syn::Error::new_spanned(constraints, CheckError::ConstraintsOnNonFn));
+    }
+    let constraints = &constraints.inner;
+    for (i, Constraint { kind, .. }) in constraints.iter().enumerate() {
+        let key = kind.to_token_stream().to_string();
+        if constraints
+            .iter()
+            .take(i)
+            .any(|prev| prev.kind.to_token_stream().to_string() == key)
+        {
+            return Err(syn::Error::new_spanned(kind, CheckError::DuplicatedConstraint(key)));
+        }
+    }
+    Ok(())
+}
+
+fn check_abi(abi: &Option<syn::Abi>) -> syn::Result<()> {
+    if let Some(syn::Abi { name: Some(name), .. }) = abi
+        && name.value().parse::<rustc_abi::ExternAbi>().is_err()
+    {
+        return Err(syn::Error::new_spanned(name, CheckError::UnknownAbi(name.value())));
+    }
+    Ok(())
+}
+
 impl<'pat> CheckFnCtxt<'_, 'pat> {
-    fn check_fn(mut self, fn_pat: &'pat FnPat) -> syn::Result<()> {
+    fn check_type_item(&self, type_decl: &'pat TypeDecl) -> syn::Result<()> {
+        let TypeDecl { generics, ty, .. } = type_decl;
+        self.check_type_params(generics.as_ref())?;
+        self.check_type(ty)?;
+        if let Some(ident) = generics
+            .iter()
+            .flat_map(|generics| &generics.params)
+            .map(TypeParam::ident)
+            .find(|ident| !mentions_var(ty.to_token_stream(), ident))
+        {
+            return Err(syn::Error::new(ident.span(), CheckError::UnusedTypeParam(ident)));
+        }
+        Ok(())
+    }
+
+    fn check_type_params(&self, generics: Option<&'pat TypeGenerics>) -> syn::Result<()> {
+        let params = generics.iter().flat_map(|generics| &generics.params);
+        for (i, param) in params.clone().enumerate() {
+            let ident = param.ident();
+            if params.clone().take(i).any(|prev| prev.ident() == ident) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    match param {
+                        TypeParam::Type(_) => CheckError::TypeVarAlreadyDeclared(ident),
+                        TypeParam::Const(_) => CheckError::ConstVarAlreadyDeclared(ident),
+                    },
+                ));
+            }
+            if let TypeParam::Const(ConstParam { ty, .. }) = param
+                && !is_primitive_type(ty)
+            {
+                return Err(syn::Error::new_spanned(ty, CheckError::ConstParamNotPrimitive(ident)));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_fragment(&mut self, fragment: &'pat syntax::Fragment) -> syn::Result<()> {
+        let syntax::Fragment {
+            params, outputs, body, ..
+        } = fragment;
+        self.check_type_params(fragment.generics.as_ref())?;
+        for FragmentParam { ident, ty, .. } in params {
+            self.fn_def.add_param(ident, ty)?;
+            self.check_type(ty)?;
+        }
+        self.check_mir(&body.mir)?;
+        // Only the locals of the fragment can be exported, not its parameters.
+        for FragmentLocal { ident, .. } in outputs.iter().flat_map(|outputs| &outputs.locals) {
+            if params.iter().any(|param| &param.ident == ident) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    CheckError::SymbolNotDeclared(SymbolKind::Local, ident),
+                ));
+            }
+            self.fn_def.get_local(ident)?;
+        }
+        Ok(())
+    }
+
+    fn check_fragment_use(&mut self, fragment_use: &'pat FragmentUse) -> syn::Result<()> {
+        let FragmentUse {
+            module,
+            ident,
+            args,
+            inputs,
+            outputs,
+            ..
+        } = fragment_use;
+        let (fragment, _) = self
+            .fragments
+            .get_fragment(module.as_ref().map(|module| &module.ident), ident)?;
+        let FragmentInner { fragment, fn_def } = &fragment.inner;
+        self.check_type_args(SymbolKind::Fragment, ident, fragment.generics.as_ref(), args)?;
+        if inputs.len() != fragment.params.len() {
+            return Err(syn::Error::new(
+                ident.span(),
+                CheckError::FragmentInputCountMismatch(ident, fragment.params.len(), inputs.len()),
+            ));
+        }
+        for FragmentLocal { ident, .. } in inputs {
+            self.fn_def
+                .get_local(ident)
+                .map(|_| ())
+                .or_else(|_| self.meta_table.get_place_var(ident).map(|_| ()))?;
+        }
+        let exports = fragment.outputs.iter().flat_map(|outputs| &outputs.locals);
+        let outputs = outputs.iter().flat_map(|outputs| &outputs.locals);
+        if exports.clone().count() != outputs.clone().count() {
+            return Err(syn::Error::new(
+                ident.span(),
+                CheckError::FragmentOutputCountMismatch(ident, exports.count(), outputs.count()),
+            ));
+        }
+        for (export, output) in exports.zip(outputs) {
+            self.fn_def.add_local(&output.ident, fn_def.get_local(&export.ident)?)?;
+        }
+        Ok(())
+    }
+
+    fn get_type_param(&self, ident: &Ident) -> Option<&'pat TypeParam> {
+        self.type_params?.params.iter().find(|param| param.ident() == ident)
+    }
+
+    fn check_fn(mut self, constraints: Option<&'pat Constraints>, fn_pat: &'pat FnPat) -> syn::Result<()> {
         if let Some(meta) = self.meta {
             self.check_meta(meta)?;
         }
+        for Constraint { kind, .. } in constraints.into_iter().flat_map(|constraints| &constraints.inner) {
+            match kind {
+                ConstraintKind::Outlives(ConstraintOutlives { longer, shorter, .. }) => {
+                    self.check_region_var(longer)?;
+                    self.check_region_var(shorter)?;
+                },
+                ConstraintKind::Unbounded(ConstraintUnbounded { region, .. }) => self.check_region_var(region)?,
+                ConstraintKind::Generic(_) | ConstraintKind::TraitMethod(_) | ConstraintKind::Attr(_) => {},
+            }
+        }
         self.check_fn_sig(&fn_pat.sig)?;
         self.check_fn_body(&fn_pat.body)?;
         Ok(())
     }
+    fn check_impl(mut self, impl_pat: &'pat syntax::Impl) -> syn::Result<()> {
+        if let Some(meta) = self.meta {
+            self.check_meta(meta)?;
+        }
+        if let ImplKind::Trait(path, _) = &impl_pat.kind {
+            // Trait paths are not resolved to declared types, unlike type paths.
+            for segment in &path.segments {
+                self.check_generic_args(&segment.arguments)?;
+            }
+        }
+        self.check_type(&impl_pat.ty)?;
+        if let Some(where_clause) = &impl_pat.where_clause {
+            self.check_where_clause(where_clause)?;
+        }
+        Ok(())
+    }
+    fn check_where_clause(&self, where_clause: &WhereClause) -> syn::Result<()> {
+        for WherePredicate { ty, bounds, .. } in &where_clause.predicates {
+            let Type::TyVar(TypeVar { ident, .. }) = ty else {
+                return Err(syn::Error::new_spanned(ty, CheckError::WhereBoundOnNonTyVar));
+            };
+            self.check_ty_var(ident)?;
+            for WhereBound { bound, .. } in bounds {
+                match bound {
+                    TypeParamBound::Region(Region {
+                        kind: RegionKind::ReStatic(_),
+                        ..
+                    }) => {},
+                    TypeParamBound::Region(region) => {
+                        return Err(syn::Error::new_spanned(
+                            region,
+                            CheckError::UnsupportedRegionInWhereBound,
+                        ));
+                    },
+                    TypeParamBound::Trait(TraitBound { path, fn_args }) => {
+                        if fn_args.is_some()
+                            || path
+                                .segments
+                                .iter()
+                                .any(|segment| matches!(segment.arguments, PathArguments::AngleBracketed(_)))
+                        {
+                            return Err(syn::Error::new_spanned(bound, CheckError::GenericTraitInWhereBound));
+                        }
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
     fn check_fn_sig(&mut self, sig: &'pat FnSig) -> syn::Result<()> {
+        check_abi(&sig.abi)?;
         for param in &sig.params {
             self.check_fn_param(param)?;
         }
         self.check_fn_ret(&sig.ret)?;
+        if let Some(where_clause) = &sig.where_clause {
+            self.check_where_clause(where_clause)?;
+        }
         Ok(())
     }
     fn check_fn_param(&mut self, param: &'pat FnParam) -> syn::Result<()> {
@@ -211,7 +584,10 @@ impl<'pat> CheckEnumCtxt<'_, 'pat> {
 trait CheckMeta<'pat> {
     fn meta_table(&mut self) -> &mut MetaTable<'pat>;
     fn check_meta(&mut self, meta: &'pat Meta) -> syn::Result<()> {
-        meta.inner.iter().try_for_each(|item| self.check_meta_item(item))
+        meta.inner.iter().try_for_each(|decl| match decl {
+            MetaDecl::Region(region_var) => self.meta_table().add_region_var(region_var),
+            MetaDecl::Item(item) => self.check_meta_item(item),
+        })
     }
     fn check_meta_item(&mut self, meta_item: &'pat MetaItem) -> syn::Result<()> {
         let meta_table = self.meta_table();
@@ -254,7 +630,16 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
 
     fn check_decl(&mut self, decl: &'pat Declaration) -> syn::Result<()> {
         match decl {
-            Declaration::TypeDecl(TypeDecl { ty, ident, .. }) => self.fn_def.add_type(ident, ty),
+            Declaration::TypeDecl(type_decl) => {
+                if type_decl.generics.is_some() {
+                    return Err(syn::Error::new(
+                        type_decl.ident.span(),
+                        CheckError::GenericTypeInFn(&type_decl.ident),
+                    ));
+                }
+                self.fn_def.add_type(type_decl)
+            },
+            Declaration::FragmentUse(fragment_use) => self.check_fragment_use(fragment_use),
             Declaration::UsePath(UsePath { path, .. }) => self.fn_def.add_path(path),
             Declaration::LocalDecl(LocalDecl {
                 local,
@@ -301,14 +686,43 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
                 self.check_rvalue_or_call(rvalue_or_call)
             },
             StatementKind::Call(CallIgnoreRet { call, .. }, _) => self.check_call(call),
-            StatementKind::Drop(Drop { place, .. }, _) => self.check_place(place),
+            StatementKind::Drop(Drop { place, unwind, .. }, _) => {
+                self.check_place(place)?;
+                self.check_unwind(unwind.as_ref())
+            },
+            StatementKind::Yield(Yield { operand, .. }, _) => self.check_operand(operand),
+            StatementKind::CoroutineDrop(..) | StatementKind::UnwindResume(..) | StatementKind::Unreachable(..) => {
+                Ok(())
+            },
+            StatementKind::Assert(assert, _) => self.check_assert(assert),
+            StatementKind::InlineAsm(..) => Ok(()),
+            StatementKind::SetDiscriminant(
+                SetDiscriminant {
+                    place, variant_index, ..
+                },
+                _,
+            ) => {
+                self.check_place(place)?;
+                variant_index.base10_parse::<u32>()?;
+                Ok(())
+            },
+            StatementKind::Deinit(Deinit { place, .. }, _) => self.check_place(place),
+            StatementKind::Intrinsic(Intrinsic { kind, .. }, _) => match kind {
+                IntrinsicKind::Assume(IntrinsicAssume { cond, .. }) => self.check_operand(cond),
+                IntrinsicKind::CopyNonOverlapping(IntrinsicCopyNonOverlapping { src, dst, count, .. }) => {
+                    self.check_operand(src)?;
+                    self.check_operand(dst)?;
+                    self.check_operand(count)
+                },
+            },
+            StatementKind::TailCall(TailCall { call, .. }, _) => self.check_call(call),
             StatementKind::Control(control, _) => self.check_control(control),
             StatementKind::Loop(Loop { label, block, .. }) => self.check_loop(label.as_ref(), block),
             StatementKind::SwitchInt(switch_int) => self.check_switch_int(switch_int),
         }
     }
 
-    fn check_rvalue_or_call(&self, rvalue_or_call: &RvalueOrCall) -> syn::Result<()> {
+    fn check_rvalue_or_call(&mut self, rvalue_or_call: &'pat RvalueOrCall) -> syn::Result<()> {
         match rvalue_or_call {
             RvalueOrCall::Rvalue(rvalue) => self.check_rvalue(rvalue),
             RvalueOrCall::Call(call) => self.check_call(call),
@@ -321,10 +735,14 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
             Rvalue::Use(RvalueUse { operand, .. })
             | Rvalue::UnaryOp(RvalueUnOp { operand, .. })
             | Rvalue::Repeat(RvalueRepeat { operand, .. }) => self.check_operand(operand),
-            Rvalue::Ref(RvalueRef { place, .. })
-            | Rvalue::RawPtr(RvalueRawPtr { place, .. })
+            Rvalue::Ref(RvalueRef { region, place, .. }) => {
+                self.check_region(region.as_ref())?;
+                self.check_place(place)
+            },
+            Rvalue::RawPtr(RvalueRawPtr { place, .. })
             | Rvalue::Len(RvalueLen { place, .. })
-            | Rvalue::Discriminant(RvalueDiscriminant { place, .. }) => self.check_place(place),
+            | Rvalue::Discriminant(RvalueDiscriminant { place, .. })
+            | Rvalue::CopyForDeref(RvalueCopyForDeref { place, .. }) => self.check_place(place),
             Rvalue::Cast(RvalueCast { operand, ty, .. }) => {
                 self.check_operand(operand)?;
                 self.check_type(ty)?;
@@ -337,15 +755,37 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
             },
             Rvalue::NullaryOp(RvalueNullOp { ty, .. }) => self.check_type(ty),
             Rvalue::Aggregate(agg) => self.check_aggregate(agg),
+            Rvalue::ThreadLocalRef(RvalueThreadLocalRef { thread_local, .. }) => match thread_local {
+                ThreadLocalStatic::Any(_) => Ok(()),
+                ThreadLocalStatic::Path(path) => self.check_path(path),
+            },
+            Rvalue::ShallowInitBox(RvalueShallowInitBox { operand, ty, .. }) => {
+                self.check_operand(operand)?;
+                self.check_type(ty)
+            },
         }
     }
 
-    fn check_call(&self, call: &Call) -> syn::Result<()> {
+    fn check_call(&mut self, call: &'pat Call) -> syn::Result<()> {
         self.check_fn_operand(&call.func)?;
         for operand in call.operands.value.iter() {
             self.check_operand(operand)?;
         }
-        Ok(())
+        self.check_unwind(call.unwind.as_ref())
+    }
+
+    fn check_assert(&mut self, assert: &'pat Assert) -> syn::Result<()> {
+        self.check_operand(&assert.cond)?;
+        if let AssertMsg::Kind(kind) = &assert.msg
+            && !ASSERT_KINDS.iter().any(|assert_kind| kind == assert_kind)
+        {
+            return Err(syn::Error::new_spanned(kind, CheckError::UnknownAssertKind(kind)));
+        }
+        self.check_unwind(assert.unwind.as_ref())
+    }
+
+    fn check_unwind(&mut self, unwind: Option<&'pat Unwind>) -> syn::Result<()> {
+        unwind.map_or(Ok(()), |unwind| self.check_block(&unwind.block))
     }
 
     fn check_fn_operand(&self, operand: &FnOperand) -> syn::Result<()> {
@@ -358,7 +798,7 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
                 value: OperandMove { place, .. },
                 ..
             }) => self.check_place(place),
-            FnOperand::Type(path) => self.check_type_path(path),
+            FnOperand::Type(path) => self.check_operand_path(path),
             FnOperand::LangItem(lang_item) => self.check_lang_item_with_args(lang_item),
             FnOperand::FnPat(_, fn_pat) => self.check_fn_pat(fn_pat),
         }
@@ -380,7 +820,22 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
     }
 
     fn check_const_var(&self, konst: &ConstVar) -> syn::Result<()> {
-        self.meta_table.get_const_var(&konst.ident)?;
+        self.check_const_var_ident(&konst.ident)
+    }
+
+    fn check_const_var_ident(&self, ident: &Ident) -> syn::Result<()> {
+        match self.get_type_param(ident) {
+            Some(TypeParam::Const(_)) => {},
+            _ => _ = self.meta_table.get_const_var(ident)?,
+        }
+        Ok(())
+    }
+
+    fn check_ty_var(&self, ident: &Ident) -> syn::Result<()> {
+        match self.get_type_param(ident) {
+            Some(TypeParam::Type(_)) => {},
+            _ => _ = self.meta_table.get_ty_var(ident)?,
+        }
         Ok(())
     }
 
@@ -401,14 +856,12 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
     fn check_const_operand(&self, konst: &ConstOperand) -> syn::Result<()> {
         match &konst.kind {
             ConstOperandKind::Lit(_) => Ok(()),
-            ConstOperandKind::ConstVar(const_var) => self.check_const_var(const_var),
-            ConstOperandKind::Type(type_path) => {
-                if let Some(qself) = &type_path.qself {
-                    self.check_type(&qself.ty)?;
-                }
-                self.check_path(&type_path.path)?;
+            // Generic parameters are not constant variables of the MIR pattern.
+            ConstOperandKind::ConstVar(const_var) => {
+                self.meta_table.get_const_var(&const_var.ident)?;
                 Ok(())
             },
+            ConstOperandKind::Type(type_path) => self.check_operand_path(type_path),
             ConstOperandKind::LangItem(lang_item) => self.check_lang_item_with_args(lang_item),
         }
     }
@@ -503,6 +956,13 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
                 self.check_operand(metadata)?;
                 Ok(())
             },
+            RvalueAggregate::Closure(AggregateClosure { operands, .. })
+            | RvalueAggregate::Coroutine(AggregateCoroutine { operands, .. }) => {
+                for operand in operands.value.iter() {
+                    self.check_operand(operand)?;
+                }
+                Ok(())
+            },
         }
     }
 
@@ -510,7 +970,7 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
         match ty {
             Type::Never(_) => Ok(()),
             Type::Reference(TypeReference { region, ty, .. }) => {
-                self.check_region(*region)?;
+                self.check_region(region.as_ref())?;
                 self.check_type(ty)?;
                 Ok(())
             },
@@ -521,10 +981,27 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
             | Type::Ptr(TypePtr { ty, .. }) => self.check_type(ty),
             Type::Path(path) => self.check_type_path(path),
             Type::Tuple(TypeTuple { tys, .. }) => tys.iter().try_for_each(|ty| self.check_type(ty)),
-            Type::TyVar(TypeVar { ident, .. }) => {
-                _ = self.meta_table.get_ty_var(ident)?;
-                Ok(())
+            Type::FnPtr(TypeFnPtr {
+                abi, inputs, output, ..
+            }) => {
+                check_abi(abi)?;
+                inputs.iter().try_for_each(|ty| self.check_type(ty))?;
+                self.check_return_type(output)
             },
+            Type::TraitObject(TypeTraitObject { bounds, .. }) => {
+                let mut regions = bounds.iter().filter(|bound| matches!(bound, TypeParamBound::Region(_)));
+                if let (Some(_), Some(region)) = (regions.next(), regions.next()) {
+                    return Err(syn::Error::new_spanned(region, CheckError::MultipleRegionBounds));
+                }
+                self.check_trait_bounds(ty, bounds, "dyn Trait")
+            },
+            Type::ImplTrait(TypeImplTrait { bounds, .. }) => {
+                if let Some(region) = bounds.iter().find(|bound| matches!(bound, TypeParamBound::Region(_))) {
+                    return Err(syn::Error::new_spanned(region, CheckError::RegionBoundOnImplTrait));
+                }
+                self.check_trait_bounds(ty, bounds, "impl Trait")
+            },
+            Type::TyVar(TypeVar { ident, .. }) => self.check_ty_var(ident),
             Type::LangItem(lang_item) => self.check_lang_item_with_args(lang_item),
             Type::SelfType(_) if self.impl_def.is_none() => {
                 Ok(())
@@ -535,6 +1012,53 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
         }
     }
 
+    fn check_return_type(&self, output: &ReturnType) -> syn::Result<()> {
+        match output {
+            ReturnType::Type(_, ty) => self.check_type(ty),
+            ReturnType::Default => Ok(()),
+        }
+    }
+
+    fn check_trait_bounds(
+        &self,
+        ty: &Type,
+        bounds: &Punctuated<TypeParamBound, Token![+]>,
+        kind: &'static str,
+    ) -> syn::Result<()> {
+        let mut has_trait = false;
+        for bound in bounds {
+            match bound {
+                TypeParamBound::Region(region) => self.check_region(Some(region))?,
+                TypeParamBound::Trait(TraitBound { path, fn_args }) => {
+                    has_trait = true;
+                    // Trait paths are not resolved to declared types, unlike type paths.
+                    for segment in &path.segments {
+                        self.check_generic_args(&segment.arguments)?;
+                    }
+                    if let Some(ParenthesizedGenericArguments { inputs, output, .. }) = fn_args {
+                        inputs.iter().try_for_each(|ty| self.check_type(ty))?;
+                        self.check_return_type(output)?;
+                    }
+                },
+            }
+        }
+        if !has_trait {
+            return Err(syn::Error::new_spanned(ty, CheckError::MissingTraitBound(kind)));
+        }
+        Ok(())
+    }
+
+    /// The path of a function or a constant in an operand, which may be type-relative, like
+    /// `<*mut _>::add`, but not qualified by a trait, like `<T as Trait>::f`.
+    fn check_operand_path(&self, path: &TypePath) -> syn::Result<()> {
+        if let Some(qself) = &path.qself
+            && (qself.position != 0 || path.path.segments.len() != 1)
+        {
+            return Err(syn::Error::new_spanned(path, CheckError::UnsupportedTypeRelativePath));
+        }
+        self.check_type_path(path)
+    }
+
     fn check_type_path(&self, path: &TypePath) -> syn::Result<()> {
         if let Some(qself) = &path.qself {
             self.check_type(&qself.ty)?;
@@ -550,13 +1074,58 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
     }
 
     fn check_path(&self, path: &Path) -> syn::Result<()> {
-        if let Some(ident) = path.as_ident() {
-            if !crate::is_primitive(ident) {
-                _ = self.fn_def.get_type(ident)?;
-            }
-        } else {
-            for segment in &path.segments {
-                self.check_generic_args(&segment.arguments)?;
+        if let Some(ident) = path.as_ident()
+            && !crate::is_primitive(ident)
+        {
+            // Types declared in the `fn` pattern shadow the `type` items.
+            let generics = match self.fn_def.get_type(ident) {
+                Ok(_) => None,
+                Err(err) => self.types.get_type(ident).ok_or(err)?.generics.as_ref(),
+            };
+            return self.check_type_args(SymbolKind::Type, ident, generics, &path.segments[0].arguments);
+        }
+        for segment in &path.segments {
+            self.check_generic_args(&segment.arguments)?;
+        }
+        Ok(())
+    }
+
+    fn check_type_args<'a>(
+        &self,
+        kind: SymbolKind,
+        ident: &'a Ident,
+        generics: Option<&'a TypeGenerics>,
+        args: &'a PathArguments,
+    ) -> syn::Result<()> {
+        let params = generics.map_or(0, |generics| generics.params.len());
+        let args = match args {
+            PathArguments::None => None,
+            PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) => Some(args),
+        };
+        let num_args = args.map_or(0, Punctuated::len);
+        if params != num_args {
+            return Err(syn::Error::new(
+                ident.span(),
+                CheckError::TypeArgCountMismatch(kind, ident, params, num_args),
+            ));
+        }
+        let (Some(generics), Some(args)) = (generics, args) else {
+            return Ok(());
+        };
+        for (param, arg) in generics.params.iter().zip(args) {
+            match (param, arg) {
+                (TypeParam::Type(_), GenericArgument::Type(ty)) => self.check_type(ty)?,
+                (TypeParam::Type(TypeVar { ident, .. }), _) => {
+                    return Err(syn::Error::new_spanned(arg, CheckError::ExpectTypeArg(ident)));
+                },
+                // `$N` is parsed as a type, which is a constant variable here.
+                (TypeParam::Const(_), GenericArgument::Type(Type::TyVar(TypeVar { ident, .. }))) => {
+                    self.check_const_var_ident(ident)?
+                },
+                (TypeParam::Const(_), GenericArgument::Const(GenericConst { konst, .. })) => self.check_const(konst)?,
+                (TypeParam::Const(ConstParam { ident, .. }), _) => {
+                    return Err(syn::Error::new_spanned(arg, CheckError::ExpectConstArg(ident)));
+                },
             }
         }
         Ok(())
@@ -573,13 +1142,24 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
 
     fn check_generic_arg(&self, arg: &GenericArgument) -> syn::Result<()> {
         match arg {
-            &GenericArgument::Region(region) => self.check_region(Some(region)),
+            GenericArgument::Region(region) => self.check_region(Some(region)),
             GenericArgument::Type(ty) => self.check_type(ty),
             GenericArgument::Const(GenericConst { konst, .. }) => self.check_const(konst),
         }
     }
 
-    fn check_region(&self, _region: Option<Region>) -> syn::Result<()> {
+    fn check_region(&self, region: Option<&Region>) -> syn::Result<()> {
+        match region {
+            Some(Region {
+                kind: RegionKind::ReVar(region_var),
+                ..
+            }) => self.check_region_var(region_var),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_region_var(&self, region_var: &RegionVar) -> syn::Result<()> {
+        _ = self.meta_table.get_region_var(&region_var.lifetime.ident)?;
         Ok(())
     }
 
@@ -637,3 +1217,21 @@ impl<'pat> CheckFnCtxt<'_, 'pat> {
         Ok(())
     }
 }
+
+fn is_primitive_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(TypePath { qself: None, path }) if path.as_ident().is_some_and(crate::is_primitive))
+}
+
+/// Whether the variable `$ident` is mentioned in `tokens`.
+fn mentions_var(tokens: TokenStream, ident: &Ident) -> bool {
+    let mut after_dollar = false;
+    tokens.into_iter().any(|tt| {
+        let found = match &tt {
+            TokenTree::Ident(var) => after_dollar && var == ident,
+            TokenTree::Group(group) => mentions_var(group.stream(), ident),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+        };
+        after_dollar = matches!(&tt, TokenTree::Punct(punct) if punct.as_char() == '$');
+        found
+    })
+}
@@ -1,11 +1,12 @@
 use proc_macro2::TokenStream;
 use quote::{ToTokens, format_ident, quote, quote_each_token, quote_token};
-use syn::Ident;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::{Pair, Pairs, Punctuated};
+use syn::{Ident, Token};
 use syntax::*;
 
 use crate::SymbolTable;
+use crate::symbol_table::{FragmentInner, FragmentTable};
 
 const MACRO_RPL: &str = "rpl";
 
@@ -58,6 +59,8 @@ impl PatternDefFn {
 struct ExpandCtxt<'pat> {
     pcx: &'pat Ident,
     symbols: &'pat SymbolTable<'pat>,
+    /// The expanded arguments of the generic parameters of the `type` item being expanded.
+    type_args: &'pat [(&'pat Ident, TokenStream)],
 }
 
 macro_rules! decl_pat_id {
@@ -124,6 +127,8 @@ struct ExpandPatCtxt<'pat> {
     meta: Option<&'pat Meta>,
     parent: Option<&'pat ExpandPatCtxt<'pat>>,
     symbols: &'pat SymbolTable<'pat>,
+    /// Where the fragments used in the pattern being expanded are resolved.
+    fragments: &'pat FragmentTable<'pat>,
 }
 
 pub fn expand(pattern: PatternDefFn) -> TokenStream {
@@ -135,7 +140,8 @@ pub fn expand(pattern: PatternDefFn) -> TokenStream {
 static PARAM_PCX: &str = "pcx";
 
 pub fn expand_pattern(pattern: &Pattern, pcx: Option<&Ident>) -> syn::Result<TokenStream> {
-    let symbols = crate::check_pattern(pattern)?;
+    let modules = crate::module::load_modules(pattern)?;
+    let symbols = crate::check_pattern(pattern, &modules)?;
     let pcx = match pcx {
         None => &syn::Ident::new(PARAM_PCX, proc_macro2::Span::call_site()),
         Some(pcx) => pcx,
@@ -183,6 +189,9 @@ trait ExpandIdent: Sized {
     fn as_place_var(&self) -> Ident {
         self.with_suffix("_place_var")
     }
+    fn as_region_var(&self) -> Ident {
+        self.with_suffix("_region_var")
+    }
     fn as_local(&self) -> Ident {
         self.with_suffix("_local")
     }
@@ -220,7 +229,32 @@ impl ExpandIdent for PlaceLocalKind {
 
 impl<'pat> ExpandCtxt<'pat> {
     fn new(pcx: &'pat Ident, symbols: &'pat SymbolTable<'pat>) -> Self {
-        Self { pcx, symbols }
+        Self {
+            pcx,
+            symbols,
+            type_args: &[],
+        }
+    }
+    fn with_type_args<'a>(self, type_args: &'a [(&'a Ident, TokenStream)]) -> ExpandCtxt<'a>
+    where
+        'pat: 'a,
+    {
+        ExpandCtxt {
+            pcx: self.pcx,
+            symbols: self.symbols,
+            type_args,
+        }
+    }
+    fn get_type_arg(&self, ident: &Ident) -> Option<&'pat TokenStream> {
+        self.type_args
+            .iter()
+            .find_map(|(param, arg)| (*param == ident).then_some(arg))
+    }
+    fn expand_const_var(&self, ident: &Ident) -> TokenStream {
+        match self.get_type_arg(ident) {
+            Some(arg) => arg.clone(),
+            None => ident.as_const().into_token_stream(),
+        }
     }
     fn with_pat(self, pat: PatId<'pat>) -> ExpandPatCtxt<'pat> {
         ExpandPatCtxt {
@@ -229,6 +263,7 @@ impl<'pat> ExpandCtxt<'pat> {
             meta: None,
             parent: None,
             symbols: self.symbols,
+            fragments: self.symbols.fragments(),
         }
     }
     fn expand<T>(self, value: T) -> Expand<'pat, T> {
@@ -260,6 +295,7 @@ impl<'pat> ExpandPatCtxt<'pat> {
             meta: self.meta,
             parent: Some(self),
             symbols: self.symbols,
+            fragments: self.fragments,
         }
     }
     fn with_opt_meta(&'pat self, meta: Option<&'pat Meta>) -> Self {
@@ -269,8 +305,12 @@ impl<'pat> ExpandPatCtxt<'pat> {
             meta,
             parent: Some(self),
             symbols: self.symbols,
+            fragments: self.fragments,
         }
     }
+    fn with_fragments(self, fragments: &'pat FragmentTable<'pat>) -> Self {
+        ExpandPatCtxt { fragments, ..self }
+    }
     fn expand_punctuated<'a, U: 'a, P: 'a>(self, value: &'a Punctuated<U, P>) -> ExpandPatPunctPairs<'pat, 'a, U, P> {
         self.expand_pairs(value, Punctuated::pairs)
     }
@@ -374,10 +414,7 @@ where
     fn to_tokens(&self, tokens: &mut TokenStream) {
         Expand {
             value: self.value.clone(),
-            ecx: ExpandCtxt {
-                pcx: self.ecx.pcx,
-                symbols: self.ecx.symbols,
-            },
+            ecx: ExpandCtxt::new(self.ecx.pcx, self.ecx.symbols),
         }
         .to_tokens(tokens);
     }
@@ -389,7 +426,9 @@ impl ToTokens for Expand<'_, &Pattern> {
         let Pattern { items } = self.value;
         let pattern = format_ident!("pattern");
         let ecx_with_pat = self.ecx.with_pat(PatId::Pattern(&pattern));
-        let items = items.iter().map(|item| ecx_with_pat.expand(item));
+        // `type` items are expanded first, so that they can be used by all `fn` patterns.
+        let (types, items): (Vec<_>, Vec<_>) = items.iter().partition(|item| matches!(item.kind, ItemKind::Type(_)));
+        let items = types.into_iter().chain(items).map(|item| ecx_with_pat.expand(item));
         quote_each_token!(tokens {
             let #pattern = #pcx.new_pattern();
             #(#items)*
@@ -400,8 +439,78 @@ impl ToTokens for Expand<'_, &Pattern> {
 
 impl ToTokens for ExpandPat<'_, &Item> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let Item { meta, kind } = self.value;
+        let Item {
+            applies_to,
+            meta,
+            constraints,
+            kind,
+        } = self.value;
         self.ecx.with_opt_meta(meta.as_ref()).expand(kind).to_tokens(tokens);
+        if let ItemKind::Fn(fn_pat) = kind {
+            if let Some(applies_to) = applies_to {
+                self.ecx.expand((applies_to, fn_pat)).to_tokens(tokens);
+            }
+            if let Some(constraints) = constraints {
+                self.ecx.expand((constraints, fn_pat)).to_tokens(tokens);
+            }
+        }
+    }
+}
+
+impl ToTokens for ExpandPat<'_, (&Constraints, &FnPat)> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let (constraints, FnPat { sig, .. }) = self.value;
+        let fn_pat = match sig.ident.as_ident() {
+            Some(ident) => ident.as_fn(),
+            None => format_ident!("fn_pat", span = sig.ident.span()),
+        };
+        for Constraint { tk_not, kind } in &constraints.inner {
+            let holds = tk_not.is_none();
+            match kind {
+                ConstraintKind::Generic(_) => {
+                    quote_each_token!(tokens #fn_pat.set_generic(#holds););
+                },
+                ConstraintKind::TraitMethod(_) => {
+                    quote_each_token!(tokens #fn_pat.set_trait_method(#holds););
+                },
+                ConstraintKind::Attr(ConstraintAttr { path, args, .. }) => {
+                    let path = path.segments.iter().map(|segment| segment.ident.to_string());
+                    match args {
+                        None => {
+                            quote_each_token!(tokens #fn_pat.add_attr_constraint(&[#(#path),*], #holds););
+                        },
+                        Some(args) => {
+                            let words = args.words.iter().map(Ident::to_string);
+                            quote_each_token!(tokens
+                                #fn_pat.add_attr_constraint_with_args(&[#(#path),*], &[#(#words),*], #holds);
+                            );
+                        },
+                    }
+                },
+                ConstraintKind::Outlives(ConstraintOutlives { longer, shorter, .. }) => {
+                    let longer = longer.lifetime.ident.as_region_var();
+                    let shorter = shorter.lifetime.ident.as_region_var();
+                    quote_each_token!(tokens #fn_pat.add_outlives_constraint(#longer, #shorter, #holds););
+                },
+                ConstraintKind::Unbounded(ConstraintUnbounded { region, .. }) => {
+                    let region = region.lifetime.ident.as_region_var();
+                    quote_each_token!(tokens #fn_pat.add_unbounded_constraint(#region, #holds););
+                },
+            }
+        }
+    }
+}
+
+impl ToTokens for ExpandPat<'_, (&AppliesTo, &FnPat)> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let (applies_to, FnPat { sig, .. }) = self.value;
+        let AppliesToItem { krate, version, .. } = &applies_to.inner;
+        let fn_pat = match sig.ident.as_ident() {
+            Some(ident) => ident.as_fn(),
+            None => format_ident!("fn_pat", span = sig.ident.span()),
+        };
+        let krate = self.ecx.expand(krate.value().to_symbol());
+        quote_each_token!(tokens #fn_pat.set_applies_to(#krate, #version););
     }
 }
 
@@ -409,9 +518,13 @@ impl ToTokens for ExpandPat<'_, &ItemKind> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self.value {
             ItemKind::Fn(fn_pat) => self.ecx.expand(fn_pat).to_tokens(tokens),
+            ItemKind::Type(type_decl) => self.ecx.expand(type_decl).to_tokens(tokens),
+            // Fragments are expanded where they are used, see `ExpandPat<&FragmentUse>`.
+            ItemKind::Fragment(_) => {},
+            ItemKind::Mod(module) => self.ecx.expand(module).to_tokens(tokens),
             ItemKind::Struct(struct_pat) => self.ecx.expand(struct_pat).to_tokens(tokens),
             ItemKind::Enum(enum_pat) => self.ecx.expand(enum_pat).to_tokens(tokens),
-            ItemKind::Impl(_impl_pat) => todo!(),
+            ItemKind::Impl(impl_pat) => self.ecx.expand(impl_pat).to_tokens(tokens),
         }
     }
 }
@@ -440,12 +553,75 @@ impl ToTokens for ExpandPat<'_, &FnPat> {
     }
 }
 
+impl ToTokens for ExpandPat<'_, &Impl> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, meta, .. } = self.ecx;
+        let pattern = pat.expect_pattern();
+        let Impl {
+            kind, ty, where_clause, ..
+        } = self.value;
+        let impl_pat = format_ident!("impl_pat");
+        quote_each_token!(tokens let #impl_pat = #pattern.new_impl(););
+        let ecx = self.ecx.with_pat(PatId::Impl(&impl_pat));
+        if let Some(meta) = meta {
+            ecx.expand(meta).to_tokens(tokens);
+        }
+        if let ImplKind::Trait(path, _) = kind {
+            let path = ecx.expand(path);
+            quote_each_token!(tokens #impl_pat.set_trait(::rpl_context::pat::TraitRef::Path(#path)););
+        }
+        let ty = ecx.expand(ty);
+        quote_each_token!(tokens #impl_pat.set_self_ty(#ty););
+        if let Some(where_clause) = where_clause {
+            ecx.expand(where_clause).to_tokens(tokens);
+        }
+    }
+}
+
+/// Expands the `where` clause of the `fn` or `impl` pattern being expanded.
+impl ToTokens for ExpandPat<'_, &WhereClause> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pcx, pat, .. } = self.ecx;
+        let pat = pat.as_ident();
+        for WherePredicate { ty, bounds, .. } in &self.value.predicates {
+            let Type::TyVar(TypeVar { ident, .. }) = ty else {
+                unreachable!("only type variables can be bounded in `where` clauses")
+            };
+            let ty_var = ident.as_ty_var();
+            for WhereBound { tk_not, bound } in bounds {
+                let holds = tk_not.is_none();
+                let bound = match bound {
+                    TypeParamBound::Trait(TraitBound { path, .. }) => {
+                        let path = self.ecx.expand(path);
+                        quote!(::rpl_context::pat::WhereBound::Trait(#pcx.mk_trait_bound(#path)))
+                    },
+                    TypeParamBound::Region(region) => {
+                        let region = self.ecx.expand(Some(region));
+                        quote!(::rpl_context::pat::WhereBound::Outlives(#region))
+                    },
+                };
+                quote_each_token!(tokens #pat.add_where_predicate(#ty_var, #bound, #holds););
+            }
+        }
+    }
+}
+
 impl ToTokens for ExpandPat<'_, &FnSig> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         let ExpandPatCtxt { pat, meta, .. } = self.ecx;
         let fn_pat = pat.expect_fn();
         let pattern = self.ecx.get_pattern().unwrap();
-        let FnSig { ident, params, ret, .. } = self.value;
+        let FnSig {
+            vis,
+            constness,
+            safety,
+            abi,
+            ident,
+            params,
+            ret,
+            where_clause,
+            ..
+        } = self.value;
         quote_each_token!(tokens let #fn_pat = #pattern.fns.);
         match ident {
             IdentPat::Underscore(_) => {
@@ -463,11 +639,35 @@ impl ToTokens for ExpandPat<'_, &FnSig> {
         if let Some(meta) = meta {
             self.ecx.expand(meta).to_tokens(tokens);
         }
+        if let Some(FnVisibility { restriction, .. }) = vis {
+            let public = restriction.is_none();
+            quote_each_token!(tokens #fn_pat.set_public(#public););
+        }
+        if constness.is_some() {
+            quote_each_token!(tokens #fn_pat.set_const(););
+        }
+        if let Some(safety) = safety {
+            let safety = match safety {
+                FnSafety::Unsafe(_) => format_ident!("Unsafe"),
+                FnSafety::Safe(_) => format_ident!("Safe"),
+            };
+            quote_each_token!(tokens #fn_pat.set_safety(::rustc_hir::Safety::#safety););
+        }
+        if let Some(syn::Abi { name, .. }) = abi {
+            let abi = name.as_ref().map_or_else(|| "C".to_owned(), syn::LitStr::value);
+            quote_each_token!(tokens #fn_pat.set_abi(#abi););
+        }
         let ret = self.ecx.expand(ret);
         quote_each_token!(tokens #fn_pat.set_ret_ty(#ret););
         for param in params.punctuated.iter() {
             self.ecx.expand(param).to_tokens(tokens);
         }
+        if params.end.is_some() {
+            quote_each_token!(tokens #fn_pat.params.set_non_exhaustive(););
+        }
+        if let Some(where_clause) = where_clause {
+            self.ecx.expand(where_clause).to_tokens(tokens);
+        }
     }
 }
 
@@ -667,10 +867,21 @@ impl ToTokens for ExpandPat<'_, &MetaItem> {
 }
 
 impl ToTokens for ExpandPat<'_, &Meta> {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let pat = pat.as_ident();
         let Meta { inner, .. } = self.value;
-        for meta_item in inner.iter() {
-            self.ecx.expand(meta_item).to_tokens(tokens);
+        for meta_decl in inner.iter() {
+            match meta_decl {
+                MetaDecl::Region(RegionVar { lifetime, .. }) => {
+                    let region_var_ident = lifetime.ident.as_region_var();
+                    quote_each_token!(tokens
+                        #[allow(non_snake_case)]
+                        let #region_var_ident = #pat.meta.new_region_var();
+                    );
+                },
+                MetaDecl::Item(meta_item) => self.ecx.expand(meta_item).to_tokens(tokens),
+            }
         }
     }
 }
@@ -679,12 +890,95 @@ impl ToTokens for ExpandPat<'_, &Declaration> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self.value {
             Declaration::TypeDecl(type_decl) => self.ecx.expand(type_decl).to_tokens(tokens),
+            Declaration::FragmentUse(fragment_use) => self.ecx.expand(fragment_use).to_tokens(tokens),
             Declaration::UsePath(use_path) => self.ecx.expand(use_path).to_tokens(tokens),
             Declaration::LocalDecl(local_decl) => self.ecx.expand(local_decl).to_tokens(tokens),
         }
     }
 }
 
+/// Tracks the file of a pattern module, so that the pattern is rebuilt when it changes.
+impl ToTokens for ExpandPat<'_, &Mod> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let path = crate::module::module_path(self.value).display().to_string();
+        quote_each_token!(tokens const _: &str = include_str!(#path););
+    }
+}
+
+/// Splices the declarations and statements of a fragment into the MIR pattern, in a block where
+/// its generic parameters and parameters are bound to the arguments, evaluating to its exported
+/// locals:
+///
+/// ```text
+/// let (cast_local,) = {
+///     let T_ty = ...;
+///     let ptr_local = alloc_local;
+///     ...
+///     (ptr_local,)
+/// };
+/// ```
+impl ToTokens for ExpandPat<'_, &FragmentUse> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let FragmentUse {
+            module,
+            ident,
+            args,
+            inputs,
+            outputs,
+            ..
+        } = self.value;
+        let (fragment, fragments) = self
+            .ecx
+            .fragments
+            .get_fragment(module.as_ref().map(|module| &module.ident), ident)
+            .expect("fragment not declared");
+        let FragmentInner { fragment, .. } = &fragment.inner;
+        let syntax::Fragment {
+            generics,
+            params,
+            outputs: exports,
+            body,
+            ..
+        } = fragment;
+        let type_args = match args {
+            PathArguments::None => Vec::new(),
+            PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) => {
+                ExpandCtxt::new(self.ecx.pcx, self.ecx.symbols).expand_type_args(generics.as_ref(), args)
+            },
+        };
+        let type_args = generics
+            .iter()
+            .flat_map(|generics| &generics.params)
+            .zip(type_args)
+            .map(|(param, (ident, arg))| match param {
+                TypeParam::Type(_) => (ident.as_ty(), arg),
+                TypeParam::Const(_) => (ident.as_const(), arg),
+            })
+            .map(|(ident, arg)| quote!(#[allow(non_snake_case)] let #ident = #arg;));
+        let params = params
+            .iter()
+            .zip(inputs)
+            .map(|(param, input)| (param.ident.as_local(), input.ident.as_local()));
+        let params = params.map(|(param, input)| quote!(let #param = #input;));
+        let ecx = self.ecx.with_fragments(fragments);
+        let declarations = body.mir.declarations.iter().map(|declaration| ecx.expand(declaration));
+        let statements = body.mir.statements.iter().map(|statement| ecx.expand(statement));
+        let exports = exports.iter().flat_map(|outputs| &outputs.locals);
+        let exports = exports.map(|local| local.ident.as_local());
+        let outputs = outputs.iter().flat_map(|outputs| &outputs.locals);
+        let outputs = outputs.map(|local| local.ident.as_local());
+        quote_each_token!(tokens
+            let (#(#outputs,)*) = {
+                #(#type_args)*
+                #(#params)*
+                #(#declarations)*
+                #(#statements)*
+                (#(#exports,)*)
+            };
+        );
+    }
+}
+
 impl ToTokens for ExpandPat<'_, &Statement> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         let Statement { export, kind, .. } = &self.value;
@@ -700,11 +994,34 @@ impl ToTokens for ExpandPat<'_, &Statement> {
 }
 
 impl<End: Parse + ToTokens> ToTokens for ExpandPat<'_, &StatementKind<End>> {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
         match &self.value {
             StatementKind::Assign(assign, _) => self.ecx.expand(assign).to_tokens(tokens),
             StatementKind::Call(call, _) => self.ecx.expand(call).to_tokens(tokens),
             StatementKind::Drop(drop, _) => self.ecx.expand(drop).to_tokens(tokens),
+            StatementKind::Yield(yield_, _) => self.ecx.expand(yield_).to_tokens(tokens),
+            StatementKind::CoroutineDrop(..) => {
+                let mir_pat = self.ecx.pat.expect_mir();
+                quote_each_token!(tokens #mir_pat.mk_coroutine_drop(); );
+            },
+            StatementKind::Assert(assert, _) => self.ecx.expand(assert).to_tokens(tokens),
+            StatementKind::InlineAsm(inline_asm, _) => self.ecx.expand(inline_asm).to_tokens(tokens),
+            StatementKind::TailCall(tail_call, _) => self.ecx.expand(tail_call).to_tokens(tokens),
+            StatementKind::UnwindResume(..) => {
+                let mir_pat = self.ecx.pat.expect_mir();
+                quote_each_token!(tokens #mir_pat.mk_unwind_resume(); );
+            },
+            StatementKind::Unreachable(..) => {
+                let mir_pat = self.ecx.pat.expect_mir();
+                quote_each_token!(tokens #mir_pat.mk_unreachable(); );
+            },
+            StatementKind::SetDiscriminant(set_discr, _) => self.ecx.expand(set_discr).to_tokens(tokens),
+            StatementKind::Deinit(Deinit { place, .. }, _) => {
+                let mir_pat = self.ecx.pat.expect_mir();
+                let place = self.ecx.expand(place);
+                quote_each_token!(tokens #mir_pat.mk_deinit(#place); );
+            },
+            StatementKind::Intrinsic(intrinsic, _) => self.ecx.expand(intrinsic).to_tokens(tokens),
             StatementKind::Control(control, _) => self.ecx.expand(control).to_tokens(tokens),
             StatementKind::Loop(loop_) => self.ecx.expand(loop_).to_tokens(tokens),
             StatementKind::SwitchInt(switch_int) => self.ecx.expand(switch_int).to_tokens(tokens),
@@ -716,10 +1033,36 @@ impl ToTokens for ExpandPat<'_, &CallIgnoreRet> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         let ExpandPatCtxt { pat, .. } = self.ecx;
         let mir_pat = pat.expect_mir();
-        let Call { func, operands } = &self.value.call;
+        let Call { func, operands, unwind } = &self.value.call;
         let func = self.ecx.expand(func);
         let operands = self.ecx.expand_punctuated(&operands.value);
-        quote_each_token!(tokens #mir_pat.mk_fn_call(#func, #mir_pat.mk_list([#operands]), None); );
+        let unwind = unwind.as_ref().map(|unwind| self.ecx.expand(unwind));
+        quote_each_token!(tokens #mir_pat.mk_fn_call(#func, #mir_pat.mk_list([#operands]), None); #unwind);
+    }
+}
+
+impl ToTokens for ExpandPat<'_, &TailCall> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let Call { func, operands, .. } = &self.value.call;
+        let func = self.ecx.expand(func);
+        let operands = self.ecx.expand_punctuated(&operands.value);
+        quote_each_token!(tokens #mir_pat.mk_tail_call(#func, #mir_pat.mk_list([#operands])); );
+    }
+}
+
+impl ToTokens for ExpandPat<'_, &Unwind> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let statements = self
+            .value
+            .block
+            .statements
+            .iter()
+            .map(|statement| self.ecx.expand(statement));
+        quote_each_token!(tokens #mir_pat.mk_unwind(|#mir_pat| { #(#statements)* }););
     }
 }
 
@@ -803,7 +1146,13 @@ impl ToTokens for ExpandPat<'_, &Block> {
 
 impl ToTokens for Expand<'_, &TypeDecl> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
-        let TypeDecl { ident, ty, .. } = self.value;
+        let TypeDecl {
+            ident, generics, ty, ..
+        } = self.value;
+        // Generic types are expanded where they are used, see `Expand<TypeAliasArgs>`.
+        if generics.is_some() {
+            return;
+        }
         let ty_ident = ident.as_ty();
         let ty = self.ecx.expand(ty);
         quote_each_token!(tokens #[allow(non_snake_case)] let #ty_ident = #ty;);
@@ -895,10 +1244,14 @@ where
                 let rvalue = self.ecx.expand(rvalue);
                 quote_each_token!(tokens #mir_pat.mk_assign(#place, #rvalue); );
             },
-            RvalueOrCall::Call(Call { func, operands }) => {
+            RvalueOrCall::Call(Call { func, operands, unwind }) => {
                 let func = self.ecx.expand(func);
                 let operands = self.ecx.expand_punctuated(&operands.value);
-                quote_each_token!(tokens #mir_pat.mk_fn_call(#func, #mir_pat.mk_list([#operands]), Some(#place)); );
+                let unwind = unwind.as_ref().map(|unwind| self.ecx.expand(unwind));
+                quote_each_token!(tokens
+                    #mir_pat.mk_fn_call(#func, #mir_pat.mk_list([#operands]), Some(#place));
+                    #unwind
+                );
             },
         }
     }
@@ -908,19 +1261,83 @@ impl ToTokens for ExpandPat<'_, &Drop> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         let ExpandPatCtxt { pat, .. } = self.ecx;
         let mir_pat = pat.expect_mir();
-        let Drop { place, .. } = self.value;
+        let Drop { place, unwind, .. } = self.value;
         let place = self.ecx.expand(place);
-        quote_each_token!(tokens #mir_pat.mk_drop(#place); );
+        let unwind = unwind.as_ref().map(|unwind| self.ecx.expand(unwind));
+        quote_each_token!(tokens #mir_pat.mk_drop(#place); #unwind);
     }
 }
 
-trait RegionExt {
-    fn kind(&self) -> RegionKind;
+impl ToTokens for ExpandPat<'_, &Assert> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let cond = self.ecx.expand(&self.value.cond);
+        let expected = self.value.expected();
+        let msg = match &self.value.msg {
+            AssertMsg::Any(_) => quote!(None),
+            AssertMsg::Kind(kind) => quote!(Some(::rpl_context::pat::AssertKind::#kind)),
+        };
+        let unwind = self.value.unwind.as_ref().map(|unwind| self.ecx.expand(unwind));
+        quote_each_token!(tokens #mir_pat.mk_assert(#cond, #expected, #msg); #unwind);
+    }
+}
+
+impl ToTokens for ExpandPat<'_, &SetDiscriminant> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let SetDiscriminant {
+            place, variant_index, ..
+        } = self.value;
+        let place = self.ecx.expand(place);
+        let variant_index = syn::LitInt::new(&format!("{}_u32", variant_index.base10_digits()), variant_index.span());
+        quote_each_token!(tokens #mir_pat.mk_set_discriminant(#place, #variant_index); );
+    }
 }
 
-impl RegionExt for Option<Region> {
-    fn kind(&self) -> RegionKind {
-        self.map_or(RegionKind::ReAny(Default::default()), |region| region.kind)
+impl ToTokens for ExpandPat<'_, &Intrinsic> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let intrinsic = match &self.value.kind {
+            IntrinsicKind::Assume(IntrinsicAssume { cond, .. }) => {
+                let cond = self.ecx.expand(cond);
+                quote!(::rpl_context::pat::NonDivergingIntrinsic::Assume(#cond))
+            },
+            IntrinsicKind::CopyNonOverlapping(IntrinsicCopyNonOverlapping { src, dst, count, .. }) => {
+                let src = self.ecx.expand(src);
+                let dst = self.ecx.expand(dst);
+                let count = self.ecx.expand(count);
+                quote!(::rpl_context::pat::NonDivergingIntrinsic::CopyNonOverlapping {
+                    src: #src,
+                    dst: #dst,
+                    count: #count,
+                })
+            },
+        };
+        quote_each_token!(tokens #mir_pat.mk_intrinsic(#intrinsic); );
+    }
+}
+
+impl ToTokens for ExpandPat<'_, &InlineAsm> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let template = match &self.value.content {
+            AsmTemplate::Any(_) => quote!(None),
+            AsmTemplate::Template(template) => quote!(Some(#template)),
+        };
+        quote_each_token!(tokens #mir_pat.mk_inline_asm(#template); );
+    }
+}
+
+impl ToTokens for ExpandPat<'_, &Yield> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandPatCtxt { pat, .. } = self.ecx;
+        let mir_pat = pat.expect_mir();
+        let operand = self.ecx.expand(&self.value.operand);
+        quote_each_token!(tokens #mir_pat.mk_yield(#operand); );
     }
 }
 
@@ -954,10 +1371,38 @@ impl ToTokens for Expand<'_, &Path> {
     }
 }
 
+/// A type-relative path, like `<*mut _>::add`.
+#[derive(Clone, Copy)]
+struct TypeRelativePath<'a>(&'a QSelf, &'a Path);
+
+impl ToTokens for Expand<'_, TypeRelativePath<'_>> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandCtxt { pcx, .. } = self.ecx;
+        let TypeRelativePath(qself, path) = self.value;
+        // The paths of several segments, or qualified by a trait, are rejected by
+        // `CheckFnCtxt::check_operand_path`.
+        let segment = &path.segments[0];
+        let ty = self.ecx.expand(&*qself.ty);
+        let ident = segment.ident.to_string();
+        let mut gen_args = TokenStream::new();
+        if let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) = &segment.arguments {
+            let trailing_punct = args.trailing_punct();
+            let args = self.ecx.expand_punctuated(args);
+            quote_each_token!(gen_args #args);
+            if !trailing_punct {
+                quote_token!(, gen_args);
+            }
+        }
+        quote_each_token!(tokens #pcx.mk_path_with_args(
+            #pcx.mk_type_relative(#ty, #ident), &[#gen_args]
+        ));
+    }
+}
+
 impl ToTokens for Expand<'_, &GenericArgument> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         match self.value {
-            GenericArgument::Region(Region { kind, .. }) => self.ecx.expand(*kind).to_tokens(tokens),
+            GenericArgument::Region(region) => self.ecx.expand(Some(region)).to_tokens(tokens),
             GenericArgument::Type(ty) => self.ecx.expand(ty).to_tokens(tokens),
             GenericArgument::Const(GenericConst { konst, .. }) => self.ecx.expand(konst).to_tokens(tokens),
         }
@@ -971,16 +1416,57 @@ impl ToTokens for Expand<'_, &Type> {
         match self.value {
             Type::Array(TypeArray { box ty, len, .. }) => {
                 let ty = self.ecx.expand(ty);
-                let len = self.ecx.expand(len);
+                let usize = format_ident!("usize");
+                let len = self.ecx.expand(TypedConst(len, &usize));
                 quote_each_token!(tokens #pcx.mk_array_ty(#ty, #len));
             },
             Type::Group(TypeGroup { box ty, .. }) | Type::Paren(TypeParen { value: box ty, .. }) => {
                 self.ecx.expand(ty).to_tokens(tokens);
             },
-            Type::Never(_) => todo!(),
+            Type::FnPtr(TypeFnPtr {
+                unsafety,
+                abi,
+                inputs,
+                output,
+                ..
+            }) => {
+                let inputs = self.ecx.expand_punctuated(inputs);
+                let output = self.ecx.expand(output);
+                let safety = match unsafety {
+                    Some(_) => format_ident!("Unsafe"),
+                    None => format_ident!("Safe"),
+                };
+                let abi = match abi {
+                    None => "Rust".to_owned(),
+                    Some(syn::Abi { name: None, .. }) => "C".to_owned(),
+                    Some(syn::Abi { name: Some(name), .. }) => name.value(),
+                };
+                quote_each_token!(tokens #pcx.mk_fn_ptr_ty(&[#inputs], #output, ::rustc_hir::Safety::#safety, #abi));
+            },
+            Type::TraitObject(TypeTraitObject { bounds, .. }) => {
+                let region = bounds.iter().find_map(|bound| match bound {
+                    TypeParamBound::Region(region) => Some(region),
+                    TypeParamBound::Trait(_) => None,
+                });
+                let region = self.ecx.expand(region);
+                let bounds = self.ecx.expand(bounds);
+                quote_each_token!(tokens #pcx.mk_dyn_ty(&[#bounds], #region));
+            },
+            Type::ImplTrait(TypeImplTrait { bounds, .. }) => {
+                let bounds = self.ecx.expand(bounds);
+                quote_each_token!(tokens #pcx.mk_impl_trait_ty(&[#bounds]));
+            },
+            Type::Never(_) => {
+                quote_each_token!(tokens #pcx.mk_never_ty());
+            },
             Type::Path(TypePath { qself: None, path }) if let Some(ident) = path.as_ident() => {
                 if crate::is_primitive(ident) {
                     quote_each_token!(tokens #pcx.primitive_types.#ident);
+                } else if let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) =
+                    &path.segments[0].arguments
+                    && let Some(type_decl) = symbols.get_type(ident)
+                {
+                    self.ecx.expand(TypeAliasArgs(type_decl, args)).to_tokens(tokens);
                 } else {
                     ident.as_ty().to_tokens(tokens);
                 }
@@ -1000,7 +1486,7 @@ impl ToTokens for Expand<'_, &Type> {
                 box ty,
                 ..
             }) => {
-                let region = self.ecx.expand(region.kind());
+                let region = self.ecx.expand(region.as_ref());
                 let ty = self.ecx.expand(ty);
                 let mutability = self.ecx.expand(*mutability);
                 quote_each_token!(tokens #pcx.mk_ref_ty(#region, #ty, #mutability));
@@ -1014,7 +1500,9 @@ impl ToTokens for Expand<'_, &Type> {
                 quote_each_token!(tokens #pcx.mk_tuple_ty(&[#tys]));
             },
             Type::TyVar(TypeVar { ident, .. }) => {
-                if symbols.contains_adt(ident) {
+                if let Some(ty) = self.ecx.get_type_arg(ident) {
+                    ty.to_tokens(tokens);
+                } else if symbols.contains_adt(ident) {
                     let adt_pat = self.ecx.expand(ident.to_symbol());
                     quote_each_token!(tokens #pcx.mk_adt_pat_ty(#adt_pat));
                 } else {
@@ -1033,6 +1521,107 @@ impl ToTokens for Expand<'_, &Type> {
     }
 }
 
+impl ToTokens for Expand<'_, &ReturnType> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandCtxt { pcx, .. } = self.ecx;
+        match self.value {
+            ReturnType::Type(_, box ty) => self.ecx.expand(ty).to_tokens(tokens),
+            ReturnType::Default => {
+                quote_each_token!(tokens #pcx.mk_unit_ty());
+            },
+        }
+    }
+}
+
+/// Expands the trait bounds, while the region bounds are skipped.
+impl ToTokens for Expand<'_, &Punctuated<TypeParamBound, Token![+]>> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let ExpandCtxt { pcx, .. } = self.ecx;
+        for bound in self.value {
+            let TypeParamBound::Trait(TraitBound { path, fn_args }) = bound else {
+                continue;
+            };
+            let path = self.ecx.expand(path);
+            match fn_args {
+                None => {
+                    quote_each_token!(tokens #pcx.mk_trait_bound(#path),);
+                },
+                Some(ParenthesizedGenericArguments { inputs, output, .. }) => {
+                    let inputs = self.ecx.expand_punctuated(inputs);
+                    let output = self.ecx.expand(output);
+                    quote_each_token!(tokens #pcx.mk_fn_trait_bound(#path, &[#inputs], #output),);
+                },
+            }
+        }
+    }
+}
+
+/// A generic `type` item with its arguments, expanded by substituting the arguments for the
+/// parameters in its definition.
+struct TypeAliasArgs<'a>(&'a TypeDecl, &'a Punctuated<GenericArgument, Token![,]>);
+
+impl ToTokens for Expand<'_, TypeAliasArgs<'_>> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let TypeAliasArgs(TypeDecl { generics, ty, .. }, args) = self.value;
+        let type_args = self.ecx.expand_type_args(generics.as_ref(), args);
+        self.ecx.with_type_args(&type_args).expand(ty).to_tokens(tokens);
+    }
+}
+
+impl ExpandCtxt<'_> {
+    /// Expands the arguments of the generic parameters of a `type` or `fragment` item.
+    fn expand_type_args<'a>(
+        &self,
+        generics: Option<&'a TypeGenerics>,
+        args: &Punctuated<GenericArgument, Token![,]>,
+    ) -> Vec<(&'a Ident, TokenStream)> {
+        generics
+            .iter()
+            .flat_map(|generics| &generics.params)
+            .zip(args)
+            .map(|(param, arg)| {
+                let arg = match (param, arg) {
+                    (TypeParam::Type(_), GenericArgument::Type(ty)) => self.expand(ty).into_token_stream(),
+                    (TypeParam::Const(_), GenericArgument::Type(Type::TyVar(TypeVar { ident, .. }))) => {
+                        self.expand_const_var(ident)
+                    },
+                    (TypeParam::Const(ConstParam { ty, .. }), GenericArgument::Const(GenericConst { konst, .. })) => {
+                        let ty = match ty {
+                            Type::Path(TypePath { path, .. }) => path.as_ident(),
+                            _ => None,
+                        }
+                        .expect("const parameter of non-primitive type");
+                        self.expand(TypedConst(konst, ty)).into_token_stream()
+                    },
+                    _ => panic!("mismatched generic argument for `${}`", param.ident()),
+                };
+                (param.ident(), arg)
+            })
+            .collect()
+    }
+}
+
+/// A constant, where an integer literal without a suffix is of the given primitive type, such as
+/// `usize` for the length of an array type.
+struct TypedConst<'a>(&'a Const, &'a Ident);
+
+impl ToTokens for Expand<'_, TypedConst<'_>> {
+    fn to_tokens(&self, mut tokens: &mut TokenStream) {
+        let TypedConst(konst, ty) = self.value;
+        match konst {
+            Const::Lit(syn::Lit::Int(lit_int)) => {
+                let lit_int = if lit_int.suffix().is_empty() {
+                    &syn::LitInt::new(&format!("{}_{ty}", lit_int.base10_digits()), lit_int.span())
+                } else {
+                    lit_int
+                };
+                quote_each_token!(tokens ::rpl_context::pat::Const::Value(#lit_int.into()));
+            },
+            konst => self.ecx.expand(konst).to_tokens(tokens),
+        }
+    }
+}
+
 impl ToTokens for Expand<'_, &LangItemWithArgs> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         let ExpandCtxt { pcx, .. } = self.ecx;
@@ -1069,7 +1658,7 @@ impl ToTokens for ExpandPat<'_, &Rvalue> {
                 place,
                 ..
             }) => {
-                let region = self.ecx.expand(region.kind());
+                let region = self.ecx.expand(region.as_ref());
                 let mutability = self.ecx.expand(BorrowKind(*mutability));
                 let place = self.ecx.expand(place);
                 quote_each_token!(tokens Ref(#region, #mutability, #place));
@@ -1091,6 +1680,16 @@ impl ToTokens for ExpandPat<'_, &Rvalue> {
                 let cast_kind = self.ecx.expand(*cast_kind);
                 quote_each_token!(tokens Cast(#cast_kind, #operand, #ty));
             },
+            Rvalue::BinaryOp(RvalueBinOp {
+                op: BinOp::Arith(_),
+                lhs,
+                rhs,
+                ..
+            }) => {
+                let lhs = self.ecx.expand(lhs);
+                let rhs = self.ecx.expand(rhs);
+                quote_each_token!(tokens ArithOp(Box::new([#lhs, #rhs])));
+            },
             Rvalue::BinaryOp(RvalueBinOp { op, lhs, rhs, .. }) => {
                 let op = self.ecx.expand(*op);
                 let lhs = self.ecx.expand(lhs);
@@ -1115,6 +1714,24 @@ impl ToTokens for ExpandPat<'_, &Rvalue> {
                 let aggregate = self.ecx.expand(aggregate);
                 quote_each_token!(tokens Aggregate(#aggregate));
             },
+            Rvalue::ThreadLocalRef(RvalueThreadLocalRef { thread_local, .. }) => match thread_local {
+                ThreadLocalStatic::Any(_) => {
+                    quote_each_token!(tokens ThreadLocalRef(None));
+                },
+                ThreadLocalStatic::Path(path) => {
+                    let path = self.ecx.expand(path);
+                    quote_each_token!(tokens ThreadLocalRef(Some(#path)));
+                },
+            },
+            Rvalue::ShallowInitBox(RvalueShallowInitBox { operand, ty, .. }) => {
+                let operand = self.ecx.expand(operand);
+                let ty = self.ecx.expand(ty);
+                quote_each_token!(tokens ShallowInitBox(#operand, #ty));
+            },
+            Rvalue::CopyForDeref(RvalueCopyForDeref { place, .. }) => {
+                let place = self.ecx.expand(place);
+                quote_each_token!(tokens CopyForDeref(#place));
+            },
         }
     }
 }
@@ -1166,10 +1783,11 @@ impl ToTokens for ExpandPat<'_, &FnOperand> {
                 quote_each_token!(tokens Constant(#mir_pat.mk_zeroed(#path)));
             },
             FnOperand::Type(TypePath {
-                qself: Some(_qself),
-                path: _,
+                qself: Some(qself),
+                path,
             }) => {
-                todo!()
+                let path = self.ecx.expand(TypeRelativePath(qself, path));
+                quote_each_token!(tokens Constant(#mir_pat.mk_zeroed(#path)));
             },
             FnOperand::LangItem(lang_item) => {
                 let lang_item = self.ecx.expand(lang_item);
@@ -1199,10 +1817,11 @@ impl ToTokens for ExpandPat<'_, &ConstOperandKind> {
                 quote_each_token!(tokens #mir_pat.mk_zeroed(#path));
             },
             ConstOperandKind::Type(TypePath {
-                qself: Some(_qself),
-                path: _,
+                qself: Some(qself),
+                path,
             }) => {
-                todo!();
+                let path = self.ecx.expand(TypeRelativePath(qself, path));
+                quote_each_token!(tokens #mir_pat.mk_zeroed(#path));
             },
             ConstOperandKind::LangItem(lang_item) => {
                 let lang_item = self.ecx.expand(lang_item);
@@ -1216,7 +1835,7 @@ impl ToTokens for Expand<'_, &Const> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self.value {
             Const::Lit(lit) => self.ecx.expand(lit).to_tokens(tokens),
-            Const::ConstVar(const_var) => const_var.ident.as_const().to_tokens(tokens),
+            Const::ConstVar(const_var) => self.ecx.expand_const_var(&const_var.ident).to_tokens(tokens),
             Const::Path(TypePath { qself: None, path }) => {
                 todo!("{}", path.to_token_stream());
                 // let path = self.expand(path);
@@ -1436,6 +2055,14 @@ impl ToTokens for ExpandPat<'_, &RvalueAggregate> {
                 let metadata = self.ecx.expand(metadata);
                 quote_each_token!(tokens RawPtr(#ty, #mutability), #mir_pat.mk_list([#ptr, #metadata]));
             },
+            RvalueAggregate::Closure(AggregateClosure { operands, .. }) => {
+                let operands = self.ecx.expand_punctuated(&operands.value);
+                quote_each_token!(tokens Closure, #mir_pat.mk_list([#operands]));
+            },
+            RvalueAggregate::Coroutine(AggregateCoroutine { operands, .. }) => {
+                let operands = self.ecx.expand_punctuated(&operands.value);
+                quote_each_token!(tokens Coroutine, #mir_pat.mk_list([#operands]));
+            },
         }
     }
 }
@@ -1449,12 +2076,17 @@ impl ToTokens for Expand<'_, &PathOrLangItem> {
     }
 }
 
-impl ToTokens for Expand<'_, RegionKind> {
+/// Expands a region, where an omitted region is `'_`.
+impl ToTokens for Expand<'_, Option<&Region>> {
     fn to_tokens(&self, mut tokens: &mut TokenStream) {
         quote_each_token!(tokens ::rpl_context::pat::RegionKind::);
-        match self.value {
-            RegionKind::ReAny(_) => quote_token!(ReAny tokens),
-            RegionKind::ReStatic(_) => quote_token!(ReStatic tokens),
+        match self.value.map(|region| &region.kind) {
+            None | Some(RegionKind::ReAny(_)) => quote_token!(ReAny tokens),
+            Some(RegionKind::ReStatic(_)) => quote_token!(ReStatic tokens),
+            Some(RegionKind::ReVar(RegionVar { lifetime, .. })) => {
+                let region_var = lifetime.ident.as_region_var();
+                quote_each_token!(tokens ReVar(#region_var));
+            },
         }
     }
 }
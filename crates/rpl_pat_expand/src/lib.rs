@@ -8,6 +8,7 @@
 
 extern crate rpl_pat_syntax as syntax;
 
+extern crate rustc_abi;
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_hash;
@@ -26,6 +27,7 @@ macro_rules! todo {
 
 mod check;
 pub(crate) mod expand;
+mod module;
 pub(crate) mod symbol_table;
 
 #[cfg(test)]
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use syn::Ident;
+use syntax::{ItemKind, Mod, Pattern};
+
+use crate::symbol_table::CheckError;
+
+/// A pattern module declared by `#[path = "..."] mod $ident;`, loaded from a `.rpl` file.
+pub(crate) struct Module<'pat> {
+    pub(crate) ident: &'pat Ident,
+    pub(crate) pattern: Pattern,
+}
+
+/// The path of a pattern module, relative to the root of the crate being compiled.
+pub(crate) fn module_path(module: &Mod) -> PathBuf {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    PathBuf::from(root).join(module.path.value())
+}
+
+/// Loads the pattern modules declared in `pattern`, which can only contain `fragment` items.
+pub(crate) fn load_modules(pattern: &Pattern) -> syn::Result<Vec<Module<'_>>> {
+    pattern
+        .items
+        .iter()
+        .filter_map(|item| match &item.kind {
+            ItemKind::Mod(module) => Some(module),
+            _ => None,
+        })
+        .map(|module| {
+            let path = module_path(module);
+            let source = std::fs::read_to_string(&path).map_err(|err| {
+                syn::Error::new_spanned(&module.path, CheckError::ReadModule(path.display().to_string(), err))
+            })?;
+            let pattern = syn::parse_str::<Pattern>(&source).map_err(|err| {
+                syn::Error::new_spanned(
+                    &module.path,
+                    format_args!("in pattern module \"{}\": {err}", path.display()),
+                )
+            })?;
+            // Spans of the module file are lost, so errors are reported at its path.
+            if pattern
+                .items
+                .iter()
+                .any(|item| !matches!(item.kind, ItemKind::Fragment(_)))
+            {
+                return Err(syn::Error::new_spanned(
+                    &module.path,
+                    format_args!(
+                        "in pattern module \"{}\": {}",
+                        path.display(),
+                        CheckError::NonFragmentInModule
+                    ),
+                ));
+            }
+            Ok(Module {
+                ident: &module.ident,
+                pattern,
+            })
+        })
+        .collect()
+}
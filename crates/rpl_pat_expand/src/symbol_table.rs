@@ -3,7 +3,9 @@ use proc_macro2::Span;
 use rustc_hash::FxHashMap;
 use syn::Ident;
 use syn_derive::ToTokens;
-use syntax::{ConstMetaVar, Path, PlaceLocal, PlaceLocalKind, PlaceMetaVar, SelfParam, TyVar, Type};
+use syntax::{
+    ConstMetaVar, Path, PlaceLocal, PlaceLocalKind, PlaceMetaVar, RegionVar, SelfParam, TyVar, Type, TypeDecl,
+};
 
 #[derive(Debug, Display)]
 pub(crate) enum SymbolKind {
@@ -21,6 +23,12 @@ pub(crate) enum SymbolKind {
     Param,
     #[display("function")]
     Fn,
+    #[display("type")]
+    Type,
+    #[display("fragment")]
+    Fragment,
+    #[display("module")]
+    Module,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -36,12 +44,16 @@ pub(crate) enum CheckError<'a> {
     ConstVarAlreadyDeclared(&'a Ident),
     #[error("place variable `${0}` is already declared")]
     PlaceVarAlreadyDeclared(&'a Ident),
+    #[error("region variable `$'{0}` is already declared")]
+    RegionVarAlreadyDeclared(&'a Ident),
     #[error("type variable `${0}` is not declared")]
     TypeVarNotDeclared(&'a Ident),
     #[error("constant variable `${0}` is not declared")]
     ConstVarNotDeclared(&'a Ident),
     #[error("place variable `${0}` is not declared")]
     PlaceVarNotDeclared(&'a Ident),
+    #[error("region variable `$'{0}` is not declared")]
+    RegionVarNotDeclared(&'a Ident),
     #[error("export named by `{0}` is already declared")]
     ExportAlreadyDeclared(&'a Ident),
     #[error("type or path named by `{0}` is already declared")]
@@ -72,13 +84,67 @@ pub(crate) enum CheckError<'a> {
     MultipleOtherwiseInSwitchInt,
     #[error("missing integer suffix in switchInt statement")]
     MissingSuffixInSwitchInt,
+    #[error("unknown kind of assert message `{0}`")]
+    UnknownAssertKind(&'a Ident),
     #[error("unknown language item \"{0}\"")]
     UnknownLangItem(String),
+    #[error("`#[applies_to]` is only supported on `fn` patterns")]
+    AppliesToNonFn,
+    #[error("`#[constraints]` is only supported on `fn` patterns")]
+    ConstraintsOnNonFn,
+    #[error("duplicated constraint `{0}`")]
+    DuplicatedConstraint(String),
+    #[error("empty crate name in `#[applies_to]`")]
+    AppliesToEmptyCrate,
+    #[error("{0} in `#[applies_to]`")]
+    AppliesToInvalidVersion(rpl_config::VersionReqError),
+    #[error("invalid ABI \"{0}\"")]
+    UnknownAbi(String),
+    #[error("at least one trait is required for `{0}`")]
+    MissingTraitBound(&'static str),
+    #[error("only a single explicit lifetime bound is permitted")]
+    MultipleRegionBounds,
+    #[error("lifetime bounds of `impl Trait` are not supported")]
+    RegionBoundOnImplTrait,
+    #[error("only type variables can be bounded in `where` clauses")]
+    WhereBoundOnNonTyVar,
+    #[error("only `'static` is supported as a lifetime bound in `where` clauses")]
+    UnsupportedRegionInWhereBound,
+    #[error("generic arguments of traits are not supported in `where` clauses")]
+    GenericTraitInWhereBound,
+    #[error("only type-relative paths of a single segment, like `<*mut _>::add`, are supported")]
+    UnsupportedTypeRelativePath,
+    #[error("items in `impl` patterns are not supported yet")]
+    ImplItemsNotSupported,
+    #[error("generic type `{0}` must be declared outside of `fn` patterns")]
+    GenericTypeInFn(&'a Ident),
+    #[error("`#[meta]` is not supported on `{0}` items, use generic parameters instead")]
+    MetaOnItem(&'static str),
+    #[error("type parameter `${0}` is never used")]
+    UnusedTypeParam(&'a Ident),
+    #[error("the type of const parameter `${0}` must be a primitive type")]
+    ConstParamNotPrimitive(&'a Ident),
+    #[error("{0} `{1}` takes {2} generic arguments but {3} were supplied")]
+    TypeArgCountMismatch(SymbolKind, &'a Ident, usize, usize),
+    #[error("expect a type argument for `${0}`")]
+    ExpectTypeArg(&'a Ident),
+    #[error("expect a constant argument for `${0}`")]
+    ExpectConstArg(&'a Ident),
+    #[error("fragment `{0}` takes {1} locals but {2} were supplied")]
+    FragmentInputCountMismatch(&'a Ident, usize, usize),
+    #[error("fragment `{0}` exports {1} locals but {2} were bound")]
+    FragmentOutputCountMismatch(&'a Ident, usize, usize),
+    #[error("cycle detected when using fragment `{0}`: {1}")]
+    FragmentCycle(&'a Ident, String),
+    #[error("failed to read pattern module \"{0}\": {1}")]
+    ReadModule(String, std::io::Error),
+    #[error("only `fragment` items are supported in pattern modules")]
+    NonFragmentInModule,
 }
 
 #[derive(Clone, Copy, From, ToTokens)]
 pub(crate) enum TypeOrPath<'a> {
-    Type(&'a Type),
+    Type(&'a TypeDecl),
     Path(&'a Path),
 }
 
@@ -108,6 +174,7 @@ pub(crate) struct MetaTable<'a> {
     ty_vars: FxHashMap<&'a Ident, &'a TyVar>,
     const_vars: FxHashMap<&'a Ident, &'a ConstMetaVar>,
     place_vars: FxHashMap<&'a Ident, &'a PlaceMetaVar>,
+    region_vars: FxHashMap<&'a Ident, &'a RegionVar>,
     exports: FxHashMap<&'a Ident, ExportKind>,
 }
 
@@ -132,6 +199,86 @@ pub(crate) struct SymbolTable<'a> {
     fns: FxHashMap<&'a Ident, Fn<'a>>,
     unnamed_fns: Vec<Fn<'a>>,
     impls: Vec<Impl<'a>>,
+    types: TypeTable<'a>,
+    fragments: FragmentTable<'a>,
+}
+
+/// The `type` items declared outside of `fn` patterns, shared by all of them.
+#[derive(Default)]
+pub(crate) struct TypeTable<'a> {
+    types: FxHashMap<&'a Ident, &'a TypeDecl>,
+}
+
+impl<'a> TypeTable<'a> {
+    pub fn add_type(&mut self, type_decl: &'a TypeDecl) -> syn::Result<()> {
+        let ident = &type_decl.ident;
+        self.types
+            .try_insert(ident, type_decl)
+            .map_err(|entry| syn::Error::new(entry.entry.key().span(), CheckError::TypeOrPathAlreadyDeclared(ident)))?;
+        Ok(())
+    }
+    pub fn get_type(&self, ident: &Ident) -> Option<&'a TypeDecl> {
+        self.types.get(ident).copied()
+    }
+}
+
+/// The `fragment` items of a pattern or a pattern module, and the pattern modules declared by it.
+#[derive(Default)]
+pub(crate) struct FragmentTable<'a> {
+    fragments: FxHashMap<&'a Ident, Fragment<'a>>,
+    modules: FxHashMap<&'a Ident, FragmentTable<'a>>,
+}
+
+impl<'a> FragmentTable<'a> {
+    pub fn add_fragment(&mut self, fragment: Fragment<'a>) -> syn::Result<()> {
+        let ident = &fragment.inner.fragment.ident;
+        self.fragments.try_insert(ident, fragment).map_err(|entry| {
+            syn::Error::new(
+                entry.entry.key().span(),
+                CheckError::SymbolAlreadyDeclared(SymbolKind::Fragment, ident),
+            )
+        })?;
+        Ok(())
+    }
+    pub fn add_module(&mut self, ident: &'a Ident, module: FragmentTable<'a>) -> syn::Result<()> {
+        self.modules.try_insert(ident, module).map_err(|entry| {
+            syn::Error::new(
+                entry.entry.key().span(),
+                CheckError::SymbolAlreadyDeclared(SymbolKind::Module, ident),
+            )
+        })?;
+        Ok(())
+    }
+    pub fn contains_fragment(&self, ident: &Ident) -> bool {
+        self.fragments.contains_key(ident)
+    }
+    /// Resolves the fragment `module::$ident`, together with the table where the fragments used by
+    /// it are resolved.
+    pub fn get_fragment<'b>(
+        &'b self,
+        module: Option<&Ident>,
+        ident: &Ident,
+    ) -> syn::Result<(&'b Fragment<'a>, &'b FragmentTable<'a>)> {
+        let fragments = match module {
+            None => self,
+            Some(module) => self.modules.get(module).ok_or_else(|| {
+                syn::Error::new(module.span(), CheckError::SymbolNotDeclared(SymbolKind::Module, module))
+            })?,
+        };
+        let fragment = fragments
+            .fragments
+            .get(ident)
+            .ok_or_else(|| syn::Error::new(ident.span(), CheckError::SymbolNotDeclared(SymbolKind::Fragment, ident)))?;
+        Ok((fragment, fragments))
+    }
+}
+
+pub(crate) type Fragment<'a> = WithMetaTable<'a, FragmentInner<'a>>;
+
+pub(crate) struct FragmentInner<'a> {
+    pub(crate) fragment: &'a syntax::Fragment,
+    /// The parameters and locals of the fragment, where the types of exported locals are looked up.
+    pub(crate) fn_def: FnInner<'a>,
 }
 
 pub(crate) type Enum<'a> = WithMetaTable<'a, EnumInner<'a>>;
@@ -196,7 +343,7 @@ pub(crate) struct FnInner<'a> {
 }
 
 impl<'a> FnInner<'a> {
-    fn new(span: Span, self_ty: Option<&'a Type>) -> Self {
+    pub fn new(span: Span, self_ty: Option<&'a Type>) -> Self {
         Self {
             span,
             types: FxHashMap::default(),
@@ -244,8 +391,8 @@ impl<'a> FnInner<'a> {
             .map_err(|entry| syn::Error::new(entry.entry.key().span(), CheckError::TypeOrPathAlreadyDeclared(ident)))?;
         Ok(())
     }
-    pub fn add_type(&mut self, ident: &'a Ident, ty: &'a Type) -> syn::Result<()> {
-        self.add_type_impl(ident, ty.into())
+    pub fn add_type(&mut self, type_decl: &'a TypeDecl) -> syn::Result<()> {
+        self.add_type_impl(&type_decl.ident, type_decl.into())
     }
     pub fn get_type(&self, ident: &Ident) -> syn::Result<TypeOrPath<'a>> {
         self.types
@@ -296,6 +443,19 @@ impl<'a> MetaTable<'a> {
             .copied()
             .ok_or_else(|| syn::Error::new(ident.span(), CheckError::PlaceVarNotDeclared(ident)))
     }
+    pub fn add_region_var(&mut self, region_var: &'a RegionVar) -> syn::Result<()> {
+        let ident = &region_var.lifetime.ident;
+        self.region_vars
+            .try_insert(ident, region_var)
+            .map_err(|entry| syn::Error::new(entry.entry.key().span(), CheckError::RegionVarAlreadyDeclared(ident)))?;
+        Ok(())
+    }
+    pub fn get_region_var(&self, ident: &Ident) -> syn::Result<&'a RegionVar> {
+        self.region_vars
+            .get(ident)
+            .copied()
+            .ok_or_else(|| syn::Error::new(ident.span(), CheckError::RegionVarNotDeclared(ident)))
+    }
     pub fn add_export(&mut self, export: &'a Ident, kind: ExportKind) -> syn::Result<()> {
         self.exports.try_insert(export, kind).map_err(|entry| {
             let ident = entry.entry.key();
@@ -338,11 +498,22 @@ impl<'a> SymbolTable<'a> {
             Ident(ident) => Err(syn::Error::new(ident.span(), CheckError::FnIdentMissingDollar(ident))),
         }
     }
-    #[expect(unused)]
     pub fn add_impl(&mut self, impl_pat: &'a syntax::Impl) -> &mut Impl<'a> {
         self.impls.push(ImplInner::new(impl_pat).into());
         self.impls.last_mut().unwrap()
     }
+    pub fn set_types(&mut self, types: TypeTable<'a>) {
+        self.types = types;
+    }
+    pub fn get_type(&self, ident: &Ident) -> Option<&'a TypeDecl> {
+        self.types.get_type(ident)
+    }
+    pub fn set_fragments(&mut self, fragments: FragmentTable<'a>) {
+        self.fragments = fragments;
+    }
+    pub fn fragments(&self) -> &FragmentTable<'a> {
+        &self.fragments
+    }
     pub fn contains_adt(&self, ident: &Ident) -> bool {
         self.structs.contains_key(ident) || self.enums.contains_key(ident)
     }
@@ -28,6 +28,7 @@ macro_rules! mir_test_case {
                 let pattern_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("pattern"));
                 $($meta_output)*
                 pattern_fn.set_ret_ty(pcx.mk_any_ty());
+                pattern_fn.params.set_non_exhaustive();
                 let mut mir_pat = ::rpl_context::pat::MirPattern::builder();
                 $($output)*
                 let mir_pat = mir_pat.build();
@@ -202,7 +203,7 @@ fn test_coercion() {
                     ::rpl_context::pat::RegionKind::ReAny,
                     pcx.mk_array_ty(
                         T_ty,
-                        ::rpl_context::pat::ConstOperand::ScalarInt(2.into())),
+                        ::rpl_context::pat::Const::Value(2_usize.into())),
                         ::rustc_middle::mir::Mutability::Not
                     )
                 );
@@ -238,6 +239,55 @@ fn test_coercion() {
     );
 }
 
+#[test]
+fn test_type_relative_fn_call() {
+    mir_test_case!(
+        #[meta($T:ty)]
+        pat! {
+            let $base: *mut u8 = _;
+            let $ptr: *mut u8 = <*mut _>::add(copy $base, _);
+            let $typed: *mut $T = move $ptr as *mut $T (PtrToPtr);
+        } => {
+            meta!{
+                #[allow(non_snake_case)]
+                let T_ty_var = pattern_fn.meta.new_ty_var(None);
+                #[allow(non_snake_case)]
+                let T_ty = pcx.mk_var_ty(T_ty_var);
+            }
+            let base_local = mir_pat.mk_local(
+                pcx.mk_raw_ptr_ty(pcx.primitive_types.u8, ::rustc_middle::mir::Mutability::Mut)
+            );
+            mir_pat.mk_assign(base_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let ptr_local = mir_pat.mk_local(
+                pcx.mk_raw_ptr_ty(pcx.primitive_types.u8, ::rustc_middle::mir::Mutability::Mut)
+            );
+            mir_pat.mk_fn_call(
+                ::rpl_context::pat::Operand::Constant(mir_pat.mk_zeroed(pcx.mk_path_with_args(
+                    pcx.mk_type_relative(
+                        pcx.mk_raw_ptr_ty(pcx.mk_any_ty(), ::rustc_middle::mir::Mutability::Mut),
+                        "add"
+                    ),
+                    &[]
+                ))),
+                mir_pat.mk_list([
+                    ::rpl_context::pat::Operand::Copy(base_local.into_place()),
+                    ::rpl_context::pat::Operand::Any
+                ]),
+                Some(ptr_local.into_place())
+            );
+            let typed_local = mir_pat.mk_local(pcx.mk_raw_ptr_ty(T_ty, ::rustc_middle::mir::Mutability::Mut));
+            mir_pat.mk_assign(
+                typed_local.into_place(),
+                ::rpl_context::pat::Rvalue::Cast(
+                    ::rustc_middle::mir::CastKind::PtrToPtr,
+                    ::rpl_context::pat::Operand::Move(ptr_local.into_place()),
+                    pcx.mk_raw_ptr_ty(T_ty, ::rustc_middle::mir::Mutability::Mut)
+                )
+            );
+        }
+    );
+}
+
 #[test]
 fn test_cve_2020_25016() {
     mir_test_case!(
@@ -2613,6 +2663,7 @@ fn test_cve_2020_35907() {
                 T_ty,
                 ::rustc_middle::mir::Mutability::Not
             ));
+            pattern_fn.params.set_non_exhaustive();
             let mut mir_pat = ::rpl_context::pat::MirPattern::builder();
             let result_local = mir_pat.mk_local(
                 pcx.mk_path_ty(
@@ -2673,3 +2724,872 @@ fn test_cve_2020_35907() {
         }
     }
 }
+
+#[test]
+fn test_applies_to() {
+    test_case!(pat! {
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        fn $pattern(..) -> _;
+    } => quote! {
+        let pattern_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("pattern"));
+        pattern_fn.set_ret_ty(pcx.mk_any_ty());
+        pattern_fn.params.set_non_exhaustive();
+        pattern_fn.set_applies_to(::rustc_span::Symbol::intern("crossbeam-utils"), "< 0.8.7");
+    });
+}
+
+#[test]
+fn test_applies_to_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            #[applies_to(crate = "crossbeam-utils", version = "< 0.8.x")]
+            fn $pattern(..) -> _;
+        },
+        "invalid version `0.8.x` in version requirement in `#[applies_to]`",
+    );
+    check_error(
+        quote! {
+            #[applies_to(crate = "", version = "< 0.8.7")]
+            fn $pattern(..) -> _;
+        },
+        "empty crate name in `#[applies_to]`",
+    );
+    check_error(
+        quote! {
+            #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+            struct $S {}
+        },
+        "`#[applies_to]` is only supported on `fn` patterns",
+    );
+}
+
+#[test]
+fn test_fn_ptr_and_trait_object_types() {
+    mir_test_case!(
+        #[meta($T:ty)]
+        pat! {
+            let $callback: unsafe extern "C" fn(*mut core::ffi::c_void) -> i32 = _;
+            let $fn_ptr: fn(&[$T; 4]) = _;
+            let $object: &dyn std::ops::Fn($T) -> ! + std::marker::Send + 'static = _;
+            let $closure: impl std::ops::FnMut() = _;
+        } => {
+            meta! {
+                #[allow(non_snake_case)]
+                let T_ty_var = pattern_fn.meta.new_ty_var(None);
+                #[allow(non_snake_case)]
+                let T_ty = pcx.mk_var_ty(T_ty_var);
+            }
+            let callback_local = mir_pat.mk_local(pcx.mk_fn_ptr_ty(
+                &[pcx.mk_raw_ptr_ty(
+                    pcx.mk_path_ty(pcx.mk_path_with_args(pcx.mk_item_path(&["core", "ffi", "c_void",]), &[])),
+                    ::rustc_middle::mir::Mutability::Mut
+                )],
+                pcx.primitive_types.i32,
+                ::rustc_hir::Safety::Unsafe,
+                "C"
+            ));
+            mir_pat.mk_assign(callback_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let fn_ptr_local = mir_pat.mk_local(pcx.mk_fn_ptr_ty(
+                &[pcx.mk_ref_ty(
+                    ::rpl_context::pat::RegionKind::ReAny,
+                    pcx.mk_array_ty(T_ty, ::rpl_context::pat::Const::Value(4_usize.into())),
+                    ::rustc_middle::mir::Mutability::Not
+                )],
+                pcx.mk_unit_ty(),
+                ::rustc_hir::Safety::Safe,
+                "Rust"
+            ));
+            mir_pat.mk_assign(fn_ptr_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let object_local = mir_pat.mk_local(pcx.mk_ref_ty(
+                ::rpl_context::pat::RegionKind::ReAny,
+                pcx.mk_dyn_ty(
+                    &[
+                        pcx.mk_fn_trait_bound(
+                            pcx.mk_path_with_args(pcx.mk_item_path(&["std", "ops", "Fn",]), &[]),
+                            &[T_ty],
+                            pcx.mk_never_ty()
+                        ),
+                        pcx.mk_trait_bound(pcx.mk_path_with_args(pcx.mk_item_path(&["std", "marker", "Send",]), &[])),
+                    ],
+                    ::rpl_context::pat::RegionKind::ReStatic
+                ),
+                ::rustc_middle::mir::Mutability::Not
+            ));
+            mir_pat.mk_assign(object_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let closure_local = mir_pat.mk_local(pcx.mk_impl_trait_ty(&[
+                pcx.mk_fn_trait_bound(
+                    pcx.mk_path_with_args(pcx.mk_item_path(&["std", "ops", "FnMut",]), &[]),
+                    &[],
+                    pcx.mk_unit_ty()
+                ),
+            ]));
+            mir_pat.mk_assign(closure_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+        }
+    );
+}
+
+#[test]
+fn test_type_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            fn $pattern($f: extern "D" fn()) -> _;
+        },
+        "invalid ABI \"D\"",
+    );
+    check_error(
+        quote! {
+            fn $pattern($x: &dyn 'static) -> _;
+        },
+        "at least one trait is required for `dyn Trait`",
+    );
+    check_error(
+        quote! {
+            fn $pattern($x: &dyn std::any::Any + 'static + '_) -> _;
+        },
+        "only a single explicit lifetime bound is permitted",
+    );
+    check_error(
+        quote! {
+            fn $pattern($x: impl std::any::Any + 'static) -> _;
+        },
+        "lifetime bounds of `impl Trait` are not supported",
+    );
+}
+
+#[test]
+fn test_generic_type() {
+    test_case! {
+        pat! {
+            type RawSlice<$T> = *const [$T];
+            type ArrayT<$T, const $N: usize> = [$T; $N];
+
+            #[meta($T:ty, $n: const(usize))]
+            fn $first(RawSlice<$T>, ArrayT<u8, 4>, ArrayT<RawSlice<$T>, $n>) -> _;
+            fn $second(RawSlice<u8>) -> _;
+        } => quote! {
+            let first_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("first"));
+            #[allow(non_snake_case)]
+            let T_ty_var = first_fn.meta.new_ty_var(None);
+            #[allow(non_snake_case)]
+            let T_ty = pcx.mk_var_ty(T_ty_var);
+            #[allow(non_snake_case)]
+            let n_const_var = first_fn.meta.new_const_var(pcx.primitive_types.usize);
+            #[allow(non_snake_case)]
+            #[allow(unused_variables)]
+            let n_const = pcx.mk_var_const(n_const_var);
+            first_fn.set_ret_ty(pcx.mk_any_ty());
+            first_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_raw_ptr_ty(pcx.mk_slice_ty(T_ty), ::rustc_middle::mir::Mutability::Not)
+            );
+            first_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_array_ty(pcx.primitive_types.u8, ::rpl_context::pat::Const::Value(4_usize.into()))
+            );
+            first_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_array_ty(
+                    pcx.mk_raw_ptr_ty(pcx.mk_slice_ty(T_ty), ::rustc_middle::mir::Mutability::Not),
+                    n_const
+                )
+            );
+            let second_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("second"));
+            second_fn.set_ret_ty(pcx.mk_any_ty());
+            second_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_raw_ptr_ty(pcx.mk_slice_ty(pcx.primitive_types.u8), ::rustc_middle::mir::Mutability::Not)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_generic_type_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            type RawSlice<$T> = *const [$T];
+            fn $pattern(RawSlice<u8, u8>) -> _;
+        },
+        "type `RawSlice` takes 1 generic arguments but 2 were supplied",
+    );
+    check_error(
+        quote! {
+            type RawSlice<$T> = *const [$T];
+            fn $pattern(RawSlice) -> _;
+        },
+        "type `RawSlice` takes 1 generic arguments but 0 were supplied",
+    );
+    check_error(
+        quote! {
+            type RawSlice<$T, $U> = *const [$T];
+        },
+        "type parameter `$U` is never used",
+    );
+    check_error(
+        quote! {
+            type ArrayT<$T, const $N: usize> = [$T; $N];
+            fn $pattern(ArrayT<4, u8>) -> _;
+        },
+        "expect a type argument for `$T`",
+    );
+    check_error(
+        quote! {
+            type ArrayT<$T, const $N: [u8; 4]> = [$T; $N];
+        },
+        "the type of const parameter `$N` must be a primitive type",
+    );
+    check_error(
+        quote! {
+            type SliceT<$T> = [$U];
+        },
+        "type variable `$U` is not declared",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty)]
+            fn $pattern(..) -> _ = mir! {
+                type SliceT<$T> = [$T];
+            }
+        },
+        "generic type `SliceT` must be declared outside of `fn` patterns",
+    );
+}
+
+#[test]
+fn test_fragment() {
+    test_case! {
+        pat! {
+            fragment $cast<$T>($ptr: *mut u8) -> ($cast) = mir! {
+                let $cast: *mut $T = move $ptr as *mut $T (PtrToPtr);
+            }
+            fragment $alloc_cast<$T>() -> ($ptr, $cast) = mir! {
+                let $ptr: *mut u8 = alloc::alloc::__rust_alloc(_, _);
+                use $cast<$T>($ptr) -> ($cast);
+            }
+
+            fn $pattern(..) -> _ = mir! {
+                use $alloc_cast<u32>() -> ($alloc, $cast);
+                let $len: usize = _;
+                let $ptr: *mut u32 = Offset(copy $cast, copy $len);
+            }
+        } => quote! {
+            let pattern_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("pattern"));
+            pattern_fn.set_ret_ty(pcx.mk_any_ty());
+            pattern_fn.params.set_non_exhaustive();
+            let mut mir_pat = ::rpl_context::pat::MirPattern::builder();
+            let (alloc_local, cast_local,) = {
+                #[allow(non_snake_case)]
+                let T_ty = pcx.primitive_types.u32;
+                let ptr_local = mir_pat.mk_local(
+                    pcx.mk_raw_ptr_ty(pcx.primitive_types.u8, ::rustc_middle::mir::Mutability::Mut)
+                );
+                mir_pat.mk_fn_call(
+                    ::rpl_context::pat::Operand::Constant(mir_pat.mk_zeroed(
+                        pcx.mk_path_with_args(pcx.mk_item_path(&["alloc", "alloc", "__rust_alloc",]), &[])
+                    )),
+                    mir_pat.mk_list([::rpl_context::pat::Operand::Any, ::rpl_context::pat::Operand::Any]),
+                    Some(ptr_local.into_place())
+                );
+                let (cast_local,) = {
+                    #[allow(non_snake_case)]
+                    let T_ty = T_ty;
+                    let ptr_local = ptr_local;
+                    let cast_local = mir_pat.mk_local(pcx.mk_raw_ptr_ty(T_ty, ::rustc_middle::mir::Mutability::Mut));
+                    mir_pat.mk_assign(
+                        cast_local.into_place(),
+                        ::rpl_context::pat::Rvalue::Cast(
+                            ::rustc_middle::mir::CastKind::PtrToPtr,
+                            ::rpl_context::pat::Operand::Move(ptr_local.into_place()),
+                            pcx.mk_raw_ptr_ty(T_ty, ::rustc_middle::mir::Mutability::Mut)
+                        )
+                    );
+                    (cast_local,)
+                };
+                (ptr_local, cast_local,)
+            };
+            let len_local = mir_pat.mk_local(pcx.primitive_types.usize);
+            mir_pat.mk_assign(len_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let ptr_local = mir_pat.mk_local(
+                pcx.mk_raw_ptr_ty(pcx.primitive_types.u32, ::rustc_middle::mir::Mutability::Mut)
+            );
+            mir_pat.mk_assign(
+                ptr_local.into_place(),
+                ::rpl_context::pat::Rvalue::BinaryOp(
+                    ::rustc_middle::mir::BinOp::Offset,
+                    Box::new([
+                        ::rpl_context::pat::Operand::Copy(cast_local.into_place()),
+                        ::rpl_context::pat::Operand::Copy(len_local.into_place())
+                    ])
+                )
+            );
+            let mir_pat = mir_pat.build();
+            let mir_pat = pcx.mk_mir_pattern(mir_pat);
+            pattern_fn.set_body(::rpl_context::pat::FnBody::Mir(mir_pat));
+        }
+    }
+}
+
+#[test]
+fn test_fragment_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            fn $pattern(..) -> _ = mir! {
+                use $alloc() -> ($ptr);
+            }
+        },
+        "fragment `alloc` is not declared",
+    );
+    check_error(
+        quote! {
+            fn $pattern(..) -> _ = mir! {
+                use alloc::$alloc() -> ($ptr);
+            }
+        },
+        "module `alloc` is not declared",
+    );
+    check_error(
+        quote! {
+            fragment $first() -> ($x) = mir! {
+                use $second() -> ($x);
+            }
+            fragment $second() -> ($x) = mir! {
+                use $first() -> ($x);
+            }
+        },
+        "cycle detected when using fragment `first`: `first` -> `second` -> `first`",
+    );
+    check_error(
+        quote! {
+            fragment $cast<$T>($ptr: *mut u8) -> ($cast) = mir! {
+                let $cast: *mut $T = move $ptr as *mut $T (PtrToPtr);
+            }
+            fn $pattern(..) -> _ = mir! {
+                let $ptr: *mut u8 = _;
+                use $cast($ptr) -> ($cast);
+            }
+        },
+        "fragment `cast` takes 1 generic arguments but 0 were supplied",
+    );
+    check_error(
+        quote! {
+            fragment $cast<$T>($ptr: *mut u8) -> ($cast) = mir! {
+                let $cast: *mut $T = move $ptr as *mut $T (PtrToPtr);
+            }
+            fn $pattern(..) -> _ = mir! {
+                use $cast<u8>() -> ($cast);
+            }
+        },
+        "fragment `cast` takes 1 locals but 0 were supplied",
+    );
+    check_error(
+        quote! {
+            fragment $cast<$T>($ptr: *mut u8) -> ($cast) = mir! {
+                let $cast: *mut $T = move $ptr as *mut $T (PtrToPtr);
+            }
+            fn $pattern(..) -> _ = mir! {
+                let $ptr: *mut u8 = _;
+                use $cast<u8>($ptr);
+            }
+        },
+        "fragment `cast` exports 1 locals but 0 were bound",
+    );
+    check_error(
+        quote! {
+            fragment $cast($ptr: *mut u8) -> ($ptr) = mir! {}
+        },
+        "local variable `ptr` is not declared",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty)]
+            fragment $cast() = mir! {}
+        },
+        "`#[meta]` is not supported on `fragment` items, use generic parameters instead",
+    );
+    check_error(
+        quote! {
+            #[path = "no/such/module.rpl"]
+            mod fragments;
+        },
+        &format!(
+            "failed to read pattern module \"{}/no/such/module.rpl\": No such file or directory (os error 2)",
+            env!("CARGO_MANIFEST_DIR")
+        ),
+    );
+}
+
+#[test]
+fn test_fn_constraints() {
+    test_case! {
+        pat! {
+            #[meta($T:ty)]
+            #[constraints(generic, !trait_method, attr(inline), !attr(inline(never)), !attr(rustfmt::skip))]
+            pub(crate) const safe extern "C" fn $pattern(..) -> _;
+        } => quote! {
+            let pattern_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("pattern"));
+            #[allow(non_snake_case)]
+            let T_ty_var = pattern_fn.meta.new_ty_var(None);
+            #[allow(non_snake_case)]
+            let T_ty = pcx.mk_var_ty(T_ty_var);
+            pattern_fn.set_public(false);
+            pattern_fn.set_const();
+            pattern_fn.set_safety(::rustc_hir::Safety::Safe);
+            pattern_fn.set_abi("C");
+            pattern_fn.set_ret_ty(pcx.mk_any_ty());
+            pattern_fn.params.set_non_exhaustive();
+            pattern_fn.set_generic(true);
+            pattern_fn.set_trait_method(false);
+            pattern_fn.add_attr_constraint(&["inline"], true);
+            pattern_fn.add_attr_constraint_with_args(&["inline"], &["never"], false);
+            pattern_fn.add_attr_constraint(&["rustfmt", "skip"], false);
+        }
+    }
+}
+
+#[test]
+fn test_fn_constraints_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            #[constraints(generic)]
+            struct $S {}
+        },
+        "`#[constraints]` is only supported on `fn` patterns",
+    );
+    check_error(
+        quote! {
+            #[constraints(generic, !generic)]
+            fn $pattern(..) -> _;
+        },
+        "duplicated constraint `generic`",
+    );
+    check_error(
+        quote! {
+            extern "D" fn $pattern(..) -> _;
+        },
+        "invalid ABI \"D\"",
+    );
+}
+
+#[test]
+fn test_region_vars() {
+    test_case! {
+        pat! {
+            #[meta($'a, $T:ty, $'b)]
+            #[constraints(outlives($'a, $'b), !unbounded($'b))]
+            fn $pattern(*const $T, &$'a $T) -> &$'b std::cell::Ref<$'a, $T>;
+        } => quote! {
+            let pattern_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("pattern"));
+            #[allow(non_snake_case)]
+            let a_region_var = pattern_fn.meta.new_region_var();
+            #[allow(non_snake_case)]
+            let T_ty_var = pattern_fn.meta.new_ty_var(None);
+            #[allow(non_snake_case)]
+            let T_ty = pcx.mk_var_ty(T_ty_var);
+            #[allow(non_snake_case)]
+            let b_region_var = pattern_fn.meta.new_region_var();
+            pattern_fn.set_ret_ty(pcx.mk_ref_ty(
+                ::rpl_context::pat::RegionKind::ReVar(b_region_var),
+                pcx.mk_path_ty(pcx.mk_path_with_args(
+                    pcx.mk_item_path(&["std", "cell", "Ref",]),
+                    &[::rpl_context::pat::RegionKind::ReVar(a_region_var).into(), T_ty.into(),]
+                )),
+                ::rustc_middle::mir::Mutability::Not
+            ));
+            pattern_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_raw_ptr_ty(T_ty, ::rustc_middle::mir::Mutability::Not)
+            );
+            pattern_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_ref_ty(
+                    ::rpl_context::pat::RegionKind::ReVar(a_region_var),
+                    T_ty,
+                    ::rustc_middle::mir::Mutability::Not
+                )
+            );
+            pattern_fn.add_outlives_constraint(a_region_var, b_region_var, true);
+            pattern_fn.add_unbounded_constraint(b_region_var, false);
+        }
+    }
+}
+
+#[test]
+fn test_region_vars_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            #[meta($'a, $'a)]
+            fn $pattern(..) -> _;
+        },
+        "region variable `$'a` is already declared",
+    );
+    check_error(
+        quote! {
+            fn $pattern(&$'a u8) -> _;
+        },
+        "region variable `$'a` is not declared",
+    );
+    check_error(
+        quote! {
+            #[meta($'a)]
+            #[constraints(outlives($'a, $'b))]
+            fn $pattern(&$'a u8) -> _;
+        },
+        "region variable `$'b` is not declared",
+    );
+    check_error(
+        quote! {
+            fn $pattern(..) -> _ = mir! {
+                let $x: u8 = _;
+                let $r: &u8 = &$'a $x;
+            }
+        },
+        "region variable `$'a` is not declared",
+    );
+}
+
+#[test]
+fn test_where_clause() {
+    test_case! {
+        pat! {
+            #[meta($W:ty)]
+            fn $pattern(std::sync::Arc<$W>) -> _ where $W: std::marker::Send + !'static;
+        } => quote! {
+            let pattern_fn = pattern.fns.new_fn_pat(::rustc_span::Symbol::intern("pattern"));
+            #[allow(non_snake_case)]
+            let W_ty_var = pattern_fn.meta.new_ty_var(None);
+            #[allow(non_snake_case)]
+            let W_ty = pcx.mk_var_ty(W_ty_var);
+            pattern_fn.set_ret_ty(pcx.mk_any_ty());
+            pattern_fn.params.add_param(
+                ::rustc_span::symbol::kw::Empty,
+                ::rustc_middle::mir::Mutability::Not,
+                pcx.mk_path_ty(pcx.mk_path_with_args(
+                    pcx.mk_item_path(&["std", "sync", "Arc",]),
+                    &[W_ty.into(),]
+                ))
+            );
+            pattern_fn.add_where_predicate(
+                W_ty_var,
+                ::rpl_context::pat::WhereBound::Trait(pcx.mk_trait_bound(pcx.mk_path_with_args(
+                    pcx.mk_item_path(&["std", "marker", "Send",]),
+                    &[]
+                ))),
+                true
+            );
+            pattern_fn.add_where_predicate(
+                W_ty_var,
+                ::rpl_context::pat::WhereBound::Outlives(::rpl_context::pat::RegionKind::ReStatic),
+                false
+            );
+        }
+    }
+    test_case! {
+        pat! {
+            #[meta($T:ty, $U:ty)]
+            impl std::marker::Sync for $crate::LazyTransform<$T, $U> where $U: !std::marker::Send {}
+        } => quote! {
+            let impl_pat = pattern.new_impl();
+            #[allow(non_snake_case)]
+            let T_ty_var = impl_pat.meta.new_ty_var(None);
+            #[allow(non_snake_case)]
+            let T_ty = pcx.mk_var_ty(T_ty_var);
+            #[allow(non_snake_case)]
+            let U_ty_var = impl_pat.meta.new_ty_var(None);
+            #[allow(non_snake_case)]
+            let U_ty = pcx.mk_var_ty(U_ty_var);
+            impl_pat.set_trait(::rpl_context::pat::TraitRef::Path(pcx.mk_path_with_args(
+                pcx.mk_item_path(&["std", "marker", "Sync",]),
+                &[]
+            )));
+            impl_pat.set_self_ty(pcx.mk_path_ty(pcx.mk_path_with_args(
+                pcx.mk_item_path(&["crate", "LazyTransform",]),
+                &[T_ty.into(), U_ty.into(),]
+            )));
+            impl_pat.add_where_predicate(
+                U_ty_var,
+                ::rpl_context::pat::WhereBound::Trait(pcx.mk_trait_bound(pcx.mk_path_with_args(
+                    pcx.mk_item_path(&["std", "marker", "Send",]),
+                    &[]
+                ))),
+                false
+            );
+        }
+    }
+}
+
+#[test]
+fn test_where_clause_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            fn $pattern(..) -> _ where $T: std::marker::Send;
+        },
+        "type variable `$T` is not declared",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty)]
+            fn $pattern(..) -> _ where std::vec::Vec<$T>: std::marker::Send;
+        },
+        "only type variables can be bounded in `where` clauses",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty, $'a)]
+            fn $pattern(..) -> _ where $T: $'a;
+        },
+        "only `'static` is supported as a lifetime bound in `where` clauses",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty)]
+            fn $pattern(..) -> _ where $T: std::convert::From<u8>;
+        },
+        "generic arguments of traits are not supported in `where` clauses",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty)]
+            impl $T {
+                fn $new() -> Self;
+            }
+        },
+        "items in `impl` patterns are not supported yet",
+    );
+}
+
+#[test]
+fn test_type_relative_path_error() {
+    #[track_caller]
+    fn check_error(input: TokenStream, msg: &str) {
+        let pattern = syn::parse2(input).unwrap();
+        let err = crate::expand_pattern(&pattern, None).unwrap_err();
+        assert_eq!(err.to_string(), msg);
+    }
+    check_error(
+        quote! {
+            fn $pattern(..) -> _ = mir! {
+                let $ptr: *const u8 = _;
+                let $end: *const u8 = <*const u8>::cast::<u8>::add(copy $ptr, _);
+            }
+        },
+        "only type-relative paths of a single segment, like `<*mut _>::add`, are supported",
+    );
+    check_error(
+        quote! {
+            #[meta($T:ty)]
+            fn $pattern(..) -> _ = mir! {
+                let $value: $T = <$T as core::default::Default>::default();
+            }
+        },
+        "only type-relative paths of a single segment, like `<*mut _>::add`, are supported",
+    );
+}
+
+#[test]
+fn test_coroutine_terminators() {
+    mir_test_case!(
+        #[meta($T:ty)]
+        pat! {
+            let $awaitee: $T = _;
+            yield(move $awaitee);
+            coroutine_drop;
+        } => {
+            meta! {
+                #[allow(non_snake_case)]
+                let T_ty_var = pattern_fn.meta.new_ty_var(None);
+                #[allow(non_snake_case)]
+                let T_ty = pcx.mk_var_ty(T_ty_var);
+            }
+            let awaitee_local = mir_pat.mk_local(T_ty);
+            mir_pat.mk_assign(awaitee_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            mir_pat.mk_yield(::rpl_context::pat::Operand::Move(awaitee_local.into_place()));
+            mir_pat.mk_coroutine_drop();
+        }
+    );
+}
+
+#[test]
+fn test_assert_and_unwind_terminators() {
+    mir_test_case!(
+        #[meta($T:ty)]
+        pat! {
+            let $x: $T = _;
+            let $i: usize = _;
+            let $len: usize = _;
+            let $in_bounds: bool = Lt(copy $i, copy $len);
+            assert(move $in_bounds, BoundsCheck);
+            asm!("nop");
+            drop($x) unwind {
+                unwind_resume;
+            };
+            unreachable;
+        } => {
+            meta! {
+                #[allow(non_snake_case)]
+                let T_ty_var = pattern_fn.meta.new_ty_var(None);
+                #[allow(non_snake_case)]
+                let T_ty = pcx.mk_var_ty(T_ty_var);
+            }
+            let x_local = mir_pat.mk_local(T_ty);
+            mir_pat.mk_assign(x_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let i_local = mir_pat.mk_local(pcx.primitive_types.usize);
+            mir_pat.mk_assign(i_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let len_local = mir_pat.mk_local(pcx.primitive_types.usize);
+            mir_pat.mk_assign(len_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let in_bounds_local = mir_pat.mk_local(pcx.primitive_types.bool);
+            mir_pat.mk_assign(
+                in_bounds_local.into_place(),
+                ::rpl_context::pat::Rvalue::BinaryOp(
+                    ::rustc_middle::mir::BinOp::Lt,
+                    Box::new([
+                        ::rpl_context::pat::Operand::Copy(i_local.into_place()),
+                        ::rpl_context::pat::Operand::Copy(len_local.into_place())
+                    ])
+                )
+            );
+            mir_pat.mk_assert(
+                ::rpl_context::pat::Operand::Move(in_bounds_local.into_place()),
+                true,
+                Some(::rpl_context::pat::AssertKind::BoundsCheck)
+            );
+            mir_pat.mk_inline_asm(Some("nop"));
+            mir_pat.mk_drop(x_local.into_place());
+            mir_pat.mk_unwind(|mir_pat| {
+                mir_pat.mk_unwind_resume();
+            });
+            mir_pat.mk_unreachable();
+        }
+    );
+}
+
+#[test]
+fn test_binary_ops() {
+    mir_test_case!(
+        #[meta()]
+        pat! {
+            let $len: usize = _;
+            let $size: (usize, bool) = MulWithOverflow(copy $len, const 8_usize);
+            let $end: usize = Arith(copy $len, _);
+        } => {
+            meta! {}
+            let len_local = mir_pat.mk_local(pcx.primitive_types.usize);
+            mir_pat.mk_assign(len_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let size_local = mir_pat.mk_local(pcx.mk_tuple_ty(&[pcx.primitive_types.usize, pcx.primitive_types.bool]));
+            mir_pat.mk_assign(
+                size_local.into_place(),
+                ::rpl_context::pat::Rvalue::BinaryOp(
+                    ::rustc_middle::mir::BinOp::MulWithOverflow,
+                    Box::new([
+                        ::rpl_context::pat::Operand::Copy(len_local.into_place()),
+                        ::rpl_context::pat::Operand::Constant(
+                            ::rpl_context::pat::ConstOperand::ScalarInt(8_usize.into())
+                        )
+                    ])
+                )
+            );
+            let end_local = mir_pat.mk_local(pcx.primitive_types.usize);
+            mir_pat.mk_assign(
+                end_local.into_place(),
+                ::rpl_context::pat::Rvalue::ArithOp(Box::new([
+                    ::rpl_context::pat::Operand::Copy(len_local.into_place()),
+                    ::rpl_context::pat::Operand::Any
+                ]))
+            );
+        }
+    );
+}
+
+#[test]
+fn test_intrinsics_and_other_statements() {
+    mir_test_case!(
+        #[meta()]
+        pat! {
+            let $src: *const u8 = _;
+            let $dst: *mut u8 = _;
+            let $count: usize = _;
+            let $cond: bool = _;
+            let $opt: std::option::Option<usize> = _;
+            Intrinsic(copy_nonoverlapping(copy $src, copy $dst, copy $count));
+            Intrinsic(assume(copy $cond));
+            Deinit($opt);
+            discriminant($opt) = 1;
+        } => {
+            meta! {}
+            let src_local = mir_pat.mk_local(
+                pcx.mk_raw_ptr_ty(pcx.primitive_types.u8, ::rustc_middle::mir::Mutability::Not)
+            );
+            mir_pat.mk_assign(src_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let dst_local = mir_pat.mk_local(
+                pcx.mk_raw_ptr_ty(pcx.primitive_types.u8, ::rustc_middle::mir::Mutability::Mut)
+            );
+            mir_pat.mk_assign(dst_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let count_local = mir_pat.mk_local(pcx.primitive_types.usize);
+            mir_pat.mk_assign(count_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let cond_local = mir_pat.mk_local(pcx.primitive_types.bool);
+            mir_pat.mk_assign(cond_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            let opt_local = mir_pat.mk_local(pcx.mk_path_ty(pcx.mk_path_with_args(
+                pcx.mk_item_path(&["std", "option", "Option",]),
+                &[pcx.primitive_types.usize.into(),]
+            )));
+            mir_pat.mk_assign(opt_local.into_place(), ::rpl_context::pat::Rvalue::Any);
+            mir_pat.mk_intrinsic(::rpl_context::pat::NonDivergingIntrinsic::CopyNonOverlapping {
+                src: ::rpl_context::pat::Operand::Copy(src_local.into_place()),
+                dst: ::rpl_context::pat::Operand::Copy(dst_local.into_place()),
+                count: ::rpl_context::pat::Operand::Copy(count_local.into_place()),
+            });
+            mir_pat.mk_intrinsic(::rpl_context::pat::NonDivergingIntrinsic::Assume(
+                ::rpl_context::pat::Operand::Copy(cond_local.into_place())
+            ));
+            mir_pat.mk_deinit(opt_local.into_place());
+            mir_pat.mk_set_discriminant(opt_local.into_place(), 1_u32);
+        }
+    );
+}
@@ -27,6 +27,21 @@ pub(crate) mod kw {
     syn::custom_keyword!(lang);
     syn::custom_keyword!(ctor);
     syn::custom_keyword!(mir);
+    syn::custom_keyword!(applies_to);
+    syn::custom_keyword!(version);
+
+    // Function constraints
+    syn::custom_keyword!(safe);
+    syn::custom_keyword!(constraints);
+    syn::custom_keyword!(generic);
+    syn::custom_keyword!(trait_method);
+    syn::custom_keyword!(attr);
+    syn::custom_keyword!(outlives);
+    syn::custom_keyword!(unbounded);
+
+    // Fragment
+    syn::custom_keyword!(fragment);
+    syn::custom_keyword!(path);
 
     // export
     syn::custom_keyword!(export);
@@ -35,6 +50,16 @@ pub(crate) mod kw {
     // Statement
     syn::custom_keyword!(drop);
     syn::custom_keyword!(switchInt);
+    syn::custom_keyword!(coroutine_drop);
+    syn::custom_keyword!(unwind);
+    syn::custom_keyword!(unwind_resume);
+    syn::custom_keyword!(unreachable);
+    syn::custom_keyword!(assert);
+    syn::custom_keyword!(asm);
+    syn::custom_keyword!(Deinit);
+    syn::custom_keyword!(Intrinsic);
+    syn::custom_keyword!(assume);
+    syn::custom_keyword!(copy_nonoverlapping);
 
     // Operand
     syn::custom_keyword!(copy);
@@ -47,6 +72,9 @@ pub(crate) mod kw {
     syn::custom_keyword!(Len);
     syn::custom_keyword!(discriminant);
     syn::custom_keyword!(raw);
+    syn::custom_keyword!(ThreadLocalRef);
+    syn::custom_keyword!(ShallowInitBox);
+    syn::custom_keyword!(CopyForDeref);
 
     // CastKind
     syn::custom_keyword!(PtrToPtr);
@@ -64,19 +92,32 @@ pub(crate) mod kw {
 
     // BinOp
     syn::custom_keyword!(Add);
+    syn::custom_keyword!(AddUnchecked);
+    syn::custom_keyword!(AddWithOverflow);
     syn::custom_keyword!(Sub);
+    syn::custom_keyword!(SubUnchecked);
+    syn::custom_keyword!(SubWithOverflow);
     syn::custom_keyword!(Mul);
+    syn::custom_keyword!(MulUnchecked);
+    syn::custom_keyword!(MulWithOverflow);
     syn::custom_keyword!(Div);
     syn::custom_keyword!(Rem);
+    syn::custom_keyword!(BitXor);
     syn::custom_keyword!(BitAnd);
     syn::custom_keyword!(BitOr);
+    syn::custom_keyword!(Shl);
+    syn::custom_keyword!(ShlUnchecked);
+    syn::custom_keyword!(Shr);
+    syn::custom_keyword!(ShrUnchecked);
     syn::custom_keyword!(Lt);
     syn::custom_keyword!(Gt);
     syn::custom_keyword!(Le);
     syn::custom_keyword!(Ge);
     syn::custom_keyword!(Eq);
     syn::custom_keyword!(Ne);
+    syn::custom_keyword!(Cmp);
     syn::custom_keyword!(Offset);
+    syn::custom_keyword!(Arith);
 
     // NullOp
     syn::custom_keyword!(SizeOf);
@@ -90,18 +131,28 @@ pub(crate) mod kw {
 
     // Aggregate
     syn::custom_keyword!(from);
+    syn::custom_keyword!(closure);
+    syn::custom_keyword!(coroutine);
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Region {
     span: Span,
     pub kind: RegionKind,
 }
 
-#[derive(Clone, Copy, ToTokens, From)]
+#[derive(Clone, ToTokens, From)]
 pub enum RegionKind {
     ReAny(Token!(_)),
     ReStatic(Token![static]),
+    ReVar(RegionVar),
+}
+
+/// A region variable `$'a`, declared in `#[meta($'a)]`.
+#[derive(Clone, ToTokens, Parse)]
+pub struct RegionVar {
+    tk_dollar: Token![$],
+    pub lifetime: syn::Lifetime,
 }
 
 #[derive(Default, Clone, Copy, ToTokens, Parse, From)]
@@ -120,17 +171,46 @@ pub enum PtrMutability {
     Mut(Token![mut]),
 }
 
+/// A const parameter of a generic type declaration: `const $N: usize`.
+#[derive(ToTokens, Parse)]
+pub struct ConstParam {
+    tk_const: Token![const],
+    tk_dollar: Token![$],
+    pub ident: Ident,
+    tk_colon: Token![:],
+    pub ty: Type,
+}
+
+/// A parameter of a generic type declaration, either `$T` or `const $N: usize`.
+#[derive(ToTokens, From)]
+pub enum TypeParam {
+    Type(TypeVar),
+    Const(ConstParam),
+}
+
+impl TypeParam {
+    pub fn ident(&self) -> &Ident {
+        match self {
+            TypeParam::Type(TypeVar { ident, .. }) | TypeParam::Const(ConstParam { ident, .. }) => ident,
+        }
+    }
+}
+
+/// The `<$T, const $N: usize>` in `type ArrayT<$T, const $N: usize> = [$T; $N];`.
+#[derive(ToTokens, Parse)]
+pub struct TypeGenerics {
+    tk_lt: Token![<],
+    #[parse(parse::parse_angle_bracketed)]
+    pub params: Punctuated<TypeParam, Token![,]>,
+    tk_gt: Token![>],
+}
+
 #[derive(ToTokens, Parse)]
 pub struct TypeDecl {
     tk_type: Token![type],
     pub ident: Ident,
-    #[parse(
-        |input| if input.peek(Token![<]) {
-            Err(input.error(ParseError::TypeWithGenericsNotSupported))
-        } else {
-            input.parse()
-        }
-    )]
+    #[parse(TypeGenerics::parse_opt)]
+    pub generics: Option<TypeGenerics>,
     tk_eq: Token![=],
     pub ty: Type,
     tk_semi: Token![;],
@@ -148,28 +228,48 @@ pub struct TypeArray {
     pub len: Const,
 }
 
-/*
-pub struct FnPtrArg {
-    pub name: Option<(Ident, Token![:])>,
-    pub ty: Type,
-}
-pub struct FnPtrVariadic {
-    pub name: Option<(Ident, Token![:])>,
-    tk_dots: Token![...],
-    tk_comma: Option<Token![,]>,
-}
-
+#[derive(Parse, ToTokens)]
 pub struct TypeFnPtr {
-    pub lifetimes: Option<syn::BoundLifetimes>,
     pub unsafety: Option<Token![unsafe]>,
     pub abi: Option<syn::Abi>,
     tk_fn: Token![fn],
+    #[syn(parenthesized)]
     paren: token::Paren,
-    pub inputs: Punctuated<FnPtrArg, Token![,]>,
-    pub variadic: Option<syn::BareVariadic>,
-    pub output: syn::ReturnType,
+    #[syn(in = paren)]
+    #[parse(Punctuated::parse_terminated)]
+    pub inputs: Punctuated<Type, Token![,]>,
+    pub output: ReturnType,
+}
+
+/// A trait used as a bound, such as `std::fmt::Debug` or `std::ops::Fn(A, B) -> C`.
+#[derive(Parse, ToTokens)]
+pub struct TraitBound {
+    pub path: Path,
+    /// The `(A, B) -> C` in `std::ops::Fn(A, B) -> C`.
+    #[parse(ParenthesizedGenericArguments::parse_opt)]
+    pub fn_args: Option<ParenthesizedGenericArguments>,
+}
+
+#[derive(Parse, ToTokens, From)]
+pub enum TypeParamBound {
+    #[parse(peek_func = Region::peek)]
+    Region(Region),
+    Trait(TraitBound),
+}
+
+#[derive(Parse, ToTokens)]
+pub struct TypeTraitObject {
+    tk_dyn: Token![dyn],
+    #[parse(Punctuated::parse_separated_nonempty)]
+    pub bounds: Punctuated<TypeParamBound, Token![+]>,
+}
+
+#[derive(Parse, ToTokens)]
+pub struct TypeImplTrait {
+    tk_impl: Token![impl],
+    #[parse(Punctuated::parse_separated_nonempty)]
+    pub bounds: Punctuated<TypeParamBound, Token![+]>,
 }
-*/
 
 pub struct TypeGroup {
     tk_group: token::Group,
@@ -191,7 +291,7 @@ pub struct GenericConst {
 #[derive(ToTokens, Parse, From)]
 pub enum GenericArgument {
     /// A region argument.
-    #[parse(peek = syn::Lifetime)]
+    #[parse(peek_func = Region::peek)]
     Region(Region),
     /// A type argument.
     #[parse(peek_func = |input| input.parse::<Type>().is_ok())]
@@ -225,7 +325,7 @@ pub struct ParenthesizedGenericArguments {
     #[parse(Punctuated::parse_terminated)]
     pub inputs: Punctuated<Type, Token![,]>,
     /// `C`
-    pub output: syn::ReturnType,
+    pub output: ReturnType,
 }
 
 #[derive(ToTokens, Parse, From)]
@@ -343,11 +443,16 @@ pub enum Type {
     /// A fixed size array type: `[T; n]`.
     Array(TypeArray),
 
-    // /// A function pointer type: `fn(usize) -> bool`.
-    // FnPtr(syn::TypeBareFn),
+    /// A function pointer type: `unsafe extern "C" fn(usize) -> bool`.
+    FnPtr(TypeFnPtr),
+
     /// A type contained within invisible delimiters.
     Group(TypeGroup),
 
+    /// An `impl Bound1 + Bound2 + Bound3` type where `Bound` is a trait or
+    /// a lifetime.
+    ImplTrait(TypeImplTrait),
+
     /// The never type: `!`.
     Never(TypeNever),
 
@@ -367,9 +472,10 @@ pub enum Type {
     /// A dynamically sized slice type: `[T]`.
     Slice(TypeSlice),
 
-    // /// A trait object type `dyn Bound1 + Bound2 + Bound3` where `Bound` is a
-    // /// trait or a lifetime.
-    // TraitObject(TypeTraitObject),
+    /// A trait object type `dyn Bound1 + Bound2 + Bound3` where `Bound` is a
+    /// trait or a lifetime.
+    TraitObject(TypeTraitObject),
+
     /// A tuple type: `(A, B, C, String)`.
     Tuple(TypeTuple),
 
@@ -773,19 +879,34 @@ pub struct RvalueCast {
 #[rustfmt::skip]
 pub enum BinOp {
     #[parse(peek = kw::Add)] Add(kw::Add),
+    #[parse(peek = kw::AddUnchecked)] AddUnchecked(kw::AddUnchecked),
+    #[parse(peek = kw::AddWithOverflow)] AddWithOverflow(kw::AddWithOverflow),
     #[parse(peek = kw::Sub)] Sub(kw::Sub),
+    #[parse(peek = kw::SubUnchecked)] SubUnchecked(kw::SubUnchecked),
+    #[parse(peek = kw::SubWithOverflow)] SubWithOverflow(kw::SubWithOverflow),
     #[parse(peek = kw::Mul)] Mul(kw::Mul),
+    #[parse(peek = kw::MulUnchecked)] MulUnchecked(kw::MulUnchecked),
+    #[parse(peek = kw::MulWithOverflow)] MulWithOverflow(kw::MulWithOverflow),
     #[parse(peek = kw::Div)] Div(kw::Div),
     #[parse(peek = kw::Rem)] Rem(kw::Rem),
+    #[parse(peek = kw::BitXor)] BitXor(kw::BitXor),
     #[parse(peek = kw::BitAnd)] BitAnd(kw::BitAnd),
     #[parse(peek = kw::BitOr)] BitOr(kw::BitOr),
+    #[parse(peek = kw::Shl)] Shl(kw::Shl),
+    #[parse(peek = kw::ShlUnchecked)] ShlUnchecked(kw::ShlUnchecked),
+    #[parse(peek = kw::Shr)] Shr(kw::Shr),
+    #[parse(peek = kw::ShrUnchecked)] ShrUnchecked(kw::ShrUnchecked),
     #[parse(peek = kw::Lt)] Lt(kw::Lt),
     #[parse(peek = kw::Gt)] Gt(kw::Gt),
     #[parse(peek = kw::Le)] Le(kw::Le),
     #[parse(peek = kw::Ge)] Ge(kw::Ge),
     #[parse(peek = kw::Eq)] Eq(kw::Eq),
     #[parse(peek = kw::Ne)] Ne(kw::Ne),
+    #[parse(peek = kw::Cmp)] Cmp(kw::Cmp),
     #[parse(peek = kw::Offset)] Offset(kw::Offset),
+    /// Any arithmetic operation, `Add`, `Sub`, `Mul`, `Div` or `Rem`, including the unchecked
+    /// ones and the ones with overflow checks.
+    #[parse(peek = kw::Arith)] Arith(kw::Arith),
 }
 
 #[derive(ToTokens, Parse)]
@@ -844,6 +965,47 @@ pub struct RvalueDiscriminant {
     pub place: Place,
 }
 
+/// A reference to a `#[thread_local]` static, `ThreadLocalRef($crate::FOO)`,
+/// or to any of them, `ThreadLocalRef(_)`.
+#[derive(ToTokens, Parse)]
+pub struct RvalueThreadLocalRef {
+    kw_thread_local_ref: kw::ThreadLocalRef,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub thread_local: ThreadLocalStatic,
+}
+
+#[derive(ToTokens, Parse)]
+pub enum ThreadLocalStatic {
+    #[parse(peek = Token![_])]
+    Any(Token![_]),
+    Path(Path),
+}
+
+/// `ShallowInitBox(move $ptr, $T)`, turning a raw pointer into a `Box<$T>`.
+#[derive(ToTokens, Parse)]
+pub struct RvalueShallowInitBox {
+    kw_shallow_init_box: kw::ShallowInitBox,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub operand: Operand,
+    #[syn(in = paren)]
+    tk_comma: Token![,],
+    #[syn(in = paren)]
+    pub ty: Type,
+}
+
+#[derive(ToTokens, Parse)]
+pub struct RvalueCopyForDeref {
+    kw_copy_for_deref: kw::CopyForDeref,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub place: Place,
+}
+
 #[derive(ToTokens, Parse)]
 pub struct AggregateArray {
     // bracket: token::Bracket,
@@ -923,6 +1085,20 @@ pub struct AggregateRawPtr {
     pub metadata: Operand,
 }
 
+/// A closure with its captured values, `closure(copy $x, move $y)`.
+#[derive(ToTokens, Parse)]
+pub struct AggregateClosure {
+    kw_closure: kw::closure,
+    pub operands: ParenthesizedOperands,
+}
+
+/// A coroutine with its upvars, `coroutine(copy $x, move $y)`.
+#[derive(ToTokens, Parse)]
+pub struct AggregateCoroutine {
+    kw_coroutine: kw::coroutine,
+    pub operands: ParenthesizedOperands,
+}
+
 #[derive(ToTokens, From)]
 pub enum RvalueAggregate {
     Array(AggregateArray),
@@ -931,6 +1107,8 @@ pub enum RvalueAggregate {
     AdtTuple(AggregateAdtTuple),
     AdtUnit(AggregateAdtUnit),
     RawPtr(AggregateRawPtr),
+    Closure(AggregateClosure),
+    Coroutine(AggregateCoroutine),
 }
 
 #[derive(ToTokens, From)]
@@ -947,8 +1125,9 @@ pub enum Rvalue {
     UnaryOp(RvalueUnOp),
     Discriminant(RvalueDiscriminant),
     Aggregate(RvalueAggregate),
-    // ShallowInitBox(Operand<'tcx>, Ty<'tcx>),
-    // CopyForDeref(CopyForDerefValue),
+    ThreadLocalRef(RvalueThreadLocalRef),
+    ShallowInitBox(RvalueShallowInitBox),
+    CopyForDeref(RvalueCopyForDeref),
 }
 
 pub type ParenthesizedOperands = Parenthesized<Punctuated<Operand, Token![,]>, parse::PunctuatedParseTerminated>;
@@ -966,6 +1145,15 @@ pub struct BracketedOperands {
 pub struct Call {
     pub func: FnOperand,
     pub operands: ParenthesizedOperands,
+    #[parse(Unwind::parse_opt)]
+    pub unwind: Option<Unwind>,
+}
+
+/// The cleanup path of a `call`, `drop` or `assert`, taken when it panics: `unwind { .. }`.
+#[derive(ToTokens, Parse)]
+pub struct Unwind {
+    kw_unwind: kw::unwind,
+    pub block: Block,
 }
 
 pub struct Macro<K, C, P = parse::ParseParse> {
@@ -1038,12 +1226,193 @@ pub struct Drop {
     paren: token::Paren,
     #[syn(in = paren)]
     pub place: Place,
+    #[parse(Unwind::parse_opt)]
+    pub unwind: Option<Unwind>,
+}
+
+/// `discriminant($x) = 1;`, setting the discriminant of `$x` to the one of the variant at the
+/// index.
+#[derive(ToTokens, Parse)]
+pub struct SetDiscriminant {
+    kw_discr: kw::discriminant,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub place: Place,
+    tk_eq: Token![=],
+    pub variant_index: syn::LitInt,
+}
+
+#[derive(ToTokens, Parse)]
+pub struct Deinit {
+    kw_deinit: kw::Deinit,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub place: Place,
+}
+
+#[derive(ToTokens, Parse)]
+pub struct IntrinsicAssume {
+    kw_assume: kw::assume,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub cond: Operand,
+}
+
+/// `copy_nonoverlapping(copy $src, copy $dst, copy $count)`, with the arguments in the same order
+/// as [`std::ptr::copy_nonoverlapping`].
+#[derive(ToTokens, Parse)]
+pub struct IntrinsicCopyNonOverlapping {
+    kw_copy_nonoverlapping: kw::copy_nonoverlapping,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub src: Operand,
+    #[syn(in = paren)]
+    tk_comma1: Token![,],
+    #[syn(in = paren)]
+    pub dst: Operand,
+    #[syn(in = paren)]
+    tk_comma2: Token![,],
+    #[syn(in = paren)]
+    pub count: Operand,
+}
+
+#[derive(ToTokens, Parse)]
+pub enum IntrinsicKind {
+    #[parse(peek = kw::assume)]
+    Assume(IntrinsicAssume),
+    #[parse(peek = kw::copy_nonoverlapping)]
+    CopyNonOverlapping(IntrinsicCopyNonOverlapping),
+}
+
+/// A non-diverging intrinsic, `Intrinsic(assume(copy $cond));` or
+/// `Intrinsic(copy_nonoverlapping(copy $src, copy $dst, copy $count));`.
+#[derive(ToTokens, Parse)]
+pub struct Intrinsic {
+    kw_intrinsic: kw::Intrinsic,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub kind: IntrinsicKind,
+}
+
+/// The message of an `assert`, either `..` for any message or the kind of it, such as
+/// `BoundsCheck` or `Overflow`.
+#[derive(ToTokens, Parse)]
+pub enum AssertMsg {
+    #[parse(peek = Token![..])]
+    Any(Token![..]),
+    Kind(Ident),
+}
+
+/// A runtime check, `assert(!move $cond, BoundsCheck);`, which panics unless `$cond` is `false`,
+/// or `true` without the `!`.
+#[derive(ToTokens, Parse)]
+pub struct Assert {
+    kw_assert: kw::assert,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    tk_not: Option<Token![!]>,
+    #[syn(in = paren)]
+    pub cond: Operand,
+    #[syn(in = paren)]
+    tk_comma: Token![,],
+    #[syn(in = paren)]
+    pub msg: AssertMsg,
+    #[parse(Unwind::parse_opt)]
+    pub unwind: Option<Unwind>,
+}
+
+impl Assert {
+    /// The value of the condition for the assertion to pass.
+    pub fn expected(&self) -> bool {
+        self.tk_not.is_none()
+    }
+}
+
+/// The template of an inline assembly, either `..` for any template or a string literal.
+#[derive(ToTokens, Parse)]
+pub enum AsmTemplate {
+    #[parse(peek = Token![..])]
+    Any(Token![..]),
+    Template(syn::LitStr),
+}
+
+/// An inline assembly, `asm!("nop");` or `asm!(..);`.
+pub type InlineAsm = Macro<kw::asm, AsmTemplate>;
+
+/// A tail call, `become $f(move $x);`.
+#[derive(ToTokens, Parse)]
+pub struct TailCall {
+    tk_become: Token![become],
+    pub call: Call,
+}
+
+/// A suspension point of a coroutine, `yield(move $value);`, such as an `.await`.
+#[derive(ToTokens, Parse)]
+pub struct Yield {
+    tk_yield: Token![yield],
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub operand: Operand,
+}
+
+/// The module of a fragment used in a `mir!` body, the `alloc::` in `use alloc::$cast() -> ();`.
+#[derive(ToTokens, Parse)]
+pub struct ModPrefix {
+    pub ident: Ident,
+    tk_colon2: Token![::],
+}
+
+/// A local of a fragment: `$ptr`.
+#[derive(ToTokens, Parse)]
+pub struct FragmentLocal {
+    tk_dollar: Token![$],
+    pub ident: Ident,
+}
+
+/// The locals exported by a fragment, or bound to them where it is used: `-> ($ptr, $cast)`.
+#[derive(ToTokens, Parse)]
+pub struct FragmentOutputs {
+    tk_arrow: Token![->],
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    #[parse(Punctuated::parse_terminated)]
+    pub locals: Punctuated<FragmentLocal, Token![,]>,
+}
+
+/// `use alloc::$cast<$T>($ptr) -> ($cast);`, splicing the statements of a fragment into a `mir!`
+/// body, with `$ptr` passed to its parameter and `$cast` bound to its exported local.
+#[derive(ToTokens, Parse)]
+pub struct FragmentUse {
+    tk_use: Token![use],
+    #[parse(ModPrefix::parse_opt)]
+    pub module: Option<ModPrefix>,
+    tk_dollar: Token![$],
+    pub ident: Ident,
+    pub args: PathArguments,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    #[parse(Punctuated::parse_terminated)]
+    pub inputs: Punctuated<FragmentLocal, Token![,]>,
+    #[parse(FragmentOutputs::parse_opt)]
+    pub outputs: Option<FragmentOutputs>,
+    tk_semi: Token![;],
 }
 
 #[derive(ToTokens, Parse, From)]
 pub enum Declaration {
     #[parse(peek = Token![type])]
     TypeDecl(TypeDecl),
+    #[parse(peek_func = FragmentUse::peek)]
+    FragmentUse(FragmentUse),
     #[parse(peek = Token![use])]
     UsePath(UsePath),
     LocalDecl(LocalDecl),
@@ -1128,6 +1497,26 @@ pub enum StatementKind<End: quote::ToTokens + syn::parse::Parse = Token![;]> {
     Call(CallIgnoreRet, End),
     #[parse(peek_func = |input| input.peek(kw::drop) && input.peek2(token::Paren))]
     Drop(Drop, End),
+    #[parse(peek = Token![yield])]
+    Yield(Yield, End),
+    #[parse(peek = kw::coroutine_drop)]
+    CoroutineDrop(kw::coroutine_drop, End),
+    #[parse(peek_func = |input| input.peek(kw::assert) && input.peek2(token::Paren))]
+    Assert(Assert, End),
+    #[parse(peek_func = |input| input.peek(kw::asm) && input.peek2(Token![!]))]
+    InlineAsm(InlineAsm, End),
+    #[parse(peek = Token![become])]
+    TailCall(TailCall, End),
+    #[parse(peek = kw::unwind_resume)]
+    UnwindResume(kw::unwind_resume, End),
+    #[parse(peek = kw::unreachable)]
+    Unreachable(kw::unreachable, End),
+    #[parse(peek_func = |input| input.peek(kw::discriminant) && input.peek2(token::Paren))]
+    SetDiscriminant(SetDiscriminant, End),
+    #[parse(peek_func = |input| input.peek(kw::Deinit) && input.peek2(token::Paren))]
+    Deinit(Deinit, End),
+    #[parse(peek_func = |input| input.peek(kw::Intrinsic) && input.peek2(token::Paren))]
+    Intrinsic(Intrinsic, End),
     #[parse(peek_func = Control::peek)]
     Control(Control, End),
     #[parse(peek = Token![loop])]
@@ -1194,7 +1583,15 @@ pub struct MetaItem {
     pub kind: MetaKind,
 }
 
-pub type Meta = Attribute<kw::meta, Punctuated<MetaItem, Token![,]>, parse::PunctuatedParseTerminated>;
+/// A declaration in `#[meta(..)]`, either a region variable `$'a` or a `$x: kind` item.
+#[derive(ToTokens, Parse, From)]
+pub enum MetaDecl {
+    #[parse(peek_func = |input| input.peek(Token![$]) && input.peek2(syn::Lifetime))]
+    Region(RegionVar),
+    Item(MetaItem),
+}
+
+pub type Meta = Attribute<kw::meta, Punctuated<MetaDecl, Token![,]>, parse::PunctuatedParseTerminated>;
 
 #[derive(Parse, ToTokens)]
 pub struct Mir {
@@ -1239,6 +1636,96 @@ pub struct ExportItem {
     pub kind: Option<PunctAnd<Token![:], ExportKind>>,
 }
 
+/// `#[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]`, restricting a pattern to the
+/// versions of a crate affected by an advisory.
+pub type AppliesTo = Attribute<kw::applies_to, AppliesToItem>;
+
+/// `attr(inline)` or `attr(inline(never))`, a constraint on an attribute of the function, and on
+/// the words in its arguments.
+#[derive(Parse, ToTokens)]
+pub struct ConstraintAttr {
+    kw_attr: kw::attr,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub path: syn::Path,
+    #[syn(in = paren)]
+    #[parse(ConstraintAttrArgs::parse_opt)]
+    pub args: Option<ConstraintAttrArgs>,
+}
+
+/// `(never)` in `attr(inline(never))`.
+#[derive(Parse, ToTokens)]
+pub struct ConstraintAttrArgs {
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    #[parse(Punctuated::parse_terminated)]
+    pub words: Punctuated<Ident, Token![,]>,
+}
+
+#[derive(Parse, ToTokens)]
+pub enum ConstraintKind {
+    #[parse(peek = kw::generic)]
+    Generic(kw::generic),
+    #[parse(peek = kw::trait_method)]
+    TraitMethod(kw::trait_method),
+    #[parse(peek = kw::attr)]
+    Attr(ConstraintAttr),
+    #[parse(peek = kw::outlives)]
+    Outlives(ConstraintOutlives),
+    #[parse(peek = kw::unbounded)]
+    Unbounded(ConstraintUnbounded),
+}
+
+/// `outlives($'a, $'b)`, requiring the region `$'a` to outlive `$'b`.
+#[derive(Parse, ToTokens)]
+pub struct ConstraintOutlives {
+    kw_outlives: kw::outlives,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub longer: RegionVar,
+    #[syn(in = paren)]
+    tk_comma: Token![,],
+    #[syn(in = paren)]
+    pub shorter: RegionVar,
+}
+
+/// `unbounded($'a)`, requiring the region `$'a` not to be tied to any input of the function.
+#[derive(Parse, ToTokens)]
+pub struct ConstraintUnbounded {
+    kw_unbounded: kw::unbounded,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    pub region: RegionVar,
+}
+
+/// A constraint on the function matched by a `fn` pattern, negated by `!`:
+/// `generic`, `!trait_method`, `attr(inline)`, `outlives($'a, $'b)` or `unbounded($'a)`.
+#[derive(Parse, ToTokens)]
+pub struct Constraint {
+    pub tk_not: Option<Token![!]>,
+    pub kind: ConstraintKind,
+}
+
+/// `#[constraints(generic, !trait_method, attr(inline))]`, restricting the functions that a `fn`
+/// pattern matches.
+pub type Constraints = Attribute<kw::constraints, Punctuated<Constraint, Token![,]>, parse::PunctuatedParseTerminated>;
+
+#[derive(Parse, ToTokens)]
+pub struct AppliesToItem {
+    tk_crate: Token![crate],
+    tk_eq_crate: Token![=],
+    pub krate: syn::LitStr,
+    tk_comma: Token![,],
+    kw_version: kw::version,
+    tk_eq_version: Token![=],
+    pub version: syn::LitStr,
+    tk_trailing_comma: Option<Token![,]>,
+}
+
 #[derive(Parse, ToTokens)]
 pub enum IdentPat {
     #[parse(peek = Token![_])]
@@ -1324,8 +1811,40 @@ pub enum FnRet {
     Ret(ReturnType),
 }
 
+/// `(crate)` in `pub(crate)`.
+#[derive(Parse, ToTokens)]
+pub struct VisRestriction {
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    tk_crate: Token![crate],
+}
+
+/// `pub` for public functions, or `pub(crate)` for functions not visible outside of the crate.
+#[derive(Parse, ToTokens)]
+pub struct FnVisibility {
+    tk_pub: Token![pub],
+    #[parse(VisRestriction::parse_opt)]
+    pub restriction: Option<VisRestriction>,
+}
+
+/// `unsafe` for unsafe functions, or `safe` for safe functions and closures.
+#[derive(Clone, Copy, Parse, ToTokens)]
+pub enum FnSafety {
+    #[parse(peek = Token![unsafe])]
+    Unsafe(Token![unsafe]),
+    #[parse(peek = kw::safe)]
+    Safe(kw::safe),
+}
+
 #[derive(Parse, ToTokens)]
 pub struct FnSig {
+    #[parse(FnVisibility::parse_opt)]
+    pub vis: Option<FnVisibility>,
+    pub constness: Option<Token![const]>,
+    #[parse(FnSafety::parse_opt)]
+    pub safety: Option<FnSafety>,
+    pub abi: Option<syn::Abi>,
     tk_fn: Token![fn],
     pub ident: IdentPat,
     #[syn(parenthesized)]
@@ -1333,6 +1852,34 @@ pub struct FnSig {
     #[syn(in = paren)]
     pub params: PunctuatedWithEnd<FnParam>,
     pub ret: FnRet,
+    #[parse(WhereClause::parse_opt)]
+    pub where_clause: Option<WhereClause>,
+}
+
+/// A bound in a `where` clause, negated by `!`: `std::marker::Send`, `!std::marker::Sync` or
+/// `!'static`.
+#[derive(Parse, ToTokens)]
+pub struct WhereBound {
+    pub tk_not: Option<Token![!]>,
+    pub bound: TypeParamBound,
+}
+
+/// `$T: std::marker::Send + !'static` in a `where` clause.
+#[derive(Parse, ToTokens)]
+pub struct WherePredicate {
+    pub ty: Type,
+    tk_colon: Token![:],
+    #[parse(Punctuated::parse_separated_nonempty)]
+    pub bounds: Punctuated<WhereBound, Token![+]>,
+}
+
+/// `where $T: std::marker::Send, $U: !std::marker::Sync`, the bounds that the matched item must
+/// imply, or must not imply if negated.
+#[derive(Parse, ToTokens)]
+pub struct WhereClause {
+    tk_where: Token![where],
+    #[parse(Punctuated::parse_separated_nonempty)]
+    pub predicates: Punctuated<WherePredicate, Token![,]>,
 }
 
 #[derive(Parse, ToTokens)]
@@ -1361,6 +1908,58 @@ pub struct FnPat {
     pub body: FnBody,
 }
 
+/// A parameter of a fragment: `$ptr: *mut u8`.
+#[derive(Parse, ToTokens)]
+pub struct FragmentParam {
+    tk_dollar: Token![$],
+    pub ident: Ident,
+    tk_colon: Token![:],
+    pub ty: Type,
+}
+
+/// A named fragment of a `mir!` body, with generic parameters, parameters passed from and locals
+/// exported to where it is used:
+///
+/// ```text
+/// fragment $cast<$T>($ptr: *mut u8) -> ($cast) = mir! {
+///     let $cast: *mut $T = move $ptr as *mut $T (PtrToPtr);
+/// }
+/// ```
+#[derive(Parse, ToTokens)]
+pub struct Fragment {
+    kw_fragment: kw::fragment,
+    tk_dollar: Token![$],
+    pub ident: Ident,
+    #[parse(TypeGenerics::parse_opt)]
+    pub generics: Option<TypeGenerics>,
+    #[syn(parenthesized)]
+    paren: token::Paren,
+    #[syn(in = paren)]
+    #[parse(Punctuated::parse_terminated)]
+    pub params: Punctuated<FragmentParam, Token![,]>,
+    #[parse(FragmentOutputs::parse_opt)]
+    pub outputs: Option<FragmentOutputs>,
+    pub body: MirBody,
+}
+
+/// `#[path = "src/fragments/alloc.rpl"] mod alloc;`, a pattern module of fragments loaded from a
+/// file, whose path is relative to the root of the crate.
+#[derive(Parse, ToTokens)]
+pub struct Mod {
+    tk_pound: Token![#],
+    #[syn(bracketed)]
+    bracket: token::Bracket,
+    #[syn(in = bracket)]
+    kw_path: kw::path,
+    #[syn(in = bracket)]
+    tk_eq: Token![=],
+    #[syn(in = bracket)]
+    pub path: syn::LitStr,
+    tk_mod: Token![mod],
+    pub ident: Ident,
+    tk_semi: Token![;],
+}
+
 #[derive(Parse, ToTokens)]
 pub struct Field {
     tk_dollar: Token![$],
@@ -1436,6 +2035,8 @@ pub struct Impl {
     tk_impl: Token![impl],
     pub kind: ImplKind,
     pub ty: Type,
+    #[parse(WhereClause::parse_opt)]
+    pub where_clause: Option<WhereClause>,
     #[syn(braced)]
     pub brace: token::Brace,
     #[syn(in = brace)]
@@ -1444,8 +2045,14 @@ pub struct Impl {
 
 #[derive(Parse, ToTokens)]
 pub enum ItemKind {
-    #[parse(peek = Token![fn])]
+    #[parse(peek_func = FnSig::peek)]
     Fn(FnPat),
+    #[parse(peek = Token![type])]
+    Type(TypeDecl),
+    #[parse(peek = kw::fragment)]
+    Fragment(Fragment),
+    #[parse(peek = Token![#])]
+    Mod(Mod),
     #[parse(peek = Token![struct])]
     Struct(Struct),
     #[parse(peek = Token![enum])]
@@ -1456,8 +2063,12 @@ pub enum ItemKind {
 
 #[derive(Parse, ToTokens)]
 pub struct Item {
+    #[parse(AppliesTo::parse_opt)]
+    pub applies_to: Option<AppliesTo>,
     #[parse(Meta::parse_opt)]
     pub meta: Option<Meta>,
+    #[parse(Constraints::parse_opt)]
+    pub constraints: Option<Constraints>,
     pub kind: ItemKind,
 }
 
@@ -6,12 +6,12 @@ use crate::*;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParseError {
-    #[error("unrecognized region {0}, expect `'static` or `'_`")]
+    #[error("unrecognized region {0}, expect `'static`, `'_` or a region variable `$'a`")]
     UnrecognizedRegion(String),
     #[error("`,` is needed for single-element tuple")]
     ExpectTuple,
-    #[error("type declaration with generic arguments are not supported")]
-    TypeWithGenericsNotSupported,
+    #[error("expect a type parameter `$T` or a const parameter `const $N: ty`")]
+    ExpectTypeParam,
     #[error("expect integer suffix")]
     ExpectIntSuffix,
     #[error("unrecognized integer suffix {0}")]
@@ -23,13 +23,24 @@ pub enum ParseError {
 }
 
 impl Region {
+    pub fn peek(input: ParseStream<'_>) -> bool {
+        input.peek(syn::Lifetime) || input.peek(Token![$]) && input.peek2(syn::Lifetime)
+    }
     pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
-        input.peek(syn::Lifetime).then(|| input.parse()).transpose()
+        Self::peek(input).then(|| input.parse()).transpose()
     }
 }
 
 impl Parse for Region {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
+        if input.peek(Token![$]) {
+            let region_var: RegionVar = input.parse()?;
+            let span = region_var.lifetime.span();
+            return Ok(Region {
+                span,
+                kind: region_var.into(),
+            });
+        }
         let lifetime: syn::Lifetime = input.parse()?;
         let ident = &lifetime.ident;
         let kind = if ident == "static" {
@@ -99,6 +110,107 @@ impl AngleBracketedGenericArguments {
     }
 }
 
+impl Parse for TypeParam {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        if input.peek(Token![$]) {
+            input.parse().map(TypeParam::Type)
+        } else if input.peek(Token![const]) {
+            input.parse().map(TypeParam::Const)
+        } else {
+            Err(input.error(ParseError::ExpectTypeParam))
+        }
+    }
+}
+
+impl VisRestriction {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(token::Paren).then(|| input.parse()).transpose()
+    }
+}
+
+impl ConstraintAttrArgs {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(token::Paren).then(|| input.parse()).transpose()
+    }
+}
+
+impl FnVisibility {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(Token![pub]).then(|| input.parse()).transpose()
+    }
+}
+
+impl FnSafety {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        (input.peek(Token![unsafe]) || input.peek(kw::safe))
+            .then(|| input.parse())
+            .transpose()
+    }
+}
+
+impl WhereClause {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(Token![where]).then(|| input.parse()).transpose()
+    }
+}
+
+impl FnSig {
+    pub fn peek(input: ParseStream<'_>) -> bool {
+        // Only `fn` patterns have a visibility.
+        if input.peek(Token![pub]) {
+            return true;
+        }
+        let forked = input.fork();
+        forked.parse::<Option<Token![const]>>().is_ok()
+            && FnSafety::parse_opt(&forked).is_ok()
+            && forked.parse::<Option<syn::Abi>>().is_ok()
+            && forked.peek(Token![fn])
+    }
+}
+
+impl Unwind {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(kw::unwind).then(|| input.parse()).transpose()
+    }
+}
+
+impl ModPrefix {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        (input.peek(Ident) && input.peek2(Token![::]))
+            .then(|| input.parse())
+            .transpose()
+    }
+}
+
+impl FragmentOutputs {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(Token![->]).then(|| input.parse()).transpose()
+    }
+}
+
+impl FragmentUse {
+    pub fn peek(input: ParseStream<'_>) -> bool {
+        let forked = input.fork();
+        // `use $crate::...` is a path.
+        forked.parse::<Token![use]>().is_ok()
+            && ModPrefix::parse_opt(&forked).is_ok()
+            && forked.peek(Token![$])
+            && forked.peek2(Ident)
+    }
+}
+
+impl TypeGenerics {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(Token![<]).then(|| input.parse()).transpose()
+    }
+}
+
+impl ParenthesizedGenericArguments {
+    pub fn parse_opt(input: ParseStream<'_>) -> Result<Option<Self>> {
+        input.peek(token::Paren).then(|| input.parse()).transpose()
+    }
+}
+
 impl PathArguments {
     fn parse_turbofish(input: ParseStream<'_>) -> Result<Self> {
         Ok(if input.peek(Token![::]) {
@@ -259,6 +371,12 @@ impl Parse for Type {
             Ok(Type::Never(input.parse()?))
         } else if input.peek(Token![#]) {
             Ok(Type::LangItem(input.parse()?))
+        } else if input.peek(Token![fn]) || input.peek(Token![unsafe]) || input.peek(Token![extern]) {
+            Ok(Type::FnPtr(input.parse()?))
+        } else if input.peek(Token![dyn]) {
+            Ok(Type::TraitObject(input.parse()?))
+        } else if input.peek(Token![impl]) {
+            Ok(Type::ImplTrait(input.parse()?))
         } else if input.peek(Token![$]) {
             if input.peek2(Token![crate]) {
                 Ok(Type::Path(input.parse()?))
@@ -494,7 +612,7 @@ impl Rvalue {
         } else {
             Rvalue::Ref(RvalueRef {
                 tk_and,
-                region: input.peek(syn::Lifetime).then(|| input.parse()).transpose()?,
+                region: Region::parse_opt(input)?,
                 mutability: input.parse()?,
                 place: input.parse()?,
             })
@@ -542,6 +660,7 @@ impl RvalueOrCall {
                     Call {
                         func: operand,
                         operands: input.parse()?,
+                        unwind: Unwind::parse_opt(input)?,
                     }
                     .into()
                 } else {
@@ -579,6 +698,16 @@ impl RvalueOrCall {
             Rvalue::BinaryOp(input.parse()?).into()
         } else if input.peek(kw::discriminant) {
             Rvalue::Discriminant(input.parse()?).into()
+        } else if input.peek(kw::ThreadLocalRef) {
+            Rvalue::ThreadLocalRef(input.parse()?).into()
+        } else if input.peek(kw::ShallowInitBox) {
+            Rvalue::ShallowInitBox(input.parse()?).into()
+        } else if input.peek(kw::CopyForDeref) {
+            Rvalue::CopyForDeref(input.parse()?).into()
+        } else if input.peek(kw::closure) {
+            Rvalue::Aggregate(RvalueAggregate::Closure(input.parse()?)).into()
+        } else if input.peek(kw::coroutine) {
+            Rvalue::Aggregate(RvalueAggregate::Coroutine(input.parse()?)).into()
         } else {
             RvalueOrCall::Call(input.parse()?)
         })
@@ -51,9 +51,15 @@ macro_rules! fail {
 #[rustfmt::skip]
 fn test_type_decl() {
     pass!(TypeDecl!( type SliceT = [T]; ));
+    pass!(TypeDecl!( type SliceT<$T> = [$T]; ));
+    pass!(TypeDecl!( type ArrayT<$T, const $N: usize,> = [$T; $N]; ));
     fail!(
         TypeDecl!( type SliceT<T> = [T]; ),
-        ParseError::TypeWithGenericsNotSupported
+        ParseError::ExpectTypeParam
+    );
+    fail!(
+        TypeDecl!( type ArrayT<$T, $N: usize> = [$T; $N]; ),
+        "expected `>`"
     );
 }
 
@@ -97,10 +103,45 @@ fn test_type() {
     pass!(Type!([T]));
     #[rustfmt::skip]
     pass!(Type!(< <core::ffi::c_str::CStr>::from_bytes_with_nul_unchecked>::___rt_impl));
+    pass!(Type!([$T; 4]));
+    pass!(Type!(&'static [u8; $N]));
+    pass!(Type!(&$'a mut $T));
+    pass!(Type!(std::cell::Ref<$'a, $T>));
+    pass!(Type!(!));
 
     fail!(Type!(*const u8(PtrToPtr)), "unexpected token");
 }
 
+#[test]
+fn test_type_fn_ptr() {
+    pass!(Type!(fn()));
+    pass!(Type!(fn(usize) -> bool));
+    pass!(Type!(unsafe fn(*mut u8, usize)));
+    pass!(Type!(extern "C" fn(*mut core::ffi::c_void)));
+    pass!(Type!(unsafe extern "C" fn(*mut core::ffi::c_void) -> i32));
+
+    fail!(Type!(unsafe extern "C" (usize)), "expected `fn`");
+    fail!(Type!(fn(usize) ->), "unexpected end of input, expected identifier");
+}
+
+#[test]
+fn test_type_trait_object() {
+    pass!(Type!(dyn std::any::Any));
+    pass!(Type!(dyn std::any::Any + 'static));
+    pass!(Type!(&dyn std::ops::Fn(*mut u8) -> usize + Send));
+    pass!(Type!(Box<dyn std::ops::FnMut() + std::marker::Send + '_>));
+    pass!(Type!(impl std::ops::FnOnce(&[$T])));
+    pass!(Type!(impl std::iter::Iterator + 'static));
+    pass!(Type!(Box<dyn std::any::Any + $'a>));
+
+    fail!(Type!(dyn), "unexpected end of input, expected identifier");
+    fail!(Type!(impl + Send), "expected identifier");
+    fail!(
+        Type!(dyn std::any::Any + 'a),
+        ParseError::UnrecognizedRegion("'a".to_owned())
+    );
+}
+
 #[test]
 fn test_place() {
     pass!(Place!($x));
@@ -232,6 +273,7 @@ fn test_meta() {
     pass!(Meta!(#[meta($T:ty, $p:place(alloc::vec::Vec<$T>))]));
     pass!(Meta!(#[meta($T:ty, $c:const($T))]));
     pass!(Meta!(#[meta($T:ty, $c:const(&$T))]));
+    pass!(Meta!(#[meta($'a, $T:ty, $'b)]));
 }
 
 #[test]
@@ -240,6 +282,39 @@ fn test_declaration() {
     pass!(Declaration!( type SliceT = [$T]; ));
     pass!(Declaration!( let $x: u32 = const 0_usize; ));
     pass!(Declaration!( let $to_ptr: *const u8 = copy $from_ptr as *const u8 (PtrToPtr); ));
+    pass!(Declaration!( use $cast<$T>($ptr) -> ($cast); ));
+    pass!(Declaration!( use alloc::$alloc<u8>() -> ($ptr,); ));
+    pass!(Declaration!( use $write(); ));
+    assert!(matches!(
+        syn::parse2::<Declaration>(quote!(use alloc::$alloc();)),
+        Ok(Declaration::FragmentUse(_))
+    ));
+    assert!(matches!(
+        syn::parse2::<Declaration>(quote!(
+            use alloc::alloc::alloc;
+        )),
+        Ok(Declaration::UsePath(_))
+    ));
+}
+
+#[test]
+fn test_fragment() {
+    pass!(Fragment!(
+        fragment $cast<$T>($ptr: *mut u8) -> ($cast) = mir! {
+            let $cast: *mut $T = move $ptr as *mut $T (PtrToPtr);
+        }
+    ));
+    pass!(Fragment!(
+        fragment $alloc() -> ($ptr_1, $ptr_2,) = mir! {
+            let $ptr_1: *mut u8 = alloc::alloc::__rust_alloc(_, _);
+            use $cast<u8>($ptr_1) -> ($ptr_2);
+        }
+    ));
+    pass!(Item!(
+        #[path = "src/fragments/alloc.rpl"]
+        mod alloc;
+    ));
+    fail!(Fragment!(fragment $cast<$T>($ptr) = mir! {}), "expected `:`");
 }
 
 #[test]
@@ -310,8 +385,20 @@ fn test_local_decl() {
     pass!(LocalDecl!( let $ty_size: usize = SizeOf($T); ));
     pass!(LocalDecl!( let $to_ptr: PtrU8 = copy $from_ptr as PtrU8 (PtrToPtr); ));
     pass!(LocalDecl!( let $to_len: usize = Mul(copy $from_len, copy $ty_size); ));
+    pass!(LocalDecl!( let $to_len: (usize, bool) = MulWithOverflow(copy $from_len, copy $ty_size); ));
+    pass!(LocalDecl!( let $mask: u32 = BitXor(copy $x, const 1_u32); ));
+    pass!(LocalDecl!( let $shifted: u64 = ShlUnchecked(copy $x, copy $n); ));
+    pass!(LocalDecl!( let $ord: std::cmp::Ordering = Cmp(copy $x, copy $y); ));
+    pass!(LocalDecl!( let $to_len: usize = Arith(copy $from_len, _); ));
     pass!(LocalDecl!( let $to_raw_slice: PtrSliceU8 = *const SliceU8 from (copy $to_ptr, copy $t_len); ));
     pass!(LocalDecl!( let $to_slice: RefSliceU8 = &*$to_raw_slice; ));
+    pass!(LocalDecl!( let $r: &$'a mut u8 = &$'a mut $x; ));
+    pass!(LocalDecl!( let $tls: &u32 = ThreadLocalRef($crate::FOO); ));
+    pass!(LocalDecl!( let $tls: &u32 = ThreadLocalRef(_); ));
+    pass!(LocalDecl!( let $b: Box<$T> = ShallowInitBox(move $ptr, $T); ));
+    pass!(LocalDecl!( let $r: &$T = CopyForDeref((*$x).0); ));
+    pass!(LocalDecl!( let $f: _ = closure(copy $x, move $y); ));
+    pass!(LocalDecl!( let $co: _ = coroutine(move $v); ));
 }
 
 #[test]
@@ -330,6 +417,26 @@ fn test_statement() {
     pass!(Statement!( _ = drop_in_place(copy $elem_ptr); ));
     pass!(Statement!( *$x = std::mem::take(move $y); ));
     pass!(Statement!(drop($y[$x]);));
+    pass!(Statement!(yield(move $awaitee);));
+    pass!(Statement!(coroutine_drop;));
+    pass!(Statement!(assert(!move $overflow, Overflow);));
+    pass!(Statement!(assert(move $in_bounds, ..);));
+    pass!(Statement!(asm!("nop");));
+    pass!(Statement!(asm!(..);));
+    pass!(Statement!(become $f(move $x);));
+    pass!(Statement!(unwind_resume;));
+    pass!(Statement!(unreachable;));
+    pass!(Statement!(discriminant($x) = 1;));
+    pass!(Statement!(Deinit($x);));
+    pass!(Statement!(Intrinsic(assume(copy $cond));));
+    pass!(Statement!(Intrinsic(copy_nonoverlapping(copy $src, copy $dst, copy $count));));
+    pass!(Statement!(drop($x) unwind { unwind_resume; };));
+    pass!(Statement!(
+        $y = std::vec::Vec::push(move $v, move $x) unwind {
+            drop($v);
+            unwind_resume;
+        };
+    ));
     pass!(Statement!(
         $from_vec_unique_ptr = core::ptr::unique::Unique::<u8> {
             pointer: copy $from_vec_ptr,
@@ -412,6 +519,99 @@ fn test_fn_pattern() {
     });
 }
 
+#[test]
+fn test_applies_to() {
+    pass!(Item! {
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        fn $pattern(..) -> _;
+    });
+    pass!(Item! {
+        #[applies_to(crate = "crossbeam-utils", version = ">= 0.8.0, < 0.8.7",)]
+        #[meta($T:ty)]
+        fn $pattern(*mut $T) -> _;
+    });
+    fail!(
+        Item! {
+            #[applies_to(version = "< 0.8.7")]
+            fn $pattern(..) -> _;
+        },
+        "expected `crate`"
+    );
+}
+
+#[test]
+fn test_where_clause() {
+    pass!(Item! {
+        #[meta($W:ty)]
+        pub fn $pattern(std::sync::Arc<$W>) -> std::task::Waker where $W: !'static;
+    });
+    pass!(Item! {
+        #[meta($T:ty, $U:ty)]
+        fn $pattern(..) -> _ where $T: std::marker::Send + !std::marker::Sync, $U: 'static = mir! {
+            let $x: $T = _;
+        }
+    });
+    pass!(Item! {
+        #[meta($T:ty, $U:ty)]
+        impl std::marker::Sync for $crate::LazyTransform<$T, $U> where $U: !std::marker::Send {}
+    });
+    pass!(Item! {
+        impl std::marker::Send for $T {}
+    });
+    fail!(
+        Item! {
+            fn $pattern(..) -> _ where;
+        },
+        "expected identifier"
+    );
+}
+
+#[test]
+fn test_fn_constraints() {
+    pass!(Item! {
+        pub safe fn $pattern(..) -> _;
+    });
+    pass!(Item! {
+        pub(crate) const unsafe extern "C" fn $pattern(..) -> _;
+    });
+    pass!(Item! {
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        #[meta($T:ty)]
+        #[constraints(generic, !trait_method, attr(inline), !attr(no_mangle),)]
+        pub fn $pattern(*mut $T) -> _;
+    });
+    pass!(Item! {
+        #[constraints(attr(inline), !attr(inline(never)))]
+        fn $pattern(..) -> _;
+    });
+    fail!(
+        Item! {
+            pub(super) fn $pattern(..) -> _;
+        },
+        "expected `crate`"
+    );
+    pass!(Item! {
+        #[meta($'a, $'b, $T:ty)]
+        #[constraints(outlives($'a, $'b), !unbounded($'b))]
+        fn $pattern(&$'a $T) -> &$'b $T;
+    });
+    fail!(
+        Item! {
+            #[constraints(inline)]
+            fn $pattern(..) -> _;
+        },
+        "expected one of: `generic`, `trait_method`, `attr`, `outlives`, `unbounded`"
+    );
+    fail!(
+        Item! {
+            #[meta($'a)]
+            #[constraints(outlives($'a, 'static))]
+            fn $pattern(..) -> _;
+        },
+        "expected `$`"
+    );
+}
+
 #[test]
 fn test_parse_cve_2018_21000() {
     pass!(Item! {
@@ -3,18 +3,14 @@ use quote::ToTokens;
 
 use crate::*;
 
-impl AsRef<str> for Region {
-    fn as_ref(&self) -> &str {
-        match self.kind {
-            RegionKind::ReAny(_) => "'_",
-            RegionKind::ReStatic(_) => "'static",
-        }
-    }
-}
-
 impl ToTokens for Region {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        syn::Lifetime::new(self.as_ref(), self.span).to_tokens(tokens);
+        let region = match &self.kind {
+            RegionKind::ReAny(_) => "'_",
+            RegionKind::ReStatic(_) => "'static",
+            RegionKind::ReVar(region_var) => return region_var.to_tokens(tokens),
+        };
+        syn::Lifetime::new(region, self.span).to_tokens(tokens);
     }
 }
 
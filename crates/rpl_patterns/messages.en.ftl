@@ -54,6 +54,17 @@ rpl_patterns_trust_exact_size_iterator = it is unsound to trust return value of
     .len_label = `std::iter::ExactSizeIterator::len` used here
     .help = incorrect implementation of `std::iter::ExactSizeIterator::len` must not cause safety issues, and consider using `std::iter::TrustedLen` instead if it's stabilized
 
+rpl_patterns_trust_exact_size_iterator_write = it is unsound to trust return value of `std::iter::ExactSizeIterator::len` and write the items through a raw pointer until the iterator is exhausted, which may write out of bounds
+    .label = item written here, regardless of the length
+    .len_label = `std::iter::ExactSizeIterator::len` used here
+    .help = stop writing after `std::iter::ExactSizeIterator::len` items, for example with `iter.take(len)`, as the length may not be implemented correctly
+
+rpl_patterns_realloc_size_confusion = the new size of a reallocated memory block is copied out of the old one, which may read out of bounds
+    .label = `new_size` bytes copied here
+    .new_size_label = the new size
+    .old_size_label = the old block only has this size
+    .help = copy at most `min(old_size, new_size)` bytes, or check that the new size is not larger than the old one before copying
+
 rpl_patterns_slice_from_raw_parts_uninitialized = it violates the precondition of `std::slice::{$fn_name}` to create a slice from uninitialized data
     .slice_label = slice created here
     .vec_label   = `std::vec::Vec` created but not initialized
@@ -66,6 +77,40 @@ rpl_patterns_set_len_uninitialized = it violates the precondition of `Vec::set_l
     .vec_label = `Vec` created here
     .help = before calling `set_len` to extend its length, make sure all elements are initialized, using such as `spare_capacity_mut` or `as_mut_ptr`
 
+rpl_patterns_uninitialized_buffer_passed_to_read = an uninitialized buffer is passed to `std::io::Read::{$method}` of the generic reader `{$reader}`
+    .label = `{$reader}` may read the uninitialized buffer here
+    .vec_label = `Vec` created here with uninitialized capacity
+    .exposed_label = the uninitialized memory is exposed here
+    .note = `{$reader}` can be any implementation of `std::io::Read`, which may read from the buffer instead of only writing to it
+    .help = initialize the buffer before reading into it, for example with `vec![0; len]`
+
+rpl_patterns_type_erased_without_static_bound = a pointer to `{$ty}` is erased into a `RawWaker` without requiring `{$ty}: 'static`
+    .label = the type of the pointer is erased here
+    .raw_waker_label = the `RawWaker` is created here, and it can outlive the data borrowed by `{$ty}`
+    .help = consider adding `{$ty}: 'static` to the bounds of `{$ty}`
+
+rpl_patterns_unvalidated_deserialization = `{$ty}` is deserialized without checking the field `{$field_name}` trusted by unsafe code
+    .label = `{$ty}` is constructed here from the deserialized fields
+    .field_label = `{$field_name}` is trusted to be consistent with the other fields
+    .note = `{$field_name}` is used as a length or an index of an unchecked access here
+    .help = implement `Deserialize` manually, and return an error if `{$field_name}` is inconsistent with the other fields
+
+rpl_patterns_ffi_out_pointer_into_short_lived_buffer = a slice is created from a pointer written by an extern function, but it may outlive the buffer it points into
+    .label = the slice is created here
+    .ffi_call_label = the pointer is written here, and it may point into any of the buffers passed to the extern function
+    .output_label = the returned slice only borrows one of the buffers
+    .help = tie the lifetime of the returned slice to all the buffers passed to the extern function
+
+rpl_patterns_unaligned_pointer_cast_from_byte_offset = a byte pointer is offset and cast to `*{$ty}` without checking the alignment of `{$ty}`
+    .label = cast to a pointer to `{$ty}` here
+    .offset_label = offset by an arbitrary number of bytes here
+    .help = check that the pointer is aligned to `align_of::<{$ty}>()` before casting it, for example with `is_aligned`
+
+rpl_patterns_unaligned_pointer_wrapped_from_byte_offset = a byte pointer is offset and wrapped in `{$wrapper}` without checking the alignment of `{$ty}`
+    .label = wrapped in `{$wrapper}` here
+    .offset_label = offset by an arbitrary number of bytes here
+    .help = check that the pointer is aligned to `align_of::<{$ty}>()` before wrapping it, for example with `is_aligned`
+
 rpl_patterns_get_mut_in_rc_unsafecell = Obtaining a mutable reference to the value wrapped by `Rc<UnsafeCell<$T>>` may be unsound
     .get_mut_label = `UnsafeCell::get_mut` called here
     .note = there will be multiple mutable references to the value at the same time
@@ -89,6 +134,10 @@ rpl_patterns_unsound_cast_between_u64_and_atomic_u64 = it is unsound to cast bet
     .note = the alignment of `u64` is smaller than `AtomicU64` on many 32-bits platforms
     .src_label = u64 created here
 
+rpl_patterns_unsound_atomic_cell_arithmetic = it is unsound to use the arithmetic operations of `AtomicCell<u64>` and `AtomicCell<i64>` of this version of `crossbeam-utils`
+    .label = called here
+    .note = they cast between `u64` and `AtomicU64`, while the alignment of `u64` is smaller than `AtomicU64` on many 32-bits platforms
+
 rpl_patterns_thread_local_static_ref = it is unsound to expose a `&'static {$ty}` from a thread-local where `{$ty}` is `Sync`
     .sync_help = `{$ty}` is `Sync` so that it can shared among threads
     .help = the thread local is destroyed after the thread has been destroyed, and the exposed `&'static {$ty}` may outlive the thread it is exposed to
@@ -157,8 +206,34 @@ rpl_patterns_transmuting_int_to_ptr = it is unsound to transmute an integer type
     .note = transmuting integers to pointers is a largely unspecified operation
     .help = See https://doc.rust-lang.org/std/mem/fn.transmute.html#transmutation-between-pointers-and-integers
 
+rpl_patterns_returned_ref_with_unbounded_lifetime = returning a reference with an unbounded lifetime, obtained by {$derived_by}
+    .label = the returned reference is created here
+    .src_label = from this value
+    .note = the lifetime of the returned reference is `'static`, or is not tied to any input
+    .help = tie the lifetime of the returned reference to the borrow it is derived from
+
+rpl_patterns_in_place_mutation_before_panic = a field of `self` is modified in place before a check that may panic
+    .label = the field is modified here
+    .check_label = the new value is checked here, and may panic
+    .note = if the panic is caught, the field is left with a value breaking the invariant of the type
+    .help = compute the new value into a temporary, check it, and only then assign it to the field
+
+rpl_patterns_unsound_send_sync_impl = `{$self_ty}` implements `{$trait_name}` without requiring `{$param}: {$bounds}`
+    .label = `{$trait_name}` is implemented for this type
+    .note = `{$param}` is stored in this field
+    .help = consider adding `{$param}: {$bounds}` to the `where` clause of this implementation
+
 rpl_patterns_bad_manually_drop_operation_sequence = invalid sequence of operations on `core::mem::ManuallyDrop`: `{$fn_1}` and `{$fn_2}`
     .create_label = created here
     .call_1_label = first call here
     .call_2_label = second call here
     .help = See https://doc.rust-lang.org/std/mem/struct.ManuallyDrop.html#method.{$fn_2}
+
+rpl_patterns_internal_error = internal RPL error in pattern `{$pattern}` on `{$def_path}`
+    .label = while checking this item
+    .note = the pattern panicked at {$location}: {$message}
+    .backtrace_note = backtrace summary:
+        {$backtrace}
+    .help = the remaining patterns and items are still checked, but findings of this pattern on this item may be missing
+
+rpl_patterns_applies_to = `{$applies_to_crate}` {$applies_to_version} is affected, as this pattern applies to versions `{$applies_to_range}`
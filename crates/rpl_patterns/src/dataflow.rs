@@ -0,0 +1,286 @@
+//! Follows the values computed in a MIR body, for the detectors whose conditions relate several
+//! statements matched separately, such as a check of a value computed from a written field.
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::visit::{PlaceContext, Visitor as MirVisitor};
+use rustc_middle::mir::{self, BinOp};
+
+/// The locals whose values are computed from some sources, see [`Taint::run`].
+pub(crate) struct Taint<'a, 'tcx> {
+    body: &'a mir::Body<'tcx>,
+    seeds: Vec<mir::Local>,
+    source: Option<Box<dyn Fn(mir::PlaceRef<'tcx>) -> bool + 'a>>,
+    follow: Option<Box<dyn Fn(mir::Local) -> bool + 'a>>,
+    after: Option<mir::Location>,
+}
+
+impl<'a, 'tcx> Taint<'a, 'tcx> {
+    pub(crate) fn new(body: &'a mir::Body<'tcx>) -> Self {
+        Self {
+            body,
+            seeds: Vec::new(),
+            source: None,
+            follow: None,
+            after: None,
+        }
+    }
+
+    /// Taint the values of `seeds`.
+    pub(crate) fn seeds(mut self, seeds: impl IntoIterator<Item = mir::Local>) -> Self {
+        self.seeds.extend(seeds);
+        self
+    }
+
+    /// Taint the values read from the places accepted by `source`, such as a field of `self`.
+    pub(crate) fn source(mut self, source: impl Fn(mir::PlaceRef<'tcx>) -> bool + 'a) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Only taint the locals accepted by `follow`, such as the pointers to a buffer but not its
+    /// length.
+    pub(crate) fn follow(mut self, follow: impl Fn(mir::Local) -> bool + 'a) -> Self {
+        self.follow = Some(Box::new(follow));
+        self
+    }
+
+    /// Only follow the statements that may be executed after `location`.
+    pub(crate) fn after(mut self, location: mir::Location) -> Self {
+        self.after = Some(location);
+        self
+    }
+
+    /// Compute the tainted locals, by the assignments and the calls whose operands are tainted,
+    /// regardless of the control flow except for [`Taint::after`].
+    pub(crate) fn run(self) -> Tainted<'a, 'tcx> {
+        let body = self.body;
+        let (blocks, first) = match self.after {
+            Some(location) => {
+                let reachable = reachable_from(body, location.block);
+                let first = (!reachable.contains(&location.block)).then_some(location);
+                (std::iter::once(location.block).chain(reachable).collect(), first)
+            },
+            None => (body.basic_blocks.indices().collect::<FxIndexSet<_>>(), None),
+        };
+        let mut tainted = Tainted {
+            locals: FxHashSet::from_iter(self.seeds),
+            source: self.source,
+        };
+        // The place written by an assignment or a call, not through a pointer.
+        let written = |place: mir::Place<'tcx>| {
+            (!place.is_indirect() && self.follow.as_ref().is_none_or(|follow| follow(place.local)))
+                .then_some(place.local)
+        };
+        loop {
+            let mut changed = false;
+            for &block in &blocks {
+                let data = &body[block];
+                let skip = first
+                    .filter(|first| first.block == block)
+                    .map_or(0, |first| first.statement_index + 1);
+                for (statement_index, statement) in data.statements.iter().enumerate().skip(skip) {
+                    if let mir::StatementKind::Assign(assign) = &statement.kind
+                        && let (place, rvalue) = &**assign
+                        && let Some(local) = written(*place)
+                        && !tainted.contains(local)
+                        && tainted.depends_on(|visitor| {
+                            visitor.visit_rvalue(rvalue, mir::Location { block, statement_index })
+                        })
+                    {
+                        changed |= tainted.locals.insert(local);
+                    }
+                }
+                if let mir::TerminatorKind::Call {
+                    ref func,
+                    ref args,
+                    destination,
+                    ..
+                } = data.terminator().kind
+                    && let Some(local) = written(destination)
+                    && !tainted.contains(local)
+                    && (tainted.operand(func) || args.iter().any(|arg| tainted.operand(&arg.node)))
+                {
+                    changed |= tainted.locals.insert(local);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        tainted
+    }
+}
+
+/// The result of [`Taint::run`].
+pub(crate) struct Tainted<'a, 'tcx> {
+    locals: FxHashSet<mir::Local>,
+    source: Option<Box<dyn Fn(mir::PlaceRef<'tcx>) -> bool + 'a>>,
+}
+
+impl<'tcx> Tainted<'_, 'tcx> {
+    pub(crate) fn contains(&self, local: mir::Local) -> bool {
+        self.locals.contains(&local)
+    }
+
+    /// Whether the value of `operand` is tainted.
+    pub(crate) fn operand(&self, operand: &mir::Operand<'tcx>) -> bool {
+        self.depends_on(|visitor| visitor.visit_operand(operand, mir::Location::START))
+    }
+
+    /// Whether `statement` uses a tainted value.
+    pub(crate) fn statement(&self, statement: &mir::Statement<'tcx>, location: mir::Location) -> bool {
+        self.depends_on(|visitor| visitor.visit_statement(statement, location))
+    }
+
+    fn depends_on(&self, visit: impl FnOnce(&mut DependsOn<'_, '_, 'tcx>)) -> bool {
+        let mut visitor = DependsOn {
+            tainted: self,
+            found: false,
+        };
+        visit(&mut visitor);
+        visitor.found
+    }
+}
+
+/// Finds the uses of the tainted locals, and the reads of the sources.
+struct DependsOn<'t, 'a, 'tcx> {
+    tainted: &'t Tainted<'a, 'tcx>,
+    found: bool,
+}
+
+impl<'tcx> MirVisitor<'tcx> for DependsOn<'_, '_, 'tcx> {
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: mir::Location) {
+        self.found |= self
+            .tainted
+            .source
+            .as_ref()
+            .is_some_and(|source| source(place.as_ref()));
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, local: mir::Local, _context: PlaceContext, _location: mir::Location) {
+        self.found |= self.tainted.contains(local);
+    }
+}
+
+/// The local assigned by the statement, or by the call terminating the block, at `location`.
+pub(crate) fn assigned_local(body: &mir::Body<'_>, location: mir::Location) -> Option<mir::Local> {
+    let data = &body[location.block];
+    let place = match data.statements.get(location.statement_index) {
+        Some(statement) => statement.kind.as_assign()?.0,
+        None => match data.terminator().kind {
+            mir::TerminatorKind::Call { destination, .. } => destination,
+            _ => return None,
+        },
+    };
+    place.as_local()
+}
+
+/// The blocks reachable from the terminator of `block`.
+pub(crate) fn reachable_from(body: &mir::Body<'_>, block: mir::BasicBlock) -> FxIndexSet<mir::BasicBlock> {
+    let mut reachable = FxIndexSet::default();
+    let mut worklist = body[block].terminator().successors().collect::<Vec<_>>();
+    while let Some(block) = worklist.pop() {
+        if reachable.insert(block) {
+            worklist.extend(body[block].terminator().successors());
+        }
+    }
+    reachable
+}
+
+/// Whether the operation at `from` may be executed before the one at `to`.
+pub(crate) fn precedes(body: &mir::Body<'_>, from: mir::Location, to: mir::Location) -> bool {
+    from.block == to.block && from.statement_index < to.statement_index
+        || reachable_from(body, from.block).contains(&to.block)
+}
+
+/// Whether `block` unconditionally ends with a call that never returns, such as `panic!`.
+pub(crate) fn diverges(body: &mir::Body<'_>, mut block: mir::BasicBlock) -> bool {
+    let mut visited = FxHashSet::default();
+    while visited.insert(block) {
+        match body[block].terminator().kind {
+            mir::TerminatorKind::Call { target: None, .. } => return true,
+            mir::TerminatorKind::Goto { target } => block = target,
+            _ => return false,
+        }
+    }
+    false
+}
+
+pub(crate) fn is_inlined(body: &mir::Body<'_>, source_info: mir::SourceInfo) -> bool {
+    source_info.scope.inlined_instance(&body.source_scopes).is_some()
+}
+
+/// The function called directly in the body whose inlined code `source_info` belongs to, if any.
+pub(crate) fn inlined_from(body: &mir::Body<'_>, source_info: mir::SourceInfo) -> Option<DefId> {
+    let mut scope = source_info.scope;
+    while let Some(parent_scope) = body.source_scopes[scope].inlined_parent_scope {
+        scope = parent_scope;
+    }
+    body.source_scopes[scope]
+        .inlined
+        .map(|(instance, _span)| instance.def_id())
+}
+
+/// A comparison that a location is only reachable after, see [`dominating_comparisons`].
+pub(crate) struct Comparison<'a, 'tcx> {
+    /// The block switching on the comparison.
+    pub(crate) block: mir::BasicBlock,
+    pub(crate) op: BinOp,
+    pub(crate) lhs: &'a mir::Operand<'tcx>,
+    pub(crate) rhs: &'a mir::Operand<'tcx>,
+    /// Whether the comparison holds at the location.
+    pub(crate) holds: bool,
+}
+
+/// The comparisons switched on before `location` is reached, such as `new_size <= old_size` in
+/// the `then` branch of `if new_size <= old_size`.
+pub(crate) fn dominating_comparisons<'a, 'tcx>(
+    body: &'a mir::Body<'tcx>,
+    location: mir::Location,
+) -> Vec<Comparison<'a, 'tcx>> {
+    let dominators = body.basic_blocks.dominators();
+    let predecessors = body.basic_blocks.predecessors();
+    body.basic_blocks
+        .indices()
+        .filter(|&block| dominators.dominates(block, location.block))
+        .filter_map(|block| {
+            let &[switch] = &predecessors[block][..] else {
+                return None;
+            };
+            let mir::TerminatorKind::SwitchInt { ref discr, ref targets } = body[switch].terminator().kind else {
+                return None;
+            };
+            let discr = discr.place()?.as_local()?;
+            let (op, lhs, rhs) = body[switch].statements.iter().rev().find_map(|statement| {
+                if let mir::StatementKind::Assign(assign) = &statement.kind
+                    && let (place, mir::Rvalue::BinaryOp(op, operands)) = &**assign
+                    && place.as_local() == Some(discr)
+                    && matches!(
+                        op,
+                        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge
+                    )
+                {
+                    Some((*op, &operands.0, &operands.1))
+                } else {
+                    None
+                }
+            })?;
+            let holds = if targets.otherwise() == block {
+                true
+            } else if targets.target_for_value(0) == block {
+                false
+            } else {
+                return None;
+            };
+            Some(Comparison {
+                block: switch,
+                op,
+                lhs,
+                rhs,
+                holds,
+            })
+        })
+        .collect()
+}
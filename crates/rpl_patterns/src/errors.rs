@@ -1,7 +1,8 @@
+use rpl_context::pat;
 use rustc_errors::IntoDiagArg;
-use rustc_macros::LintDiagnostic;
-use rustc_middle::ty::{self, Ty};
-use rustc_span::Span;
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::{Span, Symbol};
 
 pub struct Mutability(ty::Mutability);
 
@@ -17,6 +18,26 @@ impl IntoDiagArg for Mutability {
     }
 }
 
+/// The affected versions of a pattern with `#[applies_to]`, to be added to its diagnostics.
+#[derive(Subdiagnostic)]
+#[note(rpl_patterns_applies_to)]
+pub struct AppliesTo {
+    pub applies_to_crate: String,
+    pub applies_to_version: String,
+    pub applies_to_range: String,
+}
+
+impl AppliesTo {
+    pub fn new(tcx: TyCtxt<'_>, fn_pat: &pat::Fn<'_>) -> Option<Self> {
+        let applies_to = fn_pat.applies_to.as_ref()?;
+        Some(Self {
+            applies_to_crate: applies_to.krate.to_string(),
+            applies_to_version: rpl_match::applied_version(tcx, applies_to)?,
+            applies_to_range: applies_to.version.to_string(),
+        })
+    }
+}
+
 #[derive(LintDiagnostic)]
 #[diag(rpl_patterns_unsound_slice_cast)]
 pub struct UnsoundSliceCast<'tcx> {
@@ -147,6 +168,30 @@ pub struct TrustExactSizeIterator {
     pub fn_name: &'static str,
 }
 
+// for cve_2020_25795
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_trust_exact_size_iterator_write)]
+#[help]
+pub struct TrustExactSizeIteratorWrite {
+    #[label(rpl_patterns_label)]
+    pub write: Span,
+    #[label(rpl_patterns_len_label)]
+    pub len: Span,
+}
+
+// for cve_2020_35861
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_realloc_size_confusion)]
+#[help]
+pub struct ReallocSizeConfusion {
+    #[label]
+    pub copy: Span,
+    #[label(rpl_patterns_new_size_label)]
+    pub new_size: Span,
+    #[label(rpl_patterns_old_size_label)]
+    pub old_size: Span,
+}
+
 // for CVE-2021-29941 and CVE-2021-29942
 #[derive(LintDiagnostic)]
 #[diag(rpl_patterns_slice_from_raw_parts_uninitialized)]
@@ -186,6 +231,74 @@ pub struct SetLenUninitialized {
     pub vec: Span,
 }
 
+// for cve_2021_45688
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_uninitialized_buffer_passed_to_read)]
+#[note]
+#[help]
+pub struct UninitializedBufferPassedToRead {
+    #[label]
+    pub read: Span,
+    #[label(rpl_patterns_vec_label)]
+    pub vec: Span,
+    #[label(rpl_patterns_exposed_label)]
+    pub exposed: Span,
+    pub method: Symbol,
+    pub reader: Symbol,
+}
+
+// for CVE-2021-38190 and CVE-2023-28448
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_unvalidated_deserialization)]
+#[help]
+pub struct UnvalidatedDeserialization<'tcx> {
+    #[label]
+    pub construct: Span,
+    #[label(rpl_patterns_field_label)]
+    pub field: Span,
+    #[note]
+    pub unchecked: Span,
+    pub field_name: Symbol,
+    pub ty: Ty<'tcx>,
+}
+
+// for CVE-2025-24898
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_ffi_out_pointer_into_short_lived_buffer)]
+#[help]
+pub struct FfiOutPointerIntoShortLivedBuffer {
+    #[label]
+    pub slice: Span,
+    #[label(rpl_patterns_ffi_call_label)]
+    pub ffi_call: Span,
+    #[label(rpl_patterns_output_label)]
+    pub output: Span,
+}
+
+// for CVE-2023-41051
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_unaligned_pointer_cast_from_byte_offset)]
+#[help]
+pub struct UnalignedPointerCastFromByteOffset<'tcx> {
+    #[label]
+    pub cast: Span,
+    #[label(rpl_patterns_offset_label)]
+    pub offset: Span,
+    pub ty: Ty<'tcx>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_unaligned_pointer_wrapped_from_byte_offset)]
+#[help]
+pub struct UnalignedPointerWrappedFromByteOffset<'tcx> {
+    #[label]
+    pub wrap: Span,
+    #[label(rpl_patterns_offset_label)]
+    pub offset: Span,
+    pub ty: Ty<'tcx>,
+    pub wrapper: Ty<'tcx>,
+}
+
 // for cve_2020_35898_9
 #[derive(LintDiagnostic)]
 #[diag(rpl_patterns_get_mut_in_rc_unsafecell)]
@@ -212,6 +325,18 @@ pub struct DropUninitValue {
     pub assign: Span,
 }
 
+// for cve_2020_35906
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_type_erased_without_static_bound)]
+#[help]
+pub struct TypeErasedWithoutStaticBound<'tcx> {
+    #[label]
+    pub erase: Span,
+    #[label(rpl_patterns_raw_waker_label)]
+    pub raw_waker: Span,
+    pub ty: Ty<'tcx>,
+}
+
 // for cve_2020_35907
 #[derive(LintDiagnostic)]
 #[diag(rpl_patterns_thread_local_static_ref)]
@@ -252,6 +377,17 @@ pub struct UnsoundCastBetweenU64AndAtomicU64 {
     pub src: Span,
 }
 
+// for cve_2022_23639
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_unsound_atomic_cell_arithmetic)]
+#[note]
+pub struct UnsoundAtomicCellArithmetic {
+    #[label]
+    pub call: Span,
+    #[subdiagnostic]
+    pub applies_to: Option<AppliesTo>,
+}
+
 // for cve_2020_35860
 #[derive(LintDiagnostic)]
 #[diag(rpl_patterns_deref_null_pointer)]
@@ -365,6 +501,47 @@ pub struct TransmutingIntToPtr {
     pub to: Span,
 }
 
+// for cve_2020_35878
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_returned_ref_with_unbounded_lifetime)]
+#[help]
+pub struct ReturnedRefWithUnboundedLifetime {
+    #[label]
+    pub ret: Span,
+    #[label(rpl_patterns_src_label)]
+    pub src: Span,
+    #[note]
+    pub output: Span,
+    pub derived_by: &'static str,
+}
+
+// for cve_2020_35923
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_in_place_mutation_before_panic)]
+#[note]
+#[help]
+pub struct InPlaceMutationBeforePanic {
+    #[label]
+    pub write: Span,
+    #[label(rpl_patterns_check_label)]
+    pub check: Span,
+}
+
+// for cve_2021_25901
+#[derive(LintDiagnostic)]
+#[diag(rpl_patterns_unsound_send_sync_impl)]
+#[help]
+pub struct UnsoundSendSyncImpl<'tcx> {
+    #[label]
+    pub span: Span,
+    #[note]
+    pub field: Span,
+    pub trait_name: &'static str,
+    pub self_ty: Ty<'tcx>,
+    pub param: Ty<'tcx>,
+    pub bounds: String,
+}
+
 /// Bad operation sequence to [`std::mem::ManuallyDrop`].
 #[derive(LintDiagnostic)]
 #[diag(rpl_patterns_bad_manually_drop_operation_sequence)]
@@ -379,3 +556,20 @@ pub struct BadManuallyDropOperationSequence {
     #[label(rpl_patterns_call_2_label)]
     pub call_2: Span,
 }
+
+/// A detector panicked while checking an item.
+#[derive(Diagnostic)]
+#[diag(rpl_patterns_internal_error)]
+#[note]
+#[note(rpl_patterns_backtrace_note)]
+#[help]
+pub struct InternalError {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub pattern: String,
+    pub def_path: String,
+    pub message: String,
+    pub location: String,
+    pub backtrace: String,
+}
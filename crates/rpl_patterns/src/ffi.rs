@@ -0,0 +1,26 @@
+//! Finds the calls of extern functions in a MIR body, for the detectors of values passed to
+//! foreign code, as the patterns cannot match any extern function called.
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+
+/// The calls of extern functions taking `local` as any of their arguments, with the functions
+/// called.
+pub(crate) fn extern_calls_taking<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    local: mir::Local,
+) -> impl Iterator<Item = (mir::Location, DefId)> + use<'a, 'tcx> {
+    body.basic_blocks.iter_enumerated().filter_map(move |(block, data)| {
+        let mir::TerminatorKind::Call { ref func, ref args, .. } = data.terminator().kind else {
+            return None;
+        };
+        let (fn_def_id, _) = func.const_fn_def()?;
+        (tcx.is_foreign_item(fn_def_id)
+            && args
+                .iter()
+                .any(|arg| arg.node.place().and_then(|place| place.as_local()) == Some(local)))
+        .then(|| (body.terminator_loc(block), fn_def_id))
+    })
+}
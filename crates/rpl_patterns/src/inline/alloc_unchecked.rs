@@ -62,7 +62,7 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         // }
 
         if self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
 
             let pattern = alloc_cast_write(self.pcx);
 
@@ -147,11 +147,12 @@ fn alloc_cast_write(pcx: PatCtxt<'_>) -> Pattern<'_> {
     let write;
     let ty;
     let pattern = rpl! {
+        #[path = "src/fragments/alloc.rpl"]
+        mod alloc;
+
         #[meta(#[export(ty)] $T:ty)]
         fn $pattern(..) -> _ = mir! {
-            #[export(alloc)]
-            let $ptr_1: *mut u8 = alloc::alloc::__rust_alloc(_, _); // _3
-            let $ptr_2: *mut $T = move $ptr_1 as *mut $T (PtrToPtr); // _2
+            use alloc::$rust_alloc_cast<$T>() -> ($ptr_2); // _3, _2
             #[export(write)]
             (*$ptr_2) = _;
         }
@@ -206,11 +207,12 @@ fn alloc_cast_check_write(pcx: PatCtxt<'_>) -> Pattern<'_> {
     let write;
     let ty;
     let pattern = rpl! {
+        #[path = "src/fragments/alloc.rpl"]
+        mod alloc;
+
         #[meta(#[export(ty)] $T:ty)]
         fn $pattern(..) -> _ = mir! {
-            #[export(alloc)]
-            let $ptr_1: *mut u8 = alloc::alloc::__rust_alloc(_, _); // _3
-            let $ptr_2: *mut $T = move $ptr_1 as *mut $T (PtrToPtr); // _2
+            use alloc::$rust_alloc_cast<$T>() -> ($ptr_2); // _3, _2
             let $const_ptr_1: *const u8 = copy $ptr_2 as *const u8 (PtrToPtr); // _19
             let $addr_1: usize = copy $const_ptr_1 as usize (Transmute); // _20
             // switchInt(move $addr_1) {
@@ -238,11 +240,12 @@ fn alloc_cast_check_as_write(pcx: PatCtxt<'_>) -> Pattern<'_> {
     let write;
     let ty;
     let pattern = rpl! {
+        #[path = "src/fragments/alloc.rpl"]
+        mod alloc;
+
         #[meta(#[export(ty)] $T:ty)]
         fn $pattern(..) -> _ = mir! {
-            #[export(alloc)]
-            let $ptr_1: *mut u8 = alloc::alloc::__rust_alloc(_, _); // _3
-            let $ptr_2: *mut $T = move $ptr_1 as *mut $T (PtrToPtr); // _2
+            use alloc::$rust_alloc_cast<$T>() -> ($ptr_2); // _3, _2
             let $addr_1: usize = copy $ptr_2 as usize (PointerExposeProvenance); // _6
             // switchInt(move $addr_1) {
             //     0_usize => {}
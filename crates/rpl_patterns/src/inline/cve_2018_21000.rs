@@ -46,8 +46,8 @@ pub mod u8_to_t {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_misordered_params(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let span = matches[pattern.from_raw_parts].span_no_inline(body);
@@ -74,7 +74,7 @@ pub mod u8_to_t {
         let from_raw_parts;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern(..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
 
                 type VecU8 = alloc::vec::Vec::<u8>;
                 type VecT = alloc::vec::Vec::<$T>;
@@ -206,8 +206,8 @@ pub mod t_to_u8 {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_misordered_params(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let span = matches[pattern.from_raw_parts].span_no_inline(body);
@@ -235,7 +235,7 @@ pub mod t_to_u8 {
         let from_raw_parts;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern(..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
                 type VecU8 = alloc::vec::Vec::<u8>;
                 type VecT = alloc::vec::Vec::<$T>;
                 type NonNullU8 = core::ptr::non_null::NonNull::<u8>;
@@ -1,5 +1,3 @@
-use std::ops::Not;
-
 use rpl_context::PatCtxt;
 use rpl_mir::{CheckMirCtxt, pat};
 use rustc_hir as hir;
@@ -50,11 +48,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public()
-            && kind.header().is_none_or(|header| header.is_unsafe().not())
-            && self.tcx.is_mir_available(def_id)
-        {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern_cast = pattern_rust_str_as_c_str_inlined(self.pcx);
             for matches in
                 CheckMirCtxt::new(self.tcx, self.pcx, body, pattern_cast.pattern, pattern_cast.fn_pat).check()
@@ -90,7 +85,7 @@ fn pattern_rust_str_as_c_str_inlined(pcx: PatCtxt<'_>) -> PatternCast<'_> {
     let cast_to;
     let pattern = rpl! {
         #[meta($T:ty)]
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
             type c_char = libc::c_char;
 
             #[export(cast_from)]
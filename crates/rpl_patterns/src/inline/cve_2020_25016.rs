@@ -1,5 +1,3 @@
-use std::ops::Not;
-
 use rpl_context::PatCtxt;
 use rpl_mir::{CheckMirCtxt, pat};
 use rustc_hir as hir;
@@ -50,11 +48,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public()
-            && kind.header().is_none_or(|header| header.is_unsafe().not())
-            && self.tcx.is_mir_available(def_id)
-        {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern_cast = pattern_cast(self.pcx);
             for matches in
                 CheckMirCtxt::new(self.tcx, self.pcx, body, pattern_cast.pattern, pattern_cast.fn_pat).check()
@@ -122,7 +117,7 @@ fn pattern_cast(pcx: PatCtxt<'_>) -> PatternCast<'_> {
     let cast_to;
     let pattern = rpl! {
         #[meta( #[export(ty_var)] $T:ty = is_all_safe_trait)]
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
             #[export(cast_from)]
             let $from_slice: &[$T] = _;
             let $from_raw: *const [$T] = &raw const *$from_slice;
@@ -155,7 +150,7 @@ fn pattern_cast_mut(pcx: PatCtxt<'_>) -> PatternCast<'_> {
     let cast_to;
     let pattern = rpl! {
         #[meta( #[export(ty_var)] $T:ty = is_all_safe_trait)]
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
 
             #[export(cast_from)]
             let $from_slice_mut: &mut [$T] = _;
@@ -61,7 +61,7 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         // }
 
         if self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern = pattern(self.pcx);
             for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                 let use_span = matches[pattern.ptr_use].span_no_inline(body);
@@ -40,7 +40,7 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         if self.tcx.is_mir_available(def_id)
             && let Some(cstring_did) = self.tcx.get_diagnostic_item(sym::cstring_type)
         {
-            let body = self.tcx.optimized_mir(def_id);
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern = pattern(self.pcx);
             for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                 let use_span = matches[pattern.ptr_usage].span_no_inline(body);
@@ -11,7 +11,6 @@ use rustc_middle::mir::Body;
 use rustc_middle::ty::TyCtxt;
 use rustc_span::{Span, Symbol};
 use std::collections::BTreeSet;
-use std::ops::Not;
 
 #[instrument(level = "info", skip_all)]
 pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
@@ -65,8 +64,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         //     return;
         // }
 
-        if kind.header().is_none_or(|header| header.is_unsafe().not()) && self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
 
             // There are two patterns for checked offsets, one for the specific case and one for the general
             // case
@@ -202,7 +201,7 @@ fn pattern_unchecked_ptr_offset(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffset<'
     let mut reference = Location::uninitialized();
     let pattern = rpl! {
         #[meta($T:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             #[export(offset)]
             let $offset: usize = _; // _?0 <-> _2 ?bb0[0] <-> _2
             let $offset_1: usize = copy $offset; // _?1 <-> _3 ?bb0[1] <-> bb0[0]
@@ -259,7 +258,7 @@ fn pattern_unchecked_ptr_offset_(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffsetG
     let offset;
     let pattern = rpl! {
         #[meta($T:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             #[export(ptr)]
             let $ptr: *const $T = _;
             #[export(offset)]
@@ -311,7 +310,7 @@ fn pattern_checked_ptr_offset_lt(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffsetG
     let offset;
     let pattern = rpl! {
         #[meta($T:ty, $U:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             let $index: $U = _;
             #[export(ptr)]
             let $ptr: *const $T = _;
@@ -336,7 +335,7 @@ fn pattern_checked_ptr_offset_le(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffsetG
     let offset;
     let pattern = rpl! {
         #[meta($T:ty, $U:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             let $index: $U = _;
             #[export(ptr)]
             let $ptr: *const $T = _;
@@ -361,7 +360,7 @@ fn pattern_checked_ptr_offset_gt(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffsetG
     let offset;
     let pattern = rpl! {
         #[meta($T:ty, $U:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             let $index: $U = _;
             #[export(ptr)]
             let $ptr: *const $T = _;
@@ -386,7 +385,7 @@ fn pattern_checked_ptr_offset_ge(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffsetG
     let offset;
     let pattern = rpl! {
         #[meta($T:ty, $U:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             let $index: $U = _;
             #[export(ptr)]
             let $ptr: *const $T = _;
@@ -411,7 +410,7 @@ fn pattern_checked_ptr_offset_rem(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffset
     let offset;
     let pattern = rpl! {
         #[meta($T:ty, $U:ty)]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             #[export(ptr)]
             let $ptr: *const $T = _;
             let $index: $U = Rem(_, _);
@@ -446,7 +445,7 @@ fn pattern_checked_ptr_offset_const(pcx: PatCtxt<'_>) -> PatternUncheckedPtrOffs
     let const_offset;
     let pattern = rpl! {
         #[meta($T:ty, #[export(const_size)] $size: const(usize), #[export(const_offset)] $offset: const(usize))]
-        fn $pattern(..) -> _ = mir! {
+        safe fn $pattern(..) -> _ = mir! {
             let $array: &[$T; $size] = _; // _1
             let $slice_ref: &[$T] = copy $array as &[$T] (PointerCoercion(Unsize, Implicit)); // _3 bb0[0]
             let $slice_ptr: *const [$T] = &raw const (*$slice_ref); // _5 bb0[1]
@@ -48,8 +48,8 @@ pub mod const_const_Transmute_ver {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_wrong_assumption_of_fat_pointer_layout(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let ptr_transmute = matches[pattern.ptr_transmute].span_no_inline(body);
@@ -81,7 +81,7 @@ pub mod const_const_Transmute_ver {
         let data_ptr_get;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern(..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
 
                 let $ptr: *const $T = _;
                 // _4 = &_1;
@@ -157,8 +157,8 @@ pub mod mut_mut_Transmute_ver {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_wrong_assumption_of_fat_pointer_layout(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let ptr_transmute = matches[pattern.ptr_transmute].span_no_inline(body);
@@ -190,7 +190,7 @@ pub mod mut_mut_Transmute_ver {
         let data_ptr_get;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern (..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
 
                 let $ptr: *mut $T = _;
                 // _4 = &mut _1;
@@ -267,8 +267,8 @@ pub mod mut_const_PtrToPtr_ver {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_wrong_assumption_of_fat_pointer_layout(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let ptr_transmute = matches[pattern.ptr_transmute].span_no_inline(body);
@@ -300,7 +300,7 @@ pub mod mut_const_PtrToPtr_ver {
         let data_ptr_get;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern (..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
 
                 let $ptr: *const $T = _;
                 let $ref_to_ptr: &mut *const $T = &mut $ptr;
@@ -54,7 +54,7 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         def_id: LocalDefId,
     ) -> Self::Result {
         if self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
 
             let (pattern, fn_pat) = pattern_loop(self.pcx);
             let mir_pat = fn_pat.expect_mir_body();
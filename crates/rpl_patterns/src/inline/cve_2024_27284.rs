@@ -6,7 +6,6 @@ use rustc_hir::intravisit::{self, Visitor};
 use rustc_middle::hir::nested_filter::All;
 use rustc_middle::ty::TyCtxt;
 use rustc_span::{Span, Symbol};
-use std::ops::Not;
 
 #[instrument(level = "info", skip_all)]
 pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
@@ -47,11 +46,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public()
-            && kind.header().is_none_or(|header| header.is_unsafe().not())
-            && self.tcx.is_mir_available(def_id)
-        {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern = pattern_cass_iter_next_aggmeta(self.pcx);
             for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                 let cass_iter_next = matches[pattern.cass_iter_next].span_no_inline(body);
@@ -77,7 +73,7 @@ struct CassandraIterNextPtrPassedToCassIterGet<'pcx> {
 fn pattern_cass_iter_next_aggmeta(pcx: PatCtxt<'_>) -> CassandraIterNextPtrPassedToCassIterGet<'_> {
     let cass_iter_next;
     let pattern = rpl! {
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
             type CassIterator = cassandra_cpp_sys::CassIterator_;
             type CassBool = cassandra_cpp_sys::cass_bool_t;
             type AggregateMeta = cassandra_cpp_sys::CassAggregateMeta_;
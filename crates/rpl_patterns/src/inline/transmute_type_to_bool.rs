@@ -1,5 +1,4 @@
 #![allow(unused)]
-use std::ops::Not;
 
 use rpl_context::PatCtxt;
 use rpl_mir::{CheckMirCtxt, pat};
@@ -49,11 +48,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public()
-            && kind.header().is_none_or(|header| header.is_unsafe().not())
-            && self.tcx.is_mir_available(def_id)
-        {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern_transmute_to_bool = pattern_transmute_to_bool(self.pcx);
             for matches in CheckMirCtxt::new(
                 self.tcx,
@@ -96,7 +92,7 @@ fn pattern_transmute_to_bool(pcx: PatCtxt<'_>) -> PatternTransmute<'_> {
     let transmute_to;
     let pattern = rpl! {
         #[meta($T:ty)]
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
             #[export(transmute_from)]
             let $transmute_from: $T = _;
             #[export(transmute_to)]
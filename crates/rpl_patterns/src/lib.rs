@@ -1,13 +1,17 @@
 #![feature(rustc_private)]
 #![feature(let_chains)]
 #![feature(if_let_guard)]
+#![feature(panic_update_hook)]
 
+extern crate rustc_abi;
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_fluent_macro;
 extern crate rustc_hir;
+extern crate rustc_index;
 extern crate rustc_infer;
+extern crate rustc_lint;
 extern crate rustc_lint_defs;
 extern crate rustc_macros;
 extern crate rustc_middle;
@@ -23,17 +27,39 @@ use rpl_context::PatCtxt;
 use rustc_hir::ItemId;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::OptLevel;
+use rustc_span::FileName;
 
 mod inline;
 mod normal;
 mod others;
+mod panic_boundary;
+mod register;
 
+#[cfg(test)]
+mod tests;
+
+pub(crate) mod dataflow;
 pub(crate) mod errors;
+pub(crate) mod ffi;
 pub(crate) mod lints;
 
+pub use register::{declare_dynamic_lint, register_lints};
+
 rustc_fluent_macro::fluent_messages! { "../messages.en.ftl" }
 
-static ALL_PATTERNS: &[fn(TyCtxt<'_>, PatCtxt<'_>, ItemId)] = &[
+type CheckItem = fn(TyCtxt<'_>, PatCtxt<'_>, ItemId);
+
+/// Pair each detector with its module path, used to name it in internal error reports.
+macro_rules! patterns {
+    ($($(#[$attr:meta])* $first:ident $(:: $rest:ident)*),* $(,)?) => {
+        &[$($(#[$attr])* (
+            concat!(stringify!($first) $(, "::", stringify!($rest))*),
+            $first $(:: $rest)*,
+        )),*]
+    };
+}
+
+static ALL_PATTERNS: &[(&str, CheckItem)] = patterns![
     normal::cve_2018_20992::check_item,
     inline::cve_2018_21000::t_to_u8::check_item,
     inline::cve_2018_21000::u8_to_t::check_item,
@@ -43,10 +69,13 @@ static ALL_PATTERNS: &[fn(TyCtxt<'_>, PatCtxt<'_>, ItemId)] = &[
     normal::cve_2019_15548::check_item,
     normal::cve_2019_16138::check_item,
     inline::cve_2020_25016::check_item,
+    normal::cve_2020_25795::check_item,
     normal::cve_2020_35860::check_item,
+    normal::cve_2020_35861::check_item,
     inline::cve_2020_35862::check_item,
     inline::cve_2020_35873::check_item,
     inline::cve_2020_35877::check_item,
+    normal::cve_2020_35878::check_item,
     inline::cve_2020_35881::const_const_Transmute_ver::check_item,
     inline::cve_2020_35881::mut_mut_Transmute_ver::check_item,
     inline::cve_2020_35881::mut_const_PtrToPtr_ver::check_item,
@@ -54,18 +83,28 @@ static ALL_PATTERNS: &[fn(TyCtxt<'_>, PatCtxt<'_>, ItemId)] = &[
     inline::cve_2020_35892_3::check_item,
     inline::cve_2020_35898_9::check_item,
     inline::cve_2020_35901_2::check_item,
+    normal::cve_2020_35906::check_item,
     inline::cve_2020_35907::check_item,
     normal::cve_2020_35907::check_item,
+    normal::cve_2020_35923::check_item,
     inline::cve_2021_25904::check_item,
     normal::cve_2021_25904::check_item,
+    normal::cve_2021_25901::check_item,
     // inline::cve_2021_25905::check_item,
     normal::cve_2021_25905::check_item,
     normal::cve_2021_27376::check_item,
     inline::cve_2021_29941_2::check_item,
     normal::cve_2021_29941_2::check_item,
+    normal::cve_2021_38190::check_item,
+    normal::cve_2021_45688::check_item,
     normal::cve_2022_23639::check_item,
+    normal::cve_2023_41051::check_item,
     inline::cve_2024_27284::check_item,
+    normal::cve_2025_24898::check_item,
     others::private_or_generic_function_marked_inline::check_item,
+    // Only in debug builds, such as the driver built for the UI tests.
+    #[cfg(debug_assertions)]
+    others::panic_for_testing::check_item,
     // FIXME: Too loose
     // inline::transmute_type_to_bool::check_item,
     // FIXME: Too loose
@@ -76,12 +115,60 @@ static ALL_PATTERNS: &[fn(TyCtxt<'_>, PatCtxt<'_>, ItemId)] = &[
 ];
 
 #[allow(unused)]
-static DEBUG_PATTERN: &[fn(TyCtxt<'_>, PatCtxt<'_>, ItemId)] = &[inline::cve_2020_35888::check_item];
+static DEBUG_PATTERN: &[(&str, CheckItem)] = patterns![inline::cve_2020_35888::check_item];
 
 #[instrument(level = "info", skip_all, fields(item = ?item.owner_id.def_id))]
 pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item: ItemId) {
-    rustc_data_structures::sync::par_for_each_in(ALL_PATTERNS, |check| check(tcx, pcx, item))
-    // rustc_data_structures::sync::par_for_each_in(DEBUG_PATTERN, |check| check(tcx, pcx, item));
+    let conf = rpl_config::conf();
+    if is_excluded(tcx, conf, item) {
+        return;
+    }
+    rustc_data_structures::sync::par_for_each_in(ALL_PATTERNS, |&(name, check)| {
+        if conf.is_detector_enabled(detector_name(name)) {
+            check_item_with(tcx, pcx, item, name, check)
+        }
+    })
+    // rustc_data_structures::sync::par_for_each_in(DEBUG_PATTERN, |&(name, check)| {
+    //     check_item_with(tcx, pcx, item, name, check)
+    // });
+}
+
+/// The module path of a detector, e.g. `inline::cve_2020_35873`.
+fn detector_name(name: &str) -> &str {
+    name.strip_suffix("::check_item").unwrap_or(name)
+}
+
+/// Whether the source file of `item` is excluded in the configuration.
+fn is_excluded(tcx: TyCtxt<'_>, conf: &rpl_config::Conf, item: ItemId) -> bool {
+    if conf.exclude.is_empty() {
+        return false;
+    }
+    let span = tcx.def_span(item.owner_id);
+    match &tcx.sess.source_map().span_to_filename(span) {
+        FileName::Real(name) if let Some(path) = name.local_path() => {
+            let path = match tcx.sess.opts.working_dir.local_path() {
+                Some(working_dir) if path.is_relative() => &working_dir.join(path),
+                _ => path,
+            };
+            conf.is_excluded(path)
+        },
+        _ => false,
+    }
+}
+
+/// Run a single detector on `item`, reporting a panic inside it as an internal error warning
+/// instead of aborting the compilation.
+fn check_item_with(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item: ItemId, name: &str, check: CheckItem) {
+    if let Err(panic) = panic_boundary::catch_detector_panic(|| check(tcx, pcx, item)) {
+        tcx.dcx().emit_warn(errors::InternalError {
+            span: tcx.def_span(item.owner_id),
+            pattern: detector_name(name).to_owned(),
+            def_path: tcx.def_path_str(item.owner_id),
+            message: panic.message,
+            location: panic.location,
+            backtrace: panic.backtrace,
+        });
+    }
 }
 
 #[allow(unused)]
@@ -1,4 +1,4 @@
-use rustc_lint_defs::declare_tool_lint;
+use rustc_lint_defs::{Lint, declare_tool_lint};
 
 declare_tool_lint! {
     /// The `rpl::lengthless_buffer_passed_to_extern_function` lint detects a buffer
@@ -535,6 +535,7 @@ declare_tool_lint! {
     /// ### Explanation
     ///
     /// It is unsound to expose a `&'static T` from a thread-local where `T` is `Sync`.
+    /// The same holds for a `#[thread_local]` static.
     pub rpl::THREAD_LOCAL_STATIC_REF,
     Deny,
     "detects casting a reference to a thread-local static variable (which implements `Sync`) to a static reference"
@@ -584,6 +585,10 @@ declare_tool_lint! {
     ///
     /// The alignment of [`AtomicU64`] may be larger than [`u64`], which can cause undefined behavior.
     ///
+    /// The arithmetic operations of `AtomicCell<u64>` and `AtomicCell<i64>` in `crossbeam-utils`
+    /// before 0.8.7 (CVE-2022-23639) do such a cast, so calling them is detected as well in crates
+    /// depending on the affected versions.
+    ///
     /// [`AtomicU64`]: std::sync::atomic::AtomicU64
     pub rpl::UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
     Deny,
@@ -720,6 +725,326 @@ declare_tool_lint! {
     "detects using a value after it has been moved to another location"
 }
 
+declare_tool_lint! {
+    /// The `rpl::returned_ref_with_unbounded_lifetime` lint detects functions returning a reference
+    /// whose lifetime is `'static` or not tied to any input, while the reference is derived from a
+    /// raw pointer or a transmuted reference.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// pub struct Entry<'a, V> {
+    ///     value: &'a mut V,
+    /// }
+    ///
+    /// impl<'a, V> Entry<'a, V> {
+    ///     pub fn into_mut(self) -> &'static mut V {
+    ///         let value: *mut V = self.value;
+    ///         unsafe { &mut *value }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// The caller can choose any lifetime for the returned reference, so it can outlive the value
+    /// it points to, resulting in a use-after-free.
+    pub rpl::RETURNED_REF_WITH_UNBOUNDED_LIFETIME,
+    Deny,
+    "detects returning a reference with an unbounded lifetime derived from a raw pointer or transmute"
+}
+
+declare_tool_lint! {
+    /// The `rpl::in_place_mutation_before_panic` lint detects methods modifying a field of `self` in
+    /// place before checking the new value with a panicking check.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use std::ops::AddAssign;
+    ///
+    /// /// A float that is never NaN.
+    /// pub struct NotNan(f64);
+    ///
+    /// impl AddAssign<f64> for NotNan {
+    ///     fn add_assign(&mut self, other: f64) {
+    ///         self.0 += other;
+    ///         assert!(!self.0.is_nan(), "Addition resulted in NaN");
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// If the check panics and the panic is caught, e.g. by `std::panic::catch_unwind`, the value
+    /// is left with a field breaking its invariant, which unsafe code may rely on.
+    pub rpl::IN_PLACE_MUTATION_BEFORE_PANIC,
+    Deny,
+    "detects modifying a field in place before a panicking check on the new value"
+}
+
+declare_tool_lint! {
+    /// The `rpl::unsound_send_sync_impl` lint detects `unsafe impl`s of `Send` or `Sync` whose bounds
+    /// are weaker than what the fields of the type require.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use std::cell::UnsafeCell;
+    ///
+    /// pub struct Lazy<T> {
+    ///     value: UnsafeCell<Option<T>>,
+    /// }
+    ///
+    /// unsafe impl<T: Sync> Sync for Lazy<T> {}
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Each field must be `Send` (or `Sync`) for the type to be, which the trait solver decides from
+    /// the implementations of the field types, e.g. `Arc<T>` is only `Send` if `T: Send + Sync`.
+    /// A field that can never be `Sync`, such as an `UnsafeCell`, is synchronized by the type
+    /// itself, like in `Mutex`, but its value can still be written, and so sent, through a shared
+    /// reference from another thread, so sharing `Lazy<T>` across threads requires `T: Send`.
+    /// A raw pointer, or a `NonNull`, is taken as owning what it points to, like a `Box`, only
+    /// when the type also has a `PhantomData` of the pointee.
+    pub rpl::UNSOUND_SEND_SYNC_IMPL,
+    Deny,
+    "detects `unsafe impl`s of `Send` or `Sync` missing bounds on the type parameters"
+}
+
+declare_tool_lint! {
+    /// The `rpl::realloc_size_confusion` lint detects copying the new size of a reallocated memory
+    /// block out of the old one, in functions taking the `Layout` of the old block and a new size.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use std::alloc::{Layout, alloc};
+    /// use std::ptr;
+    ///
+    /// pub unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    ///     unsafe {
+    ///         let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+    ///         let new_ptr = alloc(new_layout);
+    ///         ptr::copy_nonoverlapping(ptr, new_ptr, new_size);
+    ///         new_ptr
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// When the block grows, the old block only has `layout.size()` bytes, so copying `new_size`
+    /// bytes reads out of its bounds. At most `min(old_size, new_size)` bytes should be copied.
+    pub rpl::REALLOC_SIZE_CONFUSION,
+    Deny,
+    "detects copying the new size of a reallocated memory block out of the old one"
+}
+
+declare_tool_lint! {
+    /// The `rpl::uninitialized_buffer_passed_to_read` lint detects passing the uninitialized memory
+    /// of a `Vec` created by `Vec::with_capacity` to a method of a generic `std::io::Read`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    ///
+    /// pub fn read_bytes<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    ///     let mut buf = Vec::with_capacity(len);
+    ///     unsafe { buf.set_len(len) };
+    ///     reader.read_exact(&mut buf)?;
+    ///     Ok(buf)
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// The reader can be any implementation of `std::io::Read`, which may read from the buffer
+    /// instead of only writing to it, and so observe the uninitialized memory.
+    pub rpl::UNINITIALIZED_BUFFER_PASSED_TO_READ,
+    Deny,
+    "detects passing an uninitialized buffer to a method of a generic `std::io::Read`"
+}
+
+declare_tool_lint! {
+    /// The `rpl::type_erased_without_static_bound` lint detects generic functions erasing a pointer
+    /// to a type that may not be `'static` into a `RawWaker`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::task::{RawWaker, RawWakerVTable, Waker};
+    ///
+    /// pub trait Wake: Send + Sync {
+    ///     fn wake(self: Arc<Self>);
+    /// }
+    ///
+    /// pub fn waker<W: Wake>(wake: Arc<W>) -> Waker {
+    ///     let ptr = Arc::into_raw(wake) as *const ();
+    ///     unsafe { Waker::from_raw(RawWaker::new(ptr, vtable::<W>())) }
+    /// }
+    ///
+    /// fn vtable<W: Wake>() -> &'static RawWakerVTable {
+    ///     todo!()
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// A `Waker` has no lifetime parameter, so it can outlive the data borrowed by `W`, such as
+    /// `W = Foo<'a>`, and then the vtable functions instantiated with `W` access freed memory.
+    /// `W: 'static` is required.
+    pub rpl::TYPE_ERASED_WITHOUT_STATIC_BOUND,
+    Deny,
+    "detects erasing a pointer to a type that may not be `'static` into a `RawWaker`"
+}
+
+declare_tool_lint! {
+    /// The `rpl::unvalidated_deserialization` lint detects implementations of `Deserialize`
+    /// constructing a struct from deserialized fields that unsafe code trusts as lengths or
+    /// indices, without checking them.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// pub struct Matrix<T> {
+    ///     data: Vec<T>,
+    ///     nrows: usize,
+    ///     ncols: usize,
+    /// }
+    ///
+    /// impl<T> Matrix<T> {
+    ///     pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+    ///         if row < self.nrows && col < self.ncols {
+    ///             // `data.len() == nrows * ncols` is assumed.
+    ///             Some(unsafe { self.data.get_unchecked(col * self.nrows + row) })
+    ///         } else {
+    ///             None
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// The input of a deserializer is untrusted, so the invariants between the fields, such as
+    /// `data.len() == nrows * ncols`, may not hold, and the unsafe code relying on them accesses
+    /// memory out of bounds. `Deserialize` should be implemented manually and check the invariants.
+    pub rpl::UNVALIDATED_DESERIALIZATION,
+    Deny,
+    "detects deserializing a struct without checking the fields trusted by unsafe code",
+    report_in_external_macro: true
+}
+
+declare_tool_lint! {
+    /// The `rpl::ffi_out_pointer_into_short_lived_buffer` lint detects a slice created from a
+    /// pointer written by an extern function into one of several input buffers, while the
+    /// returned slice borrows only one of them.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use std::ffi::{c_int, c_uchar, c_uint};
+    ///
+    /// unsafe extern "C" {
+    ///     fn SSL_select_next_proto(
+    ///         out: *mut *mut c_uchar,
+    ///         outlen: *mut c_uchar,
+    ///         inbuf: *const c_uchar,
+    ///         inlen: c_uint,
+    ///         client: *const c_uchar,
+    ///         client_len: c_uint,
+    ///     ) -> c_int;
+    /// }
+    ///
+    /// pub fn select_next_proto<'a>(server: &[u8], client: &'a [u8]) -> Option<&'a [u8]> {
+    ///     unsafe {
+    ///         let mut out = std::ptr::null_mut();
+    ///         let mut outlen = 0;
+    ///         let r = SSL_select_next_proto(
+    ///             &mut out,
+    ///             &mut outlen,
+    ///             server.as_ptr(),
+    ///             server.len() as c_uint,
+    ///             client.as_ptr(),
+    ///             client.len() as c_uint,
+    ///         );
+    ///         if r == 1 {
+    ///             Some(std::slice::from_raw_parts(out as *const u8, outlen as usize))
+    ///         } else {
+    ///             None
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// `SSL_select_next_proto` may set `out` to point into either `server` or `client`, but the
+    /// returned slice only borrows `client`, so it may outlive `server` and dangle. The lifetime
+    /// of the returned slice should be tied to all the buffers passed to the extern function.
+    pub rpl::FFI_OUT_POINTER_INTO_SHORT_LIVED_BUFFER,
+    Deny,
+    "detects a slice created from an out-pointer of an extern function that outlives its buffer"
+}
+
+declare_tool_lint! {
+    /// The `rpl::unaligned_pointer_cast_from_byte_offset` lint detects a byte pointer offset by
+    /// a number of bytes from the arguments, and then cast to a pointer to `T` or wrapped in a
+    /// typed reference such as `VolatileRef<T>`, without checking the alignment of `T`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// pub struct VolatileSlice {
+    ///     addr: *mut u8,
+    ///     size: usize,
+    /// }
+    ///
+    /// impl VolatileSlice {
+    ///     pub fn get_ref<T: Copy>(&self, offset: usize) -> Option<*mut T> {
+    ///         if offset.checked_add(size_of::<T>())? > self.size {
+    ///             return None;
+    ///         }
+    ///         Some(unsafe { self.addr.add(offset) } as *mut T)
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// A pointer offset by an arbitrary number of bytes is not necessarily aligned for `T`, and
+    /// creating a reference or reading a `T` through it is undefined behavior. The pointer should
+    /// be checked against `align_of::<T>()`, for example with `is_aligned`, before it is used.
+    pub rpl::UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET,
+    Deny,
+    "detects a byte pointer offset and cast to a typed pointer without checking the alignment"
+}
+
 declare_tool_lint! {
     /// The `rpl::private_function_marked_inline` lint detects private functions that are marked with `#[inline]`.
     ///
@@ -913,3 +1238,148 @@ declare_tool_lint! {
     Deny,
     "detects using a pointer after it has been reallocated"
 }
+
+/// All the lints declared above, registered in the lint store as the `rpl::all` group.
+pub(crate) static ALL_LINTS: &[&Lint] = &[
+    LENGTHLESS_BUFFER_PASSED_TO_EXTERN_FUNCTION,
+    RUST_STRING_POINTER_AS_C_STRING_POINTER,
+    UNCHECKED_POINTER_OFFSET,
+    CASSANDRA_ITER_NEXT_PTR_PASSED_TO_CASS_ITER_GET,
+    SET_LEN_TO_EXTEND,
+    SET_LEN_TO_TRUNCATE,
+    SET_LEN_UNINITIALIZED,
+    UNSOUND_SLICE_CAST,
+    USE_AFTER_DROP,
+    OFFSET_BY_ONE,
+    MISORDERED_PARAMETERS,
+    WRONG_ASSUMPTION_OF_FAT_POINTER_LAYOUT,
+    WRONG_ASSUMPTION_OF_LAYOUT_COMPATIBILITY,
+    TRUST_EXACT_SIZE_ITERATOR,
+    SLICE_FROM_RAW_PARTS_UNINITIALIZED,
+    GET_MUT_IN_RC_UNSAFECELL,
+    DROP_UNINIT_VALUE,
+    THREAD_LOCAL_STATIC_REF,
+    UNVALIDATED_SLICE_FROM_RAW_PARTS,
+    UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
+    DEREF_NULL_POINTER,
+    DEREF_UNCHECKED_PTR_OFFSET,
+    UNSOUND_PIN_PROJECT,
+    USE_AFTER_MOVE,
+    UNSOUND_SEND_SYNC_IMPL,
+    IN_PLACE_MUTATION_BEFORE_PANIC,
+    RETURNED_REF_WITH_UNBOUNDED_LIFETIME,
+    REALLOC_SIZE_CONFUSION,
+    UNINITIALIZED_BUFFER_PASSED_TO_READ,
+    TYPE_ERASED_WITHOUT_STATIC_BOUND,
+    UNVALIDATED_DESERIALIZATION,
+    FFI_OUT_POINTER_INTO_SHORT_LIVED_BUFFER,
+    UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET,
+    PRIVATE_FUNCTION_MARKED_INLINE,
+    GENERIC_FUNCTION_MARKED_INLINE,
+    TRANSMUTING_TYPE_TO_BOOL,
+    TRANSMUTING_INT_TO_PTR,
+    BAD_MANUALLY_DROP_OPERATION_SEQUENCE,
+    UNCHECKED_ALLOCATED_POINTER,
+    USE_AFTER_REALLOC,
+];
+
+/// Lint groups other than `rpl::all`. A lint may belong to several groups.
+pub(crate) static LINT_GROUPS: &[(&str, &[&Lint])] = &[
+    (
+        "rpl::memory",
+        &[
+            UNCHECKED_POINTER_OFFSET,
+            SET_LEN_TO_EXTEND,
+            SET_LEN_TO_TRUNCATE,
+            SET_LEN_UNINITIALIZED,
+            USE_AFTER_DROP,
+            OFFSET_BY_ONE,
+            MISORDERED_PARAMETERS,
+            TRUST_EXACT_SIZE_ITERATOR,
+            SLICE_FROM_RAW_PARTS_UNINITIALIZED,
+            GET_MUT_IN_RC_UNSAFECELL,
+            DROP_UNINIT_VALUE,
+            UNVALIDATED_SLICE_FROM_RAW_PARTS,
+            DEREF_NULL_POINTER,
+            DEREF_UNCHECKED_PTR_OFFSET,
+            UNSOUND_PIN_PROJECT,
+            USE_AFTER_MOVE,
+            BAD_MANUALLY_DROP_OPERATION_SEQUENCE,
+            UNCHECKED_ALLOCATED_POINTER,
+            USE_AFTER_REALLOC,
+            RETURNED_REF_WITH_UNBOUNDED_LIFETIME,
+            REALLOC_SIZE_CONFUSION,
+            UNINITIALIZED_BUFFER_PASSED_TO_READ,
+            TYPE_ERASED_WITHOUT_STATIC_BOUND,
+            UNVALIDATED_DESERIALIZATION,
+        ],
+    ),
+    (
+        "rpl::ffi",
+        &[
+            LENGTHLESS_BUFFER_PASSED_TO_EXTERN_FUNCTION,
+            RUST_STRING_POINTER_AS_C_STRING_POINTER,
+            CASSANDRA_ITER_NEXT_PTR_PASSED_TO_CASS_ITER_GET,
+            FFI_OUT_POINTER_INTO_SHORT_LIVED_BUFFER,
+        ],
+    ),
+    (
+        "rpl::layout",
+        &[
+            UNSOUND_SLICE_CAST,
+            WRONG_ASSUMPTION_OF_FAT_POINTER_LAYOUT,
+            WRONG_ASSUMPTION_OF_LAYOUT_COMPATIBILITY,
+            UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
+            TRANSMUTING_TYPE_TO_BOOL,
+            TRANSMUTING_INT_TO_PTR,
+            UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET,
+        ],
+    ),
+    (
+        "rpl::concurrency",
+        &[
+            THREAD_LOCAL_STATIC_REF,
+            UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
+            UNSOUND_SEND_SYNC_IMPL,
+        ],
+    ),
+    // Lints of the patterns derived from CVEs.
+    (
+        "rpl::cve",
+        &[
+            LENGTHLESS_BUFFER_PASSED_TO_EXTERN_FUNCTION,
+            RUST_STRING_POINTER_AS_C_STRING_POINTER,
+            UNCHECKED_POINTER_OFFSET,
+            CASSANDRA_ITER_NEXT_PTR_PASSED_TO_CASS_ITER_GET,
+            SET_LEN_TO_EXTEND,
+            SET_LEN_TO_TRUNCATE,
+            SET_LEN_UNINITIALIZED,
+            UNSOUND_SLICE_CAST,
+            USE_AFTER_DROP,
+            OFFSET_BY_ONE,
+            MISORDERED_PARAMETERS,
+            WRONG_ASSUMPTION_OF_FAT_POINTER_LAYOUT,
+            WRONG_ASSUMPTION_OF_LAYOUT_COMPATIBILITY,
+            TRUST_EXACT_SIZE_ITERATOR,
+            SLICE_FROM_RAW_PARTS_UNINITIALIZED,
+            GET_MUT_IN_RC_UNSAFECELL,
+            DROP_UNINIT_VALUE,
+            THREAD_LOCAL_STATIC_REF,
+            UNVALIDATED_SLICE_FROM_RAW_PARTS,
+            UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
+            DEREF_NULL_POINTER,
+            DEREF_UNCHECKED_PTR_OFFSET,
+            UNSOUND_PIN_PROJECT,
+            USE_AFTER_MOVE,
+            UNSOUND_SEND_SYNC_IMPL,
+            IN_PLACE_MUTATION_BEFORE_PANIC,
+            RETURNED_REF_WITH_UNBOUNDED_LIFETIME,
+            REALLOC_SIZE_CONFUSION,
+            UNINITIALIZED_BUFFER_PASSED_TO_READ,
+            TYPE_ERASED_WITHOUT_STATIC_BOUND,
+            UNVALIDATED_DESERIALIZATION,
+            FFI_OUT_POINTER_INTO_SHORT_LIVED_BUFFER,
+            UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET,
+        ],
+    ),
+];
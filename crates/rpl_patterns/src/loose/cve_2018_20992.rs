@@ -47,8 +47,8 @@ pub mod extend {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_vec_set_len_to_extend(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let set_len = matches[pattern.set_len_use].span_no_inline(body);
@@ -77,7 +77,7 @@ pub mod extend {
         let set_len_use;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern(..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
 
                 type VecT = alloc::vec::Vec::<$T>;
                 type VecTMutRef = &mut alloc::vec::Vec::<$T>;
@@ -155,8 +155,8 @@ pub mod truncate {
             _span: Span,
             def_id: LocalDefId,
         ) -> Self::Result {
-            if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-                let body = self.tcx.optimized_mir(def_id);
+            if self.tcx.is_mir_available(def_id) {
+                let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
                 let pattern = pattern_vec_set_len_to_truncate(self.pcx);
                 for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                     let span = matches[pattern.set_len_use].span_no_inline(body);
@@ -182,7 +182,7 @@ pub mod truncate {
         let set_len_use;
         let pattern = rpl! {
             #[meta($T:ty)]
-            fn $pattern(..) -> _ = mir! {
+            pub fn $pattern(..) -> _ = mir! {
 
                 type VecT = alloc::vec::Vec::<$T>;
                 type VecTRef = &alloc::vec::Vec::<$T>;
@@ -46,8 +46,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern = pattern_vec_set_len_to_extend(self.pcx);
             for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                 let set_len = matches[pattern.set_len].span_no_inline(body);
@@ -76,7 +76,7 @@ fn pattern_vec_set_len_to_extend(pcx: PatCtxt<'_>) -> VecSetLenToExtend<'_> {
     let set_len;
     let pattern = rpl! {
         #[meta($T:ty)]
-        fn $pattern(..) -> _ = mir! {
+        pub fn $pattern(..) -> _ = mir! {
             type VecT = alloc::vec::Vec::<$T>;
 
             #[export(vec)]
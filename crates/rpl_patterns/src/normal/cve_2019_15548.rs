@@ -1,14 +1,14 @@
-use std::ops::Not;
-
 use rpl_context::PatCtxt;
-use rpl_mir::{CheckMirCtxt, pat};
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
 use rustc_hir as hir;
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_middle::hir::nested_filter::All;
 use rustc_middle::ty::TyCtxt;
 use rustc_span::{Span, Symbol};
 
+use crate::dataflow::assigned_local;
+use crate::ffi::extern_calls_taking;
 use crate::lints::{LENGTHLESS_BUFFER_PASSED_TO_EXTERN_FUNCTION, RUST_STRING_POINTER_AS_C_STRING_POINTER};
 
 #[instrument(level = "info", skip_all)]
@@ -50,11 +50,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public()
-            && kind.header().is_none_or(|header| header.is_unsafe().not())
-            && self.tcx.is_mir_available(def_id)
-        {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern_cast = pattern_rust_str_as_c_str(self.pcx);
             for matches in
                 CheckMirCtxt::new(self.tcx, self.pcx, body, pattern_cast.pattern, pattern_cast.fn_pat).check()
@@ -72,6 +69,14 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
             let pattern_ptr = pattern_pass_a_pointer_to_c(self.pcx);
             for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern_ptr.pattern, pattern_ptr.fn_pat).check()
             {
+                let StatementMatch::Location(ptr) = matches[pattern_ptr.ptr] else {
+                    continue;
+                };
+                if !assigned_local(body, ptr).is_some_and(|ptr| {
+                    extern_calls_taking(self.tcx, body, ptr).any(|(_, fn_def_id)| self.is_lengthless(fn_def_id))
+                }) {
+                    continue;
+                }
                 let ptr = matches[pattern_ptr.ptr].span_no_inline(body);
                 debug!(?ptr);
                 self.tcx.emit_node_span_lint(
@@ -86,6 +91,20 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
     }
 }
 
+impl CheckFnCtxt<'_, '_> {
+    /// Whether the extern function takes no integer, which could be the length of a buffer.
+    fn is_lengthless(&self, fn_def_id: DefId) -> bool {
+        !self
+            .tcx
+            .fn_sig(fn_def_id)
+            .instantiate_identity()
+            .skip_binder()
+            .inputs()
+            .iter()
+            .any(|ty| ty.is_integral())
+    }
+}
+
 struct PatternCast<'pcx> {
     pattern: &'pcx pat::Pattern<'pcx>,
     fn_pat: &'pcx pat::Fn<'pcx>,
@@ -101,7 +120,7 @@ fn pattern_rust_str_as_c_str(pcx: PatCtxt<'_>) -> PatternCast<'_> {
     let cast_to;
     let pattern = rpl! {
         #[meta($T:ty)]
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
 
             type c_char = libc::c_char;
 
@@ -130,17 +149,17 @@ struct PatternPointer<'pcx> {
     ptr: pat::Location,
 }
 
-// FIXME: this should work for functions other than `crate::ll::instr`.
+/// A pointer to C chars, which is checked in [`CheckFnCtxt::visit_fn`] to be passed to an extern
+/// function taking no length, as the extern function may be any of them.
 #[rpl_macros::pattern_def]
 fn pattern_pass_a_pointer_to_c(pcx: PatCtxt<'_>) -> PatternPointer<'_> {
     let ptr;
     let pattern = rpl! {
-        fn $pattern (..) -> _ = mir! {
+        pub safe fn $pattern (..) -> _ = mir! {
             type c_char = libc::c_char;
 
             #[export(ptr)]
             let $ptr: *const c_char = _;
-            _ = $crate::ll::instr(move $ptr);
         }
     };
     let fn_pat = pattern.fns.get_fn_pat(Symbol::intern("pattern")).unwrap();
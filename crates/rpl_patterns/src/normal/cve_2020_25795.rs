@@ -0,0 +1,236 @@
+use rpl_context::PatCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::{Span, Symbol};
+
+use crate::dataflow::{Taint, Tainted, assigned_local, dominating_comparisons, reachable_from};
+use crate::lints::TRUST_EXACT_SIZE_ITERATOR;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, hir::Safety::Safe, ..)
+            | hir::ItemKind::Impl(_)
+            | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            let pattern = pattern_write_by_len(self.pcx);
+            // The locals in the loops are matched in several ways, so the writes are deduplicated.
+            let mut reported = FxHashSet::default();
+            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
+                let (
+                    StatementMatch::Location(len_call),
+                    StatementMatch::Location(ptr),
+                    StatementMatch::Location(write_at),
+                ) = (matches[pattern.len], matches[pattern.ptr], matches[pattern.write])
+                else {
+                    continue;
+                };
+                let mir::TerminatorKind::Call { destination, .. } = body[len_call.block].terminator().kind else {
+                    continue;
+                };
+                let from_len = Taint::new(body).seeds([destination.local]).run();
+                if !is_written_by_len(body, &from_len, ptr, write_at) || is_bounded_in_loop(body, &from_len, write_at) {
+                    continue;
+                }
+                let len = matches[pattern.len].span_no_inline(body);
+                let write = matches[pattern.write].span_no_inline(body);
+                debug!(?len, ?write);
+                if !reported.insert((len, write)) {
+                    continue;
+                }
+                self.tcx.emit_node_span_lint(
+                    TRUST_EXACT_SIZE_ITERATOR,
+                    self.tcx.local_def_id_to_hir_id(def_id),
+                    write,
+                    crate::errors::TrustExactSizeIteratorWrite { write, len },
+                );
+            }
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+/// Whether the items are written with the length, either through a pointer computed from the
+/// length, such as at `real_index - len`, or into a buffer reserved with the length, such as
+/// `buf` after `buf.reserve(len)`, or after a comparison with a value computed from the length,
+/// such as a check of the capacity. Otherwise the writes are not sized by the length.
+fn is_written_by_len<'tcx>(
+    body: &mir::Body<'tcx>,
+    from_len: &Tainted<'_, 'tcx>,
+    ptr: mir::Location,
+    write: mir::Location,
+) -> bool {
+    let Some(ptr) = assigned_local(body, ptr) else {
+        return false;
+    };
+    from_len.contains(ptr)
+        || reserved_by_len(body, from_len).into_iter().any(|(location, reserved)| {
+            Taint::new(body)
+                .source(move |place| place.local == reserved.local && place.projection.starts_with(reserved.projection))
+                .after(location)
+                .run()
+                .contains(ptr)
+        })
+        || dominating_comparisons(body, write)
+            .iter()
+            .any(|comparison| from_len.operand(comparison.lhs) || from_len.operand(comparison.rhs))
+}
+
+/// The places mutably borrowed by the calls also taking a value computed from the length, such
+/// as `buf` in `buf.reserve(len)`, with where they are called.
+fn reserved_by_len<'tcx>(
+    body: &mir::Body<'tcx>,
+    from_len: &Tainted<'_, 'tcx>,
+) -> Vec<(mir::Location, mir::PlaceRef<'tcx>)> {
+    let mut_borrows = body
+        .basic_blocks
+        .iter()
+        .flat_map(|data| &data.statements)
+        .filter_map(|statement| match statement.kind.as_assign()? {
+            (place, mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, borrowed)) => {
+                Some((place.as_local()?, borrowed.as_ref()))
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut reserved = Vec::new();
+    for (block, data) in body.basic_blocks.iter_enumerated() {
+        let mir::TerminatorKind::Call { ref args, .. } = data.terminator().kind else {
+            continue;
+        };
+        if !args.iter().any(|arg| from_len.operand(&arg.node)) {
+            continue;
+        }
+        let location = body.terminator_loc(block);
+        for arg in args.iter().filter_map(|arg| arg.node.place()?.as_local()) {
+            let borrowed = mut_borrows
+                .iter()
+                .filter(|&&(local, _)| local == arg)
+                .map(|&(_, borrowed)| borrowed)
+                .collect::<Vec<_>>();
+            if !borrowed.is_empty() {
+                reserved.extend(borrowed.into_iter().map(|borrowed| (location, borrowed)));
+            } else if body.local_decls[arg].ty.is_mutable_ptr() {
+                // A mutable reference passed on, such as the `buf: &mut Vec<T>` argument, is
+                // followed with all the places it is the base of.
+                reserved.push((location, mir::Place::from(arg).as_ref()));
+            }
+        }
+    }
+    reserved
+}
+
+/// Whether the write at `write` is only reachable after a comparison in the loop with a value
+/// computed from the length, such as `write_index < end` with `end = write_index + len`. The
+/// checks before the loop, such as the check of the capacity, do not bound the number of items
+/// written.
+fn is_bounded_in_loop<'tcx>(body: &mir::Body<'tcx>, from_len: &Tainted<'_, 'tcx>, write: mir::Location) -> bool {
+    let in_loop = reachable_from(body, write.block);
+    dominating_comparisons(body, write).iter().any(|comparison| {
+        in_loop.contains(&comparison.block) && (from_len.operand(comparison.lhs) || from_len.operand(comparison.rhs))
+    })
+}
+
+/// Whether `ty` is a type parameter, as the length of a concrete iterator can be trusted, or
+/// known to be wrong.
+fn is_param<'tcx>(_tcx: TyCtxt<'tcx>, _typing_env: ty::TypingEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    matches!(ty.kind(), ty::Param(_))
+}
+
+struct PatternWriteByLen<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    fn_pat: &'pcx pat::Fn<'pcx>,
+    len: pat::Location,
+    ptr: pat::Location,
+    write: pat::Location,
+}
+
+/// The length of an `ExactSizeIterator` is used to reserve the space, and then the items are
+/// written through a raw pointer until the iterator is exhausted, instead of at most `len` items.
+#[rpl_macros::pattern_def]
+fn pattern_write_by_len(pcx: PatCtxt<'_>) -> PatternWriteByLen<'_> {
+    let len;
+    // Exported from the loop, which is built in a closure.
+    let mut ptr = pat::Location::uninitialized();
+    let mut write = pat::Location::uninitialized();
+    let pattern = rpl! {
+        #[meta($T:ty, $I:ty = is_param)]
+        fn $pattern(..) -> _ where $I: std::iter::ExactSizeIterator = mir! {
+            let $iter: $I = _;
+            let $iter_ref: &$I = &$iter;
+            #[export(len)]
+            let $len: usize = std::iter::ExactSizeIterator::len(move $iter_ref);
+            // `IntoIterator::into_iter` of an iterator is the identity, and is inlined away.
+            let $iter_into: $I = move $iter;
+            let mut $into_iter: $I = move $iter_into;
+            let mut $iter_mut: &mut $I;
+            let $next: std::option::Option<$T>;
+            let $discr: isize;
+            let $value: $T;
+            let $ptr: *mut $T;
+            loop {
+                $iter_mut = &mut $into_iter;
+                $next = std::iter::Iterator::next(copy $iter_mut);
+                $discr = discriminant($next);
+                switchInt(move $discr) {
+                    0_isize => break,
+                    1_isize => {
+                        $value = move ($next as Some).0;
+                        #[export(ptr)]
+                        $ptr = _;
+                        #[export(write)]
+                        (*$ptr) = copy $value;
+                    }
+                }
+            }
+        }
+    };
+    let fn_pat = pattern.fns.get_fn_pat(Symbol::intern("pattern")).unwrap();
+
+    PatternWriteByLen {
+        pattern,
+        fn_pat,
+        len,
+        ptr,
+        write,
+    }
+}
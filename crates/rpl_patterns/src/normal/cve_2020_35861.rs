@@ -0,0 +1,271 @@
+use rpl_context::PatCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::LangItem;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir::{self, BinOp};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::{Span, Symbol, sym};
+
+use crate::dataflow::{Taint, Tainted, dominating_comparisons, inlined_from};
+use crate::lints::REALLOC_SIZE_CONFUSION;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, ..) | hir::ItemKind::Impl(_) | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            self.check_body(def_id, body);
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+impl<'tcx> CheckFnCtxt<'_, 'tcx> {
+    /// Report the copies in a function taking the `Layout` of an old allocation and a new size,
+    /// such as `realloc`, whose byte count is the new size, and that are not guarded by a check of
+    /// the new size against the old one.
+    fn check_body(&self, def_id: LocalDefId, body: &mir::Body<'tcx>) {
+        let Some(layout) = body.args_iter().find(|&arg| {
+            matches!(body.local_decls[arg].ty.kind(), ty::Adt(adt_def, _)
+                if self.tcx.is_lang_item(adt_def.did(), LangItem::AllocLayout))
+        }) else {
+            return;
+        };
+        let pattern = pattern_copy_new_size(self.pcx);
+        let old = Taint::new(body).seeds([layout]).run();
+        // The sizes passed in, and the values computed from them, except for those bounded by
+        // `cmp::min`.
+        let bounded = bounded_by_min(self.tcx, body);
+        let new_sizes = body
+            .args_iter()
+            .filter(|&arg| body.local_decls[arg].ty.is_usize())
+            .map(|arg| {
+                let new = Taint::new(body)
+                    .seeds([arg])
+                    .follow(|local| !bounded.contains(&local))
+                    .run();
+                (arg, new)
+            })
+            .collect::<Vec<_>>();
+        let old_size = old_size_span(body, &old).unwrap_or(body.local_decls[layout].source_info.span);
+
+        let mut reported = FxHashSet::default();
+        for (fn_pat, copy) in pattern.fn_pats {
+            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                let StatementMatch::Location(location) = matches[copy] else {
+                    continue;
+                };
+                let Some(count) = copied_count(body, location) else {
+                    continue;
+                };
+                if old.contains(count) {
+                    continue;
+                }
+                let Some((new_size, new)) = new_sizes.iter().find(|(_, new)| new.contains(count)) else {
+                    continue;
+                };
+                let sizes = Sizes { new, old: &old };
+                if is_guarded(body, &sizes, location) {
+                    continue;
+                }
+                let copy = matches[copy].span_no_inline(body);
+                if !reported.insert(copy) {
+                    continue;
+                }
+                self.tcx.emit_node_span_lint(
+                    REALLOC_SIZE_CONFUSION,
+                    self.tcx.local_def_id_to_hir_id(def_id),
+                    copy,
+                    crate::errors::ReallocSizeConfusion {
+                        copy,
+                        new_size: body.local_decls[*new_size].source_info.span,
+                        old_size,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// The local of the number of items copied by the `copy_nonoverlapping` intrinsic or the call at
+/// `location`.
+fn copied_count(body: &mir::Body<'_>, location: mir::Location) -> Option<mir::Local> {
+    let data = &body[location.block];
+    let count = match data.statements.get(location.statement_index) {
+        Some(statement) => {
+            if let mir::StatementKind::Intrinsic(intrinsic) = &statement.kind
+                && let mir::NonDivergingIntrinsic::CopyNonOverlapping(mir::CopyNonOverlapping { count, .. }) =
+                    &**intrinsic
+            {
+                count
+            } else {
+                return None;
+            }
+        },
+        None => {
+            let mir::TerminatorKind::Call { ref args, .. } = data.terminator().kind else {
+                return None;
+            };
+            &args.get(2)?.node
+        },
+    };
+    count.place()?.as_local()
+}
+
+/// The locals assigned by `cmp::min` or `Ord::min`, either called or inlined.
+fn bounded_by_min<'tcx>(tcx: TyCtxt<'tcx>, body: &mir::Body<'tcx>) -> FxHashSet<mir::Local> {
+    let is_min = |def_id| {
+        tcx.get_diagnostic_name(def_id)
+            .is_some_and(|name| matches!(name, sym::cmp_min | sym::cmp_ord_min))
+    };
+    let mut bounded = FxHashSet::default();
+    for data in body.basic_blocks.iter() {
+        for statement in &data.statements {
+            if let Some((place, _)) = statement.kind.as_assign()
+                && inlined_from(body, statement.source_info).is_some_and(is_min)
+            {
+                bounded.insert(place.local);
+            }
+        }
+        let terminator = data.terminator();
+        if let mir::TerminatorKind::Call {
+            ref func, destination, ..
+        } = terminator.kind
+            && (func.const_fn_def().is_some_and(|(def_id, _)| is_min(def_id))
+                || inlined_from(body, terminator.source_info).is_some_and(is_min))
+        {
+            bounded.insert(destination.local);
+        }
+    }
+    bounded
+}
+
+/// The locals computed from the new size and from the `Layout` of the old allocation.
+struct Sizes<'a, 'tcx> {
+    new: &'a Tainted<'a, 'tcx>,
+    old: &'a Tainted<'a, 'tcx>,
+}
+
+impl<'tcx> Sizes<'_, 'tcx> {
+    fn is_new_only(&self, operand: &mir::Operand<'tcx>) -> bool {
+        self.new.operand(operand) && !self.old.operand(operand)
+    }
+
+    fn is_old_only(&self, operand: &mir::Operand<'tcx>) -> bool {
+        self.old.operand(operand) && !self.new.operand(operand)
+    }
+}
+
+/// Whether `location` is only reachable after a comparison showing that the new size is not larger
+/// than the old one, such as in the `then` branch of `if new_size <= old_size`.
+fn is_guarded<'tcx>(body: &mir::Body<'tcx>, sizes: &Sizes<'_, 'tcx>, location: mir::Location) -> bool {
+    dominating_comparisons(body, location).into_iter().any(|comparison| {
+        let (lhs, rhs) = (comparison.lhs, comparison.rhs);
+        // Whether the new size is not larger than the old one when the comparison holds.
+        let not_larger = match comparison.op {
+            BinOp::Le | BinOp::Lt if sizes.is_new_only(lhs) && sizes.is_old_only(rhs) => true,
+            BinOp::Ge | BinOp::Gt if sizes.is_old_only(lhs) && sizes.is_new_only(rhs) => true,
+            BinOp::Ge | BinOp::Gt if sizes.is_new_only(lhs) && sizes.is_old_only(rhs) => false,
+            BinOp::Le | BinOp::Lt if sizes.is_old_only(lhs) && sizes.is_new_only(rhs) => false,
+            _ => return false,
+        };
+        not_larger == comparison.holds
+    })
+}
+
+/// The span where the size of the old allocation is first read from its `Layout`.
+fn old_size_span(body: &mir::Body<'_>, old: &Tainted<'_, '_>) -> Option<Span> {
+    body.basic_blocks.iter_enumerated().find_map(|(block, data)| {
+        data.statements
+            .iter()
+            .enumerate()
+            .find_map(|(statement_index, statement)| {
+                if let mir::StatementKind::Assign(assign) = &statement.kind
+                    && let Some(local) = assign.0.as_local()
+                    && old.contains(local)
+                    && body.local_decls[local].ty.is_usize()
+                {
+                    Some(StatementMatch::Location(mir::Location { block, statement_index }).span_no_inline(body))
+                } else {
+                    None
+                }
+            })
+    })
+}
+
+struct PatternCopyNewSize<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns, with where the new size is copied.
+    fn_pats: [(&'pcx pat::Fn<'pcx>, pat::Location); 2],
+}
+
+/// A copy of `$new_size` items, either by a call of a function copying from a pointer to another,
+/// such as `ptr::copy`, or by the `copy_nonoverlapping` intrinsic `ptr::copy_nonoverlapping` is
+/// inlined into.
+#[rpl_macros::pattern_def]
+fn pattern_copy_new_size(pcx: PatCtxt<'_>) -> PatternCopyNewSize<'_> {
+    let copy;
+    let intrinsic_copy;
+    let pattern = rpl! {
+        #[meta($T:ty)]
+        fn $copy_fn(*const $T, *mut $T, usize);
+
+        fn $copy(..) -> _ = mir! {
+            let $new_size: usize;
+            #[export(copy)]
+            _ = $copy_fn(_, _, copy $new_size);
+        }
+
+        #[meta($T:ty)]
+        fn $copy_nonoverlapping(..) -> _ = mir! {
+            let $src: *const $T;
+            let $dst: *mut $T;
+            let $new_size: usize;
+            #[export(intrinsic_copy)]
+            Intrinsic(copy_nonoverlapping(copy $src, copy $dst, copy $new_size));
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternCopyNewSize {
+        pattern,
+        fn_pats: [(fn_pat("copy"), copy), (fn_pat("copy_nonoverlapping"), intrinsic_copy)],
+    }
+}
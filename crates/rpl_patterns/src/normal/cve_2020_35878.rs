@@ -0,0 +1,300 @@
+use rpl_context::PatCtxt;
+use rpl_match::MatchFnCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::{Span, Symbol};
+
+use crate::dataflow::{Taint, assigned_local};
+use crate::lints::RETURNED_REF_WITH_UNBOUNDED_LIFETIME;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, hir::Safety::Safe, ..)
+            | hir::ItemKind::Impl(_)
+            | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            && self.tcx.is_mir_available(def_id)
+            && self.returns_unbounded_ref(def_id)
+        {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            // The values derived from the arguments borrowing something or holding a raw pointer,
+            // such as `&self` or a `BitPtr`, and not from, e.g., a `static` or a leaked `Box`.
+            let borrowed = Taint::new(body)
+                .seeds(
+                    body.args_iter()
+                        .filter(|&arg| points_to_memory(self.tcx, body.local_decls[arg].ty, &mut FxHashSet::default())),
+                )
+                .run();
+            let pattern = pattern_derive_ref(self.pcx);
+            let mut reported = FxHashSet::default();
+            for &(fn_pat, src, ret, derived_by) in &pattern.derivations {
+                for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                    let src_local = match matches[src] {
+                        StatementMatch::Arg(local) => Some(local),
+                        StatementMatch::Location(location) => assigned_local(body, location),
+                    };
+                    if !src_local.is_some_and(|local| borrowed.contains(local)) {
+                        continue;
+                    }
+                    let src = matches[src].span_no_inline(body);
+                    let ret = matches[ret].span_no_inline(body);
+                    debug!(?src, ?ret);
+                    if !reported.insert(ret) {
+                        continue;
+                    }
+                    self.tcx.emit_node_span_lint(
+                        RETURNED_REF_WITH_UNBOUNDED_LIFETIME,
+                        self.tcx.local_def_id_to_hir_id(def_id),
+                        ret,
+                        crate::errors::ReturnedRefWithUnboundedLifetime {
+                            ret,
+                            src,
+                            output: decl.output.span(),
+                            derived_by,
+                        },
+                    );
+                }
+            }
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+impl CheckFnCtxt<'_, '_> {
+    /// Whether the function returns a reference whose lifetime is `'static`, or is not tied to any
+    /// of the inputs.
+    fn returns_unbounded_ref(&self, def_id: LocalDefId) -> bool {
+        let pattern = pattern_unbounded_ref_sig(self.pcx);
+        pattern
+            .fn_pats
+            .iter()
+            .any(|fn_pat| MatchFnCtxt::new(self.tcx, self.pcx, pattern.pattern, fn_pat).match_fn(def_id))
+    }
+}
+
+/// Whether `ty` borrows something, or holds a raw pointer, possibly in the fields of an ADT, such
+/// as the `NonNull<u8>` of a `BitPtr`.
+fn points_to_memory<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, visited: &mut FxHashSet<Ty<'tcx>>) -> bool {
+    ty.walk().any(|arg| match arg.unpack() {
+        ty::GenericArgKind::Lifetime(_) => true,
+        ty::GenericArgKind::Type(ty) => match *ty.kind() {
+            ty::RawPtr(..) => true,
+            ty::Adt(adt_def, args) => {
+                visited.insert(ty)
+                    && adt_def
+                        .all_fields()
+                        .any(|field| points_to_memory(tcx, field.ty(tcx, args), visited))
+            },
+            _ => false,
+        },
+        ty::GenericArgKind::Const(_) => false,
+    })
+}
+
+struct PatternUnboundedRefSig<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    fn_pats: [&'pcx pat::Fn<'pcx>; 4],
+}
+
+#[rpl_macros::pattern_def]
+fn pattern_unbounded_ref_sig(pcx: PatCtxt<'_>) -> PatternUnboundedRefSig<'_> {
+    let pattern = rpl! {
+        #[meta($'a, $T:ty)]
+        #[constraints(unbounded($'a))]
+        fn $unbounded_ref(..) -> &$'a $T;
+
+        #[meta($'a, $T:ty)]
+        #[constraints(unbounded($'a))]
+        fn $unbounded_mut(..) -> &$'a mut $T;
+
+        #[meta($T:ty)]
+        fn $static_ref(..) -> &'static $T;
+
+        #[meta($T:ty)]
+        fn $static_mut(..) -> &'static mut $T;
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternUnboundedRefSig {
+        pattern,
+        fn_pats: [
+            fn_pat("unbounded_ref"),
+            fn_pat("unbounded_mut"),
+            fn_pat("static_ref"),
+            fn_pat("static_mut"),
+        ],
+    }
+}
+
+struct PatternDeriveRef<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns deriving a reference, with where the value it is derived from is
+    /// defined, where the reference is created, and how it is derived. The slices created from a
+    /// raw pointer come first, as the pointer to the slice is also matched by the dereferences.
+    derivations: [(&'pcx pat::Fn<'pcx>, pat::Location, pat::Location, &'static str); 8],
+}
+
+#[rpl_macros::pattern_def]
+fn pattern_derive_ref(pcx: PatCtxt<'_>) -> PatternDeriveRef<'_> {
+    let slice_src;
+    let slice_ret;
+    let slice_mut_src;
+    let slice_mut_ret;
+    let call_slice_src;
+    let call_slice_ret;
+    let call_slice_mut_src;
+    let call_slice_mut_ret;
+    let deref_src;
+    let deref_ret;
+    let deref_mut_src;
+    let deref_mut_ret;
+    let transmute_src;
+    let transmute_ret;
+    let transmute_mut_src;
+    let transmute_mut_ret;
+    #[allow(non_snake_case)]
+    let pattern = rpl! {
+        #[meta($T:ty)]
+        fn $slice_from_raw_parts(..) -> _ = mir! {
+            #[export(slice_src)]
+            let $data: *const $T = _;
+            let $slice: *const [$T] = *const [$T] from (copy $data, _);
+            #[export(slice_ret)]
+            let $RET: &[$T] = &(*$slice);
+        }
+
+        #[meta($T:ty)]
+        fn $slice_from_raw_parts_mut(..) -> _ = mir! {
+            #[export(slice_mut_src)]
+            let $data: *mut $T = _;
+            let $slice: *mut [$T] = *mut [$T] from (copy $data, _);
+            #[export(slice_mut_ret)]
+            let $RET: &mut [$T] = &mut (*$slice);
+        }
+
+        #[meta($T:ty)]
+        fn $call_from_raw_parts(..) -> _ = mir! {
+            #[export(call_slice_src)]
+            let $data: *const $T = _;
+            #[export(call_slice_ret)]
+            let $RET: &[$T] = std::slice::from_raw_parts::<'_, $T>(move $data, _);
+        }
+
+        #[meta($T:ty)]
+        fn $call_from_raw_parts_mut(..) -> _ = mir! {
+            #[export(call_slice_mut_src)]
+            let $data: *mut $T = _;
+            #[export(call_slice_mut_ret)]
+            let $RET: &mut [$T] = std::slice::from_raw_parts_mut::<'_, $T>(move $data, _);
+        }
+
+        #[meta($T:ty)]
+        fn $deref_const_ptr(..) -> _ = mir! {
+            #[export(deref_src)]
+            let $ptr: *const $T = _;
+            #[export(deref_ret)]
+            let $RET: &$T = &(*$ptr);
+        }
+
+        #[meta($T:ty)]
+        fn $deref_mut_ptr(..) -> _ = mir! {
+            #[export(deref_mut_src)]
+            let $ptr: *mut $T = _;
+            #[export(deref_mut_ret)]
+            let $RET: &mut $T = &mut (*$ptr);
+        }
+
+        #[meta($T:ty, $U:ty)]
+        fn $transmute_ref(..) -> _ = mir! {
+            #[export(transmute_src)]
+            let $src: &$U = _;
+            #[export(transmute_ret)]
+            let $RET: &$T = copy $src as &$T (Transmute);
+        }
+
+        #[meta($T:ty, $U:ty)]
+        fn $transmute_mut_ref(..) -> _ = mir! {
+            #[export(transmute_mut_src)]
+            let $src: &mut $U = _;
+            #[export(transmute_mut_ret)]
+            let $RET: &mut $T = move $src as &mut $T (Transmute);
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+    let from_raw_parts = "creating a slice from a raw pointer";
+    let deref = "dereferencing a raw pointer";
+    let transmute = "transmuting a reference";
+
+    PatternDeriveRef {
+        pattern,
+        derivations: [
+            (fn_pat("slice_from_raw_parts"), slice_src, slice_ret, from_raw_parts),
+            (
+                fn_pat("slice_from_raw_parts_mut"),
+                slice_mut_src,
+                slice_mut_ret,
+                from_raw_parts,
+            ),
+            (
+                fn_pat("call_from_raw_parts"),
+                call_slice_src,
+                call_slice_ret,
+                from_raw_parts,
+            ),
+            (
+                fn_pat("call_from_raw_parts_mut"),
+                call_slice_mut_src,
+                call_slice_mut_ret,
+                from_raw_parts,
+            ),
+            (fn_pat("deref_const_ptr"), deref_src, deref_ret, deref),
+            (fn_pat("deref_mut_ptr"), deref_mut_src, deref_mut_ret, deref),
+            (fn_pat("transmute_ref"), transmute_src, transmute_ret, transmute),
+            (
+                fn_pat("transmute_mut_ref"),
+                transmute_mut_src,
+                transmute_mut_ret,
+                transmute,
+            ),
+        ],
+    }
+}
@@ -0,0 +1,381 @@
+use rpl_context::PatCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_abi::FieldIdx;
+use rustc_hir as hir;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::{Span, Symbol, sym};
+
+use crate::dataflow::inlined_from;
+use crate::lints::TYPE_ERASED_WITHOUT_STATIC_BOUND;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, hir::Safety::Safe, ..)
+            | hir::ItemKind::Impl(_)
+            | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            let pattern = pattern_raw_waker(self.pcx);
+            let args = ty::GenericArgs::identity_for_item(self.tcx, def_id);
+            for &(fn_pat, ty_var, erase, raw_waker) in &pattern.raw_wakers {
+                for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                    let ty = matches[ty_var.idx];
+                    let (StatementMatch::Location(erase_at), StatementMatch::Location(raw_waker_at)) =
+                        (matches[erase], matches[raw_waker])
+                    else {
+                        continue;
+                    };
+                    // The pointer owns a `$W` by `Arc::into_raw` or `Box::into_raw`, which the
+                    // functions of the vtable are instantiated to take back.
+                    if !self.is_into_raw(body, erase_at)
+                        || !raw_waker_vtable(body, raw_waker_at).is_some_and(|vtable| {
+                            self.vtable_fns(body, vtable, args, 0)
+                                .iter()
+                                .any(|fn_args| fn_args.types().any(|arg| arg == ty))
+                        })
+                    {
+                        continue;
+                    }
+                    let erase = matches[erase].span_no_inline(body);
+                    let raw_waker = matches[raw_waker].span_no_inline(body);
+                    debug!(?erase, ?raw_waker, ?ty);
+                    self.tcx.emit_node_span_lint(
+                        TYPE_ERASED_WITHOUT_STATIC_BOUND,
+                        self.tcx.local_def_id_to_hir_id(def_id),
+                        erase,
+                        crate::errors::TypeErasedWithoutStaticBound { erase, raw_waker, ty },
+                    );
+                }
+            }
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+/// How many functions returning a vtable are followed, such as `waker_vtable::<W>()`.
+const VTABLE_CALL_DEPTH: usize = 2;
+
+impl<'tcx> CheckFnCtxt<'_, 'tcx> {
+    /// Whether the pointer assigned at `location` is returned by `Arc::into_raw` or
+    /// `Box::into_raw`, either called or inlined.
+    fn is_into_raw(&self, body: &mir::Body<'tcx>, location: mir::Location) -> bool {
+        let data = &body[location.block];
+        let source_info = match data.statements.get(location.statement_index) {
+            Some(statement) => statement.source_info,
+            None => {
+                let terminator = data.terminator();
+                if let mir::TerminatorKind::Call { ref func, .. } = terminator.kind
+                    && let Some((fn_def_id, _)) = func.const_fn_def()
+                    && self.is_into_raw_fn(fn_def_id)
+                {
+                    return true;
+                }
+                terminator.source_info
+            },
+        };
+        inlined_from(body, source_info).is_some_and(|fn_def_id| self.is_into_raw_fn(fn_def_id))
+    }
+
+    fn is_into_raw_fn(&self, fn_def_id: DefId) -> bool {
+        let tcx = self.tcx;
+        tcx.item_name(fn_def_id).as_str() == "into_raw"
+            && tcx.impl_of_method(fn_def_id).is_some_and(|impl_def_id| {
+                matches!(tcx.type_of(impl_def_id).instantiate_identity().kind(), ty::Adt(adt_def, _)
+                    if adt_def.is_box() || tcx.is_diagnostic_item(sym::Arc, adt_def.did()))
+            })
+    }
+
+    /// The generic arguments of the functions in the vtable `vtable` of `body`, instantiated with
+    /// `args`. The vtable is either a promoted `&RawWakerVTable::new(..)`, or returned by a
+    /// function, such as `waker_vtable::<W>()`.
+    fn vtable_fns(
+        &self,
+        body: &mir::Body<'tcx>,
+        vtable: &mir::Operand<'tcx>,
+        args: ty::GenericArgsRef<'tcx>,
+        depth: usize,
+    ) -> Vec<ty::GenericArgsRef<'tcx>> {
+        let tcx = self.tcx;
+        let instantiate = |inner: ty::GenericArgsRef<'tcx>| ty::EarlyBinder::bind(inner).instantiate(tcx, args);
+        match vtable {
+            mir::Operand::Constant(constant) => match constant.const_ {
+                mir::Const::Unevaluated(unevaluated, _) if let Some(promoted) = unevaluated.promoted => {
+                    let promoted = &tcx.promoted_mir(unevaluated.def)[promoted];
+                    self.fns_in_vtable(promoted, instantiate(unevaluated.args))
+                },
+                _ => Vec::new(),
+            },
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                let Some(local) = place.as_local() else {
+                    return Vec::new();
+                };
+                let mut fns = Vec::new();
+                for data in body.basic_blocks.iter() {
+                    for statement in &data.statements {
+                        if let Some((assigned, mir::Rvalue::Use(operand))) = statement.kind.as_assign()
+                            && assigned.as_local() == Some(local)
+                        {
+                            fns.extend(self.vtable_fns(body, operand, args, depth));
+                        }
+                    }
+                    if let mir::TerminatorKind::Call {
+                        ref func, destination, ..
+                    } = data.terminator().kind
+                        && destination.as_local() == Some(local)
+                        && let Some((fn_def_id, fn_args)) = func.const_fn_def()
+                        && depth < VTABLE_CALL_DEPTH
+                        && tcx.is_mir_available(fn_def_id)
+                    {
+                        let callee = tcx.optimized_mir(fn_def_id);
+                        let fn_args = instantiate(fn_args);
+                        for data in callee.basic_blocks.iter() {
+                            for statement in &data.statements {
+                                if let Some((assigned, mir::Rvalue::Use(operand))) = statement.kind.as_assign()
+                                    && assigned.local == mir::RETURN_PLACE
+                                {
+                                    fns.extend(self.vtable_fns(callee, operand, fn_args, depth + 1));
+                                }
+                            }
+                        }
+                    }
+                }
+                fns
+            },
+        }
+    }
+
+    /// The generic arguments of the functions a `RawWakerVTable` is built with in `body`,
+    /// instantiated with `args`.
+    fn fns_in_vtable(&self, body: &mir::Body<'tcx>, args: ty::GenericArgsRef<'tcx>) -> Vec<ty::GenericArgsRef<'tcx>> {
+        let pattern = pattern_vtable(self.pcx);
+        let mut fns = Vec::new();
+        for (fn_pat, fn_ptrs) in &pattern.vtables {
+            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                for &fn_ptr in fn_ptrs {
+                    let StatementMatch::Location(location) = matches[fn_ptr] else {
+                        continue;
+                    };
+                    if let Some(statement) = body[location.block].statements.get(location.statement_index)
+                        && let Some((_, mir::Rvalue::Cast(_, mir::Operand::Constant(constant), _))) =
+                            statement.kind.as_assign()
+                        && let &ty::FnDef(_, fn_args) = constant.ty().kind()
+                    {
+                        fns.push(ty::EarlyBinder::bind(fn_args).instantiate(self.tcx, args));
+                    }
+                }
+            }
+        }
+        fns
+    }
+}
+
+/// The vtable operand of the `RawWaker` created at `location`.
+fn raw_waker_vtable<'a, 'tcx>(body: &'a mir::Body<'tcx>, location: mir::Location) -> Option<&'a mir::Operand<'tcx>> {
+    let data = &body[location.block];
+    match data.statements.get(location.statement_index) {
+        Some(statement) => match statement.kind.as_assign()? {
+            (_, mir::Rvalue::Aggregate(_, operands)) => operands.get(FieldIdx::from_u32(1)),
+            _ => None,
+        },
+        None => match data.terminator().kind {
+            mir::TerminatorKind::Call { ref args, .. } => Some(&args.get(1)?.node),
+            _ => None,
+        },
+    }
+}
+
+struct PatternRawWaker<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns erasing a pointer into a `RawWaker`, with the type of the pointee,
+    /// where the pointer is created, and where the `RawWaker` is.
+    raw_wakers: [(&'pcx pat::Fn<'pcx>, pat::TyVar, pat::Location, pat::Location); 4],
+}
+
+/// A pointer to `$W`, usually from `Arc::into_raw` or `Box::into_raw`, is erased into the data
+/// pointer of a `RawWaker`, whose vtable functions are instantiated with `$W`, but `$W` may not
+/// outlive the `Waker`. The `RawWaker` is either built by `RawWaker::new`, or by an aggregate
+/// when `RawWaker::new` is inlined.
+#[rpl_macros::pattern_def]
+fn pattern_raw_waker(pcx: PatCtxt<'_>) -> PatternRawWaker<'_> {
+    let new_const_ty;
+    let new_const_erase;
+    let new_const_raw_waker;
+    let new_mut_ty;
+    let new_mut_erase;
+    let new_mut_raw_waker;
+    let aggregate_const_ty;
+    let aggregate_const_erase;
+    let aggregate_const_raw_waker;
+    let aggregate_mut_ty;
+    let aggregate_mut_erase;
+    let aggregate_mut_raw_waker;
+    let pattern = rpl! {
+        #[meta(#[export(new_const_ty)] $W:ty)]
+        fn $raw_waker_new_const(..) -> _ where $W: !'static = mir! {
+            #[export(new_const_erase)]
+            let $raw: *const $W = _;
+            let $data: *const () = move $raw as *const () (PtrToPtr);
+            #[export(new_const_raw_waker)]
+            let $raw_waker: std::task::RawWaker = std::task::RawWaker::new(copy $data, _);
+        }
+
+        #[meta(#[export(new_mut_ty)] $W:ty)]
+        fn $raw_waker_new_mut(..) -> _ where $W: !'static = mir! {
+            #[export(new_mut_erase)]
+            let $raw: *mut $W = _;
+            let $data: *const () = move $raw as *const () (PtrToPtr);
+            #[export(new_mut_raw_waker)]
+            let $raw_waker: std::task::RawWaker = std::task::RawWaker::new(copy $data, _);
+        }
+
+        #[meta(#[export(aggregate_const_ty)] $W:ty)]
+        fn $raw_waker_aggregate_const(..) -> _ where $W: !'static = mir! {
+            #[export(aggregate_const_erase)]
+            let $raw: *const $W = _;
+            let $data: *const () = move $raw as *const () (PtrToPtr);
+            #[export(aggregate_const_raw_waker)]
+            let $raw_waker: std::task::RawWaker = std::task::RawWaker { data: copy $data, vtable: _ };
+        }
+
+        #[meta(#[export(aggregate_mut_ty)] $W:ty)]
+        fn $raw_waker_aggregate_mut(..) -> _ where $W: !'static = mir! {
+            #[export(aggregate_mut_erase)]
+            let $raw: *mut $W = _;
+            let $data: *const () = move $raw as *const () (PtrToPtr);
+            #[export(aggregate_mut_raw_waker)]
+            let $raw_waker: std::task::RawWaker = std::task::RawWaker { data: copy $data, vtable: _ };
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternRawWaker {
+        pattern,
+        raw_wakers: [
+            (
+                fn_pat("raw_waker_new_const"),
+                new_const_ty,
+                new_const_erase,
+                new_const_raw_waker,
+            ),
+            (
+                fn_pat("raw_waker_new_mut"),
+                new_mut_ty,
+                new_mut_erase,
+                new_mut_raw_waker,
+            ),
+            (
+                fn_pat("raw_waker_aggregate_const"),
+                aggregate_const_ty,
+                aggregate_const_erase,
+                aggregate_const_raw_waker,
+            ),
+            (
+                fn_pat("raw_waker_aggregate_mut"),
+                aggregate_mut_ty,
+                aggregate_mut_erase,
+                aggregate_mut_raw_waker,
+            ),
+        ],
+    }
+}
+
+struct PatternVtable<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns building a `RawWakerVTable`, with where the function pointers in it
+    /// are created.
+    vtables: [(&'pcx pat::Fn<'pcx>, [pat::Location; 4]); 2],
+}
+
+/// A `RawWakerVTable` built from function pointers, either by `RawWakerVTable::new` or by an
+/// aggregate when it is inlined.
+#[rpl_macros::pattern_def]
+fn pattern_vtable(pcx: PatCtxt<'_>) -> PatternVtable<'_> {
+    let new_clone;
+    let new_wake;
+    let new_wake_by_ref;
+    let new_drop;
+    let aggregate_clone;
+    let aggregate_wake;
+    let aggregate_wake_by_ref;
+    let aggregate_drop;
+    let pattern = rpl! {
+        fn $vtable_new(..) -> _ = mir! {
+            #[export(new_clone)]
+            let $clone: unsafe fn(*const ()) -> std::task::RawWaker = _;
+            #[export(new_wake)]
+            let $wake: unsafe fn(*const ()) = _;
+            #[export(new_wake_by_ref)]
+            let $wake_by_ref: unsafe fn(*const ()) = _;
+            #[export(new_drop)]
+            let $drop: unsafe fn(*const ()) = _;
+            let $vtable: std::task::RawWakerVTable =
+                std::task::RawWakerVTable::new(move $clone, move $wake, move $wake_by_ref, move $drop);
+        }
+
+        fn $vtable_aggregate(..) -> _ = mir! {
+            #[export(aggregate_clone)]
+            let $clone: unsafe fn(*const ()) -> std::task::RawWaker = _;
+            #[export(aggregate_wake)]
+            let $wake: unsafe fn(*const ()) = _;
+            #[export(aggregate_wake_by_ref)]
+            let $wake_by_ref: unsafe fn(*const ()) = _;
+            #[export(aggregate_drop)]
+            let $drop: unsafe fn(*const ()) = _;
+            let $vtable: std::task::RawWakerVTable = std::task::RawWakerVTable {
+                clone: move $clone,
+                wake: move $wake,
+                wake_by_ref: move $wake_by_ref,
+                drop: move $drop,
+            };
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternVtable {
+        pattern,
+        vtables: [
+            (fn_pat("vtable_new"), [new_clone, new_wake, new_wake_by_ref, new_drop]),
+            (
+                fn_pat("vtable_aggregate"),
+                [aggregate_clone, aggregate_wake, aggregate_wake_by_ref, aggregate_drop],
+            ),
+        ],
+    }
+}
@@ -58,26 +58,28 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         if self.tcx.is_mir_available(def_id)
             && (vis == ty::Visibility::Public || vis == ty::Visibility::Restricted(CRATE_DEF_ID))
         {
-            let body = self.tcx.optimized_mir(def_id);
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
 
             let pattern = pattern_thread_local_static(self.pcx);
-            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
-                let thread_local = matches[pattern.thread_local].span_no_inline(body);
-                let ret = matches[pattern.ret].span_no_inline(body);
-                let ty = matches[pattern.ty_var.idx];
-                debug!(?thread_local, ?ty);
-                let span = decl.output.span();
-                self.tcx.emit_node_span_lint(
-                    THREAD_LOCAL_STATIC_REF,
-                    self.tcx.local_def_id_to_hir_id(def_id),
-                    span,
-                    crate::errors::ThreadLocalStaticRef {
+            for &(fn_pat, thread_local, ret, ty_var) in &pattern.fn_pats {
+                for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                    let thread_local = matches[thread_local].span_no_inline(body);
+                    let ret = matches[ret].span_no_inline(body);
+                    let ty = matches[ty_var.idx];
+                    debug!(?thread_local, ?ty);
+                    let span = decl.output.span();
+                    self.tcx.emit_node_span_lint(
+                        THREAD_LOCAL_STATIC_REF,
+                        self.tcx.local_def_id_to_hir_id(def_id),
                         span,
-                        thread_local,
-                        ret,
-                        ty,
-                    },
-                );
+                        crate::errors::ThreadLocalStaticRef {
+                            span,
+                            thread_local,
+                            ret,
+                            ty,
+                        },
+                    );
+                }
             }
         }
         intravisit::walk_fn(self, kind, decl, body_id, def_id);
@@ -86,37 +88,69 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
 
 struct PatternThreadLocalStatic<'pcx> {
     pattern: &'pcx pat::Pattern<'pcx>,
-    fn_pat: &'pcx pat::Fn<'pcx>,
-    thread_local: pat::Location,
-    ret: pat::Location,
-    ty_var: pat::TyVar,
+    /// The function pattern, with where the thread local is used, where the reference is returned,
+    /// and the type it refers to.
+    fn_pats: [(&'pcx pat::Fn<'pcx>, pat::Location, pat::Location, pat::TyVar); 3],
 }
 
+/// `thread_local!` with a `LocalKey::with` whose result is returned, and a `#[thread_local]`
+/// static whose `UnsafeCell` is dereferenced into the returned reference.
 #[rpl_macros::pattern_def]
 fn pattern_thread_local_static(pcx: PatCtxt<'_>) -> PatternThreadLocalStatic<'_> {
     let ty_var;
     let thread_local;
     let ret;
+    let tls_ty_var;
+    let tls;
+    let tls_ret;
+    let tls_inlined_ty_var;
+    let tls_inlined;
+    let tls_inlined_ret;
     #[allow(non_snake_case)]
     let pattern = rpl! {
         #[meta(#[export(ty_var)] $T:ty = is_sync)]
         // FIXME: the return type is not actually checked to be matched
-        fn $pattern(..) -> &'static $T = mir! {
+        fn $with_local_key(..) -> &'static $T = mir! {
             #[export(thread_local)]
             let $local_key: &std::thread::LocalKey::<std::cell::UnsafeCell<$T>> = _;
             #[export(ret)]
             let $RET: &T =
                 std::thread::LocalKey::<std::cell::UnsafeCell<$T>>::with::<_, _>(move $local_key, _);
         }
+
+        #[meta(#[export(tls_ty_var)] $T:ty = is_sync)]
+        fn $thread_local(..) -> &'static $T = mir! {
+            #[export(tls)]
+            let $tls: &std::cell::UnsafeCell<$T> = ThreadLocalRef(_);
+            let $ptr: *mut $T = core::cell::UnsafeCell::<$T>::get(copy $tls);
+            #[export(tls_ret)]
+            let $RET: &$T = &(*$ptr);
+        }
+
+        #[meta(#[export(tls_inlined_ty_var)] $T:ty = is_sync)]
+        fn $thread_local_inlined(..) -> &'static $T = mir! {
+            #[export(tls_inlined)]
+            let $tls: &std::cell::UnsafeCell<$T> = ThreadLocalRef(_);
+            let $cell_ptr: *const std::cell::UnsafeCell<$T> = &raw const (*$tls);
+            let $ptr: *mut $T = copy $cell_ptr as *mut $T (PtrToPtr);
+            #[export(tls_inlined_ret)]
+            let $RET: &$T = &(*$ptr);
+        }
     };
-    let fn_pat = pattern.fns.get_fn_pat(Symbol::intern("pattern")).unwrap();
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
 
     PatternThreadLocalStatic {
         pattern,
-        fn_pat,
-        thread_local,
-        ret,
-        ty_var,
+        fn_pats: [
+            (fn_pat("with_local_key"), thread_local, ret, ty_var),
+            (fn_pat("thread_local"), tls, tls_ret, tls_ty_var),
+            (
+                fn_pat("thread_local_inlined"),
+                tls_inlined,
+                tls_inlined_ret,
+                tls_inlined_ty_var,
+            ),
+        ],
     }
 }
 
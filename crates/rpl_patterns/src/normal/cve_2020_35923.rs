@@ -0,0 +1,268 @@
+use rpl_context::PatCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::LangItem;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir::{self, PlaceRef};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{Span, Symbol};
+
+use crate::dataflow::{Taint, diverges, is_inlined, reachable_from};
+use crate::lints::IN_PLACE_MUTATION_BEFORE_PANIC;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, ..) | hir::ItemKind::Impl(_) => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if self.tcx.is_mir_available(def_id)
+            && self
+                .tcx
+                .opt_associated_item(def_id.to_def_id())
+                .is_some_and(|item| item.fn_has_self_parameter)
+        {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            self.check_body(def_id, body);
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+impl<'tcx> CheckFnCtxt<'_, 'tcx> {
+    /// Report the fields of `*self` that are written in place, and then checked by a `SwitchInt`
+    /// or an `Assert` that may panic, so the broken invariant can be observed after the panic is
+    /// caught.
+    fn check_body(&self, def_id: LocalDefId, body: &mir::Body<'tcx>) {
+        let pattern = pattern_in_place_mutation(self.pcx);
+        let mut reported = FxHashSet::default();
+        let mut report = |write: mir::Location, check: Span| {
+            if !reported.insert(check) {
+                return;
+            }
+            let write = StatementMatch::Location(write).span_no_inline(body);
+            self.tcx.emit_node_span_lint(
+                IN_PLACE_MUTATION_BEFORE_PANIC,
+                self.tcx.local_def_id_to_hir_id(def_id),
+                write,
+                crate::errors::InPlaceMutationBeforePanic { write, check },
+            );
+        };
+        for &(fn_pat, place, write) in &pattern.writes {
+            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                let (StatementMatch::Location(place), StatementMatch::Location(write)) =
+                    (matches[place], matches[write])
+                else {
+                    continue;
+                };
+                if self.is_in_place_write(body, write)
+                    && let Some(field) = written_field(body, place)
+                    && let Some(check) = panicking_check(body, write, field)
+                {
+                    report(write, check);
+                }
+            }
+        }
+        for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.indexed.0).check() {
+            if let StatementMatch::Location(write) = matches[pattern.indexed.1] {
+                report(write, matches[pattern.indexed.2].span_no_inline(body));
+            }
+        }
+    }
+
+    /// Whether the write at `location` is in the body itself, and, for a call, is a call of an
+    /// operator such as `AddAssign::add_assign`, instead of any method taking `&mut self.field`,
+    /// such as `Vec::push`.
+    fn is_in_place_write(&self, body: &mir::Body<'tcx>, location: mir::Location) -> bool {
+        let data = &body[location.block];
+        let source_info = match data.statements.get(location.statement_index) {
+            Some(statement) => statement.source_info,
+            None => {
+                let terminator = data.terminator();
+                let mir::TerminatorKind::Call { ref func, .. } = terminator.kind else {
+                    return false;
+                };
+                let is_op_assign = func
+                    .const_fn_def()
+                    .and_then(|(fn_def_id, _)| self.tcx.trait_of_item(fn_def_id))
+                    .is_some_and(|trait_def_id| {
+                        OP_ASSIGN_TRAITS
+                            .iter()
+                            .any(|&lang_item| self.tcx.is_lang_item(trait_def_id, lang_item))
+                    });
+                if !is_op_assign {
+                    return false;
+                }
+                terminator.source_info
+            },
+        };
+        !is_inlined(body, source_info)
+    }
+}
+
+const OP_ASSIGN_TRAITS: [LangItem; 10] = [
+    LangItem::AddAssign,
+    LangItem::SubAssign,
+    LangItem::MulAssign,
+    LangItem::DivAssign,
+    LangItem::RemAssign,
+    LangItem::BitXorAssign,
+    LangItem::BitAndAssign,
+    LangItem::BitOrAssign,
+    LangItem::ShlAssign,
+    LangItem::ShrAssign,
+];
+
+/// The field of `*self` assigned to or mutably borrowed by the statement at `location`, as the
+/// place `(*self).field`.
+fn written_field<'tcx>(body: &mir::Body<'tcx>, location: mir::Location) -> Option<PlaceRef<'tcx>> {
+    let (place, rvalue) = body[location.block]
+        .statements
+        .get(location.statement_index)?
+        .kind
+        .as_assign()?;
+    let written = match *rvalue {
+        mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, borrowed) => borrowed,
+        _ => *place,
+    };
+    match written.as_ref() {
+        PlaceRef {
+            local,
+            projection: projection @ [mir::ProjectionElem::Deref, mir::ProjectionElem::Field(..), ..],
+        } => Some(PlaceRef {
+            local,
+            projection: &projection[..2],
+        }),
+        _ => None,
+    }
+}
+
+/// The span of the first `SwitchInt` or `Assert` after `location` that depends on `field`, and may
+/// panic.
+fn panicking_check<'tcx>(body: &mir::Body<'tcx>, location: mir::Location, field: PlaceRef<'tcx>) -> Option<Span> {
+    // The values computed from the field, or from the places inside it. Places containing it, such
+    // as `*self`, are left out, as they are mostly read for their other fields.
+    let tainted = Taint::new(body)
+        .source(move |place| place.local == field.local && place.projection.starts_with(field.projection))
+        .after(location)
+        .run();
+    // The checks inlined from other functions, such as bounds checks in `<[T]>::index`, are not
+    // checks of the invariant.
+    std::iter::once(location.block)
+        .chain(reachable_from(body, location.block))
+        .find_map(|block| {
+            let terminator = body[block].terminator();
+            if is_inlined(body, terminator.source_info) {
+                return None;
+            }
+            let panics = match terminator.kind {
+                mir::TerminatorKind::Assert { ref cond, .. } => tainted.operand(cond),
+                mir::TerminatorKind::SwitchInt { ref discr, ref targets } => {
+                    tainted.operand(discr) && targets.all_targets().iter().any(|&target| diverges(body, target))
+                },
+                _ => false,
+            };
+            panics.then_some(terminator.source_info.span)
+        })
+}
+
+struct PatternInPlaceMutation<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns writing a field of `*self`, with where the field is assigned to or
+    /// borrowed, and where it is written.
+    writes: [(&'pcx pat::Fn<'pcx>, pat::Location, pat::Location); 2],
+    /// The function pattern indexing with a field of `*self` right after writing it, with where
+    /// the field is written and where its bounds are checked.
+    indexed: (&'pcx pat::Fn<'pcx>, pat::Location, pat::Location),
+}
+
+/// A field of `*self`, either assigned to or updated by an operator such as `+=`, and a field
+/// used as an index right after it is assigned, whose bounds check unwinds through the drops of
+/// the values in scope.
+#[rpl_macros::pattern_def]
+fn pattern_in_place_mutation(pcx: PatCtxt<'_>) -> PatternInPlaceMutation<'_> {
+    let assign;
+    let op_borrow;
+    let op_assign;
+    let index_assign;
+    let bounds_check;
+    let pattern = rpl! {
+        #[meta($F:ty)]
+        struct $S {
+            $field: $F,
+        }
+
+        #[meta($L:ty, $R:ty)]
+        fn $op(&mut $L, $R);
+
+        fn $assign(..) -> _ = mir! {
+            let $self: &mut $S;
+            #[export(assign)]
+            (*$self).$field = _;
+        }
+
+        #[meta($F:ty)]
+        fn $op_assign(..) -> _ = mir! {
+            let $self: &mut $S;
+            #[export(op_borrow)]
+            let $field_ref: &mut $F = &mut (*$self).$field;
+            #[export(op_assign)]
+            _ = $op(move $field_ref, _);
+        }
+
+        fn $indexed_after_assign(..) -> _ = mir! {
+            let $self: &mut $S;
+            let $index: usize;
+            let $in_bounds: bool;
+            #[export(index_assign)]
+            (*$self).$field = _;
+            $index = copy (*$self).$field;
+            $in_bounds = Lt(copy $index, _);
+            #[export(bounds_check)]
+            assert(move $in_bounds, BoundsCheck) unwind {};
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternInPlaceMutation {
+        pattern,
+        writes: [
+            (fn_pat("assign"), assign, assign),
+            (fn_pat("op_assign"), op_borrow, op_assign),
+        ],
+        indexed: (fn_pat("indexed_after_assign"), index_assign, bounds_check),
+    }
+}
@@ -0,0 +1,231 @@
+use rpl_context::PatCtxt;
+use rpl_match::MatchImplCtxt;
+use rpl_mir::pat;
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap, FxIndexSet};
+use rustc_hir as hir;
+use rustc_infer::infer::{InferCtxt, TyCtxtInferExt};
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeFoldable, TypeFolder, TypeSuperFoldable, Upcast};
+use rustc_span::{Span, Symbol, sym};
+
+use crate::lints::UNSOUND_SEND_SYNC_IMPL;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let hir::ItemKind::Impl(impl_) = item.kind else {
+        return;
+    };
+    if impl_.safety != hir::Safety::Unsafe {
+        return;
+    }
+    let def_id = item.owner_id.def_id;
+    let pattern = pattern_send_sync_impl(pcx);
+    let auto_trait = if MatchImplCtxt::new(tcx, pcx, pattern.pattern, pattern.send_impl).match_impl(def_id) {
+        AutoTrait::Send
+    } else if MatchImplCtxt::new(tcx, pcx, pattern.pattern, pattern.sync_impl).match_impl(def_id) {
+        AutoTrait::Sync
+    } else {
+        return;
+    };
+    let self_ty = tcx.type_of(def_id).instantiate_identity();
+    let ty::Adt(adt_def, args) = *self_ty.kind() else {
+        return;
+    };
+
+    let typing_env = ty::TypingEnv::non_body_analysis(tcx, def_id);
+    let solver = BoundsSolver {
+        tcx,
+        infcx: tcx.infer_ctxt().build(typing_env.typing_mode),
+        param_env: typing_env.param_env,
+    };
+    let mut raw_pointers_as_owned = RawPointersAsOwned {
+        tcx,
+        owned: adt_def
+            .all_fields()
+            .flat_map(|field| field.ty(tcx, args).walk())
+            .filter_map(|arg| match *arg.as_type()?.kind() {
+                ty::Adt(adt_def, args) if adt_def.is_phantom_data() => Some(args.type_at(0)),
+                _ => None,
+            })
+            .collect(),
+    };
+    let mut required = FxIndexMap::<ty::ParamTy, RequiredBounds>::default();
+    for field in adt_def.all_fields() {
+        let field_ty = field.ty(tcx, args).fold_with(&mut raw_pointers_as_owned);
+        for (param, bound) in solver.missing_bounds(field_ty, auto_trait) {
+            let required = required.entry(param).or_insert_with(|| RequiredBounds {
+                bounds: Vec::new(),
+                field_span: tcx.def_span(field.did),
+            });
+            if !required.bounds.contains(&bound) {
+                required.bounds.push(bound);
+            }
+        }
+    }
+
+    for (param, RequiredBounds { bounds, field_span }) in required {
+        let param = Ty::new_param(tcx, param.index, param.name);
+        let missing = bounds.iter().map(|bound| bound.name()).collect::<Vec<_>>();
+        tcx.emit_node_span_lint(
+            UNSOUND_SEND_SYNC_IMPL,
+            tcx.local_def_id_to_hir_id(def_id),
+            impl_.self_ty.span,
+            crate::errors::UnsoundSendSyncImpl {
+                span: impl_.self_ty.span,
+                field: field_span,
+                trait_name: auto_trait.name(),
+                self_ty,
+                param,
+                bounds: missing.join(" + "),
+            },
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum AutoTrait {
+    Send,
+    Sync,
+}
+
+impl AutoTrait {
+    fn name(self) -> &'static str {
+        match self {
+            AutoTrait::Send => "Send",
+            AutoTrait::Sync => "Sync",
+        }
+    }
+
+    fn diagnostic_item(self) -> Symbol {
+        match self {
+            AutoTrait::Send => sym::Send,
+            AutoTrait::Sync => sym::Sync,
+        }
+    }
+}
+
+/// The bounds a type parameter requires for the implementation to be sound, and the first field
+/// the type parameter appears in.
+struct RequiredBounds {
+    bounds: Vec<AutoTrait>,
+    field_span: Span,
+}
+
+/// Asks the trait solver which `Send` and `Sync` bounds on the type parameters a field type needs
+/// to be `Send` or `Sync` itself, according to the implementations of the types it is made of.
+struct BoundsSolver<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    infcx: InferCtxt<'tcx>,
+    /// The `param_env` of the `unsafe impl`.
+    param_env: ty::ParamEnv<'tcx>,
+}
+
+impl<'tcx> BoundsSolver<'tcx> {
+    /// The bounds on the type parameters of `field_ty` that are required for it to be
+    /// `auto_trait`, but not implied by the bounds of the `unsafe impl`.
+    ///
+    /// A field of `Sync` implementation that can never be `Sync`, such as an `UnsafeCell`, is
+    /// taken as synchronized by the implementation, the way `Mutex` is, and is then only required
+    /// to be `Send`, as the value can be sent through a shared reference to another thread.
+    fn missing_bounds(&self, field_ty: Ty<'tcx>, auto_trait: AutoTrait) -> Vec<(ty::ParamTy, AutoTrait)> {
+        let params = field_ty
+            .walk()
+            .filter_map(|arg| match arg.as_type()?.kind() {
+                &ty::Param(param) => Some(param),
+                _ => None,
+            })
+            .collect::<FxIndexSet<_>>();
+        let candidates = params
+            .iter()
+            .flat_map(|&param| [(param, AutoTrait::Send), (param, AutoTrait::Sync)])
+            .collect::<Vec<_>>();
+        let Some(auto_trait) = [auto_trait, AutoTrait::Send]
+            .into_iter()
+            .find(|&auto_trait| self.meets_bound(field_ty, auto_trait, &candidates, None))
+        else {
+            // Neither `Send` nor `Sync` with any bounds, so it is up to the implementation.
+            return Vec::new();
+        };
+        candidates
+            .iter()
+            .copied()
+            .filter(|&candidate| !self.meets_bound(field_ty, auto_trait, &candidates, Some(candidate)))
+            .collect()
+    }
+
+    /// Whether `ty` is `auto_trait` with the bounds of the `unsafe impl` and the `candidates`,
+    /// except for `excluded`.
+    fn meets_bound(
+        &self,
+        ty: Ty<'tcx>,
+        auto_trait: AutoTrait,
+        candidates: &[(ty::ParamTy, AutoTrait)],
+        excluded: Option<(ty::ParamTy, AutoTrait)>,
+    ) -> bool {
+        let tcx = self.tcx;
+        let Some(trait_def_id) = tcx.get_diagnostic_item(auto_trait.diagnostic_item()) else {
+            return true;
+        };
+        let clauses = candidates
+            .iter()
+            .filter(|&&candidate| Some(candidate) != excluded)
+            .filter_map(|&(param, auto_trait)| {
+                let trait_def_id = tcx.get_diagnostic_item(auto_trait.diagnostic_item())?;
+                Some(ty::TraitRef::new(tcx, trait_def_id, [param.to_ty(tcx)]).upcast(tcx))
+            });
+        let param_env =
+            ty::ParamEnv::new(tcx.mk_clauses_from_iter(self.param_env.caller_bounds().iter().chain(clauses)));
+        rustc_trait_selection::traits::type_known_to_meet_bound_modulo_regions(&self.infcx, param_env, ty, trait_def_id)
+    }
+}
+
+/// Replaces raw pointers, and `NonNull`, with `Box`es of their pointees, when the type declares
+/// to own the pointees with a `PhantomData`, like `Vec` does. Other raw pointers are left as they
+/// are, and so are neither `Send` nor `Sync`, leaving what they point to up to the implementation.
+struct RawPointersAsOwned<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The types in the `PhantomData`s of the fields.
+    owned: FxHashSet<Ty<'tcx>>,
+}
+
+impl<'tcx> TypeFolder<TyCtxt<'tcx>> for RawPointersAsOwned<'tcx> {
+    fn cx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        match *ty.kind() {
+            ty::RawPtr(pointee, _) if self.owned.contains(&pointee) => Ty::new_box(self.tcx, pointee.fold_with(self)),
+            ty::Adt(adt_def, args)
+                if self.tcx.is_diagnostic_item(sym::NonNull, adt_def.did())
+                    && self.owned.contains(&args.type_at(0)) =>
+            {
+                Ty::new_box(self.tcx, args.type_at(0).fold_with(self))
+            },
+            _ => ty.super_fold_with(self),
+        }
+    }
+}
+
+struct PatternSendSyncImpl<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    send_impl: &'pcx pat::Impl<'pcx>,
+    sync_impl: &'pcx pat::Impl<'pcx>,
+}
+
+#[rpl_macros::pattern_def]
+fn pattern_send_sync_impl(pcx: PatCtxt<'_>) -> PatternSendSyncImpl<'_> {
+    let pattern = rpl! {
+        #[meta($T:ty)]
+        impl std::marker::Send for $T {}
+
+        #[meta($T:ty)]
+        impl std::marker::Sync for $T {}
+    };
+
+    PatternSendSyncImpl {
+        pattern,
+        send_impl: &pattern.impls[0],
+        sync_impl: &pattern.impls[1],
+    }
+}
@@ -6,7 +6,6 @@ use rustc_hir::intravisit::{self, Visitor};
 use rustc_middle::hir::nested_filter::All;
 use rustc_middle::ty::TyCtxt;
 use rustc_span::{Span, Symbol};
-use std::ops::Not;
 
 use crate::lints::SLICE_FROM_RAW_PARTS_UNINITIALIZED;
 
@@ -49,8 +48,8 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if kind.header().is_none_or(|header| header.is_unsafe().not()) && self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             let pattern = pattern_from_raw_parts_iter(self.pcx);
             for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
                 let fn_name = pattern.fn_name;
@@ -93,7 +92,7 @@ fn pattern_from_raw_parts_iter(pcx: PatCtxt<'_>) -> PatternFromRawParts<'_> {
             $T: ty,
             $src: place(alloc::vec::Vec<$T>),
         )]
-        fn $pattern (..) -> _ = mir! {
+        safe fn $pattern (..) -> _ = mir! {
             // let $src: alloc::vec::Vec<$T> = _; // _1
             let $src_ref_1: &alloc::vec::Vec<$T> = &$src; // _3
             #[export(len)]
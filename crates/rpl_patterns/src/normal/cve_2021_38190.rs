@@ -0,0 +1,393 @@
+use rpl_context::PatCtxt;
+use rpl_match::{MatchAdtCtxt, MatchImplCtxt};
+use rpl_mir::{StatementMatch, pat};
+use rustc_abi::FieldIdx;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_index::IndexSlice;
+use rustc_middle::mir;
+use rustc_middle::mir::visit::Visitor as MirVisitor;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{Span, Symbol, sym};
+
+use crate::dataflow::{Taint, Tainted, dominating_comparisons};
+use crate::lints::UNVALIDATED_DESERIALIZATION;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    if !matches!(item.kind, hir::ItemKind::Struct(..)) {
+        return;
+    }
+    let adt_def = tcx.adt_def(item.owner_id);
+    let pattern = pattern_deserialize(pcx);
+    let Some(adt_match) = MatchAdtCtxt::new(tcx, pcx, pattern.pattern, pattern.storage).match_adt(adt_def) else {
+        return;
+    };
+    let data = &adt_match.expect_struct().candidates.candidates[&Symbol::intern("data")];
+    let fields = adt_def
+        .non_enum_variant()
+        .fields
+        .indices()
+        .filter(|&field| !data.contains(field))
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return;
+    }
+
+    let crate_items = tcx.hir_crate_items(());
+    let deserialize_impls = crate_items
+        .definitions()
+        .filter(|&impl_did| {
+            matches!(tcx.def_kind(impl_did), DefKind::Impl { of_trait: true })
+                && tcx.type_of(impl_did).instantiate_identity().ty_adt_def() == Some(adt_def)
+                && MatchImplCtxt::new(tcx, pcx, pattern.pattern, pattern.deserialize_impl).match_impl(impl_did)
+        })
+        .collect::<Vec<_>>();
+    if deserialize_impls.is_empty() {
+        return;
+    }
+
+    // The fields are read directly by the methods of the ADT, and through the functions returning
+    // them anywhere in the crate, such as `shape()` in the methods of a matrix wrapping the ADT.
+    let bodies = tcx
+        .hir()
+        .body_owners()
+        .filter(|&def_id| {
+            matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) && tcx.is_mir_available(def_id)
+        })
+        .map(|def_id| {
+            let is_method = tcx.def_kind(def_id) == DefKind::AssocFn
+                && tcx.associated_item(def_id).fn_has_self_parameter
+                && tcx
+                    .impl_of_method(def_id.to_def_id())
+                    .is_some_and(|impl_did| tcx.type_of(impl_did).instantiate_identity().ty_adt_def() == Some(adt_def));
+            (def_id, rpl_mir_transform::mir_for_matching(tcx, def_id), is_method)
+        })
+        .collect::<Vec<_>>();
+    let summaries = Summaries::new(tcx, &bodies, &fields);
+
+    // The fields used as lengths or indices of unchecked accesses.
+    let mut unchecked = FxIndexMap::default();
+    for &(_, body, is_method) in &bodies {
+        let accesses = summaries.unchecked_accesses(tcx, body);
+        for &field in &fields {
+            let tainted = summaries.tainted_by_field(body, field, is_method);
+            if let Some(&(span, _)) = accesses.iter().find(|&&(_, len)| tainted.contains(len)) {
+                unchecked.entry(field).or_insert(span);
+            }
+        }
+    }
+    if unchecked.is_empty() {
+        return;
+    }
+
+    let mut reported = FxHashSet::default();
+    for impl_did in deserialize_impls {
+        for &(def_id, body, _) in &bodies {
+            if !tcx.is_descendant_of(def_id.to_def_id(), impl_did.to_def_id()) {
+                continue;
+            }
+            for (location, operands) in constructions(body, adt_def.did()) {
+                let Some((&field, &unchecked)) = unchecked.iter().find(|&(&field, _)| {
+                    !operands[field]
+                        .place()
+                        .is_some_and(|place| is_validated(body, origin(body, place.local), location))
+                }) else {
+                    continue;
+                };
+                let construct = StatementMatch::Location(location).span_no_inline(body);
+                if !reported.insert(construct) {
+                    continue;
+                }
+                let field_def = &adt_def.non_enum_variant().fields[field];
+                tcx.emit_node_span_lint(
+                    UNVALIDATED_DESERIALIZATION,
+                    tcx.local_def_id_to_hir_id(def_id),
+                    construct,
+                    crate::errors::UnvalidatedDeserialization {
+                        construct,
+                        field: tcx.def_span(field_def.did),
+                        unchecked,
+                        field_name: field_def.name,
+                        ty: tcx.type_of(adt_def.did()).instantiate_identity(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// What the functions of the crate do with the fields of the ADT, followed across the calls.
+struct Summaries {
+    /// The fields each function returns a value computed from, such as `nrows` and `ncols` for
+    /// `shape()`.
+    returns: FxHashMap<DefId, FxHashSet<FieldIdx>>,
+    /// The arguments each function uses as a length or an index of an unchecked access, with the
+    /// span of the access, such as the index of `get_unchecked_linear(&self, i)`.
+    sinks: FxHashMap<DefId, FxIndexMap<usize, Span>>,
+}
+
+impl Summaries {
+    /// Summarize `bodies` until no more fields are returned and no more arguments reach the
+    /// unchecked accesses.
+    fn new<'tcx>(tcx: TyCtxt<'tcx>, bodies: &[(LocalDefId, &mir::Body<'tcx>, bool)], fields: &[FieldIdx]) -> Self {
+        let mut summaries = Summaries {
+            returns: FxHashMap::default(),
+            sinks: FxHashMap::default(),
+        };
+        loop {
+            let mut changed = false;
+            for &(def_id, body, is_method) in bodies {
+                // The implementations of trait methods are summarized for the calls to the trait
+                // methods, such as `Storage::shape` called on a generic storage.
+                let def_ids = std::iter::once(def_id.to_def_id())
+                    .chain(
+                        tcx.opt_associated_item(def_id.to_def_id())
+                            .and_then(|item| item.trait_item_def_id),
+                    )
+                    .collect::<Vec<_>>();
+                let accesses = summaries.unchecked_accesses(tcx, body);
+                for (index, arg) in body.args_iter().enumerate() {
+                    let tainted = Taint::new(body).seeds([arg]).run();
+                    if let Some(&(span, _)) = accesses.iter().find(|&&(_, len)| tainted.contains(len)) {
+                        for &def_id in &def_ids {
+                            changed |= summaries.sinks.entry(def_id).or_default().insert(index, span).is_none();
+                        }
+                    }
+                }
+                for &field in fields {
+                    if summaries
+                        .tainted_by_field(body, field, is_method)
+                        .contains(mir::RETURN_PLACE)
+                    {
+                        for &def_id in &def_ids {
+                            changed |= summaries.returns.entry(def_id).or_default().insert(field);
+                        }
+                    }
+                }
+            }
+            if !changed {
+                return summaries;
+            }
+        }
+    }
+
+    /// The unchecked accesses through slices and raw pointers, that is `<[T]>::get_unchecked`,
+    /// `<[T]>::get_unchecked_mut`, `slice::from_raw_parts`, `slice::from_raw_parts_mut`, and the
+    /// offsets of raw pointers, either inlined or not, and the calls to the functions passing on
+    /// their arguments to them, with the locals of the index or the length.
+    fn unchecked_accesses(&self, tcx: TyCtxt<'_>, body: &mir::Body<'_>) -> Vec<(Span, mir::Local)> {
+        let is_unchecked_access = |def_id| {
+            tcx.is_diagnostic_item(sym::slice_from_raw_parts, def_id)
+                || tcx.is_diagnostic_item(sym::slice_from_raw_parts_mut, def_id)
+                || tcx.impl_of_method(def_id).is_some_and(|impl_did| {
+                    let self_ty = tcx.type_of(impl_did).instantiate_identity();
+                    match tcx.item_name(def_id).as_str() {
+                        "get_unchecked" | "get_unchecked_mut" => self_ty.is_slice(),
+                        "add" | "offset" | "wrapping_add" | "wrapping_offset" => self_ty.is_raw_ptr(),
+                        _ => false,
+                    }
+                })
+        };
+        // The innermost scope containing `scope` that is inlined from an unchecked access.
+        let inlined_access = |mut scope: mir::SourceScope| loop {
+            let data = &body.source_scopes[scope];
+            if let Some((instance, _)) = data.inlined
+                && is_unchecked_access(instance.def_id())
+            {
+                return Some(scope);
+            }
+            scope = data.parent_scope?;
+        };
+        let mut accesses = Vec::new();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            // The debug info of the arguments of an inlined function is not kept without `-g`, so
+            // the index or the length is any integer read in the inlined body, including by the
+            // intrinsics called, such as `arith_offset`.
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                if let Some(scope) = inlined_access(statement.source_info.scope) {
+                    let mut reads = Reads(Vec::new());
+                    reads.visit_statement(statement, mir::Location { block, statement_index });
+                    accesses.extend(reads.integers(body).map(|local| (callsite_span(body, scope), local)));
+                }
+            }
+            if let Some(scope) = inlined_access(data.terminator().source_info.scope) {
+                let mut reads = Reads(Vec::new());
+                reads.visit_terminator(data.terminator(), body.terminator_loc(block));
+                accesses.extend(reads.integers(body).map(|local| (callsite_span(body, scope), local)));
+            }
+            let mir::TerminatorKind::Call { ref func, ref args, .. } = data.terminator().kind else {
+                continue;
+            };
+            let Some((fn_def_id, _)) = func.const_fn_def() else {
+                continue;
+            };
+            let arg_local = |index: usize| Some(args.get(index)?.node.place()?.local);
+            if is_unchecked_access(fn_def_id)
+                && let Some(len) = arg_local(1)
+            {
+                let span = StatementMatch::Location(body.terminator_loc(block)).span_no_inline(body);
+                accesses.push((span, len));
+            }
+            if let Some(sinks) = self.sinks.get(&fn_def_id) {
+                accesses.extend(
+                    sinks
+                        .iter()
+                        .filter_map(|(&index, &span)| Some((span, arg_local(index)?))),
+                );
+            }
+        }
+        accesses
+    }
+
+    /// The locals whose values are computed from the field `field`, regardless of the control
+    /// flow. The field is read from `self` by the methods of the ADT, or returned by the functions
+    /// called.
+    fn tainted_by_field<'a, 'tcx>(
+        &self,
+        body: &'a mir::Body<'tcx>,
+        field: FieldIdx,
+        is_method: bool,
+    ) -> Tainted<'a, 'tcx> {
+        let returned = body
+            .basic_blocks
+            .iter()
+            .filter_map(|data| match data.terminator().kind {
+                mir::TerminatorKind::Call {
+                    ref func, destination, ..
+                } => {
+                    let (fn_def_id, _) = func.const_fn_def()?;
+                    self.returns
+                        .get(&fn_def_id)
+                        .is_some_and(|fields| fields.contains(&field))
+                        .then_some(destination.local)
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        Taint::new(body)
+            .seeds(returned)
+            .source(move |place| {
+                is_method
+                    && place.local == mir::Local::from_u32(1)
+                    && place
+                        .projection
+                        .iter()
+                        .find(|elem| !matches!(elem, mir::ProjectionElem::Deref))
+                        .is_some_and(|elem| matches!(*elem, mir::ProjectionElem::Field(read, _) if read == field))
+            })
+            .run()
+    }
+}
+
+/// The span of the call in the body, from which the function of `scope` is inlined.
+fn callsite_span(body: &mir::Body<'_>, mut scope: mir::SourceScope) -> Span {
+    while let Some(parent_scope) = body.source_scopes[scope].inlined_parent_scope {
+        scope = parent_scope;
+    }
+    body.source_scopes[scope]
+        .inlined
+        .map_or(body.span, |(_instance, span)| span)
+}
+
+/// The locations where a value of the ADT `adt_did` is constructed, with the operands of its
+/// fields.
+fn constructions<'a, 'tcx>(
+    body: &'a mir::Body<'tcx>,
+    adt_did: DefId,
+) -> Vec<(mir::Location, &'a IndexSlice<FieldIdx, mir::Operand<'tcx>>)> {
+    let mut constructions = Vec::new();
+    for (block, data) in body.basic_blocks.iter_enumerated() {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            if let mir::StatementKind::Assign(assign) = &statement.kind
+                && let mir::Rvalue::Aggregate(kind, operands) = &assign.1
+                && let mir::AggregateKind::Adt(did, ..) = **kind
+                && did == adt_did
+            {
+                constructions.push((mir::Location { block, statement_index }, operands.as_slice()));
+            }
+        }
+    }
+    constructions
+}
+
+/// The local whose value is copied or moved into `local`, such as `_3` for `_2 = move (_3.1)`.
+fn origin(body: &mir::Body<'_>, mut local: mir::Local) -> mir::Local {
+    let mut visited = FxHashSet::default();
+    while visited.insert(local)
+        && let Some(from) = body.basic_blocks.iter().find_map(|data| {
+            data.statements.iter().find_map(|statement| {
+                if let mir::StatementKind::Assign(assign) = &statement.kind
+                    && let (place, mir::Rvalue::Use(operand)) = &**assign
+                    && place.as_local() == Some(local)
+                {
+                    operand.place().map(|place| place.local)
+                } else {
+                    None
+                }
+            })
+        })
+    {
+        local = from;
+    }
+    local
+}
+
+/// Whether `location` is only reachable after a comparison of a value computed from `local`, such
+/// as in the `else` branch of `if nrows * ncols != data.len()`.
+fn is_validated(body: &mir::Body<'_>, local: mir::Local, location: mir::Location) -> bool {
+    let tainted = Taint::new(body).seeds([local]).run();
+    dominating_comparisons(body, location)
+        .iter()
+        .any(|comparison| tainted.operand(comparison.lhs) || tainted.operand(comparison.rhs))
+}
+
+/// Collects the locals read.
+struct Reads(Vec<mir::Local>);
+
+impl Reads {
+    fn integers<'a>(self, body: &'a mir::Body<'_>) -> impl Iterator<Item = mir::Local> + 'a {
+        self.0
+            .into_iter()
+            .filter(|&local| body.local_decls[local].ty.is_integral())
+    }
+}
+
+impl MirVisitor<'_> for Reads {
+    fn visit_local(&mut self, local: mir::Local, context: mir::visit::PlaceContext, _location: mir::Location) {
+        if context.is_use() && !context.is_mutating_use() {
+            self.0.push(local);
+        }
+    }
+}
+
+struct PatternDeserialize<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    storage: &'pcx pat::Adt<'pcx>,
+    deserialize_impl: &'pcx pat::Impl<'pcx>,
+}
+
+/// A struct storing its items in a `Vec`, whose other fields, such as the dimensions of a matrix
+/// or the length in a header, are trusted by unsafe code, and an implementation of `Deserialize`
+/// for it.
+#[rpl_macros::pattern_def]
+fn pattern_deserialize(pcx: PatCtxt<'_>) -> PatternDeserialize<'_> {
+    let pattern = rpl! {
+        #[meta($T:ty)]
+        struct $Storage {
+            $data: alloc::vec::Vec<$T>,
+        }
+
+        #[meta($T:ty)]
+        impl serde::de::Deserialize<'_> for $T {}
+    };
+
+    PatternDeserialize {
+        pattern,
+        storage: pattern.get_adt(Symbol::intern("Storage")).unwrap(),
+        deserialize_impl: &pattern.impls[0],
+    }
+}
@@ -0,0 +1,254 @@
+use rpl_context::PatCtxt;
+use rpl_mir::StatementMatch;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::LangItem;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::source_map::Spanned;
+use rustc_span::{Span, Symbol, sym};
+
+use crate::dataflow::{Taint, Tainted, inlined_from, precedes};
+use crate::lints::UNINITIALIZED_BUFFER_PASSED_TO_READ;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, _pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, ..) | hir::ItemKind::Impl(_) | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            self.check_body(def_id, body);
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+/// A call of a method of `std::io::Read` on a reader whose type is a type parameter.
+struct GenericRead<'a, 'tcx> {
+    location: mir::Location,
+    args: &'a [Spanned<mir::Operand<'tcx>>],
+    method: Symbol,
+    reader: Symbol,
+}
+
+/// What an operation on the memory of a `Vec` created by `Vec::with_capacity` does to it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Access {
+    /// Neither reads nor initializes the memory, such as `Vec::as_mut_ptr` or
+    /// `DerefMut::deref_mut`.
+    Borrow,
+    /// Makes the uninitialized memory accessible as initialized, such as `Vec::set_len` or
+    /// `slice::from_raw_parts_mut`.
+    Expose,
+    /// May initialize the memory, such as `Vec::resize` or a write through a raw pointer.
+    Initialize,
+}
+
+impl<'tcx> CheckFnCtxt<'tcx> {
+    /// Report the buffers created by `Vec::with_capacity`, exposed by `Vec::set_len` or
+    /// `slice::from_raw_parts_mut`, and passed to a method of a generic `std::io::Read` without
+    /// being initialized first.
+    fn check_body(&self, def_id: LocalDefId, body: &mir::Body<'tcx>) {
+        let reads = self.generic_reads(body);
+        if reads.is_empty() {
+            return;
+        }
+        let mut reported = FxHashSet::default();
+        for (vec, created) in self.uninitialized_vecs(body) {
+            let tainted = self.holding_memory_of(body, vec);
+            let accesses = self.accesses(body, &tainted);
+            debug!(?vec, ?accesses);
+            for read in &reads {
+                if !read.args[1..].iter().any(|arg| tainted.operand(&arg.node)) {
+                    continue;
+                }
+                let before_read = |&&(location, access): &&(mir::Location, Access)| {
+                    access != Access::Borrow && precedes(body, location, read.location)
+                };
+                let mut accesses = accesses.iter().filter(before_read);
+                let Some(&(exposed, _)) = accesses.clone().find(|(_, access)| *access == Access::Expose) else {
+                    continue;
+                };
+                if accesses.any(|(_, access)| *access == Access::Initialize) || !reported.insert(read.location) {
+                    continue;
+                }
+                let span = StatementMatch::Location(read.location).span_no_inline(body);
+                self.tcx.emit_node_span_lint(
+                    UNINITIALIZED_BUFFER_PASSED_TO_READ,
+                    self.tcx.local_def_id_to_hir_id(def_id),
+                    span,
+                    crate::errors::UninitializedBufferPassedToRead {
+                        read: span,
+                        vec: StatementMatch::Location(created).span_no_inline(body),
+                        exposed: StatementMatch::Location(exposed).span_no_inline(body),
+                        method: read.method,
+                        reader: read.reader,
+                    },
+                );
+            }
+        }
+    }
+
+    fn generic_reads<'a>(&self, body: &'a mir::Body<'tcx>) -> Vec<GenericRead<'a, 'tcx>> {
+        body.basic_blocks
+            .iter_enumerated()
+            .filter_map(|(block, data)| {
+                let mir::TerminatorKind::Call { ref func, ref args, .. } = data.terminator().kind else {
+                    return None;
+                };
+                let (fn_def_id, fn_args) = func.const_fn_def()?;
+                let read_trait = self.tcx.get_diagnostic_item(sym::IoRead)?;
+                if self.tcx.trait_of_item(fn_def_id) != Some(read_trait) || args.len() < 2 {
+                    return None;
+                }
+                let ty::Param(reader) = *fn_args.type_at(0).peel_refs().kind() else {
+                    return None;
+                };
+                Some(GenericRead {
+                    location: body.terminator_loc(block),
+                    args,
+                    method: self.tcx.item_name(fn_def_id),
+                    reader: reader.name,
+                })
+            })
+            .collect()
+    }
+
+    /// The `Vec` locals created by `Vec::with_capacity`, either called or inlined, with the
+    /// locations where they are created.
+    fn uninitialized_vecs(&self, body: &mir::Body<'tcx>) -> Vec<(mir::Local, mir::Location)> {
+        let is_with_capacity = |def_id| self.tcx.is_diagnostic_item(sym::vec_with_capacity, def_id);
+        let mut vecs = Vec::new();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                if let mir::StatementKind::Assign(assign) = &statement.kind
+                    && let Some(local) = assign.0.as_local()
+                    && let ty::Adt(adt_def, _) = body.local_decls[local].ty.kind()
+                    && self.tcx.is_diagnostic_item(sym::Vec, adt_def.did())
+                    && inlined_from(body, statement.source_info).is_some_and(is_with_capacity)
+                {
+                    vecs.push((local, mir::Location { block, statement_index }));
+                }
+            }
+            if let mir::TerminatorKind::Call {
+                ref func, destination, ..
+            } = data.terminator().kind
+                && let Some((fn_def_id, _)) = func.const_fn_def()
+                && is_with_capacity(fn_def_id)
+                && let Some(local) = destination.as_local()
+            {
+                vecs.push((local, body.terminator_loc(block)));
+            }
+        }
+        vecs
+    }
+
+    /// The operations on the `tainted` locals, which are either calls, or inlined calls, or
+    /// writes through raw pointers.
+    fn accesses(&self, body: &mir::Body<'tcx>, tainted: &Tainted<'_, 'tcx>) -> Vec<(mir::Location, Access)> {
+        let mut accesses = Vec::new();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = mir::Location { block, statement_index };
+                if !tainted.statement(statement, location) {
+                    continue;
+                }
+                if let Some(fn_def_id) = inlined_from(body, statement.source_info) {
+                    accesses.push((location, self.access(fn_def_id)));
+                } else if let mir::StatementKind::Assign(assign) = &statement.kind
+                    && assign.0.is_indirect()
+                    && tainted.contains(assign.0.local)
+                {
+                    accesses.push((location, Access::Initialize));
+                }
+            }
+            let terminator = data.terminator();
+            let location = body.terminator_loc(block);
+            if let mir::TerminatorKind::Call { ref func, ref args, .. } = terminator.kind
+                && args.iter().any(|arg| tainted.operand(&arg.node))
+                && let Some(fn_def_id) = inlined_from(body, terminator.source_info)
+                    .or_else(|| func.const_fn_def().map(|(fn_def_id, _)| fn_def_id))
+            {
+                accesses.push((location, self.access(fn_def_id)));
+            }
+        }
+        accesses
+    }
+
+    /// The pointers, references and other values holding the memory of `vec`, regardless of the
+    /// control flow. Integers, such as the length, are not followed.
+    fn holding_memory_of<'a>(&self, body: &'a mir::Body<'tcx>, vec: mir::Local) -> Tainted<'a, 'tcx> {
+        Taint::new(body)
+            .seeds([vec])
+            .follow(|local| {
+                let ty = body.local_decls[local].ty;
+                ty.is_any_ptr() || ty.is_adt()
+            })
+            .run()
+    }
+
+    fn access(&self, fn_def_id: DefId) -> Access {
+        let tcx = self.tcx;
+        if tcx.is_diagnostic_item(sym::slice_from_raw_parts_mut, fn_def_id) {
+            return Access::Expose;
+        }
+        let impl_def_id = tcx.impl_of_method(fn_def_id);
+        if let Some(trait_def_id) = tcx
+            .trait_of_item(fn_def_id)
+            .or_else(|| impl_def_id.and_then(|impl_def_id| tcx.trait_id_of_impl(impl_def_id)))
+        {
+            let is_borrow = [LangItem::Deref, LangItem::DerefMut, LangItem::Index, LangItem::IndexMut]
+                .into_iter()
+                .any(|lang_item| tcx.is_lang_item(trait_def_id, lang_item))
+                || tcx.is_diagnostic_item(sym::IoRead, trait_def_id);
+            return if is_borrow { Access::Borrow } else { Access::Initialize };
+        }
+        let is_vec_method = impl_def_id.is_some_and(|impl_def_id| {
+            matches!(tcx.type_of(impl_def_id).instantiate_identity().kind(), ty::Adt(adt_def, _)
+                if tcx.is_diagnostic_item(sym::Vec, adt_def.did()))
+        });
+        if !is_vec_method {
+            return Access::Initialize;
+        }
+        match tcx.item_name(fn_def_id).as_str() {
+            "set_len" => Access::Expose,
+            "with_capacity" | "as_mut_ptr" | "as_ptr" | "as_mut_slice" | "as_slice" | "len" | "capacity"
+            | "reserve" | "reserve_exact" => Access::Borrow,
+            _ => Access::Initialize,
+        }
+    }
+}
@@ -3,7 +3,7 @@ use rustc_hir as hir;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_middle::hir::nested_filter::All;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_span::{Span, Symbol};
 
 use rpl_mir::{CheckMirCtxt, pat};
@@ -46,17 +46,33 @@ impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
         _span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.tcx.visibility(def_id).is_public() && self.tcx.is_mir_available(def_id) {
-            let body = self.tcx.optimized_mir(def_id);
-            let pattern = pattern_unsound_cast_between_u64_and_atomic_u64(self.pcx);
-            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
-                let transmute = matches[pattern.transmute].span_no_inline(body);
-                let src = matches[pattern.src].span_no_inline(body);
+        if !self.tcx.is_mir_available(def_id) {
+            return;
+        }
+        let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+        let pattern = pattern_unsound_cast_between_u64_and_atomic_u64(self.pcx);
+        for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, pattern.fn_pat).check() {
+            let transmute = matches[pattern.transmute].span_no_inline(body);
+            let src = matches[pattern.src].span_no_inline(body);
+            self.tcx.emit_node_span_lint(
+                UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
+                self.tcx.local_def_id_to_hir_id(def_id),
+                transmute,
+                crate::errors::UnsoundCastBetweenU64AndAtomicU64 { transmute, src },
+            );
+        }
+        let pattern = pattern_atomic_cell_arithmetic(self.pcx);
+        for (fn_pat, call) in pattern.fn_pats {
+            for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                let call = matches[call].span_no_inline(body);
                 self.tcx.emit_node_span_lint(
                     UNSOUND_CAST_BETWEEN_U64_AND_ATOMIC_U64,
                     self.tcx.local_def_id_to_hir_id(def_id),
-                    transmute,
-                    crate::errors::UnsoundCastBetweenU64AndAtomicU64 { transmute, src },
+                    call,
+                    crate::errors::UnsoundAtomicCellArithmetic {
+                        call,
+                        applies_to: crate::errors::AppliesTo::new(self.tcx, fn_pat),
+                    },
                 );
             }
         }
@@ -75,7 +91,7 @@ fn pattern_unsound_cast_between_u64_and_atomic_u64(pcx: PatCtxt<'_>) -> UnsoundC
     let transmute;
     let src;
     let pattern = rpl! {
-        fn $pattern(..) -> _ = mir! {
+        pub fn $pattern(..) -> _ = mir! {
             type AtomicU64 = std::sync::atomic::AtomicU64;
 
             #[export(src)]
@@ -93,3 +109,72 @@ fn pattern_unsound_cast_between_u64_and_atomic_u64(pcx: PatCtxt<'_>) -> UnsoundC
         src,
     }
 }
+
+struct PatternAtomicCellArithmetic<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    fn_pats: [(&'pcx pat::Fn<'pcx>, pat::Location); 5],
+}
+
+/// The arithmetic operations of `AtomicCell<u64>` and `AtomicCell<i64>` in `crossbeam-utils`
+/// before 0.8.7 perform the unsound cast above internally, so calling them from a crate depending
+/// on an affected version is reported as well.
+#[rpl_macros::pattern_def]
+fn pattern_atomic_cell_arithmetic(pcx: PatCtxt<'_>) -> PatternAtomicCellArithmetic<'_> {
+    let add;
+    let sub;
+    let and;
+    let or;
+    let xor;
+    let pattern = rpl! {
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        #[meta($T:ty = is_64_bit_integer)]
+        fn $fetch_add(..) -> _ = mir! {
+            #[export(add)]
+            let $prev: $T = crossbeam_utils::atomic::AtomicCell::fetch_add(_, _);
+        }
+
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        #[meta($T:ty = is_64_bit_integer)]
+        fn $fetch_sub(..) -> _ = mir! {
+            #[export(sub)]
+            let $prev: $T = crossbeam_utils::atomic::AtomicCell::fetch_sub(_, _);
+        }
+
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        #[meta($T:ty = is_64_bit_integer)]
+        fn $fetch_and(..) -> _ = mir! {
+            #[export(and)]
+            let $prev: $T = crossbeam_utils::atomic::AtomicCell::fetch_and(_, _);
+        }
+
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        #[meta($T:ty = is_64_bit_integer)]
+        fn $fetch_or(..) -> _ = mir! {
+            #[export(or)]
+            let $prev: $T = crossbeam_utils::atomic::AtomicCell::fetch_or(_, _);
+        }
+
+        #[applies_to(crate = "crossbeam-utils", version = "< 0.8.7")]
+        #[meta($T:ty = is_64_bit_integer)]
+        fn $fetch_xor(..) -> _ = mir! {
+            #[export(xor)]
+            let $prev: $T = crossbeam_utils::atomic::AtomicCell::fetch_xor(_, _);
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternAtomicCellArithmetic {
+        pattern,
+        fn_pats: [
+            (fn_pat("fetch_add"), add),
+            (fn_pat("fetch_sub"), sub),
+            (fn_pat("fetch_and"), and),
+            (fn_pat("fetch_or"), or),
+            (fn_pat("fetch_xor"), xor),
+        ],
+    }
+}
+
+fn is_64_bit_integer<'tcx>(_tcx: TyCtxt<'tcx>, _typing_env: ty::TypingEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    matches!(ty.kind(), ty::Uint(ty::UintTy::U64) | ty::Int(ty::IntTy::I64))
+}
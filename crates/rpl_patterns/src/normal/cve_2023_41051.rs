@@ -0,0 +1,409 @@
+use rpl_context::PatCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::{Span, Symbol};
+
+use crate::dataflow::{Taint, assigned_local};
+use crate::lints::UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, hir::Safety::Safe, ..)
+            | hir::ItemKind::Impl(_)
+            | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) && self.tcx.is_mir_available(def_id) {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            // The offsets computed from the arguments other than the receiver, which the callers
+            // control, and not from the fields of `self`.
+            let has_self =
+                self.tcx.def_kind(def_id) == DefKind::AssocFn && self.tcx.associated_item(def_id).fn_has_self_parameter;
+            let from_args = Taint::new(body)
+                .seeds(body.args_iter().skip(usize::from(has_self)))
+                .run();
+            let is_unchecked = |ty, offset| {
+                offset_operand(body, offset).is_some_and(|operand| from_args.operand(operand))
+                    && !assigned_local(body, offset).is_some_and(|ptr| self.checks_alignment_of(body, ty, ptr))
+            };
+
+            let pattern = pattern_cast(self.pcx);
+            for &(fn_pat, ty_var, offset, cast) in &pattern.casts {
+                for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                    let ty = matches[ty_var.idx];
+                    let StatementMatch::Location(offset_at) = matches[offset] else {
+                        continue;
+                    };
+                    if !is_unchecked(ty, offset_at) {
+                        continue;
+                    }
+                    let offset = matches[offset].span_no_inline(body);
+                    let cast = matches[cast].span_no_inline(body);
+                    debug!(?offset, ?cast, ?ty);
+                    self.tcx.emit_node_span_lint(
+                        UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET,
+                        self.tcx.local_def_id_to_hir_id(def_id),
+                        cast,
+                        crate::errors::UnalignedPointerCastFromByteOffset { cast, offset, ty },
+                    );
+                }
+            }
+
+            let pattern = pattern_offset(self.pcx);
+            let typing_env = ty::TypingEnv::non_body_analysis(self.tcx, def_id);
+            for &(fn_pat, offset) in &pattern.offsets {
+                for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                    let StatementMatch::Location(offset_at) = matches[offset] else {
+                        continue;
+                    };
+                    let Some(ptr) = assigned_local(body, offset_at) else {
+                        continue;
+                    };
+                    for (wrap, wrapper, ty) in self.wrapped(body, typing_env, ptr) {
+                        if !is_unchecked(ty, offset_at) {
+                            continue;
+                        }
+                        let offset = matches[offset].span_no_inline(body);
+                        let wrap = StatementMatch::Location(wrap).span_no_inline(body);
+                        debug!(?offset, ?wrap, ?wrapper, ?ty);
+                        self.tcx.emit_node_span_lint(
+                            UNALIGNED_POINTER_CAST_FROM_BYTE_OFFSET,
+                            self.tcx.local_def_id_to_hir_id(def_id),
+                            wrap,
+                            crate::errors::UnalignedPointerWrappedFromByteOffset {
+                                wrap,
+                                offset,
+                                ty,
+                                wrapper,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+impl<'tcx> CheckFnCtxt<'_, 'tcx> {
+    /// Whether the alignment of `ty` is checked against the pointer `ptr`, that is, a branch or
+    /// an assertion depends on both `ptr` and `AlignOf($T)`, `align_of::<$T>()` or `is_aligned`,
+    /// such as `(ptr as usize) % align_of::<T>() != 0`.
+    fn checks_alignment_of(&self, body: &mir::Body<'tcx>, ty: Ty<'tcx>, ptr: mir::Local) -> bool {
+        let pattern = pattern_align_check(self.pcx);
+        let from_ptr = Taint::new(body).seeds([ptr]).run();
+        pattern.align_checks.iter().any(|&(fn_pat, ty_var, align)| {
+            CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat)
+                .check()
+                .into_iter()
+                .any(|matches| {
+                    if matches[ty_var.idx] == ty
+                        && let StatementMatch::Location(align) = matches[align]
+                        && let Some(align) = assigned_local(body, align)
+                    {
+                        let from_align = Taint::new(body).seeds([align]).run();
+                        branches_on(body, |operand| from_ptr.operand(operand) && from_align.operand(operand))
+                    } else {
+                        false
+                    }
+                })
+        })
+    }
+}
+
+impl<'tcx> CheckFnCtxt<'_, 'tcx> {
+    /// The calls wrapping the byte pointer `ptr` in a typed reference wrapper, such as
+    /// `VolatileRef::<T>::new(ptr)`, with the wrapper and the type it refers to. The wrapper is
+    /// constructed by one of its associated functions, and is generic over a type not aligned to
+    /// a single byte.
+    fn wrapped(
+        &self,
+        body: &mir::Body<'tcx>,
+        typing_env: ty::TypingEnv<'tcx>,
+        ptr: mir::Local,
+    ) -> Vec<(mir::Location, Ty<'tcx>, Ty<'tcx>)> {
+        let tcx = self.tcx;
+        let mut wrapped = Vec::new();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            if let mir::TerminatorKind::Call {
+                ref func,
+                ref args,
+                destination,
+                ..
+            } = data.terminator().kind
+                && let Some((fn_def_id, _)) = func.const_fn_def()
+                && args
+                    .iter()
+                    .any(|arg| arg.node.place().and_then(|place| place.as_local()) == Some(ptr))
+                && let wrapper = destination.ty(body, tcx).ty
+                && let &ty::Adt(adt_def, wrapper_args) = wrapper.kind()
+                && tcx.impl_of_method(fn_def_id).is_some_and(|impl_def_id| {
+                    tcx.type_of(impl_def_id).instantiate_identity().ty_adt_def() == Some(adt_def)
+                })
+                && let Some(ty) = wrapper_args
+                    .types()
+                    .find(|&ty| is_not_byte_aligned(tcx, typing_env, ty))
+            {
+                wrapped.push((body.terminator_loc(block), wrapper, ty));
+            }
+        }
+        wrapped
+    }
+}
+
+/// The number of bytes a pointer is offset by at `location`, either by `<*const u8>::add` or
+/// by `Offset` after it is inlined.
+fn offset_operand<'a, 'tcx>(body: &'a mir::Body<'tcx>, location: mir::Location) -> Option<&'a mir::Operand<'tcx>> {
+    let data = &body[location.block];
+    match data.statements.get(location.statement_index) {
+        Some(statement) => match statement.kind.as_assign()? {
+            (_, mir::Rvalue::BinaryOp(mir::BinOp::Offset, operands)) => Some(&operands.1),
+            _ => None,
+        },
+        None => match data.terminator().kind {
+            mir::TerminatorKind::Call { ref args, .. } => Some(&args.get(1)?.node),
+            _ => None,
+        },
+    }
+}
+
+/// Whether a `SwitchInt` or an `Assert` in `body` is on an operand accepted by `on`.
+fn branches_on<'tcx>(body: &mir::Body<'tcx>, on: impl Fn(&mir::Operand<'tcx>) -> bool) -> bool {
+    body.basic_blocks.iter().any(|data| match data.terminator().kind {
+        mir::TerminatorKind::SwitchInt { ref discr, .. } => on(discr),
+        mir::TerminatorKind::Assert { ref cond, .. } => on(cond),
+        _ => false,
+    })
+}
+
+struct PatternCast<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns casting an offset byte pointer, with the type cast to, where the
+    /// pointer is offset, and where it is cast.
+    casts: [(&'pcx pat::Fn<'pcx>, pat::TyVar, pat::Location, pat::Location); 4],
+}
+
+/// A byte pointer, such as `slice.addr` of `VolatileSlice` in vm-memory, is offset by `offset`,
+/// either by `<*const u8>::add` or by its inlined body, and then cast to a pointer to `$T`.
+#[rpl_macros::pattern_def]
+fn pattern_cast(pcx: PatCtxt<'_>) -> PatternCast<'_> {
+    let const_ty;
+    let const_offset;
+    let const_cast;
+    let mut_ty;
+    let mut_offset;
+    let mut_cast;
+    let const_inlined_ty;
+    let const_inlined_offset;
+    let const_inlined_cast;
+    let mut_inlined_ty;
+    let mut_inlined_offset;
+    let mut_inlined_cast;
+    let pattern = rpl! {
+        #[meta(#[export(const_ty)] $T:ty = is_not_byte_aligned)]
+        fn $cast_const(..) -> _ = mir! {
+            #[export(const_offset)]
+            let $ptr: *const u8 = <*const _>::add(_, _);
+            #[export(const_cast)]
+            let $typed: *const $T = move $ptr as *const $T (PtrToPtr);
+        }
+
+        #[meta(#[export(mut_ty)] $T:ty = is_not_byte_aligned)]
+        fn $cast_mut(..) -> _ = mir! {
+            #[export(mut_offset)]
+            let $ptr: *mut u8 = <*mut _>::add(_, _);
+            #[export(mut_cast)]
+            let $typed: *mut $T = move $ptr as *mut $T (PtrToPtr);
+        }
+
+        #[meta(#[export(const_inlined_ty)] $T:ty = is_not_byte_aligned)]
+        fn $cast_const_inlined(..) -> _ = mir! {
+            #[export(const_inlined_offset)]
+            let $ptr: *const u8 = Offset(_, _);
+            #[export(const_inlined_cast)]
+            let $typed: *const $T = move $ptr as *const $T (PtrToPtr);
+        }
+
+        #[meta(#[export(mut_inlined_ty)] $T:ty = is_not_byte_aligned)]
+        fn $cast_mut_inlined(..) -> _ = mir! {
+            #[export(mut_inlined_offset)]
+            let $ptr: *mut u8 = Offset(_, _);
+            #[export(mut_inlined_cast)]
+            let $typed: *mut $T = move $ptr as *mut $T (PtrToPtr);
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternCast {
+        pattern,
+        casts: [
+            (fn_pat("cast_const"), const_ty, const_offset, const_cast),
+            (fn_pat("cast_mut"), mut_ty, mut_offset, mut_cast),
+            (
+                fn_pat("cast_const_inlined"),
+                const_inlined_ty,
+                const_inlined_offset,
+                const_inlined_cast,
+            ),
+            (
+                fn_pat("cast_mut_inlined"),
+                mut_inlined_ty,
+                mut_inlined_offset,
+                mut_inlined_cast,
+            ),
+        ],
+    }
+}
+
+struct PatternOffset<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns offsetting a byte pointer, with where it is offset. The typed
+    /// reference wrappers it is passed to are found in [`CheckFnCtxt::wrapped`], as they may be
+    /// any type generic over the type referred to.
+    offsets: [(&'pcx pat::Fn<'pcx>, pat::Location); 4],
+}
+
+/// A byte pointer is offset by `offset`, either by `<*const u8>::add` or by its inlined body.
+#[rpl_macros::pattern_def]
+fn pattern_offset(pcx: PatCtxt<'_>) -> PatternOffset<'_> {
+    let const_offset;
+    let mut_offset;
+    let const_inlined_offset;
+    let mut_inlined_offset;
+    let pattern = rpl! {
+        fn $offset_const(..) -> _ = mir! {
+            #[export(const_offset)]
+            let $ptr: *const u8 = <*const _>::add(_, _);
+        }
+
+        fn $offset_mut(..) -> _ = mir! {
+            #[export(mut_offset)]
+            let $ptr: *mut u8 = <*mut _>::add(_, _);
+        }
+
+        fn $offset_const_inlined(..) -> _ = mir! {
+            #[export(const_inlined_offset)]
+            let $ptr: *const u8 = Offset(_, _);
+        }
+
+        fn $offset_mut_inlined(..) -> _ = mir! {
+            #[export(mut_inlined_offset)]
+            let $ptr: *mut u8 = Offset(_, _);
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternOffset {
+        pattern,
+        offsets: [
+            (fn_pat("offset_const"), const_offset),
+            (fn_pat("offset_mut"), mut_offset),
+            (fn_pat("offset_const_inlined"), const_inlined_offset),
+            (fn_pat("offset_mut_inlined"), mut_inlined_offset),
+        ],
+    }
+}
+
+struct PatternAlignCheck<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns taking the alignment of a type, with the type, and where the
+    /// alignment, or whether a pointer is aligned, is computed.
+    align_checks: [(&'pcx pat::Fn<'pcx>, pat::TyVar, pat::Location); 4],
+}
+
+/// The negative side of the patterns above: `AlignOf($T)`, which is also what `align_of::<$T>()`
+/// and `is_aligned` are lowered to after inlining, or a call of `align_of::<$T>()` or
+/// `is_aligned`.
+#[rpl_macros::pattern_def]
+fn pattern_align_check(pcx: PatCtxt<'_>) -> PatternAlignCheck<'_> {
+    let align_of_ty;
+    let align_of_align;
+    let align_of_call_ty;
+    let align_of_call_align;
+    let is_aligned_const_ty;
+    let is_aligned_const_align;
+    let is_aligned_mut_ty;
+    let is_aligned_mut_align;
+    let pattern = rpl! {
+        #[meta(#[export(align_of_ty)] $T:ty)]
+        fn $align_of(..) -> _ = mir! {
+            #[export(align_of_align)]
+            let $align: usize = AlignOf($T);
+        }
+
+        #[meta(#[export(align_of_call_ty)] $T:ty)]
+        fn $align_of_call(..) -> _ = mir! {
+            #[export(align_of_call_align)]
+            let $align: usize = std::mem::align_of::<$T>();
+        }
+
+        #[meta(#[export(is_aligned_const_ty)] $T:ty)]
+        fn $is_aligned_const(..) -> _ = mir! {
+            let $typed: *const $T = _;
+            #[export(is_aligned_const_align)]
+            let $aligned: bool = <*const _>::is_aligned(copy $typed);
+        }
+
+        #[meta(#[export(is_aligned_mut_ty)] $T:ty)]
+        fn $is_aligned_mut(..) -> _ = mir! {
+            let $typed: *mut $T = _;
+            #[export(is_aligned_mut_align)]
+            let $aligned: bool = <*mut _>::is_aligned(copy $typed);
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternAlignCheck {
+        pattern,
+        align_checks: [
+            (fn_pat("align_of"), align_of_ty, align_of_align),
+            (fn_pat("align_of_call"), align_of_call_ty, align_of_call_align),
+            (fn_pat("is_aligned_const"), is_aligned_const_ty, is_aligned_const_align),
+            (fn_pat("is_aligned_mut"), is_aligned_mut_ty, is_aligned_mut_align),
+        ],
+    }
+}
+
+/// Types aligned to a single byte can be read from any byte offset.
+#[instrument(level = "debug", skip(tcx), ret)]
+fn is_not_byte_aligned<'tcx>(tcx: TyCtxt<'tcx>, typing_env: ty::TypingEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    !tcx.layout_of(typing_env.as_query_input(ty))
+        .is_ok_and(|layout| layout.align.abi.bytes() == 1)
+}
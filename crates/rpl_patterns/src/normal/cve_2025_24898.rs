@@ -0,0 +1,243 @@
+use rpl_context::PatCtxt;
+use rpl_match::MatchFnCtxt;
+use rpl_mir::{CheckMirCtxt, StatementMatch, pat};
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter::All;
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{Span, Symbol};
+
+use crate::dataflow::precedes;
+use crate::ffi::extern_calls_taking;
+use crate::lints::FFI_OUT_POINTER_INTO_SHORT_LIVED_BUFFER;
+
+#[instrument(level = "info", skip_all)]
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    let item = tcx.hir().item(item_id);
+    let mut check_ctxt = CheckFnCtxt { tcx, pcx };
+    check_ctxt.visit_item(item);
+}
+
+struct CheckFnCtxt<'pcx, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
+}
+
+impl<'tcx> Visitor<'tcx> for CheckFnCtxt<'_, 'tcx> {
+    type NestedFilter = All;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    #[instrument(level = "debug", skip_all, fields(?item.owner_id))]
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) -> Self::Result {
+        match item.kind {
+            hir::ItemKind::Trait(hir::IsAuto::No, hir::Safety::Safe, ..)
+            | hir::ItemKind::Impl(_)
+            | hir::ItemKind::Fn { .. } => {},
+            _ => return,
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    #[instrument(level = "info", skip_all, fields(?def_id))]
+    fn visit_fn(
+        &mut self,
+        kind: intravisit::FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl<'tcx>,
+        body_id: hir::BodyId,
+        _span: Span,
+        def_id: LocalDefId,
+    ) -> Self::Result {
+        if matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            && self.tcx.is_mir_available(def_id)
+            && self.returns_slice_of_one_buffer(def_id)
+        {
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
+            let pattern = pattern_out_pointer(self.pcx);
+            for &(fn_pat, out_ptr, slice) in &pattern.out_pointers {
+                for matches in CheckMirCtxt::new(self.tcx, self.pcx, body, pattern.pattern, fn_pat).check() {
+                    let (StatementMatch::Location(out_ptr), StatementMatch::Location(slice)) =
+                        (matches[out_ptr], matches[slice])
+                    else {
+                        continue;
+                    };
+                    let Some(ffi_call) = self.ffi_call(body, out_ptr, slice) else {
+                        continue;
+                    };
+                    let ffi_call = StatementMatch::Location(ffi_call).span_no_inline(body);
+                    let slice = StatementMatch::Location(slice).span_no_inline(body);
+                    debug!(?ffi_call, ?slice);
+                    self.tcx.emit_node_span_lint(
+                        FFI_OUT_POINTER_INTO_SHORT_LIVED_BUFFER,
+                        self.tcx.local_def_id_to_hir_id(def_id),
+                        slice,
+                        crate::errors::FfiOutPointerIntoShortLivedBuffer {
+                            slice,
+                            ffi_call,
+                            output: decl.output.span(),
+                        },
+                    );
+                }
+            }
+        }
+        intravisit::walk_fn(self, kind, decl, body_id, def_id);
+    }
+}
+
+impl<'tcx> CheckFnCtxt<'_, 'tcx> {
+    /// The call of an extern function, between the locations `out_ptr` and `slice`, that takes
+    /// the pointer to the out pointer taken at `out_ptr` as any of its arguments.
+    fn ffi_call(&self, body: &mir::Body<'tcx>, out_ptr: mir::Location, slice: mir::Location) -> Option<mir::Location> {
+        let (out_ptr_place, _) = body[out_ptr.block]
+            .statements
+            .get(out_ptr.statement_index)?
+            .kind
+            .as_assign()?;
+        let out_ptr_local = out_ptr_place.as_local()?;
+        extern_calls_taking(self.tcx, body, out_ptr_local)
+            .map(|(location, _)| location)
+            .find(|&location| precedes(body, out_ptr, location) && precedes(body, location, slice))
+    }
+
+    /// Whether the function takes two buffers, followed by any other arguments, and returns a
+    /// slice whose lifetime is tied to only one of them.
+    fn returns_slice_of_one_buffer(&self, def_id: LocalDefId) -> bool {
+        let pattern = pattern_slice_of_one_buffer_sig(self.pcx);
+        pattern
+            .fn_pats
+            .iter()
+            .any(|fn_pat| MatchFnCtxt::new(self.tcx, self.pcx, pattern.pattern, fn_pat).match_fn(def_id))
+    }
+}
+
+struct PatternSliceOfOneBufferSig<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    fn_pats: [&'pcx pat::Fn<'pcx>; 4],
+}
+
+#[rpl_macros::pattern_def]
+fn pattern_slice_of_one_buffer_sig(pcx: PatCtxt<'_>) -> PatternSliceOfOneBufferSig<'_> {
+    let pattern = rpl! {
+        #[meta($'a, $'b)]
+        #[constraints(!outlives($'a, $'b))]
+        fn $first_short(&$'a [u8], &$'b [u8], ..) -> core::option::Option<&$'b [u8]>;
+
+        #[meta($'a, $'b)]
+        #[constraints(!outlives($'b, $'a))]
+        fn $second_short(&$'a [u8], &$'b [u8], ..) -> core::option::Option<&$'a [u8]>;
+
+        #[meta($'a, $'b)]
+        #[constraints(!outlives($'a, $'b))]
+        fn $first_short_unwrapped(&$'a [u8], &$'b [u8], ..) -> &$'b [u8];
+
+        #[meta($'a, $'b)]
+        #[constraints(!outlives($'b, $'a))]
+        fn $second_short_unwrapped(&$'a [u8], &$'b [u8], ..) -> &$'a [u8];
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternSliceOfOneBufferSig {
+        pattern,
+        fn_pats: [
+            fn_pat("first_short"),
+            fn_pat("second_short"),
+            fn_pat("first_short_unwrapped"),
+            fn_pat("second_short_unwrapped"),
+        ],
+    }
+}
+
+struct PatternOutPointer<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    /// The function patterns creating a slice from an out pointer, with where the pointer to it
+    /// is taken, and where the slice is created.
+    out_pointers: [(&'pcx pat::Fn<'pcx>, pat::Location, pat::Location); 4],
+}
+
+/// A pointer to `$out` is taken, so that an extern function, such as `SSL_select_next_proto` of
+/// OpenSSL, writes a pointer into one of its input buffers to it, and a slice is then created from
+/// `$out`, either by `slice::from_raw_parts` or by its inlined body. The extern function is found
+/// in [`CheckFnCtxt::ffi_call`], as it may take the pointer to `$out` as any of its arguments.
+#[rpl_macros::pattern_def]
+fn pattern_out_pointer(pcx: PatCtxt<'_>) -> PatternOutPointer<'_> {
+    let mut_out_ptr;
+    let mut_slice;
+    let const_out_ptr;
+    let const_slice;
+    let mut_inlined_out_ptr;
+    let mut_inlined_slice;
+    let const_inlined_out_ptr;
+    let const_inlined_slice;
+    let pattern = rpl! {
+        fn $out_mut(..) -> _ = mir! {
+            let $out: *mut u8;
+            let $out_ref: &mut *mut u8 = &mut $out;
+            #[export(mut_out_ptr)]
+            let $out_ptr: *mut *mut u8 = &raw mut (*$out_ref);
+            let $out_copy: *mut u8;
+            let $data: *const u8;
+            let $slice: &[u8];
+            $out_copy = copy $out;
+            $data = move $out_copy as *const u8 (PtrToPtr);
+            #[export(mut_slice)]
+            $slice = std::slice::from_raw_parts::<'_, u8>(move $data, _);
+        }
+
+        fn $out_const(..) -> _ = mir! {
+            let $out: *const u8;
+            let $out_ref: &mut *const u8 = &mut $out;
+            #[export(const_out_ptr)]
+            let $out_ptr: *mut *const u8 = &raw mut (*$out_ref);
+            let $data: *const u8;
+            let $slice: &[u8];
+            $data = copy $out;
+            #[export(const_slice)]
+            $slice = std::slice::from_raw_parts::<'_, u8>(move $data, _);
+        }
+
+        fn $out_mut_inlined(..) -> _ = mir! {
+            let $out: *mut u8;
+            let $out_ref: &mut *mut u8 = &mut $out;
+            #[export(mut_inlined_out_ptr)]
+            let $out_ptr: *mut *mut u8 = &raw mut (*$out_ref);
+            let $out_copy: *mut u8;
+            let $data: *const u8;
+            let $slice_ptr: *const [u8];
+            let $slice: &[u8];
+            $out_copy = copy $out;
+            $data = move $out_copy as *const u8 (PtrToPtr);
+            $slice_ptr = *const [u8] from (copy $data, _);
+            #[export(mut_inlined_slice)]
+            $slice = &(*$slice_ptr);
+        }
+
+        fn $out_const_inlined(..) -> _ = mir! {
+            let $out: *const u8;
+            let $out_ref: &mut *const u8 = &mut $out;
+            #[export(const_inlined_out_ptr)]
+            let $out_ptr: *mut *const u8 = &raw mut (*$out_ref);
+            let $data: *const u8;
+            let $slice_ptr: *const [u8];
+            let $slice: &[u8];
+            $data = copy $out;
+            $slice_ptr = *const [u8] from (copy $data, _);
+            #[export(const_inlined_slice)]
+            $slice = &(*$slice_ptr);
+        }
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternOutPointer {
+        pattern,
+        out_pointers: [
+            (fn_pat("out_mut"), mut_out_ptr, mut_slice),
+            (fn_pat("out_const"), const_out_ptr, const_slice),
+            (fn_pat("out_mut_inlined"), mut_inlined_out_ptr, mut_inlined_slice),
+            (fn_pat("out_const_inlined"), const_inlined_out_ptr, const_inlined_slice),
+        ],
+    }
+}
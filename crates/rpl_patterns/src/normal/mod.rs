@@ -2,13 +2,23 @@ pub(crate) mod cve_2018_20992;
 pub(crate) mod cve_2018_21000;
 pub(crate) mod cve_2019_15548;
 pub(crate) mod cve_2019_16138;
+pub(crate) mod cve_2020_25795;
 pub(crate) mod cve_2020_35860;
+pub(crate) mod cve_2020_35861;
+pub(crate) mod cve_2020_35878;
+pub(crate) mod cve_2020_35906;
 pub(crate) mod cve_2020_35907;
+pub(crate) mod cve_2020_35923;
+pub(crate) mod cve_2021_25901;
 pub(crate) mod cve_2021_25904;
 pub(crate) mod cve_2021_25905;
 pub(crate) mod cve_2021_27376;
 pub(crate) mod cve_2021_29941_2;
+pub(crate) mod cve_2021_38190;
+pub(crate) mod cve_2021_45688;
 pub(crate) mod cve_2022_23639;
+pub(crate) mod cve_2023_41051;
+pub(crate) mod cve_2025_24898;
 
 pub(crate) mod alloc_unchecked;
 pub(crate) mod manually_drop;
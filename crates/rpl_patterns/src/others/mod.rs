@@ -1 +1,3 @@
+#[cfg(debug_assertions)]
+pub(crate) mod panic_for_testing;
 pub(crate) mod private_or_generic_function_marked_inline;
@@ -0,0 +1,21 @@
+//! A detector that panics on the items named in the `RPL_PANIC_FOR_TESTING` environment variable,
+//! separated by commas, to test the panic boundary of the detectors. It is only built with debug
+//! assertions, so it never runs in a release build of the driver.
+
+use std::sync::OnceLock;
+
+use rpl_context::PatCtxt;
+use rustc_hir as hir;
+use rustc_middle::ty::TyCtxt;
+
+pub fn check_item(tcx: TyCtxt<'_>, _pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+    static ITEMS: OnceLock<Option<String>> = OnceLock::new();
+    let Some(items) = ITEMS.get_or_init(|| std::env::var("RPL_PANIC_FOR_TESTING").ok()) else {
+        return;
+    };
+    if let Some(name) = tcx.opt_item_name(item_id.owner_id.to_def_id())
+        && items.split(',').any(|item| item == name.as_str())
+    {
+        panic!("panicking on `{name}` for testing");
+    }
+}
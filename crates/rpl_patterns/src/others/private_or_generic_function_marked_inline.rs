@@ -1,44 +1,25 @@
-use rpl_context::PatCtxt;
+use rpl_context::{PatCtxt, pat};
+use rpl_match::MatchFnCtxt;
+use rustc_hir as hir;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, Visitor};
-use rustc_hir::{self as hir};
 use rustc_middle::hir::nested_filter::All;
 use rustc_middle::ty::TyCtxt;
-use rustc_span::{Span, sym};
+use rustc_span::{Span, Symbol};
 
 #[instrument(level = "info", skip_all)]
-pub fn check_item(tcx: TyCtxt<'_>, _pcx: PatCtxt<'_>, item_id: hir::ItemId) {
+pub fn check_item(tcx: TyCtxt<'_>, pcx: PatCtxt<'_>, item_id: hir::ItemId) {
     let item = tcx.hir().item(item_id);
-    let mut check_ctxt = CheckAttrCtxt { tcx };
+    let mut check_ctxt = CheckAttrCtxt { tcx, pcx };
     check_ctxt.visit_item(item);
 }
 
-struct CheckAttrCtxt<'tcx> {
+struct CheckAttrCtxt<'pcx, 'tcx> {
     tcx: TyCtxt<'tcx>,
+    pcx: PatCtxt<'pcx>,
 }
 
-impl CheckAttrCtxt<'_> {
-    fn has_inline_attr(&self, def_id: LocalDefId) -> bool {
-        let hir_id = self.tcx.local_def_id_to_hir_id(def_id);
-        let attrs = self.tcx.hir().attrs(hir_id);
-        for attr in attrs {
-            if let [sym::inline, ..] = attr.path().as_slice() {
-                let is_inline = true;
-                let is_never = attr.meta_item_list().is_some_and(|meta_item_list| {
-                    meta_item_list
-                        .iter()
-                        .any(|meta_item| meta_item.ident().is_some_and(|ident| ident.name == sym::never))
-                });
-                if is_inline && !is_never {
-                    return true;
-                }
-            }
-        }
-        false
-    }
-}
-
-impl<'tcx> Visitor<'tcx> for CheckAttrCtxt<'tcx> {
+impl<'tcx> Visitor<'tcx> for CheckAttrCtxt<'_, 'tcx> {
     type NestedFilter = All;
     fn nested_visit_map(&mut self) -> Self::Map {
         self.tcx.hir()
@@ -59,27 +40,53 @@ impl<'tcx> Visitor<'tcx> for CheckAttrCtxt<'tcx> {
         kind: intravisit::FnKind<'tcx>,
         decl: &'tcx hir::FnDecl<'tcx>,
         body_id: hir::BodyId,
-        _span: Span,
+        span: Span,
         def_id: LocalDefId,
     ) -> Self::Result {
-        if self.has_inline_attr(def_id) {
-            if !self.tcx.visibility(def_id).is_public() {
-                self.tcx.emit_node_span_lint(
-                    crate::lints::PRIVATE_FUNCTION_MARKED_INLINE,
-                    self.tcx.local_def_id_to_hir_id(def_id),
-                    _span,
-                    crate::errors::PrivateFunctionMarkedInline { span: _span },
-                );
-            }
-            if self.tcx.generics_of(def_id).requires_monomorphization(self.tcx) {
-                self.tcx.emit_node_span_lint(
-                    crate::lints::GENERIC_FUNCTION_MARKED_INLINE,
-                    self.tcx.local_def_id_to_hir_id(def_id),
-                    _span,
-                    crate::errors::GenericFunctionMarkedInline { span: _span },
-                );
-            }
+        let pattern = pattern_marked_inline(self.pcx);
+        let matches = |fn_pat| MatchFnCtxt::new(self.tcx, self.pcx, pattern.pattern, fn_pat).match_fn(def_id);
+        if matches(pattern.private) {
+            self.tcx.emit_node_span_lint(
+                crate::lints::PRIVATE_FUNCTION_MARKED_INLINE,
+                self.tcx.local_def_id_to_hir_id(def_id),
+                span,
+                crate::errors::PrivateFunctionMarkedInline { span },
+            );
+        }
+        if matches(pattern.generic) {
+            self.tcx.emit_node_span_lint(
+                crate::lints::GENERIC_FUNCTION_MARKED_INLINE,
+                self.tcx.local_def_id_to_hir_id(def_id),
+                span,
+                crate::errors::GenericFunctionMarkedInline { span },
+            );
         }
         intravisit::walk_fn(self, kind, decl, body_id, def_id);
     }
 }
+
+struct PatternMarkedInline<'pcx> {
+    pattern: &'pcx pat::Pattern<'pcx>,
+    private: &'pcx pat::Fn<'pcx>,
+    generic: &'pcx pat::Fn<'pcx>,
+}
+
+/// Functions marked `#[inline]`, but not `#[inline(never)]`, that are either not public, or
+/// generic, so they can already be inlined across crates.
+#[rpl_macros::pattern_def]
+fn pattern_marked_inline(pcx: PatCtxt<'_>) -> PatternMarkedInline<'_> {
+    let pattern = rpl! {
+        #[constraints(attr(inline), !attr(inline(never)))]
+        pub(crate) fn $private(..) -> _;
+
+        #[constraints(generic, attr(inline), !attr(inline(never)))]
+        fn $generic(..) -> _;
+    };
+    let fn_pat = |name| pattern.fns.get_fn_pat(Symbol::intern(name)).unwrap();
+
+    PatternMarkedInline {
+        pattern,
+        private: fn_pat("private"),
+        generic: fn_pat("generic"),
+    }
+}
@@ -0,0 +1,120 @@
+//! Run each detector under a panic boundary, so that a bug in a single pattern or in the matcher
+//! does not abort the whole compilation and lose every other finding.
+
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::sync::Once;
+
+use rustc_data_structures::FatalErrorMarker;
+
+/// The maximum number of frames kept in [`DetectorPanic::backtrace`].
+const MAX_BACKTRACE_FRAMES: usize = 8;
+
+/// The caller of [`catch_detector_panic`], where the backtrace summary stops.
+const BOUNDARY_ENTRY: &str = "rpl_patterns::check_item_with";
+
+thread_local! {
+    /// Whether the current thread is running a detector under [`catch_detector_panic`].
+    static IN_DETECTOR: Cell<bool> = const { Cell::new(false) };
+    /// The panic recorded by the panic hook, taken after unwinding to [`catch_detector_panic`].
+    static CAPTURED: RefCell<Option<DetectorPanic>> = const { RefCell::new(None) };
+}
+
+/// A panic caught from a detector.
+pub(crate) struct DetectorPanic {
+    pub message: String,
+    pub location: String,
+    /// The `rpl_*` frames of the backtrace, innermost first.
+    pub backtrace: String,
+}
+
+impl DetectorPanic {
+    fn from_hook(info: &PanicHookInfo<'_>) -> Self {
+        Self {
+            message: payload_message(info.payload()),
+            location: info
+                .location()
+                .map_or_else(|| "<unknown>".to_owned(), ToString::to_string),
+            backtrace: summarize_backtrace(&Backtrace::force_capture().to_string()),
+        }
+    }
+
+    fn from_payload(payload: &(dyn std::any::Any + Send)) -> Self {
+        Self {
+            message: payload_message(payload),
+            location: "<unknown>".to_owned(),
+            backtrace: "<unavailable>".to_owned(),
+        }
+    }
+}
+
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(&msg) = payload.downcast_ref::<&'static str>() {
+        msg.to_owned()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+/// Keep only the frames from RPL crates between the panic and the detector entry, which are the
+/// ones a pattern author can act on.
+pub(crate) fn summarize_backtrace(backtrace: &str) -> String {
+    let frames = backtrace
+        .lines()
+        .filter_map(|line| {
+            let (index, symbol) = line.trim_start().split_once(": ")?;
+            index.parse::<usize>().ok()?;
+            Some(strip_symbol_hash(symbol))
+        })
+        .filter(|symbol| symbol.starts_with("rpl_") && !symbol.starts_with(module_path!()))
+        .take_while(|symbol| !symbol.starts_with(BOUNDARY_ENTRY))
+        .take(MAX_BACKTRACE_FRAMES)
+        .collect::<Vec<_>>();
+    if frames.is_empty() {
+        return "<unavailable>".to_owned();
+    }
+    frames.join("\n")
+}
+
+/// Strip the trailing `::h0123456789abcdef` of a legacy-mangled symbol.
+pub(crate) fn strip_symbol_hash(symbol: &str) -> &str {
+    match symbol.rsplit_once("::h") {
+        Some((path, hash)) if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => path,
+        _ => symbol,
+    }
+}
+
+/// Chain a panic hook in front of the one installed by the driver, which would otherwise report
+/// every detector panic as an ICE.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        panic::update_hook(|prev, info| {
+            if IN_DETECTOR.get() {
+                CAPTURED.set(Some(DetectorPanic::from_hook(info)));
+            } else {
+                prev(info);
+            }
+        })
+    });
+}
+
+/// Run `f`, turning a panic into a [`DetectorPanic`].
+///
+/// Fatal errors are not bugs of the detector and keep unwinding.
+pub(crate) fn catch_detector_panic<R>(f: impl FnOnce() -> R) -> Result<R, DetectorPanic> {
+    install_panic_hook();
+    let in_detector = IN_DETECTOR.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    IN_DETECTOR.set(in_detector);
+    result.map_err(|payload| {
+        let captured = CAPTURED.take();
+        if payload.is::<FatalErrorMarker>() {
+            panic::resume_unwind(payload);
+        }
+        captured.unwrap_or_else(|| DetectorPanic::from_payload(&*payload))
+    })
+}
@@ -0,0 +1,86 @@
+//! Registration of RPL lints and lint groups in the lint store of rustc.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use rpl_config::qualified_name;
+use rustc_lint::LintStore;
+use rustc_lint_defs::{Level, Lint, LintId};
+
+use crate::lints::{ALL_LINTS, LINT_GROUPS};
+
+/// The group containing every RPL lint, including the ones declared at runtime.
+const ALL_GROUP: &str = "rpl::all";
+
+/// Lints declared at runtime, with the names of the groups they belong to.
+static DYNAMIC_LINTS: Mutex<Vec<(&'static Lint, Vec<&'static str>)>> = Mutex::new(Vec::new());
+
+/// Declare a lint at runtime, e.g. for a pattern loaded from outside of the driver, and add it to
+/// `groups`, which may be existing groups like `memory` or new ones. The lint is also in
+/// `rpl::all`.
+///
+/// This must be called before the compiler session is created, as lints are registered only once
+/// per session by [`register_lints`]. Declaring a lint with the name of an existing one returns the
+/// existing lint.
+pub fn declare_dynamic_lint(name: &str, default_level: Level, desc: &str, groups: &[&str]) -> &'static Lint {
+    let name = qualified_name(name).to_ascii_uppercase();
+    if let Some(&lint) = ALL_LINTS.iter().find(|lint| lint.name == name) {
+        return lint;
+    }
+    let mut dynamic_lints = DYNAMIC_LINTS.lock().unwrap();
+    if let Some(&(lint, _)) = dynamic_lints.iter().find(|(lint, _)| lint.name == name) {
+        return lint;
+    }
+    let lint: &'static Lint = Box::leak(Box::new(Lint {
+        name: name.leak(),
+        default_level,
+        desc: desc.to_owned().leak(),
+        is_externally_loaded: true,
+        ..Lint::default_fields_for_macro()
+    }));
+    let groups = groups
+        .iter()
+        .map(|group| {
+            let group = qualified_name(group);
+            LINT_GROUPS
+                .iter()
+                .map(|&(name, _)| name)
+                .find(|&name| name == group)
+                .unwrap_or_else(|| group.leak())
+        })
+        .collect();
+    dynamic_lints.push((lint, groups));
+    lint
+}
+
+/// Register all RPL lints and lint groups, including the ones from [`declare_dynamic_lint`].
+pub fn register_lints(store: &mut LintStore) {
+    let dynamic_lints = DYNAMIC_LINTS.lock().unwrap();
+
+    store.register_lints(ALL_LINTS);
+    store.register_lints(&dynamic_lints.iter().map(|&(lint, _)| lint).collect::<Vec<_>>());
+
+    let mut groups = BTreeMap::<&'static str, Vec<LintId>>::new();
+    for &(group, lints) in LINT_GROUPS {
+        groups
+            .entry(group)
+            .or_default()
+            .extend(lints.iter().copied().map(LintId::of));
+    }
+    for (lint, lint_groups) in dynamic_lints.iter() {
+        for &group in lint_groups {
+            groups.entry(group).or_default().push(LintId::of(lint));
+        }
+    }
+    let all = ALL_LINTS
+        .iter()
+        .chain(dynamic_lints.iter().map(|(lint, _)| lint))
+        .copied()
+        .map(LintId::of)
+        .collect();
+    groups.insert(ALL_GROUP, all);
+
+    for (group, lints) in groups {
+        store.register_group(true, group, None, lints);
+    }
+}
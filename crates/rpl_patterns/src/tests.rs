@@ -0,0 +1,61 @@
+use crate::panic_boundary::{strip_symbol_hash, summarize_backtrace};
+
+#[test]
+fn test_strip_symbol_hash() {
+    assert_eq!(
+        strip_symbol_hash("rpl_mir::CheckMirCtxt::check::h0123456789abcdef"),
+        "rpl_mir::CheckMirCtxt::check"
+    );
+    assert_eq!(
+        strip_symbol_hash("rpl_mir::CheckMirCtxt::check"),
+        "rpl_mir::CheckMirCtxt::check"
+    );
+    // Not a 16-digit hexadecimal hash.
+    assert_eq!(strip_symbol_hash("rpl_mir::helper::h0123"), "rpl_mir::helper::h0123");
+    assert_eq!(
+        strip_symbol_hash("rpl_mir::helper::hello_world_hello_"),
+        "rpl_mir::helper::hello_world_hello_"
+    );
+}
+
+#[test]
+fn test_summarize_backtrace() {
+    let backtrace = "\
+   0: std::backtrace_rs::backtrace::libunwind::trace::h1111111111111111
+             at /rustc/library/std/src/../../backtrace/src/backtrace/libunwind.rs:116:5
+   1: rpl_patterns::panic_boundary::DetectorPanic::from_hook::h2222222222222222
+             at ./crates/rpl_patterns/src/panic_boundary.rs:40:24
+   2: std::panicking::rust_panic_with_hook::h3333333333333333
+   3: rpl_match::ty::MatchTyCtxt::match_ty::h4444444444444444
+             at ./crates/rpl_match/src/ty.rs:100:9
+   4: rpl_mir::CheckMirCtxt::check::h5555555555555555
+   5: rpl_patterns::normal::cve_2020_35878::check_item::h6666666666666666
+   6: rpl_patterns::check_item_with::{{closure}}::h7777777777777777
+   7: rpl_patterns::check_item::h8888888888888888
+";
+    assert_eq!(
+        summarize_backtrace(backtrace),
+        "rpl_match::ty::MatchTyCtxt::match_ty\n\
+         rpl_mir::CheckMirCtxt::check\n\
+         rpl_patterns::normal::cve_2020_35878::check_item"
+    );
+}
+
+#[test]
+fn test_summarize_backtrace_truncated() {
+    let backtrace = (0..20)
+        .map(|i| format!("  {i}: rpl_mir::frame_{i}::h0123456789abcdef\n"))
+        .collect::<String>();
+    let summary = summarize_backtrace(&backtrace);
+    assert_eq!(summary.lines().count(), 8);
+    assert_eq!(summary.lines().next(), Some("rpl_mir::frame_0"));
+}
+
+#[test]
+fn test_summarize_backtrace_unavailable() {
+    assert_eq!(summarize_backtrace("disabled backtrace"), "<unavailable>");
+    assert_eq!(
+        summarize_backtrace("   0: std::panicking::begin_panic::h0123456789abcdef\n"),
+        "<unavailable>"
+    );
+}
@@ -141,7 +141,7 @@ impl<'tcx> Visitor<'tcx> for DebugVisitor<'tcx> {
     ) -> Self::Result {
         let hir_id = self.tcx.local_def_id_to_hir_id(def_id);
         if let Some((attr, DumpMirAllowed(true))) = self.get_dump_mir_attrs(hir_id) {
-            let body = self.tcx.optimized_mir(def_id);
+            let body = rpl_mir_transform::mir_for_matching(self.tcx, def_id);
             dump_mir(self.tcx, body, span, &attr);
         }
         intravisit::walk_fn(self, kind, decl, body_id, def_id);
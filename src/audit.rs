@@ -0,0 +1,191 @@
+//! The report of `cargo rpl --audit-deps`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anstream::{eprintln, println};
+use rpl_config::{CrateSummary, UnsafeStats};
+use serde_json::{Value, json};
+
+/// A package, identified by its name and version.
+type Package = (String, String);
+
+/// Parse the name and version of a package from its package ID in the messages of cargo, and
+/// whether it is a local package, i.e. not a dependency to be audited.
+///
+/// Both the current format, e.g. `registry+https://github.com/rust-lang/crates.io-index#libc@0.2.169`
+/// or `path+file:///path/to/foo#0.1.0`, and the legacy one, e.g. `libc 0.2.169 (registry+...)`, are
+/// supported.
+pub fn parse_package_id(id: &str) -> Option<(Package, bool)> {
+    if let Some((name_version, source)) = id.split_once(" (") {
+        let (name, version) = name_version.split_once(' ')?;
+        return Some(((name.to_owned(), version.to_owned()), source.starts_with("path+")));
+    }
+    let (url, fragment) = id.rsplit_once('#')?;
+    let (name, version) = match fragment.split_once('@') {
+        Some((name, version)) => (name, version),
+        // The name is omitted if it is the same as the last segment of the URL.
+        None => {
+            let path = url.split(['?', '#']).next()?;
+            (path.trim_end_matches('/').rsplit('/').next()?, fragment)
+        },
+    };
+    Some(((name.to_owned(), version.to_owned()), url.starts_with("path+")))
+}
+
+struct Finding {
+    lint: String,
+    level: String,
+    message: String,
+    location: String,
+}
+
+impl Finding {
+    fn from_diagnostic(diag: &Value) -> Option<Self> {
+        let lint = diag.get("code")?.get("code")?.as_str()?;
+        if !lint.starts_with("rpl::") {
+            return None;
+        }
+        let location = diag
+            .get("spans")?
+            .as_array()?
+            .iter()
+            .find(|span| span.get("is_primary").and_then(Value::as_bool) == Some(true))
+            .map(|span| {
+                let file = span.get("file_name").and_then(Value::as_str).unwrap_or_default();
+                let line = span.get("line_start").and_then(Value::as_u64).unwrap_or_default();
+                let column = span.get("column_start").and_then(Value::as_u64).unwrap_or_default();
+                format!("{file}:{line}:{column}")
+            })
+            .unwrap_or_default();
+        Some(Self {
+            lint: lint.to_owned(),
+            level: diag.get("level")?.as_str()?.to_owned(),
+            message: diag.get("message")?.as_str()?.to_owned(),
+            location,
+        })
+    }
+}
+
+/// Findings in dependencies, collected from the messages of cargo.
+#[derive(Default)]
+pub struct AuditReport {
+    /// All dependencies built, including the ones without findings.
+    packages: BTreeSet<Package>,
+    findings: BTreeMap<Package, Vec<Finding>>,
+    /// The file names of the artifacts built, without their extensions.
+    ///
+    /// Summaries of other builds, left in the audit directory by previous runs, are ignored.
+    artifacts: BTreeSet<String>,
+}
+
+impl AuditReport {
+    /// Record a message of cargo, returning `false` if it is not about a dependency, so that it
+    /// should be handled as usual.
+    pub fn record(&mut self, message: &Value) -> bool {
+        let Some((package, false)) = message
+            .get("package_id")
+            .and_then(Value::as_str)
+            .and_then(parse_package_id)
+        else {
+            return false;
+        };
+        match message.get("reason").and_then(Value::as_str) {
+            Some("compiler-message") => {
+                if let Some(finding) = message.get("message").and_then(Finding::from_diagnostic) {
+                    self.findings.entry(package.clone()).or_default().push(finding);
+                }
+            },
+            Some("compiler-artifact") => {
+                let filenames = message.get("filenames").and_then(Value::as_array);
+                let stems = filenames
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|filename| Path::new(filename.as_str()?).file_stem()?.to_str());
+                self.artifacts.extend(stems.map(str::to_owned));
+            },
+            _ => return false,
+        }
+        self.packages.insert(package);
+        true
+    }
+
+    /// Whether the crate of `summary` is built in this run, as one of the artifacts recorded, which
+    /// are prefixed with `lib` for libraries.
+    pub fn is_built(&self, summary: &CrateSummary) -> bool {
+        let artifact = summary.artifact();
+        self.artifacts.contains(&artifact) || self.artifacts.contains(&format!("lib{artifact}"))
+    }
+
+    /// Print the report, with the usage of `unsafe` summarized by `rpl-driver` in `audit_dir`.
+    pub fn print(&self, audit_dir: &Path, json: bool) {
+        let mut stats = BTreeMap::<Package, UnsafeStats>::new();
+        match CrateSummary::read_dir(audit_dir) {
+            Ok(summaries) => {
+                for summary in summaries.into_iter().filter(|summary| self.is_built(summary)) {
+                    *stats.entry((summary.package, summary.version)).or_default() += summary.stats;
+                }
+            },
+            Err(err) => eprintln!("warning: failed to read the RPL audit summaries: {err}"),
+        }
+
+        let mut packages = self
+            .packages
+            .iter()
+            .map(|package| {
+                let findings = self.findings.get(package).map_or(&[][..], Vec::as_slice);
+                let stats = stats.get(package).copied().unwrap_or_default();
+                (package, findings, stats)
+            })
+            .collect::<Vec<_>>();
+        packages
+            .sort_by_key(|&(package, findings, stats)| (std::cmp::Reverse((findings.len(), stats.total())), package));
+
+        if json {
+            let packages = packages
+                .iter()
+                .map(|&((name, version), findings, stats)| {
+                    json!({
+                        "name": name,
+                        "version": version,
+                        "unsafe_heavy": stats.is_unsafe_heavy(),
+                        "unsafe": stats,
+                        "findings": findings.iter().map(|finding| json!({
+                            "lint": finding.lint,
+                            "level": finding.level,
+                            "message": finding.message,
+                            "location": finding.location,
+                        })).collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            println!("{}", json!({ "dependencies": packages }));
+            return;
+        }
+
+        let with_findings = packages.iter().filter(|(_, findings, _)| !findings.is_empty()).count();
+        let unsafe_heavy = packages.iter().filter(|(_, _, stats)| stats.is_unsafe_heavy()).count();
+        println!(
+            "RPL audit: {} dependencies, {with_findings} with findings, {unsafe_heavy} unsafe-heavy",
+            packages.len()
+        );
+        for ((name, version), findings, stats) in packages {
+            if findings.is_empty() && !stats.is_unsafe_heavy() {
+                continue;
+            }
+            let heavy = if stats.is_unsafe_heavy() { " (unsafe-heavy)" } else { "" };
+            println!();
+            println!("{name} {version}{heavy}");
+            println!(
+                "    unsafe: {} blocks, {} functions, {} impls, {} traits in {} lines",
+                stats.unsafe_blocks, stats.unsafe_fns, stats.unsafe_impls, stats.unsafe_traits, stats.lines
+            );
+            for finding in findings {
+                println!(
+                    "    {}[{}]: {}: {}",
+                    finding.level, finding.lint, finding.location, finding.message
+                );
+            }
+        }
+    }
+}
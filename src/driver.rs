@@ -22,7 +22,7 @@ use rustc_session::config::ErrorOutputType;
 use std::env;
 use std::fs::read_to_string;
 use std::ops::Deref;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
 use anstream::println;
@@ -71,6 +71,17 @@ fn display_help() {
 
 const BUG_REPORT_URL: &str = "https://github.com/stuuupidcat/RPL/issues";
 
+/// Read the configuration file of RPL, looked up from `RPL_CONF_DIR` (set by `cargo rpl`), the
+/// manifest directory of the crate being checked, or the current directory.
+fn read_conf() -> rpl_config::Conf {
+    let dir = env::var_os(rpl_config::CONF_DIR_ENV)
+        .or_else(|| env::var_os("CARGO_MANIFEST_DIR"))
+        .map_or_else(|| env::current_dir().unwrap_or_default(), PathBuf::from);
+    rpl_config::Conf::lookup_and_read(&dir).unwrap_or_else(|err| {
+        EarlyDiagCtxt::new(ErrorOutputType::default()).early_fatal(format!("failed to load RPL configuration: {err}"))
+    })
+}
+
 fn logger_config() -> rustc_log::LoggerConfig {
     let mut cfg = rustc_log::LoggerConfig::from_env("RUSTC_LOG");
 
@@ -183,6 +194,7 @@ pub fn main() {
         pass_sysroot_env_if_given(&mut args, sys_root_env);
 
         let mut no_deps = false;
+        let mut audit_deps = false;
         let rpl_args_var = env::var(rpl_interface::RPL_ARGS_ENV).ok();
         let rpl_args = rpl_args_var
             .as_deref()
@@ -194,12 +206,17 @@ pub fn main() {
                     no_deps = true;
                     None
                 },
+                "--audit-deps" => {
+                    audit_deps = true;
+                    None
+                },
                 _ => Some(s.to_string()),
             })
             .chain(vec!["--cfg".into(), "rpl".into()])
             .collect::<Vec<String>>();
 
         // We enable RPL if one of the following conditions is met
+        // - IF RPL is auditing dependencies OR
         // - IF RPL is run on its test suite OR
         // - IF RPL is run on the main crate, not on deps (`!cap_lints_allow`) THEN
         //    - IF `--no-deps` is not set (`!no_deps`) OR
@@ -208,13 +225,30 @@ pub fn main() {
             && arg_value(&orig_args, "--force-warn", |val| val.contains("rpl::")).is_none();
         let in_primary_package = env::var("CARGO_PRIMARY_PACKAGE").is_ok();
 
-        let rpl_enabled = !cap_lints_allow && (!no_deps || in_primary_package);
+        // When auditing dependencies (`--audit-deps`), RPL also runs on the dependencies capped by
+        // `--cap-lints allow`, with its lints forced to be warnings, so that findings in
+        // dependencies never fail the build.
+        let audit_dep = audit_deps && cap_lints_allow;
+
+        let rpl_enabled = audit_dep || (!cap_lints_allow && (!no_deps || in_primary_package));
         if rpl_enabled {
+            let conf = read_conf();
+            // Lint levels in the configuration file go first, so that the ones passed on the
+            // command line take precedence.
+            args.extend(conf.lint_level_args());
             args.extend(rpl_args);
+            rpl_config::init(conf);
+            let mut callbacks = RplCallbacks::new(rpl_args_var);
+            if audit_dep {
+                args.extend(["--force-warn".into(), "rpl::all".into()]);
+                if let Some(audit_dir) = env::var_os(rpl_config::AUDIT_DIR_ENV) {
+                    callbacks = callbacks.audit_to(audit_dir.into());
+                }
+            }
             /* rustc_driver::RunCompiler::new(&args, &mut RplCallbacks::new(rpl_args_var))
             .set_using_internal_features(using_internal_features)
             .run() */
-            rustc_driver::run_compiler(&args, &mut RplCallbacks::new(rpl_args_var))
+            rustc_driver::run_compiler(&args, &mut callbacks)
         } else {
             /* rustc_driver::RunCompiler::new(&args, &mut RustcCallbacks::new(rpl_args_var))
             .set_using_internal_features(using_internal_features)
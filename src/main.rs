@@ -1,11 +1,16 @@
 // warn on lints, that are included in `rust-lang/rust`s bootstrap
 #![warn(rust_2018_idioms, unused_lifetimes)]
 
+mod audit;
+
 use std::env;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::io::{BufRead, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
 
-use anstream::println;
+use anstream::{eprint, eprintln, println};
+use audit::AuditReport;
+use rpl_config::{Baseline, BaselineEntry, Conf, OutputFormat};
 
 #[allow(clippy::ignored_unit_patterns)]
 fn show_help() {
@@ -48,6 +53,8 @@ struct RplCmd {
     cargo_subcommand: &'static str,
     args: Vec<String>,
     rpl_args: Vec<String>,
+    update_baseline: bool,
+    audit_deps: bool,
 }
 
 impl RplCmd {
@@ -58,6 +65,8 @@ impl RplCmd {
         let mut cargo_subcommand = "check";
         let mut args = vec![];
         let mut rpl_args: Vec<String> = vec![];
+        let mut update_baseline = false;
+        let mut audit_deps = false;
 
         for arg in old_args.by_ref() {
             match arg.as_str() {
@@ -65,10 +74,19 @@ impl RplCmd {
                     cargo_subcommand = "fix";
                     continue;
                 },
+                "--update-baseline" => {
+                    update_baseline = true;
+                    continue;
+                },
                 "--no-deps" => {
                     rpl_args.push("--no-deps".into());
                     continue;
                 },
+                "--audit-deps" => {
+                    audit_deps = true;
+                    rpl_args.push("--audit-deps".into());
+                    continue;
+                },
                 "--" => break,
                 _ => {},
             }
@@ -85,6 +103,43 @@ impl RplCmd {
             cargo_subcommand,
             args,
             rpl_args,
+            update_baseline,
+            audit_deps,
+        }
+    }
+
+    /// The output of `cargo metadata`, or `None` if it fails, in which case the error is reported
+    /// by the cargo command run later.
+    fn metadata(&self) -> Option<serde_json::Value> {
+        let output = Command::new(env::var("CARGO").unwrap_or("cargo".into()))
+            .args(["metadata", "--format-version", "1"])
+            .args(self.manifest_path().map(|path| format!("--manifest-path={path}")))
+            .args(
+                self.args
+                    .iter()
+                    .filter(|arg| matches!(arg.as_str(), "--frozen" | "--locked" | "--offline")),
+            )
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| serde_json::from_slice(&output.stdout).ok())?
+    }
+
+    fn manifest_path(&self) -> Option<&str> {
+        self.args.iter().enumerate().find_map(|(i, arg)| {
+            arg.strip_prefix("--manifest-path=")
+                .or_else(|| (arg == "--manifest-path").then(|| self.args.get(i + 1).map(String::as_str))?)
+        })
+    }
+
+    /// The directory to look up the configuration file from.
+    fn conf_dir(&self) -> PathBuf {
+        match self.manifest_path().map(Path::new).and_then(Path::parent) {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => env::current_dir().expect("current directory invalid"),
         }
     }
 
@@ -100,38 +155,211 @@ impl RplCmd {
         path
     }
 
-    fn into_std_cmd(self) -> Command {
+    fn into_std_cmd(
+        self,
+        conf: &Conf,
+        json_messages: bool,
+        metadata: Option<&serde_json::Value>,
+        audit_dir: Option<&Path>,
+    ) -> Command {
         let mut cmd = Command::new(env::var("CARGO").unwrap_or("cargo".into()));
         let rpl_args: String = self
             .rpl_args
             .iter()
             .fold(String::new(), |s, arg| s + arg + "__RPL_HACKERY__");
 
-        cmd.env("RUSTC_WORKSPACE_WRAPPER", Self::path())
+        // Dependencies are only compiled with `RUSTC_WRAPPER`, not `RUSTC_WORKSPACE_WRAPPER`
+        let wrapper = if audit_dir.is_some() {
+            "RUSTC_WRAPPER"
+        } else {
+            "RUSTC_WORKSPACE_WRAPPER"
+        };
+        cmd.env(wrapper, Self::path())
             .env("RPL_ARGS", rpl_args)
             .arg(self.cargo_subcommand)
             .args(&self.args);
 
+        // Let the driver use the same configuration, even when checking dependencies
+        if let Some(root) = conf.root() {
+            cmd.env(rpl_config::CONF_DIR_ENV, root);
+        }
+
+        if let Some(audit_dir) = audit_dir {
+            cmd.env(rpl_config::AUDIT_DIR_ENV, audit_dir);
+        }
+
+        // Let the driver know the versions of the dependencies for patterns with `#[applies_to]`
+        if let Some(crate_versions) = metadata.and_then(crate_versions) {
+            cmd.env(rpl_config::CRATE_VERSIONS_ENV, crate_versions);
+        }
+
+        if json_messages {
+            let format = if std::io::stderr().is_terminal() {
+                "--message-format=json-diagnostic-rendered-ansi"
+            } else {
+                "--message-format=json"
+            };
+            cmd.arg(format).stdout(Stdio::piped());
+        }
+
         cmd
     }
 }
 
+/// Filter and print the JSON messages of cargo, according to the output format and the baseline.
+struct Messages {
+    format: OutputFormat,
+    baseline: Baseline,
+    /// All findings, if the baseline is being updated.
+    recorded: Option<Baseline>,
+    /// Findings in dependencies, if they are being audited.
+    audit: Option<AuditReport>,
+    /// The errors reported, and the errors hidden as they are in the baseline.
+    errors: usize,
+    baselined_errors: usize,
+}
+
+impl Messages {
+    fn handle_line(&mut self, line: &str) {
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(line) else {
+            // Not a message of cargo, e.g. printed by a build script
+            println!("{line}");
+            return;
+        };
+        if self.audit.as_mut().is_some_and(|audit| audit.record(&message)) {
+            return;
+        }
+        if message.get("reason").and_then(serde_json::Value::as_str) != Some("compiler-message") {
+            return;
+        }
+        let Some(diag) = message.get("message") else {
+            return;
+        };
+        let is_error = diag
+            .get("level")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|level| level.starts_with("error"));
+        if let Some(entry) = BaselineEntry::from_diagnostic(diag) {
+            if let Some(recorded) = &mut self.recorded {
+                recorded.insert(entry.clone());
+            }
+            if self.baseline.remove(&entry) {
+                self.baselined_errors += usize::from(is_error);
+                return;
+            }
+        }
+        self.errors += usize::from(is_error);
+        match self.format {
+            OutputFormat::Human => {
+                if let Some(rendered) = diag.get("rendered").and_then(serde_json::Value::as_str) {
+                    eprint!("{rendered}");
+                }
+            },
+            OutputFormat::Json => println!("{diag}"),
+        }
+    }
+}
+
+/// The directory `rpl-driver` writes the summaries of the audited dependencies to, in the target
+/// directory of cargo.
+fn audit_dir(metadata: Option<&serde_json::Value>) -> PathBuf {
+    let target_dir = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(|| metadata?.get("target_directory")?.as_str().map(PathBuf::from));
+    target_dir.unwrap_or_else(|| PathBuf::from("target")).join("rpl-audit")
+}
+
+/// The resolved versions of all packages, as comma-separated `name@version`s.
+fn crate_versions(metadata: &serde_json::Value) -> Option<String> {
+    let packages = metadata.get("packages")?.as_array()?;
+    let crate_versions = packages
+        .iter()
+        .filter_map(|package| {
+            let name = package.get("name")?.as_str()?;
+            let version = package.get("version")?.as_str()?;
+            Some(format!("{name}@{version}"))
+        })
+        .collect::<Vec<_>>();
+    Some(crate_versions.join(","))
+}
+
+fn baseline_path(conf: &Conf) -> PathBuf {
+    conf.resolve(conf.baseline.as_deref().unwrap_or(Path::new("rpl-baseline.json")))
+}
+
 fn process<I>(old_args: I) -> Result<(), i32>
 where
     I: Iterator<Item = String>,
 {
     let cmd = RplCmd::new(old_args);
 
-    let mut cmd = cmd.into_std_cmd();
+    // Dependencies are audited with `RUSTC_WRAPPER`, which would replace the user's wrapper.
+    if let Some(wrapper) = env::var_os("RUSTC_WRAPPER").filter(|wrapper| cmd.audit_deps && !wrapper.is_empty()) {
+        eprintln!(
+            "error: `--audit-deps` cannot be used with `RUSTC_WRAPPER` set to `{}`, unset it to audit the dependencies",
+            Path::new(&wrapper).display()
+        );
+        return Err(1);
+    }
+
+    let conf = Conf::lookup_and_read(&cmd.conf_dir()).map_err(|err| {
+        eprintln!("error: failed to load RPL configuration: {err}");
+        1
+    })?;
+    let baseline = match &conf.baseline {
+        Some(_) if !cmd.update_baseline => Baseline::read(&baseline_path(&conf)).map_err(|err| {
+            eprintln!("error: failed to load RPL baseline: {err}");
+            1
+        })?,
+        _ => Baseline::default(),
+    };
+    let mut messages = Messages {
+        format: conf.output_format,
+        baseline,
+        recorded: cmd.update_baseline.then(Baseline::default),
+        audit: cmd.audit_deps.then(AuditReport::default),
+        errors: 0,
+        baselined_errors: 0,
+    };
+    let json_messages = messages.format == OutputFormat::Json
+        || !messages.baseline.is_empty()
+        || messages.recorded.is_some()
+        || messages.audit.is_some();
+    let metadata = cmd.metadata();
+    let audit_dir = cmd.audit_deps.then(|| audit_dir(metadata.as_ref()));
+
+    let mut cmd = cmd.into_std_cmd(&conf, json_messages, metadata.as_ref(), audit_dir.as_deref());
+
+    let mut child = cmd.spawn().expect("could not run cargo");
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            messages.handle_line(&line.expect("failed to read the output of cargo"));
+        }
+    }
+    let exit_status = child.wait().expect("failed to wait for cargo?");
+
+    if let (Some(audit), Some(audit_dir)) = (&messages.audit, &audit_dir) {
+        audit.print(audit_dir, messages.format == OutputFormat::Json);
+    }
 
-    let exit_status = cmd
-        .spawn()
-        .expect("could not run cargo")
-        .wait()
-        .expect("failed to wait for cargo?");
+    if let Some(recorded) = &messages.recorded {
+        let path = baseline_path(&conf);
+        if let Err(err) = recorded.write(&path) {
+            eprintln!("error: failed to write RPL baseline: {err}");
+            return Err(1);
+        }
+        eprintln!("note: recorded {} findings in `{}`", recorded.len(), path.display());
+    }
 
     if exit_status.success() {
         Ok(())
+    } else if messages.errors == 0 && messages.baselined_errors > 0 {
+        // The build only failed because of the denied findings in the baseline.
+        eprintln!(
+            "note: the only errors are {} findings in the baseline, so the build does not fail",
+            messages.baselined_errors
+        );
+        Ok(())
     } else {
         Err(exit_status.code().unwrap_or(-1))
     }
@@ -148,6 +376,9 @@ pub fn help_message() -> &'static str {
 <green,bold>Common options:</>
     <cyan,bold>--no-deps</>                Run RPL only on the given crate, without linting the dependencies
     <cyan,bold>--fix</>                    Automatically apply lint suggestions. This flag implies <cyan>--no-deps</> and <cyan>--all-targets</>
+    <cyan,bold>--update-baseline</>        Record all findings in the baseline file, so that they are not reported again
+    <cyan,bold>--audit-deps</>             Also run RPL on all dependencies and print a report of their findings and usage of
+                             <cyan>unsafe</>, without failing the build on findings in dependencies
     <cyan,bold>-h</>, <cyan,bold>--help</>               Print this message
     <cyan,bold>-V</>, <cyan,bold>--version</>            Print version info and exit
     <cyan,bold>--explain [LINT]</>         Print the documentation for a given lint
@@ -163,6 +394,12 @@ To allow or deny a lint from the command line you can use <cyan,bold>cargo rpl -
     <cyan,bold>-D</> / <cyan,bold>--deny</> <cyan>[LINT]</>       Set lint denied
     <cyan,bold>-F</> / <cyan,bold>--forbid</> <cyan>[LINT]</>     Set lint forbidden
 
+<green,bold>Configuration</>
+
+RPL reads its configuration from <cyan>rpl.toml</> or the <cyan>[workspace.metadata.rpl]</> section of
+the workspace <cyan>Cargo.toml</>, including the detectors to run, lint levels, paths to exclude,
+the output format and the baseline file.
+
 <green,bold>Manifest Options:</>
     <cyan,bold>--manifest-path</> <cyan><<PATH>></>  Path to Cargo.toml
     <cyan,bold>--frozen</>                Require Cargo.lock and cache are up to date
@@ -198,6 +435,82 @@ mod tests {
         assert_eq!(cmd.rpl_args.iter().filter(|arg| *arg == "--no-deps").count(), 1);
     }
 
+    #[test]
+    fn update_baseline() {
+        let args = "cargo rpl --update-baseline -- -D rpl::use_after_drop"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = RplCmd::new(args);
+        assert!(cmd.update_baseline);
+        assert!(!cmd.args.iter().any(|arg| arg == "--update-baseline"));
+        assert_eq!(cmd.rpl_args, ["-D", "rpl::use_after_drop"]);
+    }
+
+    #[test]
+    fn audit_deps() {
+        let args = "cargo rpl --audit-deps".split_whitespace().map(ToString::to_string);
+        let cmd = RplCmd::new(args);
+        assert!(cmd.audit_deps);
+        assert!(!cmd.args.iter().any(|arg| arg == "--audit-deps"));
+        assert_eq!(cmd.rpl_args, ["--audit-deps"]);
+    }
+
+    #[test]
+    fn parse_package_id() {
+        use super::audit::parse_package_id;
+
+        let package = |name: &str, version: &str, local| Some(((name.to_owned(), version.to_owned()), local));
+        assert_eq!(
+            parse_package_id("registry+https://github.com/rust-lang/crates.io-index#libc@0.2.169"),
+            package("libc", "0.2.169", false)
+        );
+        assert_eq!(
+            parse_package_id("path+file:///path/to/foo#0.1.0"),
+            package("foo", "0.1.0", true)
+        );
+        assert_eq!(
+            parse_package_id("git+https://github.com/foo/bar?branch=main#baz@0.2.0"),
+            package("baz", "0.2.0", false)
+        );
+        assert_eq!(
+            parse_package_id("libc 0.2.169 (registry+https://github.com/rust-lang/crates.io-index)"),
+            package("libc", "0.2.169", false)
+        );
+        assert_eq!(
+            parse_package_id("foo 0.1.0 (path+file:///path/to/foo)"),
+            package("foo", "0.1.0", true)
+        );
+    }
+
+    #[test]
+    fn audit_summaries_of_built_artifacts() {
+        use super::audit::AuditReport;
+        use rpl_config::{CrateSummary, UnsafeStats};
+
+        let mut report = AuditReport::default();
+        let message = serde_json::json!({
+            "reason": "compiler-artifact",
+            "package_id": "registry+https://github.com/rust-lang/crates.io-index#libc@0.2.169",
+            "filenames": [
+                "/target/debug/deps/liblibc-0123456789abcdef.rmeta",
+                "/target/debug/build/libc-fedcba9876543210/build_script_build-fedcba9876543210",
+            ],
+        });
+        assert!(report.record(&message));
+
+        let summary = |krate: &str, extra_filename: &str| CrateSummary {
+            package: "libc".to_owned(),
+            version: "0.2.169".to_owned(),
+            krate: krate.to_owned(),
+            extra_filename: extra_filename.to_owned(),
+            stats: UnsafeStats::default(),
+        };
+        assert!(report.is_built(&summary("libc", "-0123456789abcdef")));
+        assert!(report.is_built(&summary("build_script_build", "-fedcba9876543210")));
+        // Left by a previous run, e.g. with other features enabled.
+        assert!(!report.is_built(&summary("libc", "-00000000deadbeef")));
+    }
+
     #[test]
     fn check() {
         let args = "cargo rpl".split_whitespace().map(ToString::to_string);
@@ -0,0 +1,67 @@
+//! Runs `cargo rpl` on small crates, to check its exit status.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn cargo_rpl(dir: &Path, args: &[&str]) -> bool {
+    cargo_rpl_with(dir, args, |_| {})
+}
+
+fn cargo_rpl_with(dir: &Path, args: &[&str], configure: impl FnOnce(&mut Command)) -> bool {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_cargo-rpl"));
+    cmd.arg("rpl")
+        .args(args)
+        .current_dir(dir)
+        .env("CARGO_TARGET_DIR", dir.join("target"));
+    configure(&mut cmd);
+    cmd.status().expect("failed to run cargo-rpl").success()
+}
+
+/// Create a crate named `name` in a temporary directory, with `lib` as its `src/lib.rs`.
+fn new_crate(name: &str, lib: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2024\"\n\n[workspace]\n"),
+    )
+    .unwrap();
+    fs::write(dir.join("src/lib.rs"), lib).unwrap();
+    dir
+}
+
+#[test]
+fn baselined_findings_do_not_fail_the_build() {
+    let lib = "#[inline]\nfn private() {}\n\npub fn public() {\n    private();\n}\n";
+    let dir = new_crate("baseline", lib);
+    let lints = "[lints]\nprivate_function_marked_inline = \"deny\"\n";
+    fs::write(dir.join("rpl.toml"), lints).unwrap();
+    assert!(!cargo_rpl(&dir, &[]), "the finding is denied");
+
+    fs::write(
+        dir.join("rpl.toml"),
+        format!("baseline = \"rpl-baseline.json\"\n\n{lints}"),
+    )
+    .unwrap();
+    cargo_rpl(&dir, &["--update-baseline"]);
+    assert!(dir.join("rpl-baseline.json").exists());
+    assert!(cargo_rpl(&dir, &[]), "the finding is in the baseline");
+
+    fs::write(
+        dir.join("src/lib.rs"),
+        format!("{lib}\n#[inline]\nfn another() {{}}\n\npub fn other() {{\n    another();\n}}\n"),
+    )
+    .unwrap();
+    assert!(!cargo_rpl(&dir, &[]), "the new finding is not in the baseline");
+}
+
+#[test]
+fn audit_deps_rejects_rustc_wrapper() {
+    let dir = new_crate("audit_deps_wrapper", "");
+    assert!(!cargo_rpl_with(&dir, &["--audit-deps"], |cmd| {
+        cmd.env("RUSTC_WRAPPER", "sccache");
+    }));
+    assert!(!dir.join("target").exists(), "cargo is not run");
+}
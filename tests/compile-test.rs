@@ -19,6 +19,7 @@ use std::path::{Path, PathBuf};
 
 extern crate byte_slice_cast;
 extern crate bytes;
+extern crate crossbeam_utils;
 // extern crate cassandra_cpp_sys;
 extern crate futures;
 extern crate if_chain;
@@ -44,6 +45,7 @@ mod test_utils;
 static TEST_DEPENDENCIES: &[&str] = &[
     "byte_slice_cast",
     "bytes",
+    "crossbeam_utils",
     "futures",
     "if_chain",
     "itertools",
@@ -0,0 +1,23 @@
+//@compile-flags: -Z inline-mir=false
+//@edition: 2021
+
+use std::future::{Future, ready};
+use std::slice;
+
+pub fn copy_from_raw_parts_async_closure<I, IU>(src: I, mut src_linesize: IU) -> impl Future<Output = ()>
+where
+    I: Iterator<Item = *const u8>,
+    IU: Iterator<Item = usize>,
+{
+    let copy = async move || {
+        let mut src = src;
+        //~^ERROR: it is unsound to trust pointers from passed-in iterators in a public safe function
+        let rr = src.next().unwrap();
+        ready(()).await;
+        let s_linesize = src_linesize.next().unwrap();
+        let ss = unsafe { slice::from_raw_parts(rr, s_linesize) };
+    };
+    copy()
+}
+
+fn main() {}
@@ -1,5 +1,3 @@
-//@ ignore-on-host
-
 use std::{mem::MaybeUninit, ptr};
 
 pub trait Unsigned: Copy + Default + 'static {
@@ -75,28 +73,37 @@ where
     /// Get the length of the chunk.
     #[inline]
     pub fn len(&self) -> usize {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         self.right - self.left
     }
 
     #[inline]
     unsafe fn force_write(index: usize, value: A, chunk: &mut Self) {
-        chunk.mut_ptr(index).write(value)
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+        //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
+        unsafe { chunk.mut_ptr(index).write(value) }
     }
 
     #[inline]
     unsafe fn mut_ptr(&mut self, index: usize) -> *mut A {
-        (&mut self.data as *mut _ as *mut A).add(index)
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+        //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
+        unsafe { (&mut self.data as *mut _ as *mut A).add(index) }
     }
 
     #[inline]
     unsafe fn ptr(&self, index: usize) -> *const A {
-        (&self.data as *const _ as *const A).add(index)
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+        //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
+        unsafe { (&self.data as *const _ as *const A).add(index) }
     }
 
     #[inline]
     unsafe fn force_copy(from: usize, to: usize, count: usize, chunk: &mut Self) {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+        //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
         if count > 0 {
-            ptr::copy(chunk.ptr(from), chunk.mut_ptr(to), count)
+            unsafe { ptr::copy(chunk.ptr(from), chunk.mut_ptr(to), count) }
         }
     }
 
@@ -125,6 +132,7 @@ where
                 let mut write_index = real_index - insert_size;
                 for value in iter {
                     Chunk::force_write(write_index, value, self);
+                    //~^ ERROR: it is unsound to trust return value of `std::iter::ExactSizeIterator::len` and write the items through a raw pointer
                     write_index += 1;
                 }
             }
@@ -135,6 +143,7 @@ where
                 let mut write_index = real_index;
                 for value in iter {
                     Chunk::force_write(write_index, value, self);
+                    //~^ ERROR: it is unsound to trust return value of `std::iter::ExactSizeIterator::len` and write the items through a raw pointer
                     write_index += 1;
                 }
             }
@@ -146,6 +155,7 @@ where
                 let mut write_index = left_size;
                 for value in iter {
                     Chunk::force_write(write_index, value, self);
+                    //~^ ERROR: it is unsound to trust return value of `std::iter::ExactSizeIterator::len` and write the items through a raw pointer
                     write_index += 1;
                 }
             }
@@ -155,3 +165,18 @@ where
         }
     }
 }
+
+pub fn extend_vec<A, I>(buf: &mut Vec<A>, iter: I)
+where
+    I: ExactSizeIterator<Item = A>,
+{
+    let len = iter.len();
+    buf.reserve(len);
+    let mut write_index = buf.len();
+    for value in iter {
+        unsafe { buf.as_mut_ptr().add(write_index).write(value) };
+        //~^ ERROR: it is unsound to trust return value of `std::iter::ExactSizeIterator::len` and write the items through a raw pointer
+        write_index += 1;
+    }
+    unsafe { buf.set_len(write_index) };
+}
@@ -0,0 +1,70 @@
+//@check-pass
+
+use std::mem::MaybeUninit;
+
+pub struct Chunk<A> {
+    len: usize,
+    data: [MaybeUninit<A>; 64],
+}
+
+impl<A> Chunk<A> {
+    pub const CAPACITY: usize = 64;
+
+    pub fn extend_from<Iterable, I>(&mut self, iter: Iterable)
+    where
+        Iterable: IntoIterator<Item = A, IntoIter = I>,
+        I: ExactSizeIterator<Item = A>,
+    {
+        let iter = iter.into_iter();
+        let insert_size = iter.len();
+        if self.len + insert_size > Self::CAPACITY {
+            panic!("Chunk::extend_from: chunk cannot fit {} elements", insert_size);
+        }
+        let mut write_index = self.len;
+        // At most `insert_size` items are written, even if `ExactSizeIterator::len` is wrong.
+        for value in iter.take(insert_size) {
+            unsafe { self.data.as_mut_ptr().add(write_index).cast::<A>().write(value) };
+            write_index += 1;
+        }
+        self.len = write_index;
+    }
+
+    pub fn extend_bounded<Iterable, I>(&mut self, iter: Iterable)
+    where
+        Iterable: IntoIterator<Item = A, IntoIter = I>,
+        I: ExactSizeIterator<Item = A>,
+    {
+        let iter = iter.into_iter();
+        let insert_size = iter.len();
+        if self.len + insert_size > Self::CAPACITY {
+            panic!("Chunk::extend_bounded: chunk cannot fit {} elements", insert_size);
+        }
+        let mut write_index = self.len;
+        let end = write_index + insert_size;
+        // The loop stops after `insert_size` items, even if `ExactSizeIterator::len` is wrong.
+        for value in iter {
+            if write_index < end {
+                unsafe { self.data.as_mut_ptr().add(write_index).cast::<A>().write(value) };
+                write_index += 1;
+            } else {
+                break;
+            }
+        }
+        self.len = write_index;
+    }
+}
+
+/// The writes are not sized by the length, which is only returned as a hint.
+pub fn fill_fixed<A, I>(buf: &mut [A; 16], iter: I) -> usize
+where
+    I: ExactSizeIterator<Item = A>,
+{
+    let hint = iter.len();
+    let ptr = buf.as_mut_ptr();
+    let mut index = 0;
+    for value in iter {
+        unsafe { ptr.add(index).write(value) };
+        index += 1;
+    }
+    hint
+}
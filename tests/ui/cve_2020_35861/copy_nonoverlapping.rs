@@ -0,0 +1,55 @@
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use std::alloc::{self, Layout};
+use std::ptr::{self, NonNull};
+
+pub struct Heap;
+
+impl Heap {
+    /// `ptr::copy_nonoverlapping` is inlined into the `copy_nonoverlapping` intrinsic with
+    /// `-Z inline-mir`.
+    pub unsafe fn realloc(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        unsafe {
+            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+            let new_ptr = NonNull::new_unchecked(alloc::alloc(new_layout));
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_size);
+            //~^ ERROR: the new size of a reallocated memory block is copied out of the old one
+            alloc::dealloc(ptr.as_ptr(), layout);
+            new_ptr
+        }
+    }
+
+    pub unsafe fn realloc_min(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        unsafe {
+            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+            let new_ptr = NonNull::new_unchecked(alloc::alloc(new_layout));
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_size.min(layout.size()));
+            alloc::dealloc(ptr.as_ptr(), layout);
+            new_ptr
+        }
+    }
+    /// The new size is copied after a header is added to it.
+    pub unsafe fn realloc_with_header(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        unsafe {
+            let total = new_size + 16;
+            let new_layout = Layout::from_size_align_unchecked(total, layout.align());
+            let new_ptr = NonNull::new_unchecked(alloc::alloc(new_layout));
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), total);
+            //~^ ERROR: the new size of a reallocated memory block is copied out of the old one
+            alloc::dealloc(ptr.as_ptr(), layout);
+            new_ptr
+        }
+    }
+
+    /// The count is bounded by `cmp::min`, here by the size of the smallest blocks allocated.
+    pub unsafe fn realloc_block(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        unsafe {
+            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+            let new_ptr = NonNull::new_unchecked(alloc::alloc(new_layout));
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), std::cmp::min(new_size, 64));
+            alloc::dealloc(ptr.as_ptr(), layout);
+            new_ptr
+        }
+    }
+}
@@ -1,5 +1,3 @@
-//@ ignore-on-host
-
 extern crate alloc as core_alloc;
 
 mod alloc {
@@ -12,7 +10,7 @@ mod alloc {
     use core::ptr::{self, NonNull};
     use core::usize;
 
-    pub use core::alloc::{Layout, LayoutErr};
+    pub use core::alloc::{Layout, LayoutError as LayoutErr};
 
     fn new_layout_err() -> LayoutErr {
         Layout::from_size_align(1, 3).unwrap_err()
@@ -99,6 +97,7 @@ mod alloc {
 
         #[inline]
         fn usable_size(&self, layout: &Layout) -> (usize, usize) {
+            //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
             (layout.size(), layout.size())
         }
 
@@ -108,44 +107,50 @@ mod alloc {
             layout: Layout,
             new_size: usize,
         ) -> Result<NonNull<u8>, AllocErr> {
-            let old_size = layout.size();
-
-            if new_size >= old_size {
-                if let Ok(()) = self.grow_in_place(ptr, layout, new_size) {
-                    return Ok(ptr);
-                }
-            } else if new_size < old_size {
-                if let Ok(()) = self.shrink_in_place(ptr, layout, new_size) {
-                    return Ok(ptr);
+            unsafe {
+                let old_size = layout.size();
+
+                if new_size >= old_size {
+                    if let Ok(()) = self.grow_in_place(ptr, layout, new_size) {
+                        return Ok(ptr);
+                    }
+                } else if new_size < old_size {
+                    if let Ok(()) = self.shrink_in_place(ptr, layout, new_size) {
+                        return Ok(ptr);
+                    }
                 }
-            }
 
-            // otherwise, fall back on alloc + copy + dealloc.
-            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
-            let result = self.alloc(new_layout);
-            if let Ok(new_ptr) = result {
-                ptr::copy_nonoverlapping(
-                    ptr.as_ptr(),
-                    new_ptr.as_ptr(),
-                    cmp::min(old_size, new_size),
-                );
-                self.dealloc(ptr, layout);
+                // otherwise, fall back on alloc + copy + dealloc.
+                let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+                let result = self.alloc(new_layout);
+                if let Ok(new_ptr) = result {
+                    ptr::copy_nonoverlapping(
+                        ptr.as_ptr(),
+                        new_ptr.as_ptr(),
+                        cmp::min(old_size, new_size),
+                    );
+                    self.dealloc(ptr, layout);
+                }
+                result
             }
-            result
         }
 
         unsafe fn alloc_zeroed(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
-            let size = layout.size();
-            let p = self.alloc(layout);
-            if let Ok(p) = p {
-                ptr::write_bytes(p.as_ptr(), 0, size);
+            unsafe {
+                let size = layout.size();
+                let p = self.alloc(layout);
+                if let Ok(p) = p {
+                    ptr::write_bytes(p.as_ptr(), 0, size);
+                }
+                p
             }
-            p
         }
 
         unsafe fn alloc_excess(&mut self, layout: Layout) -> Result<Excess, AllocErr> {
-            let usable_size = self.usable_size(&layout);
-            self.alloc(layout).map(|p| Excess(p, usable_size.1))
+            unsafe {
+                let usable_size = self.usable_size(&layout);
+                self.alloc(layout).map(|p| Excess(p, usable_size.1))
+            }
         }
 
         unsafe fn realloc_excess(
@@ -154,10 +159,12 @@ mod alloc {
             layout: Layout,
             new_size: usize,
         ) -> Result<Excess, AllocErr> {
-            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
-            let usable_size = self.usable_size(&new_layout);
-            self.realloc(ptr, layout, new_size)
-                .map(|p| Excess(p, usable_size.1))
+            unsafe {
+                let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+                let usable_size = self.usable_size(&new_layout);
+                self.realloc(ptr, layout, new_size)
+                    .map(|p| Excess(p, usable_size.1))
+            }
         }
 
         unsafe fn grow_in_place(
@@ -212,9 +219,11 @@ mod alloc {
         where
             Self: Sized,
         {
-            let k = Layout::new::<T>();
-            if k.size() > 0 {
-                self.dealloc(ptr.cast(), k);
+            unsafe {
+                let k = Layout::new::<T>();
+                if k.size() > 0 {
+                    self.dealloc(ptr.cast(), k);
+                }
             }
         }
 
@@ -237,13 +246,15 @@ mod alloc {
         where
             Self: Sized,
         {
-            match (Layout::array::<T>(n_old), Layout::array::<T>(n_new)) {
-                (Ok(ref k_old), Ok(ref k_new)) if k_old.size() > 0 && k_new.size() > 0 => {
-                    debug_assert!(k_old.align() == k_new.align());
-                    self.realloc(ptr.cast(), k_old.clone(), k_new.size())
-                        .map(NonNull::cast)
+            unsafe {
+                match (Layout::array::<T>(n_old), Layout::array::<T>(n_new)) {
+                    (Ok(ref k_old), Ok(ref k_new)) if k_old.size() > 0 && k_new.size() > 0 => {
+                        debug_assert!(k_old.align() == k_new.align());
+                        self.realloc(ptr.cast(), k_old.clone(), k_new.size())
+                            .map(NonNull::cast)
+                    }
+                    _ => Err(AllocErr),
                 }
-                _ => Err(AllocErr),
             }
         }
 
@@ -251,12 +262,14 @@ mod alloc {
         where
             Self: Sized,
         {
-            match Layout::array::<T>(n) {
-                Ok(k) if k.size() > 0 => {
-                    self.dealloc(ptr.cast(), k);
-                    Ok(())
+            unsafe {
+                match Layout::array::<T>(n) {
+                    Ok(k) if k.size() > 0 => {
+                        self.dealloc(ptr.cast(), k);
+                        Ok(())
+                    }
+                    _ => Err(AllocErr),
                 }
-                _ => Err(AllocErr),
             }
         }
     }
@@ -310,9 +323,12 @@ impl Drop for Bump {
 
 #[inline]
 unsafe fn dealloc_chunk_list(mut footer: Option<NonNull<ChunkFooter>>) {
-    while let Some(f) = footer {
-        footer = f.as_ref().prev.get();
-        dealloc(f.as_ref().data.as_ptr(), f.as_ref().layout);
+    //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+    unsafe {
+        while let Some(f) = footer {
+            footer = f.as_ref().prev.get();
+            dealloc(f.as_ref().data.as_ptr(), f.as_ref().layout);
+        }
     }
 }
 
@@ -320,6 +336,7 @@ unsafe impl Send for Bump {}
 
 #[inline]
 pub(crate) fn round_up_to(n: usize, divisor: usize) -> Option<usize> {
+    //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
     debug_assert!(divisor > 0);
     debug_assert!(divisor.is_power_of_two());
     Some(n.checked_add(divisor - 1)? & !(divisor - 1))
@@ -354,6 +371,8 @@ const DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER: usize = FIRST_ALLOCATION_GOAL - OVERHEA
 
 #[inline]
 fn layout_for_array<T>(len: usize) -> Option<Layout> {
+    //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+    //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
     let layout = Layout::new::<T>();
     let size_rounded_up = round_up_to(layout.size(), layout.align())?;
     let total_size = len.checked_mul(size_rounded_up)?;
@@ -364,10 +383,13 @@ fn layout_for_array<T>(len: usize) -> Option<Layout> {
 /// Wrapper around `Layout::from_size_align` that adds debug assertions.
 #[inline]
 unsafe fn layout_from_size_align(size: usize, align: usize) -> Layout {
-    if cfg!(debug_assertions) {
-        Layout::from_size_align(size, align).unwrap()
-    } else {
-        Layout::from_size_align_unchecked(size, align)
+    //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+    unsafe {
+        if cfg!(debug_assertions) {
+            Layout::from_size_align(size, align).unwrap()
+        } else {
+            Layout::from_size_align_unchecked(size, align)
+        }
     }
 }
 
@@ -528,6 +550,7 @@ impl Bump {
     #[inline(always)]
     #[allow(clippy::mut_from_ref)]
     pub fn alloc<T>(&self, val: T) -> &mut T {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         self.alloc_with(|| val)
     }
 
@@ -537,12 +560,19 @@ impl Bump {
     where
         F: FnOnce() -> T,
     {
+        //~^^^^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         #[inline(always)]
         unsafe fn inner_writer<T, F>(ptr: *mut T, f: F)
         where
             F: FnOnce() -> T,
         {
-            ptr::write(ptr, f())
+            //~^^^^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+            //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
+            //~| ERROR: it usually isn't necessary to apply #[inline] to private functions
+            //~| ERROR: it usually isn't necessary to apply #[inline] to generic functions
+            unsafe {
+                ptr::write(ptr, f())
+            }
         }
 
         let layout = Layout::new::<T>();
@@ -561,6 +591,7 @@ impl Bump {
     where
         T: Copy,
     {
+        //~^^^^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         let layout = Layout::for_value(src);
         let dst = self.alloc_layout(layout).cast::<T>();
 
@@ -576,6 +607,7 @@ impl Bump {
     where
         T: Clone,
     {
+        //~^^^^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         let layout = Layout::for_value(src);
         let dst = self.alloc_layout(layout).cast::<T>();
 
@@ -604,6 +636,7 @@ impl Bump {
     where
         F: FnMut(usize) -> T,
     {
+        //~^^^^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         let layout = layout_for_array::<T>(len).unwrap_or_else(|| oom());
         let dst = self.alloc_layout(layout).cast::<T>();
 
@@ -621,12 +654,14 @@ impl Bump {
     #[inline(always)]
     #[allow(clippy::mut_from_ref)]
     pub fn alloc_slice_fill_copy<T: Copy>(&self, len: usize, value: T) -> &mut [T] {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         self.alloc_slice_fill_with(len, |_| value)
     }
 
     #[inline(always)]
     #[allow(clippy::mut_from_ref)]
     pub fn alloc_slice_fill_clone<T: Clone>(&self, len: usize, value: &T) -> &mut [T] {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         self.alloc_slice_fill_with(len, |_| value.clone())
     }
 
@@ -637,6 +672,7 @@ impl Bump {
         I: IntoIterator<Item = T>,
         I::IntoIter: ExactSizeIterator,
     {
+        //~^^^^^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         let mut iter = iter.into_iter();
         self.alloc_slice_fill_with(iter.len(), |_| {
             iter.next().expect("Iterator supplied too few elements")
@@ -646,6 +682,7 @@ impl Bump {
     #[inline(always)]
     #[allow(clippy::mut_from_ref)]
     pub fn alloc_slice_fill_default<T: Default>(&self, len: usize) -> &mut [T] {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         self.alloc_slice_fill_with(len, |_| T::default())
     }
 
@@ -660,6 +697,7 @@ impl Bump {
 
     #[inline(always)]
     fn try_alloc_layout_fast(&self, layout: Layout) -> Option<NonNull<u8>> {
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
         unsafe {
             if layout.size() == 0 {
                 let ptr = layout.align() as *mut u8;
@@ -759,9 +797,12 @@ impl Bump {
 
     #[inline]
     unsafe fn is_last_allocation(&self, ptr: NonNull<u8>) -> bool {
-        let footer = self.current_chunk_footer.get();
-        let footer = footer.as_ref();
-        footer.ptr.get() == ptr
+        //~^ ERROR: it usually isn't necessary to apply #[inline] to private functions
+        unsafe {
+            let footer = self.current_chunk_footer.get();
+            let footer = footer.as_ref();
+            footer.ptr.get() == ptr
+        }
     }
 }
 
@@ -806,11 +847,13 @@ unsafe impl<'a> alloc::Alloc for &'a Bump {
 
     #[inline]
     unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
-        // If the pointer is the last allocation we made, we can reuse the bytes,
-        // otherwise they are simply leaked -- at least until somebody calls reset().
-        if layout.size() != 0 && self.is_last_allocation(ptr) {
-            let ptr = NonNull::new_unchecked(ptr.as_ptr().add(layout.size()));
-            self.current_chunk_footer.get().as_ref().ptr.set(ptr);
+        unsafe {
+            // If the pointer is the last allocation we made, we can reuse the bytes,
+            // otherwise they are simply leaked -- at least until somebody calls reset().
+            if layout.size() != 0 && self.is_last_allocation(ptr) {
+                let ptr = NonNull::new_unchecked(ptr.as_ptr().add(layout.size()));
+                self.current_chunk_footer.get().as_ref().ptr.set(ptr);
+            }
         }
     }
 
@@ -821,51 +864,54 @@ unsafe impl<'a> alloc::Alloc for &'a Bump {
         layout: Layout,
         new_size: usize,
     ) -> Result<NonNull<u8>, alloc::AllocErr> {
-        let old_size = layout.size();
-
-        if old_size == 0 {
-            return self.alloc(layout);
-        }
-
-        if new_size <= old_size {
-            if self.is_last_allocation(ptr)
-                 // Only reclaim the excess space (which requires a copy) if it
-                 // is worth it: we are actually going to recover "enough" space
-                 // and we can do a non-overlapping copy.
-                 && new_size <= old_size / 2
-            {
-                let delta = old_size - new_size;
-                let footer = self.current_chunk_footer.get();
-                let footer = footer.as_ref();
-                footer
-                    .ptr
-                    .set(NonNull::new_unchecked(footer.ptr.get().as_ptr().add(delta)));
-                let new_ptr = footer.ptr.get();
-                // NB: we know it is non-overlapping because of the size check
-                // in the `if` condition.
-                ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_size);
-                return Ok(new_ptr);
-            } else {
-                return Ok(ptr);
+        unsafe {
+            let old_size = layout.size();
+
+            if old_size == 0 {
+                return self.alloc(layout);
             }
-        }
 
-        if self.is_last_allocation(ptr) {
-            // Try to allocate the delta size within this same block so we can
-            // reuse the currently allocated space.
-            let delta = new_size - old_size;
-            if let Some(p) =
-                self.try_alloc_layout_fast(layout_from_size_align(delta, layout.align()))
-            {
-                ptr::copy(ptr.as_ptr(), p.as_ptr(), new_size);
-                return Ok(p);
+            if new_size <= old_size {
+                if self.is_last_allocation(ptr)
+                     // Only reclaim the excess space (which requires a copy) if it
+                     // is worth it: we are actually going to recover "enough" space
+                     // and we can do a non-overlapping copy.
+                     && new_size <= old_size / 2
+                {
+                    let delta = old_size - new_size;
+                    let footer = self.current_chunk_footer.get();
+                    let footer = footer.as_ref();
+                    footer
+                        .ptr
+                        .set(NonNull::new_unchecked(footer.ptr.get().as_ptr().add(delta)));
+                    let new_ptr = footer.ptr.get();
+                    // NB: we know it is non-overlapping because of the size check
+                    // in the `if` condition.
+                    ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_size);
+                    return Ok(new_ptr);
+                } else {
+                    return Ok(ptr);
+                }
+            }
+
+            if self.is_last_allocation(ptr) {
+                // Try to allocate the delta size within this same block so we can
+                // reuse the currently allocated space.
+                let delta = new_size - old_size;
+                if let Some(p) =
+                    self.try_alloc_layout_fast(layout_from_size_align(delta, layout.align()))
+                {
+                    ptr::copy(ptr.as_ptr(), p.as_ptr(), new_size);
+                    //~^ ERROR: the new size of a reallocated memory block is copied out of the old one
+                    return Ok(p);
+                }
             }
-        }
 
-        // Fallback: do a fresh allocation and copy the existing data into it.
-        let new_layout = layout_from_size_align(new_size, layout.align());
-        let new_ptr = self.alloc_layout(new_layout);
-        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_size);
-        Ok(new_ptr)
+            // Fallback: do a fresh allocation and copy the existing data into it.
+            let new_layout = layout_from_size_align(new_size, layout.align());
+            let new_ptr = self.alloc_layout(new_layout);
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_size);
+            Ok(new_ptr)
+        }
     }
 }
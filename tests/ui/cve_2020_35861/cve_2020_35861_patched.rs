@@ -0,0 +1,67 @@
+//@check-pass
+
+use std::alloc::{self, Layout};
+use std::cell::Cell;
+use std::ptr::{self, NonNull};
+
+pub struct Bump {
+    start: NonNull<u8>,
+    ptr: Cell<NonNull<u8>>,
+    end: NonNull<u8>,
+}
+
+impl Bump {
+    fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        let ptr = self.ptr.get().as_ptr() as usize;
+        let start = (ptr + layout.align() - 1) & !(layout.align() - 1);
+        if start + layout.size() > self.end.as_ptr() as usize {
+            alloc::handle_alloc_error(layout);
+        }
+        let new_ptr = self.start.as_ptr().wrapping_add(start + layout.size() - self.start.as_ptr() as usize);
+        self.ptr.set(NonNull::new(new_ptr).unwrap());
+        NonNull::new(start as *mut u8).unwrap()
+    }
+
+    fn is_last_allocation(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        ptr.as_ptr().wrapping_add(layout.size()) == self.ptr.get().as_ptr()
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by `self` with `layout`.
+    pub unsafe fn realloc(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        let old_size = layout.size();
+
+        if new_size <= old_size {
+            if self.is_last_allocation(ptr, layout) && new_size <= old_size / 2 {
+                let new_ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr().add(old_size - new_size)) };
+                // The new size is checked to be not larger than the old one.
+                unsafe { ptr::copy(ptr.as_ptr(), new_ptr.as_ptr(), new_size) };
+                return new_ptr;
+            }
+            return ptr;
+        }
+
+        let new_layout = Layout::from_size_align(new_size, layout.align()).unwrap();
+        let new_ptr = self.alloc_layout(new_layout);
+        // Only the `old_size` bytes of the old allocation are copied.
+        unsafe { ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_size) };
+        new_ptr
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by `self` with `layout`.
+    pub unsafe fn realloc_min(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        let new_layout = Layout::from_size_align(new_size, layout.align()).unwrap();
+        let new_ptr = self.alloc_layout(new_layout);
+        unsafe {
+            ptr::copy_nonoverlapping(
+                ptr.as_ptr(),
+                new_ptr.as_ptr(),
+                std::cmp::min(layout.size(), new_size),
+            )
+        };
+        new_ptr
+    }
+}
@@ -169,7 +169,11 @@ where
         //~| NOTE: generic functions are always `#[inline]` (monomorphization)
         //~| NOTE: `-D rpl::generic-function-marked-inline` implied by `-D warnings`
         //~| HELP: to override `-D warnings` add `#[allow(rpl::generic_function_marked_inline)]`
+        //~| NOTE: the lifetime of the returned reference is `'static`, or is not tied to any input
         unsafe { slice::from_raw_parts(self.pointer().r, self.elements()) }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by creating a slice from a raw pointer
+        //~| HELP: tie the lifetime of the returned reference to the borrow it is derived from
+        //~| NOTE: `#[deny(rpl::returned_ref_with_unbounded_lifetime)]` on by default
     }
 
     /// Accesses the element slice behind the pointer as a Rust mutable slice.
@@ -190,7 +194,10 @@ where
         //~^ ERROR: it usually isn't necessary to apply #[inline] to generic functions
         //~| HELP: See https://matklad.github.io/2021/07/09/inline-in-rust.html and https://rustc-dev-guide.rust-lang.org/backend/monomorph.html
         //~| NOTE: generic functions are always `#[inline]` (monomorphization)
+        //~| NOTE: the lifetime of the returned reference is `'static`, or is not tied to any input
         unsafe { slice::from_raw_parts_mut(self.pointer().w, self.elements()) }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by creating a slice from a raw pointer
+        //~| HELP: tie the lifetime of the returned reference to the borrow it is derived from
     }
 
     pub fn elements(&self) -> usize {
@@ -247,11 +254,14 @@ where
     }
 
     pub(crate) fn into_bitslice_mut<'a, O>(self) -> &'a mut BitSlice<O, T>
+    //~^ NOTE: the lifetime of the returned reference is `'static`, or is not tied to any input
     where
         O: BitOrder,
     {
         unsafe {
             &mut *(slice::from_raw_parts_mut(
+            //~^ ERROR: returning a reference with an unbounded lifetime, obtained by dereferencing a raw pointer
+            //~| HELP: tie the lifetime of the returned reference to the borrow it is derived from
                 Pointer::from(self.ptr.as_ptr()).w() as *mut (),
                 self.len,
             ) as *mut [()] as *mut BitSlice<O, T>)
@@ -0,0 +1,39 @@
+//@compile-flags: -Z inline-mir=false
+use std::mem;
+
+pub struct Elem<K, V> {
+    key: K,
+    value: V,
+    hash: u64,
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    elem: &'a mut Elem<K, V>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.elem.key
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.elem.hash
+    }
+
+    pub fn into_mut(self) -> &'static mut V {
+        let value: *mut V = &mut self.elem.value;
+        unsafe { &mut *value }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by dereferencing a raw pointer
+    }
+
+    pub fn get_unbounded<'b>(&self) -> &'b V {
+        let value: *const V = &self.elem.value;
+        unsafe { &*value }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by dereferencing a raw pointer
+    }
+
+    pub fn hash_bytes<'b>(&self) -> &'b [u8; 8] {
+        unsafe { mem::transmute::<&u64, &[u8; 8]>(&self.elem.hash) }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by transmuting a reference
+    }
+}
@@ -0,0 +1,49 @@
+//@compile-flags: -Z inline-mir=false
+//@check-pass
+pub struct Elem<K, V> {
+    key: K,
+    value: V,
+    hash: u64,
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    elem: &'a mut Elem<K, V>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.elem.key
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.elem.hash
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        let value: *mut V = &mut self.elem.value;
+        unsafe { &mut *value }
+    }
+
+    pub fn get(&self) -> &V {
+        let value: *const V = &self.elem.value;
+        unsafe { &*value }
+    }
+
+    pub fn get_static(&self) -> &'static u64 {
+        &0
+    }
+
+    /// Not derived from `self`, but leaked, so it lives as long as the program.
+    pub fn leak_hash<'b>(&self, hash: u64) -> &'b mut u64 {
+        let leaked = Box::into_raw(Box::new(hash));
+        unsafe { &mut *leaked }
+    }
+}
+
+static mut COUNTER: u64 = 0;
+
+/// Not derived from any argument, but from a `static`.
+pub fn counter<'b>(_entry: &OccupiedEntry<'_, u8, u8>) -> &'b u64 {
+    let counter = &raw const COUNTER;
+    unsafe { &*counter }
+}
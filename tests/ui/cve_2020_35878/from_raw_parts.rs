@@ -0,0 +1,25 @@
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use std::slice;
+
+pub struct Buffer {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl Buffer {
+    pub fn as_slice<'b>(&self) -> &'b [u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by creating a slice from a raw pointer
+    }
+
+    pub fn as_mut_slice(&mut self) -> &'static mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+        //~^ ERROR: returning a reference with an unbounded lifetime, obtained by creating a slice from a raw pointer
+    }
+
+    pub fn as_bounded_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
@@ -1,5 +1,6 @@
-//@ ignore-on-host
-
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
 use core::task::{RawWaker, RawWakerVTable, Waker};
 use std::sync::Arc;
 
@@ -10,6 +11,7 @@ where
     W: ArcWake,
 {
     let ptr = Arc::into_raw(wake) as *const ();
+    //~^ ERROR: a pointer to `W` is erased into a `RawWaker` without requiring `W: 'static`
 
     unsafe { Waker::from_raw(RawWaker::new(ptr, waker_vtable::<W>())) }
 }
@@ -35,30 +37,40 @@ pub fn waker_vtable<W: ArcWake>() -> &'static RawWakerVTable {
 
 #[allow(clippy::redundant_clone)] // The clone here isn't actually redundant.
 unsafe fn increase_refcount<T: ArcWake>(data: *const ()) {
-    // Retain Arc, but don't touch refcount by wrapping in ManuallyDrop
-    let arc = std::mem::ManuallyDrop::new(Arc::<T>::from_raw(data as *const T));
-    // Now increase refcount, but don't drop new refcount either
-    let _arc_clone: std::mem::ManuallyDrop<_> = arc.clone();
+    unsafe {
+        // Retain Arc, but don't touch refcount by wrapping in ManuallyDrop
+        let arc = std::mem::ManuallyDrop::new(Arc::<T>::from_raw(data as *const T));
+        // Now increase refcount, but don't drop new refcount either
+        let _arc_clone: std::mem::ManuallyDrop<_> = arc.clone();
+    }
 }
 
 // used by `waker_ref`
 unsafe fn clone_arc_raw<T: ArcWake>(data: *const ()) -> RawWaker {
-    increase_refcount::<T>(data);
-    RawWaker::new(data, waker_vtable::<T>())
+    unsafe {
+        increase_refcount::<T>(data);
+        RawWaker::new(data, waker_vtable::<T>())
+    }
 }
 
 unsafe fn wake_arc_raw<T: ArcWake>(data: *const ()) {
-    let arc: Arc<T> = Arc::from_raw(data as *const T);
-    ArcWake::wake(arc);
+    unsafe {
+        let arc: Arc<T> = Arc::from_raw(data as *const T);
+        ArcWake::wake(arc);
+    }
 }
 
 // used by `waker_ref`
 unsafe fn wake_by_ref_arc_raw<T: ArcWake>(data: *const ()) {
-    // Retain Arc, but don't touch refcount by wrapping in ManuallyDrop
-    let arc = std::mem::ManuallyDrop::new(Arc::<T>::from_raw(data as *const T));
-    ArcWake::wake_by_ref(&arc);
+    unsafe {
+        // Retain Arc, but don't touch refcount by wrapping in ManuallyDrop
+        let arc = std::mem::ManuallyDrop::new(Arc::<T>::from_raw(data as *const T));
+        ArcWake::wake_by_ref(&arc);
+    }
 }
 
 unsafe fn drop_arc_raw<T: ArcWake>(data: *const ()) {
-    drop(Arc::<T>::from_raw(data as *const T))
+    unsafe {
+        drop(Arc::<T>::from_raw(data as *const T))
+    }
 }
@@ -0,0 +1,53 @@
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use core::task::{RawWaker, RawWakerVTable, Waker};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+pub trait ArcWake: Send + Sync {
+    fn wake_by_ref(arc_self: &Arc<Self>);
+}
+
+pub struct ArcWaker<W>(PhantomData<W>);
+
+// The bound of `W` is declared on the `impl`, not on the method.
+impl<W: ArcWake> ArcWaker<W> {
+    pub fn waker(wake: Arc<W>) -> Waker {
+        let ptr = Arc::into_raw(wake) as *const ();
+        //~^ ERROR: a pointer to `W` is erased into a `RawWaker` without requiring `W: 'static`
+
+        unsafe { Waker::from_raw(RawWaker::new(ptr, vtable::<W>())) }
+    }
+}
+
+pub struct StaticArcWaker<W>(PhantomData<W>);
+
+impl<W> StaticArcWaker<W>
+where
+    W: ArcWake + 'static,
+{
+    pub fn waker(wake: Arc<W>) -> Waker {
+        let ptr = Arc::into_raw(wake) as *const ();
+
+        unsafe { Waker::from_raw(RawWaker::new(ptr, vtable::<W>())) }
+    }
+}
+
+fn vtable<W: ArcWake>() -> &'static RawWakerVTable {
+    &RawWakerVTable::new(clone::<W>, wake_by_ref::<W>, wake_by_ref::<W>, drop::<W>)
+}
+
+unsafe fn clone<W: ArcWake>(data: *const ()) -> RawWaker {
+    unsafe { Arc::increment_strong_count(data as *const W) };
+    RawWaker::new(data, vtable::<W>())
+}
+
+unsafe fn wake_by_ref<W: ArcWake>(data: *const ()) {
+    let arc = std::mem::ManuallyDrop::new(unsafe { Arc::<W>::from_raw(data as *const W) });
+    ArcWake::wake_by_ref(&arc);
+}
+
+unsafe fn drop<W: ArcWake>(data: *const ()) {
+    std::mem::drop(unsafe { Arc::<W>::from_raw(data as *const W) });
+}
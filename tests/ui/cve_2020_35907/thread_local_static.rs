@@ -0,0 +1,28 @@
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+#![feature(thread_local)]
+
+use std::cell::UnsafeCell;
+use std::task::Waker;
+
+#[thread_local]
+static WAKER: UnsafeCell<Option<Waker>> = UnsafeCell::new(None);
+
+#[thread_local]
+static COUNTER: UnsafeCell<std::cell::Cell<u32>> = UnsafeCell::new(std::cell::Cell::new(0));
+
+pub fn waker_ref() -> &'static Option<Waker> {
+    //~^ERROR: it is unsound to expose a `&'static std::option::Option<std::task::Waker>` from a thread-local where `std::option::Option<std::task::Waker>` is `Sync`
+    unsafe { &*WAKER.get() }
+}
+
+// OK because `Cell<u32>` is not `Sync`
+pub fn counter_ref() -> &'static std::cell::Cell<u32> {
+    unsafe { &*COUNTER.get() }
+}
+
+pub fn take_waker() -> Option<Waker> {
+    // OK because no reference is returned
+    unsafe { (*WAKER.get()).take() }
+}
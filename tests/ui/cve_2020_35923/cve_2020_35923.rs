@@ -1,5 +1,3 @@
-//@ ignore-on-host
-
 use std::cmp::Ordering;
 use std::fmt;
 use std::ops::{AddAssign, Deref, DivAssign, MulAssign, RemAssign, SubAssign};
@@ -63,6 +61,7 @@ impl<T: Float + PartialEq> Eq for NotNan<T> {}
 impl<T: Float + AddAssign> AddAssign<T> for NotNan<T> {
     fn add_assign(&mut self, other: T) {
         self.0 += other;
+        //~^ ERROR: a field of `self` is modified in place before a check that may panic
         assert!(!self.0.is_nan(), "Addition resulted in NaN");
     }
 }
@@ -71,6 +70,7 @@ impl<T: Float + AddAssign> AddAssign<T> for NotNan<T> {
 impl<T: Float + SubAssign> SubAssign<T> for NotNan<T> {
     fn sub_assign(&mut self, other: T) {
         self.0 -= other;
+        //~^ ERROR: a field of `self` is modified in place before a check that may panic
         assert!(!self.0.is_nan(), "Subtraction resulted in NaN");
     }
 }
@@ -79,6 +79,7 @@ impl<T: Float + SubAssign> SubAssign<T> for NotNan<T> {
 impl<T: Float + MulAssign> MulAssign<T> for NotNan<T> {
     fn mul_assign(&mut self, other: T) {
         self.0 *= other;
+        //~^ ERROR: a field of `self` is modified in place before a check that may panic
         assert!(!self.0.is_nan(), "Multiplication resulted in NaN");
     }
 }
@@ -87,6 +88,7 @@ impl<T: Float + MulAssign> MulAssign<T> for NotNan<T> {
 impl<T: Float + DivAssign> DivAssign<T> for NotNan<T> {
     fn div_assign(&mut self, other: T) {
         self.0 /= other;
+        //~^ ERROR: a field of `self` is modified in place before a check that may panic
         assert!(!self.0.is_nan(), "Division resulted in NaN");
     }
 }
@@ -95,6 +97,7 @@ impl<T: Float + DivAssign> DivAssign<T> for NotNan<T> {
 impl<T: Float + RemAssign> RemAssign<T> for NotNan<T> {
     fn rem_assign(&mut self, other: T) {
         self.0 %= other;
+        //~^ ERROR: a field of `self` is modified in place before a check that may panic
         assert!(!self.0.is_nan(), "Rem resulted in NaN");
     }
 }
@@ -118,6 +121,20 @@ impl fmt::Display for FloatIsNan {
     }
 }
 
+/// Names whose `cursor` is always in bounds.
+pub struct Names {
+    names: [String; 4],
+    cursor: usize,
+}
+
+impl Names {
+    pub fn set_cursor(&mut self, cursor: usize, name: String) {
+        self.cursor = cursor;
+        //~^ ERROR: a field of `self` is modified in place before a check that may panic
+        self.names[self.cursor] = name;
+    }
+}
+
 fn not_nan<T: Float>(x: T) -> NotNan<T> {
     NotNan::new(x).unwrap()
 }
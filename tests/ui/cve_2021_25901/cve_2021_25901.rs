@@ -1,7 +1,7 @@
-//@ ignore-on-host
-
 use std::{
     cell::UnsafeCell,
+    marker::PhantomData,
+    ptr::NonNull,
     sync::{atomic::AtomicBool, Mutex},
 };
 
@@ -19,8 +19,32 @@ pub struct LazyTransform<T, U> {
 }
 
 unsafe impl<T, U> Sync for LazyTransform<T, U>
+//~^ ERROR: `LazyTransform<T, U>` implements `Sync` without requiring `U: Send`
 where
     T: Sync + Send,
     U: Sync, // fix: Sync + Send
 {
 }
+
+/// Owns `T` through a raw pointer, like `Box`.
+pub struct RawBox<T>(*mut T, PhantomData<T>);
+
+unsafe impl<T> Send for RawBox<T> {}
+//~^ ERROR: `RawBox<T>` implements `Send` without requiring `T: Send`
+
+/// Shares `T` between clones, like `Arc`.
+pub struct Shared<T>(std::sync::Arc<T>);
+
+unsafe impl<T: Send> Send for Shared<T> {}
+//~^ ERROR: `Shared<T>` implements `Send` without requiring `T: Sync`
+
+/// Owns `T` through a `NonNull`, like `Vec`, and is shared without `T: Sync`.
+pub struct RawVec<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for RawVec<T> {}
+unsafe impl<T: Send> Sync for RawVec<T> {}
+//~^ ERROR: `RawVec<T>` implements `Sync` without requiring `T: Sync`
@@ -0,0 +1,55 @@
+//@check-pass
+use std::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    sync::{atomic::AtomicBool, Mutex},
+};
+
+enum ThisOrThat<T, U> {
+    This(T),
+    That(U),
+}
+
+/// `LazyTransform<T, U>` is a synchronized holder type, that holds a value of
+/// type T until it is lazily converted into a value of type U.
+pub struct LazyTransform<T, U> {
+    initialized: AtomicBool,
+    lock: Mutex<()>,
+    value: UnsafeCell<Option<ThisOrThat<T, U>>>,
+}
+
+unsafe impl<T, U> Sync for LazyTransform<T, U>
+where
+    T: Sync + Send,
+    U: Sync + Send,
+{
+}
+
+/// A spin lock only hands out exclusive access to `T`, so `T: Send` is enough, as for `Mutex`.
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+pub struct RawMutex<T> {
+    inner: Mutex<()>,
+    data: UnsafeCell<Option<T>>,
+}
+
+unsafe impl<T> Send for RawMutex<T> where T: Send {}
+unsafe impl<T> Sync for RawMutex<T> where T: Send {}
+
+/// Owns `T` through a raw pointer, like `Box`.
+pub struct RawBox<T>(*mut T, PhantomData<T>);
+
+unsafe impl<T: Send> Send for RawBox<T> {}
+unsafe impl<T: Sync> Sync for RawBox<T> {}
+
+/// Only hands out the address of `T` without owning it, so the implementation is up to its
+/// users, and no bounds are required.
+pub struct Handle<T>(*const T);
+
+unsafe impl<T> Send for Handle<T> {}
+unsafe impl<T> Sync for Handle<T> {}
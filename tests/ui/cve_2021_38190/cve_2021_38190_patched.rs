@@ -0,0 +1,46 @@
+//@check-pass
+//@compile-flags: -Z inline-mir
+use serde::Deserialize;
+use serde::de::{self, Deserializer};
+
+/// A Vec-based matrix data storage.
+pub struct VecStorage<T> {
+    data: Vec<T>,
+    nrows: usize,
+    ncols: usize,
+}
+
+#[derive(Deserialize)]
+struct VecStorageUnchecked<T> {
+    data: Vec<T>,
+    nrows: usize,
+    ncols: usize,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for VecStorage<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let storage = VecStorageUnchecked::<T>::deserialize(deserializer)?;
+        if storage.nrows * storage.ncols != storage.data.len() {
+            return Err(de::Error::custom(
+                "the number of elements does not match the dimensions",
+            ));
+        }
+        Ok(VecStorage {
+            data: storage.data,
+            nrows: storage.nrows,
+            ncols: storage.ncols,
+        })
+    }
+}
+
+impl<T> VecStorage<T> {
+    /// # Safety
+    ///
+    /// `row` and `col` must be less than the number of rows and columns.
+    pub unsafe fn get_unchecked_linear(&self, row: usize, col: usize) -> &T {
+        unsafe { self.data.get_unchecked(col * self.nrows + row) }
+    }
+}
@@ -0,0 +1,143 @@
+//@compile-flags: -Z inline-mir
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::ops::Index;
+
+/// Trait implemented by any type that can be used as a dimension.
+pub trait Dim: Debug + Copy + PartialEq + Send + Sync + 'static {
+    /// Gets the run-time value of `self`.
+    fn value(&self) -> usize;
+
+    /// Builds an instance of `Self` from a run-time value.
+    fn from_usize(dim: usize) -> Self;
+}
+
+/// Dim of dynamically-sized algebraic entities.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Dynamic {
+    value: usize,
+}
+
+impl Dim for Dynamic {
+    fn value(&self) -> usize {
+        self.value
+    }
+
+    fn from_usize(dim: usize) -> Self {
+        Self { value: dim }
+    }
+}
+
+/// The type-level dimension 1.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct U1;
+
+impl Dim for U1 {
+    fn value(&self) -> usize {
+        1
+    }
+
+    fn from_usize(_dim: usize) -> Self {
+        U1
+    }
+}
+
+/// The trait shared by all matrix data storage.
+///
+/// # Safety
+///
+/// The storage must hold at least `nrows * ncols` elements at the address returned by `ptr`.
+pub unsafe trait Storage<T, R: Dim, C: Dim>: Debug + Sized {
+    type RStride: Dim;
+    type CStride: Dim;
+
+    /// The matrix data pointer.
+    fn ptr(&self) -> *const T;
+
+    /// The dimension of the matrix at run-time.
+    fn shape(&self) -> (R, C);
+
+    /// The spacing between consecutive row elements and consecutive column elements.
+    fn strides(&self) -> (Self::RStride, Self::CStride);
+
+    /// Compute the index corresponding to the irow-th row and icol-th column of this matrix.
+    fn linear_index(&self, irow: usize, icol: usize) -> usize {
+        let (rstride, cstride) = self.strides();
+
+        irow * rstride.value() + icol * cstride.value()
+    }
+
+    /// Gets the address of the i-th matrix component without performing bound-checking.
+    fn get_address_unchecked_linear(&self, i: usize) -> *const T {
+        self.ptr().wrapping_offset(i as isize)
+    }
+
+    /// Gets the address of the i-th matrix component without performing bound-checking.
+    fn get_address_unchecked(&self, irow: usize, icol: usize) -> *const T {
+        self.get_address_unchecked_linear(self.linear_index(irow, icol))
+    }
+
+    /// Retrieves a reference to the i-th element without bound-checking.
+    unsafe fn get_unchecked_linear(&self, i: usize) -> &T {
+        unsafe { &*self.get_address_unchecked_linear(i) }
+    }
+
+    /// Retrieves a reference to the i-th element without bound-checking.
+    unsafe fn get_unchecked(&self, irow: usize, icol: usize) -> &T {
+        unsafe { self.get_unchecked_linear(self.linear_index(irow, icol)) }
+    }
+}
+
+/// A Vec-based matrix data storage. It may be dynamically-sized.
+#[repr(C)]
+#[derive(Eq, Debug, Clone, PartialEq, Serialize, Deserialize)]
+//~^ ERROR: `VecStorage<T, R, C>` is deserialized without checking the field `nrows` trusted by unsafe code
+pub struct VecStorage<T, R: Dim, C: Dim> {
+    data: Vec<T>,
+    nrows: R,
+    ncols: C,
+}
+
+unsafe impl<T: Debug, C: Dim> Storage<T, Dynamic, C> for VecStorage<T, Dynamic, C> {
+    type RStride = U1;
+    type CStride = Dynamic;
+
+    fn ptr(&self) -> *const T {
+        self.data.as_ptr()
+    }
+
+    fn shape(&self) -> (Dynamic, C) {
+        (self.nrows, self.ncols)
+    }
+
+    fn strides(&self) -> (Self::RStride, Self::CStride) {
+        (U1, self.nrows)
+    }
+}
+
+/// The most generic column-major matrix (and vector) type.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Matrix<T, R, C, S> {
+    pub data: S,
+    _phantoms: std::marker::PhantomData<(T, R, C)>,
+}
+
+impl<T, R: Dim, C: Dim, S: Storage<T, R, C>> Matrix<T, R, C, S> {
+    /// The shape of this matrix returned as the tuple (number of rows, number of columns).
+    pub fn shape(&self) -> (usize, usize) {
+        let (nrows, ncols) = self.data.shape();
+        (nrows.value(), ncols.value())
+    }
+}
+
+impl<T, R: Dim, C: Dim, S: Storage<T, R, C>> Index<(usize, usize)> for Matrix<T, R, C, S> {
+    type Output = T;
+
+    fn index(&self, (irow, icol): (usize, usize)) -> &T {
+        let shape = self.shape();
+        assert!(irow < shape.0 && icol < shape.1, "Matrix index out of bounds.");
+
+        unsafe { self.data.get_unchecked(irow, icol) }
+    }
+}
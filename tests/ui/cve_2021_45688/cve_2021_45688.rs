@@ -1,6 +1,5 @@
 //@revisions: inline regular
 //@[inline] compile-flags: -Z inline-mir=true
-//@[inline] check-pass
 //@[regular] compile-flags: -Z inline-mir=false
 use std::io;
 use std::slice;
@@ -21,6 +20,7 @@ pub fn read_spv<R: io::Read + io::Seek>(x: &mut R) -> io::Result<Vec<u32>> {
     x.seek(io::SeekFrom::Start(0))?;
     unsafe {
         x.read_exact(slice::from_raw_parts_mut(
+            //~^ ERROR: an uninitialized buffer is passed to `std::io::Read::read_exact` of the generic reader `R`
             //FIXME: a false negative, `result` is not initialized
             //ERROR: it violates the precondition of `std::slice::from_raw_parts_mut` to create a slice from uninitialized data
             result.as_mut_ptr() as *mut u8,
@@ -43,3 +43,22 @@ pub fn read_spv<R: io::Read + io::Seek>(x: &mut R) -> io::Result<Vec<u32>> {
     }
     Ok(result)
 }
+
+pub fn read_words<R: io::Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(len);
+    unsafe { buf.set_len(len) };
+    //~[regular]^ ERROR: it violates the precondition of `Vec::set_len` to extend a `Vec`'s length without initializing its content in advance
+    reader.read_exact(&mut buf)?;
+    //~^ ERROR: an uninitialized buffer is passed to `std::io::Read::read_exact` of the generic reader `R`
+    Ok(buf)
+}
+
+pub fn read_some(mut reader: impl io::Read, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(len);
+    unsafe { buf.set_len(len) };
+    //~[regular]^ ERROR: it violates the precondition of `Vec::set_len` to extend a `Vec`'s length without initializing its content in advance
+    let read = reader.read(buf.as_mut_slice())?;
+    //~^ ERROR: an uninitialized buffer is passed to `std::io::Read::read` of the generic reader `impl io::Read`
+    buf.truncate(read);
+    Ok(buf)
+}
@@ -0,0 +1,39 @@
+//@check-pass
+use std::io;
+use std::slice;
+
+pub fn read_spv<R: io::Read + io::Seek>(x: &mut R) -> io::Result<Vec<u32>> {
+    let size = x.seek(io::SeekFrom::End(0))?;
+    if size % 4 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "input length not divisible by 4",
+        ));
+    }
+    if size > usize::MAX as u64 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "input too long"));
+    }
+    let words = (size / 4) as usize;
+    // The buffer is zeroed before it is passed to the reader.
+    let mut result = vec![0u32; words];
+    x.seek(io::SeekFrom::Start(0))?;
+    x.read_exact(unsafe { slice::from_raw_parts_mut(result.as_mut_ptr() as *mut u8, words * 4) })?;
+    Ok(result)
+}
+
+pub fn read_words<R: io::Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(len);
+    buf.resize(len, 0);
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+pub fn read_file(file: &mut std::fs::File, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(len);
+    unsafe {
+        std::ptr::write_bytes(buf.as_mut_ptr(), 0, len);
+        buf.set_len(len);
+    }
+    io::Read::read_exact(file, &mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,13 @@
+//@rustc-env: RPL_CRATE_VERSIONS=crossbeam-utils@0.8.6
+use crossbeam_utils::atomic::AtomicCell;
+
+pub fn count(counter: &AtomicCell<u64>, delta: i64, signed: &AtomicCell<i64>) -> u64 {
+    signed.fetch_sub(delta);
+    //~^ ERROR: it is unsound to use the arithmetic operations of `AtomicCell<u64>` and `AtomicCell<i64>` of this version of `crossbeam-utils`
+    counter.fetch_add(1)
+    //~^ ERROR: it is unsound to use the arithmetic operations of `AtomicCell<u64>` and `AtomicCell<i64>` of this version of `crossbeam-utils`
+}
+
+pub fn count_u32(counter: &AtomicCell<u32>) -> u32 {
+    counter.fetch_add(1)
+}
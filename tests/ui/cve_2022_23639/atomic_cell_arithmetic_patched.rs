@@ -0,0 +1,12 @@
+//@rustc-env: RPL_CRATE_VERSIONS=crossbeam-utils@0.8.7
+//@check-pass
+use crossbeam_utils::atomic::AtomicCell;
+
+pub fn count(counter: &AtomicCell<u64>, delta: i64, signed: &AtomicCell<i64>) -> u64 {
+    signed.fetch_sub(delta);
+    counter.fetch_add(1)
+}
+
+pub fn count_u32(counter: &AtomicCell<u32>) -> u32 {
+    counter.fetch_add(1)
+}
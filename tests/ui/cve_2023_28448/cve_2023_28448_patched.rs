@@ -0,0 +1,46 @@
+//@check-pass
+//@compile-flags: -Z inline-mir
+use serde::de::{Deserialize, Deserializer};
+use std::slice;
+
+/// A struct with a flexible array member, whose entries follow the header.
+///
+/// # Safety
+///
+/// `len` must return the number of entries.
+pub unsafe trait FamStruct {
+    type Entry;
+
+    fn len(&self) -> usize;
+}
+
+pub struct FamStructWrapper<T: FamStruct> {
+    header: T,
+    entries: Vec<T::Entry>,
+}
+
+impl<T: FamStruct> FamStructWrapper<T> {
+    pub fn as_slice(&self) -> &[T::Entry] {
+        let len = self.header.len();
+        unsafe { slice::from_raw_parts(self.entries.as_ptr(), len) }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for FamStructWrapper<T>
+where
+    T: FamStruct + Deserialize<'de>,
+    T::Entry: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (header, entries) = <(T, Vec<T::Entry>)>::deserialize(deserializer)?;
+        if header.len() != entries.len() {
+            return Err(serde::de::Error::custom(
+                "mismatch between the length of the FAM and its entries",
+            ));
+        }
+        Ok(FamStructWrapper { header, entries })
+    }
+}
@@ -0,0 +1,102 @@
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+
+pub struct VolatileSlice<'a> {
+    addr: *mut u8,
+    size: usize,
+    _phantom: PhantomData<&'a [u8]>,
+}
+
+pub struct VolatileRef<'a, T> {
+    pub addr: *mut T,
+    _phantom: PhantomData<&'a T>,
+}
+
+pub struct Wrapped<'a, T> {
+    pub addr: *mut T,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T> Wrapped<'a, T> {
+    /// # Safety
+    ///
+    /// `addr` must be valid for the lifetime `'a`.
+    #[inline(never)]
+    pub unsafe fn new(addr: *mut u8) -> Self {
+        Wrapped {
+            addr: addr as *mut T,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pub struct Ref<'a, T> {
+    pub addr: *const T,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a> VolatileSlice<'a> {
+    // `offset` is checked against the size, but not against the alignment of `T`.
+    pub fn get_ref<T: Copy>(&self, offset: usize) -> Option<VolatileRef<'a, T>> {
+        if offset.checked_add(size_of::<T>())? > self.size {
+            return None;
+        }
+        let addr = unsafe { self.addr.add(offset) } as *mut T;
+        //~^ ERROR: a byte pointer is offset and cast to `*T` without checking the alignment of `T`
+        Some(VolatileRef {
+            addr,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn get_const_ref<T: Copy>(&self, offset: usize) -> Option<Ref<'a, T>> {
+        if offset.checked_add(size_of::<T>())? > self.size {
+            return None;
+        }
+        let base = self.addr as *const u8;
+        let addr = unsafe { base.add(offset) } as *const T;
+        //~^ ERROR: a byte pointer is offset and cast to `*T` without checking the alignment of `T`
+        Some(Ref {
+            addr,
+            _phantom: PhantomData,
+        })
+    }
+
+    // The alignment of `T` is taken, but only to round the size, not to check the pointer.
+    pub fn get_padded_ref<T: Copy>(&self, offset: usize) -> Option<VolatileRef<'a, T>> {
+        let padded_size = size_of::<T>().next_multiple_of(align_of::<T>());
+        if offset.checked_add(padded_size)? > self.size {
+            return None;
+        }
+        let addr = unsafe { self.addr.add(offset) } as *mut T;
+        //~^ ERROR: a byte pointer is offset and cast to `*T` without checking the alignment of `T`
+        Some(VolatileRef {
+            addr,
+            _phantom: PhantomData,
+        })
+    }
+
+    // Byte-aligned types can be read from any offset.
+    pub fn get_byte_array_ref(&self, offset: usize) -> Option<VolatileRef<'a, [u8; 4]>> {
+        if offset.checked_add(4)? > self.size {
+            return None;
+        }
+        let addr = unsafe { self.addr.add(offset) } as *mut [u8; 4];
+        Some(VolatileRef {
+            addr,
+            _phantom: PhantomData,
+        })
+    }
+
+    // The pointer is wrapped in a typed reference instead of being cast.
+    pub fn get_wrapped_ref<T: Copy>(&self, offset: usize) -> Option<Wrapped<'a, T>> {
+        if offset.checked_add(size_of::<T>())? > self.size {
+            return None;
+        }
+        Some(unsafe { Wrapped::new(self.addr.add(offset)) })
+        //~^ ERROR: a byte pointer is offset and wrapped in `Wrapped<'_, T>` without checking the alignment of `T`
+    }
+}
@@ -0,0 +1,86 @@
+//@revisions: inline regular
+//@check-pass
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+
+pub struct VolatileSlice<'a> {
+    addr: *mut u8,
+    size: usize,
+    _phantom: PhantomData<&'a [u8]>,
+}
+
+pub struct VolatileRef<'a, T> {
+    pub addr: *mut T,
+    _phantom: PhantomData<&'a T>,
+}
+
+pub struct Wrapped<'a, T> {
+    pub addr: *mut T,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T> Wrapped<'a, T> {
+    /// # Safety
+    ///
+    /// `addr` must be valid for the lifetime `'a`, and aligned for `T`.
+    #[inline(never)]
+    pub unsafe fn new(addr: *mut u8) -> Self {
+        Wrapped {
+            addr: addr as *mut T,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> VolatileSlice<'a> {
+    pub fn get_ref<T: Copy>(&self, offset: usize) -> Option<VolatileRef<'a, T>> {
+        if offset.checked_add(size_of::<T>())? > self.size {
+            return None;
+        }
+        let addr = unsafe { self.addr.add(offset) };
+        if (addr as usize) % align_of::<T>() != 0 {
+            return None;
+        }
+        Some(VolatileRef {
+            addr: addr as *mut T,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn get_aligned_ref<T: Copy>(&self, offset: usize) -> Option<VolatileRef<'a, T>> {
+        if offset.checked_add(size_of::<T>())? > self.size {
+            return None;
+        }
+        let addr = unsafe { self.addr.add(offset) } as *mut T;
+        if !addr.is_aligned() {
+            return None;
+        }
+        Some(VolatileRef {
+            addr,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn get_wrapped_ref<T: Copy>(&self, offset: usize) -> Option<Wrapped<'a, T>> {
+        if offset.checked_add(size_of::<T>())? > self.size {
+            return None;
+        }
+        let addr = unsafe { self.addr.add(offset) };
+        if (addr as usize) % align_of::<T>() != 0 {
+            return None;
+        }
+        Some(unsafe { Wrapped::new(addr) })
+    }
+
+    // The offset is not controlled by the caller, but computed from the fields of `self`.
+    pub fn get_last<T: Copy>(&self) -> Option<VolatileRef<'a, T>> {
+        let offset = self.size.checked_sub(size_of::<T>())?;
+        let addr = unsafe { self.addr.add(offset) } as *mut T;
+        Some(VolatileRef {
+            addr,
+            _phantom: PhantomData,
+        })
+    }
+}
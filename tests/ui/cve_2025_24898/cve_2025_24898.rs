@@ -0,0 +1,73 @@
+//@revisions: inline regular
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use std::ffi::{c_int, c_uchar, c_uint};
+
+unsafe extern "C" {
+    fn SSL_select_next_proto(
+        out: *mut *mut c_uchar,
+        outlen: *mut c_uchar,
+        inbuf: *const c_uchar,
+        inlen: c_uint,
+        client: *const c_uchar,
+        client_len: c_uint,
+    ) -> c_int;
+
+    fn find_token(haystack: *const c_uchar, len: usize, token: *mut *const c_uchar) -> usize;
+}
+
+const OPENSSL_NPN_NEGOTIATED: c_int = 1;
+
+// `out` may point into `server`, which is not borrowed by the returned slice.
+pub fn select_next_proto<'a>(server: &[u8], client: &'a [u8]) -> Option<&'a [u8]> {
+    unsafe {
+        let mut out = std::ptr::null_mut();
+        let mut outlen = 0;
+        let r = SSL_select_next_proto(
+            &mut out,
+            &mut outlen,
+            server.as_ptr(),
+            server.len() as c_uint,
+            client.as_ptr(),
+            client.len() as c_uint,
+        );
+        if r == OPENSSL_NPN_NEGOTIATED {
+            Some(std::slice::from_raw_parts(out as *const u8, outlen as usize))
+            //~^ ERROR: a slice is created from a pointer written by an extern function, but it may outlive the buffer it points into
+        } else {
+            None
+        }
+    }
+}
+
+// `token` may point into `haystack`, whatever the position of the out pointer among the arguments.
+pub fn first_token<'a>(haystack: &[u8], _fallback: &'a [u8]) -> &'a [u8] {
+    unsafe {
+        let mut token = std::ptr::null();
+        let len = find_token(haystack.as_ptr(), haystack.len(), &mut token);
+        std::slice::from_raw_parts(token, len)
+        //~^ ERROR: a slice is created from a pointer written by an extern function, but it may outlive the buffer it points into
+    }
+}
+
+// The buffers may be followed by other arguments, such as the protocol preferred by default.
+pub fn select_next_proto_or<'a>(server: &[u8], client: &'a [u8], _default: usize) -> Option<&'a [u8]> {
+    unsafe {
+        let mut out = std::ptr::null_mut();
+        let mut outlen = 0;
+        let r = SSL_select_next_proto(
+            &mut out,
+            &mut outlen,
+            server.as_ptr(),
+            server.len() as c_uint,
+            client.as_ptr(),
+            client.len() as c_uint,
+        );
+        if r == OPENSSL_NPN_NEGOTIATED {
+            Some(std::slice::from_raw_parts(out as *const u8, outlen as usize))
+            //~^ ERROR: a slice is created from a pointer written by an extern function, but it may outlive the buffer it points into
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//@revisions: inline regular
+//@check-pass
+//@[inline] compile-flags: -Z inline-mir=true
+//@[regular] compile-flags: -Z inline-mir=false
+use std::ffi::{c_int, c_uchar, c_uint};
+
+unsafe extern "C" {
+    fn SSL_select_next_proto(
+        out: *mut *mut c_uchar,
+        outlen: *mut c_uchar,
+        inbuf: *const c_uchar,
+        inlen: c_uint,
+        client: *const c_uchar,
+        client_len: c_uint,
+    ) -> c_int;
+}
+
+const OPENSSL_NPN_NEGOTIATED: c_int = 1;
+
+// Both buffers outlive the returned slice.
+pub fn select_next_proto<'a>(server: &'a [u8], client: &'a [u8]) -> Option<&'a [u8]> {
+    unsafe {
+        let mut out = std::ptr::null_mut();
+        let mut outlen = 0;
+        let r = SSL_select_next_proto(
+            &mut out,
+            &mut outlen,
+            server.as_ptr(),
+            server.len() as c_uint,
+            client.as_ptr(),
+            client.len() as c_uint,
+        );
+        if r == OPENSSL_NPN_NEGOTIATED {
+            Some(std::slice::from_raw_parts(out as *const u8, outlen as usize))
+        } else {
+            None
+        }
+    }
+}
+
+/// Writes a pointer to the first byte of `haystack` to `*token`, and returns the length of the
+/// token, which is always `haystack` itself.
+unsafe fn find_token(haystack: *const u8, len: usize, token: *mut *const u8) -> usize {
+    unsafe { *token = haystack };
+    len
+}
+
+// Only the pointers written by extern functions, whose bodies are unknown, are reported.
+pub fn first_token<'a>(haystack: &'a [u8], _fallback: &[u8]) -> &'a [u8] {
+    unsafe {
+        let mut token = std::ptr::null();
+        let len = find_token(haystack.as_ptr(), haystack.len(), &mut token);
+        std::slice::from_raw_parts(token, len)
+    }
+}
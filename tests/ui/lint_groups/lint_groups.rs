@@ -0,0 +1,27 @@
+#![allow(rpl::private_function_marked_inlin)]
+//~^ ERROR: unknown lint: `rpl::private_function_marked_inlin`
+
+// `rpl::private_function_marked_inline` is not a memory lint
+#[allow(rpl::memory)]
+#[inline]
+fn foo() {}
+//~^ private_function_marked_inline
+
+#[allow(rpl::all)]
+#[inline]
+fn bar() {}
+
+#[expect(rpl::private_function_marked_inline)]
+#[inline]
+fn baz() {}
+
+#[expect(rpl::use_after_drop)]
+//~^ ERROR: this lint expectation is unfulfilled
+fn qux() {}
+
+fn main() {
+    foo();
+    bar();
+    baz();
+    qux();
+}
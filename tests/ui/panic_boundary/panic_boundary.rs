@@ -0,0 +1,12 @@
+//@rustc-env: RPL_PANIC_FOR_TESTING=boom
+// A panic in one detector is reported as an internal error, and the findings of the other
+// detectors, on the same item or not, are still reported.
+
+#[inline]
+fn boom() {}
+//~^ WARN: internal RPL error in pattern `others::panic_for_testing` on `boom`
+//~| ERROR: it usually isn't necessary to apply #[inline] to private functions
+
+#[inline]
+fn fine() {}
+//~^ ERROR: it usually isn't necessary to apply #[inline] to private functions